@@ -0,0 +1,479 @@
+// Procedural macros used by chapter 8 of rust-concepts
+//
+// Only the compiler's built-in `proc_macro` API is used (no syn or quote), so this crate
+// builds without downloading anything. The macros parse the incoming TokenStream by hand,
+// build the generated code as a string and parse it back into a TokenStream.
+
+use proc_macro::{Delimiter, Literal, Spacing, TokenStream, TokenTree};
+
+// #[derive(Describe)] implements the `Describe` trait, which reports a type's name and the
+// names and types of its fields (or the variants of an enum)
+// The trait itself must be in scope where the derive is used, the same way serde does it:
+// `use rust_concepts::ch08_macros::Describe;` imports both the trait and this derive
+#[proc_macro_derive(Describe)]
+pub fn derive_describe(input: TokenStream) -> TokenStream {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    match parse_type_definition(&tokens) {
+        Ok(definition) => definition.expand(),
+        Err(message) => compile_error(&message),
+    }
+}
+
+// #[traced(recorder)] wraps a free function so that every call is reported to `recorder`,
+// a function taking a String, as "name(arg1, arg2) -> result"
+// Arguments and the return value must implement Debug
+#[proc_macro_attribute]
+pub fn traced(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let recorder = attr.to_string();
+    if recorder.trim().is_empty() {
+        return compile_error("#[traced] needs a recorder function, e.g. #[traced(record_call)]");
+    }
+    let tokens: Vec<TokenTree> = item.into_iter().collect();
+    match parse_function(&tokens) {
+        Ok(function) => function.expand(&recorder),
+        Err(message) => compile_error(&message),
+    }
+}
+
+// Turns an error message into a `compile_error!` invocation, which rustc reports at the call site
+fn compile_error(message: &str) -> TokenStream {
+    format!("compile_error!({});", Literal::string(message))
+        .parse()
+        .unwrap()
+}
+
+// The parts of a struct or enum that Describe needs
+struct TypeDefinition {
+    kind: &'static str,
+    name: String,
+    // (name, type) for struct fields, (variant, payload) for enum variants
+    fields: Vec<(String, String)>,
+    // How the fields were written, used to render the description
+    shape: Shape,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Shape {
+    Named,
+    Tuple,
+    Unit,
+    Enum,
+}
+
+impl TypeDefinition {
+    fn expand(&self) -> TokenStream {
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(name, ty)| format!("({}, {})", Literal::string(name), Literal::string(ty)))
+            .collect();
+        let description = self.description();
+        format!(
+            "impl Describe for {name} {{
+                fn type_name() -> &'static str {{ {type_name} }}
+                fn kind() -> &'static str {{ {kind} }}
+                fn fields() -> &'static [(&'static str, &'static str)] {{ &[{fields}] }}
+                fn describe() -> &'static str {{ {description} }}
+            }}",
+            name = self.name,
+            type_name = Literal::string(&self.name),
+            kind = Literal::string(self.kind),
+            fields = fields.join(", "),
+            description = Literal::string(&description),
+        )
+        .parse()
+        .unwrap()
+    }
+
+    // Renders the definition back as Rust-like text, e.g. "struct Point { x: i32, y: i32 }"
+    fn description(&self) -> String {
+        let body = match self.shape {
+            Shape::Unit => String::new(),
+            Shape::Tuple => {
+                let types: Vec<&str> = self.fields.iter().map(|(_, ty)| ty.as_str()).collect();
+                format!("({})", types.join(", "))
+            }
+            Shape::Named => {
+                let fields: Vec<String> = self
+                    .fields
+                    .iter()
+                    .map(|(name, ty)| format!("{}: {}", name, ty))
+                    .collect();
+                format!(" {{ {} }}", fields.join(", "))
+            }
+            Shape::Enum => {
+                let variants: Vec<String> = self
+                    .fields
+                    .iter()
+                    .map(|(name, payload)| format!("{}{}", name, payload))
+                    .collect();
+                format!(" {{ {} }}", variants.join(", "))
+            }
+        };
+        format!("{} {}{}", self.kind, self.name, body)
+    }
+}
+
+// Parses `[attributes] [visibility] struct|enum Name ...`
+fn parse_type_definition(tokens: &[TokenTree]) -> Result<TypeDefinition, String> {
+    let mut rest = skip_attributes_and_visibility(tokens);
+    let kind = match rest.first() {
+        Some(TokenTree::Ident(ident)) if ident.to_string() == "struct" => "struct",
+        Some(TokenTree::Ident(ident)) if ident.to_string() == "enum" => "enum",
+        _ => {
+            return Err(String::from(
+                "#[derive(Describe)] only supports structs and enums",
+            ))
+        }
+    };
+    let name = match rest.get(1) {
+        Some(TokenTree::Ident(ident)) => ident.to_string(),
+        _ => return Err(String::from("expected a type name")),
+    };
+    rest = &rest[2..];
+    if is_punct(rest.first(), '<') {
+        return Err(String::from(
+            "#[derive(Describe)] does not support generic types",
+        ));
+    }
+
+    let (shape, fields) = match (kind, rest.first()) {
+        ("enum", Some(TokenTree::Group(group))) if group.delimiter() == Delimiter::Brace => {
+            (Shape::Enum, parse_variants(group.stream())?)
+        }
+        ("struct", Some(TokenTree::Group(group))) if group.delimiter() == Delimiter::Brace => {
+            (Shape::Named, parse_named_fields(group.stream())?)
+        }
+        ("struct", Some(TokenTree::Group(group)))
+            if group.delimiter() == Delimiter::Parenthesis =>
+        {
+            (Shape::Tuple, parse_tuple_fields(group.stream()))
+        }
+        ("struct", _) if is_punct(rest.first(), ';') => (Shape::Unit, Vec::new()),
+        _ => return Err(format!("could not parse the body of `{}`", name)),
+    };
+
+    Ok(TypeDefinition {
+        kind,
+        name,
+        fields,
+        shape,
+    })
+}
+
+// Parses `name: Type, ...` inside the braces of a struct
+fn parse_named_fields(stream: TokenStream) -> Result<Vec<(String, String)>, String> {
+    let tokens: Vec<TokenTree> = stream.into_iter().collect();
+    split_top_level_commas(&tokens)
+        .into_iter()
+        .map(|field| {
+            let field = skip_attributes_and_visibility(field);
+            match (field.first(), field.get(1)) {
+                (Some(TokenTree::Ident(name)), Some(TokenTree::Punct(colon)))
+                    if colon.as_char() == ':' =>
+                {
+                    Ok((name.to_string(), tokens_to_string(&field[2..])))
+                }
+                _ => Err(String::from("expected `name: Type` in struct fields")),
+            }
+        })
+        .collect()
+}
+
+// Parses `Type, ...` inside the parentheses of a tuple struct; fields are named "0", "1", ...
+fn parse_tuple_fields(stream: TokenStream) -> Vec<(String, String)> {
+    let tokens: Vec<TokenTree> = stream.into_iter().collect();
+    split_top_level_commas(&tokens)
+        .into_iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let field = skip_attributes_and_visibility(field);
+            (index.to_string(), tokens_to_string(field))
+        })
+        .collect()
+}
+
+// Parses `Variant`, `Variant(Type)` and `Variant { field: Type }` inside an enum
+// The payload is kept as text, e.g. ("Move", " { x: i32, y: i32 }")
+fn parse_variants(stream: TokenStream) -> Result<Vec<(String, String)>, String> {
+    let tokens: Vec<TokenTree> = stream.into_iter().collect();
+    split_top_level_commas(&tokens)
+        .into_iter()
+        .map(|variant| {
+            let variant = skip_attributes_and_visibility(variant);
+            let name = match variant.first() {
+                Some(TokenTree::Ident(name)) => name.to_string(),
+                _ => return Err(String::from("expected a variant name")),
+            };
+            let payload = match variant.get(1) {
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                    let fields: Vec<String> = parse_named_fields(group.stream())?
+                        .into_iter()
+                        .map(|(name, ty)| format!("{}: {}", name, ty))
+                        .collect();
+                    format!(" {{ {} }}", fields.join(", "))
+                }
+                Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                    let types: Vec<String> = parse_tuple_fields(group.stream())
+                        .into_iter()
+                        .map(|(_, ty)| ty)
+                        .collect();
+                    format!("({})", types.join(", "))
+                }
+                _ => String::new(),
+            };
+            Ok((name, payload))
+        })
+        .collect()
+}
+
+// The parts of a function that #[traced] needs
+struct Function {
+    // Everything before `fn`: attributes, visibility and qualifiers such as `extern "C"`
+    prefix: String,
+    name: String,
+    // (binding, type), with `mut` kept on the binding if it was there
+    params: Vec<(String, String)>,
+    return_type: String,
+    body: String,
+}
+
+impl Function {
+    fn expand(&self, recorder: &str) -> TokenStream {
+        let inner_params: Vec<String> = self
+            .params
+            .iter()
+            .map(|(binding, ty)| format!("{}: {}", binding, ty))
+            .collect();
+        // The outer function never mutates its parameters, so `mut` only stays on the inner one
+        let names: Vec<&str> = self
+            .params
+            .iter()
+            .map(|(binding, _)| binding.trim_start_matches("mut ").trim())
+            .collect();
+        let outer_params: Vec<String> = names
+            .iter()
+            .zip(&self.params)
+            .map(|(name, (_, ty))| format!("{}: {}", name, ty))
+            .collect();
+        let formatted_args: Vec<String> = names
+            .iter()
+            .map(|name| format!("::std::format!(\"{{:?}}\", &{})", name))
+            .collect();
+
+        format!(
+            "{prefix} fn {name}({outer_params}) -> {ret} {{
+                fn __traced_inner({inner_params}) -> {ret} {body}
+                let __traced_args: ::std::vec::Vec<::std::string::String> = ::std::vec![{formatted_args}];
+                let __traced_result = __traced_inner({names});
+                {recorder}(::std::format!(\"{{}}({{}}) -> {{:?}}\", {name_literal}, __traced_args.join(\", \"), __traced_result));
+                __traced_result
+            }}",
+            prefix = self.prefix,
+            name = self.name,
+            name_literal = Literal::string(&self.name),
+            outer_params = outer_params.join(", "),
+            inner_params = inner_params.join(", "),
+            ret = self.return_type,
+            body = self.body,
+            formatted_args = formatted_args.join(", "),
+            names = names.join(", "),
+            recorder = recorder,
+        )
+        .parse()
+        .unwrap_or_else(|_| compile_error("#[traced] could not build a wrapper for this function"))
+    }
+}
+
+// Parses `[attributes] [visibility] [qualifiers] fn name(params) [-> Type] { body }`
+fn parse_function(tokens: &[TokenTree]) -> Result<Function, String> {
+    let fn_index = tokens
+        .iter()
+        .position(|token| matches!(token, TokenTree::Ident(ident) if ident.to_string() == "fn"))
+        .ok_or_else(|| String::from("#[traced] can only be applied to functions"))?;
+    let prefix = tokens[..fn_index]
+        .iter()
+        .cloned()
+        .collect::<TokenStream>()
+        .to_string();
+    // The wrapper calls a plain inner fn and formats the result, which none of these allow
+    let unsupported = tokens[..fn_index].iter().find_map(|token| match token {
+        TokenTree::Ident(ident) => {
+            let word = ident.to_string();
+            matches!(word.as_str(), "async" | "const" | "unsafe").then_some(word)
+        }
+        _ => None,
+    });
+    if let Some(qualifier) = unsupported {
+        return Err(format!(
+            "#[traced] does not support {} functions",
+            qualifier
+        ));
+    }
+
+    let rest = &tokens[fn_index + 1..];
+    let name = match rest.first() {
+        Some(TokenTree::Ident(ident)) => ident.to_string(),
+        _ => return Err(String::from("expected a function name")),
+    };
+    if is_punct(rest.get(1), '<') {
+        return Err(String::from("#[traced] does not support generic functions"));
+    }
+    let params = match rest.get(1) {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+            let tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+            split_top_level_commas(&tokens)
+                .into_iter()
+                .map(parse_param)
+                .collect::<Result<Vec<_>, _>>()?
+        }
+        _ => return Err(String::from("expected a parameter list")),
+    };
+
+    // Everything between the parameters and the body is `-> Type` (or nothing)
+    let after_params = &rest[2..];
+    let body_index = after_params
+        .iter()
+        .rposition(|token| matches!(token, TokenTree::Group(group) if group.delimiter() == Delimiter::Brace))
+        .ok_or_else(|| String::from("expected a function body"))?;
+    let signature_tail = &after_params[..body_index];
+    let return_type = if signature_tail.len() >= 2
+        && is_punct(signature_tail.first(), '-')
+        && is_punct(signature_tail.get(1), '>')
+    {
+        tokens_to_string(&signature_tail[2..])
+    } else if signature_tail.is_empty() {
+        String::from("()")
+    } else {
+        return Err(String::from("#[traced] does not support where clauses"));
+    };
+
+    Ok(Function {
+        prefix,
+        name,
+        params,
+        return_type,
+        body: after_params[body_index].to_string(),
+    })
+}
+
+// Parses a single `name: Type` or `mut name: Type` parameter
+fn parse_param(tokens: &[TokenTree]) -> Result<(String, String), String> {
+    let colon = tokens
+        .iter()
+        .position(|token| is_punct(Some(token), ':'))
+        .ok_or_else(|| String::from("#[traced] does not support methods (self parameters)"))?;
+    let binding = tokens_to_string(&tokens[..colon]);
+    let simple = match &tokens[..colon] {
+        [TokenTree::Ident(_)] => true,
+        [TokenTree::Ident(keyword), TokenTree::Ident(_)] => keyword.to_string() == "mut",
+        _ => false,
+    };
+    if !simple {
+        return Err(format!(
+            "#[traced] only supports simple parameter names, found `{}`",
+            binding
+        ));
+    }
+    Ok((binding, tokens_to_string(&tokens[colon + 1..])))
+}
+
+// Skips `#[...]` attributes (including doc comments) and `pub`, `pub(crate)` and similar
+fn skip_attributes_and_visibility(mut tokens: &[TokenTree]) -> &[TokenTree] {
+    loop {
+        match tokens {
+            [TokenTree::Punct(hash), TokenTree::Group(_), rest @ ..] if hash.as_char() == '#' => {
+                tokens = rest;
+            }
+            [TokenTree::Ident(ident), TokenTree::Group(group), rest @ ..]
+                if ident.to_string() == "pub" && group.delimiter() == Delimiter::Parenthesis =>
+            {
+                tokens = rest;
+            }
+            [TokenTree::Ident(ident), rest @ ..] if ident.to_string() == "pub" => tokens = rest,
+            _ => return tokens,
+        }
+    }
+}
+
+// Splits a comma-separated list, ignoring commas nested inside `<...>`
+// Commas inside (), [] and {} are already hidden inside Group tokens
+fn split_top_level_commas(tokens: &[TokenTree]) -> Vec<&[TokenTree]> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (index, token) in tokens.iter().enumerate() {
+        if let TokenTree::Punct(punct) = token {
+            match punct.as_char() {
+                '<' => depth += 1,
+                // `->` in a function pointer type is not a closing angle bracket
+                '>' if !(index > 0 && is_punct(tokens.get(index - 1), '-')) => {
+                    depth = depth.saturating_sub(1)
+                }
+                ',' if depth == 0 => {
+                    parts.push(&tokens[start..index]);
+                    start = index + 1;
+                }
+                _ => {}
+            }
+        }
+    }
+    if start < tokens.len() {
+        parts.push(&tokens[start..]);
+    }
+    parts
+}
+
+fn is_punct(token: Option<&TokenTree>, ch: char) -> bool {
+    matches!(token, Some(TokenTree::Punct(punct)) if punct.as_char() == ch)
+}
+
+// Renders tokens the way a person would write them, e.g. `Vec<&'a str>` rather than
+// the `Vec < & 'a str >` produced by TokenStream's own Display
+fn tokens_to_string(tokens: &[TokenTree]) -> String {
+    let mut text = String::new();
+    let mut previous_is_word = false;
+    let mut previous_joint = false;
+    for token in tokens {
+        match token {
+            TokenTree::Ident(_) | TokenTree::Literal(_) => {
+                if previous_is_word {
+                    text.push(' ');
+                }
+                text.push_str(&token.to_string());
+                previous_is_word = true;
+                previous_joint = false;
+            }
+            TokenTree::Punct(punct) => {
+                let ch = punct.as_char();
+                match ch {
+                    ',' | ';' => text.push_str(&format!("{} ", ch)),
+                    '+' | '=' => text.push_str(&format!(" {} ", ch)),
+                    '-' if punct.spacing() == Spacing::Joint => text.push_str(" -"),
+                    '>' if previous_joint && text.ends_with('-') => text.push_str("> "),
+                    _ => text.push(ch),
+                }
+                previous_is_word = false;
+                previous_joint = punct.spacing() == Spacing::Joint;
+            }
+            TokenTree::Group(group) => {
+                let inner: Vec<TokenTree> = group.stream().into_iter().collect();
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace => ("{ ", " }"),
+                    Delimiter::None => ("", ""),
+                };
+                if previous_is_word && group.delimiter() == Delimiter::Brace {
+                    text.push(' ');
+                }
+                text.push_str(open);
+                text.push_str(&tokens_to_string(&inner));
+                text.push_str(close);
+                previous_is_word = false;
+                previous_joint = false;
+            }
+        }
+    }
+    text.trim().to_string()
+}
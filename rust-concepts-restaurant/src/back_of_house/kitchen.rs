@@ -0,0 +1,8 @@
+// This function can be called from back_of_house (the parent module), but not from anywhere else
+pub(super) fn cook(dish: &str) -> String {
+    clean_station();
+    format!("The kitchen cooked {}", dish)
+}
+
+// Private functions are only visible inside this module
+fn clean_station() {}
@@ -0,0 +1,50 @@
+// A private child module; its pub(super) functions are usable here but not in the rest of the crate
+mod kitchen;
+
+// Struct fields are private by default, even when the struct itself is public
+pub struct Breakfast {
+    pub toast: String,
+    seasonal_fruit: String,
+}
+
+impl Breakfast {
+    // Because seasonal_fruit is private, other modules need a constructor to build a Breakfast
+    pub fn summer(toast: &str) -> Breakfast {
+        Breakfast {
+            toast: String::from(toast),
+            seasonal_fruit: String::from("peaches"),
+        }
+    }
+
+    // Code inside the module can still read the private field
+    pub fn describe(&self) -> String {
+        format!("{} toast with {}", self.toast, self.seasonal_fruit)
+    }
+}
+
+// The variants of a public enum are always public
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Appetizer {
+    Soup,
+    Salad,
+}
+
+// This function hands an appetizer to the kitchen
+pub fn prepare(appetizer: Appetizer) -> String {
+    let dish = match appetizer {
+        Appetizer::Soup => "soup",
+        Appetizer::Salad => "salad",
+    };
+    kitchen::cook(dish)
+}
+
+// This function is pub(crate): the front of house can call it, other crates can't
+// It uses `super::` to reach a sibling module through the crate root
+pub(crate) fn fix_incorrect_order() -> String {
+    let fixed = kitchen::cook("a fresh order");
+    format!(
+        "{}, then {}",
+        fixed,
+        super::front_of_house::hosting::location()
+    )
+}
@@ -0,0 +1,5 @@
+// This module only exists when the `catering` feature is enabled
+
+pub fn book_event(guests: u32) -> String {
+    format!("Catering booked for {} guests", guests)
+}
@@ -0,0 +1,5 @@
+// This module only exists when the `delivery` feature is enabled
+
+pub fn deliver(address: &str) -> String {
+    format!("Order on its way to {}", address)
+}
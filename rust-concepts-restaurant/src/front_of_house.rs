@@ -0,0 +1,4 @@
+// The children of this module live in front_of_house/hosting.rs and front_of_house/serving.rs
+// This is the file layout the compiler looks for when it sees `mod hosting;` here
+pub mod hosting;
+pub mod serving;
@@ -0,0 +1,21 @@
+// Public functions here are visible to other crates through the `pub use` in lib.rs
+
+// This function is fully public
+pub fn add_to_waitlist(guest: &str) -> String {
+    format!("{} was added to the waitlist", guest)
+}
+
+// This function is visible anywhere in this crate, but not to other crates
+pub(crate) fn seat_at_table(guest: &str) -> String {
+    // take_payment is pub(in crate::front_of_house), and we are inside front_of_house
+    format!(
+        "{} was seated (a card is {})",
+        guest,
+        super::serving::take_payment()
+    )
+}
+
+// This function reports the module's full path, which follows the file layout
+pub fn location() -> &'static str {
+    module_path!()
+}
@@ -0,0 +1,16 @@
+// This function is public, but its parent module is private,
+// so other crates can't reach it; it is only used inside the crate
+pub fn serve_order(dish: &str) -> String {
+    format!("Serving {}, {}", dish, take_order())
+}
+
+// This function is visible inside front_of_house and its children (such as hosting),
+// but nowhere else in the crate
+pub(in crate::front_of_house) fn take_payment() -> &'static str {
+    "held at the front desk"
+}
+
+// This function is visible only in the parent module (front_of_house) and within this one
+pub(super) fn take_order() -> &'static str {
+    "exactly as ordered"
+}
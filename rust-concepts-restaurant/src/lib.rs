@@ -0,0 +1,96 @@
+//! A small restaurant library used by chapter 9 of rust-concepts to show how code is
+//! organized into modules and crates.
+//!
+//! Some items are deliberately hidden from other crates. These examples don't compile,
+//! and `cargo test` checks that rustc rejects each of them with the expected error code:
+//!
+//! `front_of_house` is a private module, so only its re-exported `hosting` child is reachable:
+//!
+//! ```compile_fail,E0603
+//! rust_concepts_restaurant::front_of_house::hosting::add_to_waitlist("Ferris");
+//! ```
+//!
+//! `seat_at_table` is `pub(crate)`, so it is invisible outside this crate:
+//!
+//! ```compile_fail,E0603
+//! rust_concepts_restaurant::hosting::seat_at_table("Ferris");
+//! ```
+//!
+//! `Breakfast::seasonal_fruit` is a private field, so a Breakfast can't be built with a literal:
+//!
+//! ```compile_fail,E0451
+//! let meal = rust_concepts_restaurant::Breakfast {
+//!     toast: String::from("Rye"),
+//!     seasonal_fruit: String::from("peaches"),
+//! };
+//! ```
+//!
+//! The `catering` module only exists when the `catering` feature is enabled, so this example
+//! only compiles when it is (the code fence itself is switched with cfg_attr):
+//!
+#![cfg_attr(not(feature = "catering"), doc = "```compile_fail,E0433")]
+#![cfg_attr(feature = "catering", doc = "```")]
+//! rust_concepts_restaurant::catering::book_event(40);
+//! ```
+
+// A private module: its contents are only reachable from outside through re-exports
+// It lives in front_of_house.rs, with its children in the front_of_house/ directory
+mod front_of_house;
+
+// A public module using the older mod.rs layout: back_of_house/mod.rs
+pub mod back_of_house;
+
+// The #[path] attribute loads a module from a file whose name doesn't match the module
+#[path = "specials_of_the_day.rs"]
+pub mod specials;
+
+// Modules that are only compiled when a cargo feature is enabled
+#[cfg(feature = "delivery")]
+pub mod delivery;
+
+#[cfg(feature = "catering")]
+pub mod catering;
+
+// A module of re-exports meant to be glob-imported with `use restaurant::prelude::*;`
+pub mod prelude;
+
+// Re-exports: `hosting` becomes reachable as `restaurant::hosting` even though its parent is private,
+// and the most used types can be named without the `back_of_house::` prefix
+pub use back_of_house::{Appetizer, Breakfast};
+pub use front_of_house::hosting;
+
+#[cfg(test)]
+mod tests;
+
+// This function is the public entry point that walks a guest through a visit
+// Being inside the crate, it can use private modules and pub(crate) items directly
+pub fn eat_at_restaurant(guest: &str) -> Vec<String> {
+    let mut log = Vec::new();
+
+    // Absolute path, starting from the crate root
+    log.push(crate::front_of_house::hosting::add_to_waitlist(guest));
+    // Relative path, starting from the current module
+    log.push(front_of_house::hosting::seat_at_table(guest));
+
+    // The public `toast` field can be changed, the private `seasonal_fruit` can't
+    let mut meal = Breakfast::summer("Rye");
+    meal.toast = String::from("Wheat");
+    log.push(front_of_house::serving::serve_order(&meal.describe()));
+
+    log.push(back_of_house::prepare(Appetizer::Soup));
+    log.push(back_of_house::fix_incorrect_order());
+    log
+}
+
+// This function lists the cargo features this crate was compiled with
+// cfg! is evaluated at compile time, so each feature check becomes a constant true or false
+pub fn enabled_features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+    if cfg!(feature = "delivery") {
+        features.push("delivery");
+    }
+    if cfg!(feature = "catering") {
+        features.push("catering");
+    }
+    features
+}
@@ -0,0 +1,5 @@
+// Re-exports of the items most callers need, meant for `use restaurant::prelude::*;`
+// A glob import brings every public name of a module into scope at once
+pub use crate::back_of_house::{prepare, Appetizer, Breakfast};
+pub use crate::hosting::add_to_waitlist;
+pub use crate::specials::todays_special;
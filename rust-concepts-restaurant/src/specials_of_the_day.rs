@@ -0,0 +1,12 @@
+// This file is loaded as `crate::specials` through #[path = "specials_of_the_day.rs"] in lib.rs
+
+pub const SOUP_OF_THE_DAY: &str = "tomato";
+
+// The module path uses the module name, not the file name
+pub fn location() -> &'static str {
+    module_path!()
+}
+
+pub fn todays_special() -> String {
+    format!("Today's special is {} soup", SOUP_OF_THE_DAY)
+}
@@ -0,0 +1 @@
+mod visibility_tests;
@@ -0,0 +1,83 @@
+use crate::{back_of_house, front_of_house, hosting, specials, Appetizer, Breakfast};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reexport_and_original_path_reach_the_same_function() {
+        // Inside the crate, the private module path still works; `hosting` is the public alias
+        assert_eq!(
+            hosting::add_to_waitlist("Ferris"),
+            front_of_house::hosting::add_to_waitlist("Ferris")
+        );
+        assert_eq!(
+            hosting::location(),
+            "rust_concepts_restaurant::front_of_house::hosting"
+        );
+    }
+
+    #[test]
+    fn test_pub_crate_items_are_usable_inside_the_crate() {
+        // seat_at_table is pub(crate), and it calls take_payment, which is
+        // pub(in crate::front_of_house) and therefore visible to its sibling module
+        assert_eq!(
+            hosting::seat_at_table("Corro"),
+            "Corro was seated (a card is held at the front desk)"
+        );
+        assert_eq!(
+            back_of_house::fix_incorrect_order(),
+            "The kitchen cooked a fresh order, then rust_concepts_restaurant::front_of_house::hosting"
+        );
+    }
+
+    #[test]
+    fn test_pub_super_item_used_by_its_parent() {
+        // serve_order calls take_order, which is pub(super): visible in front_of_house and serving
+        assert_eq!(
+            front_of_house::serving::serve_order("tea"),
+            "Serving tea, exactly as ordered"
+        );
+        // prepare calls kitchen::cook, which is pub(super) in the private kitchen module
+        assert_eq!(
+            back_of_house::prepare(Appetizer::Salad),
+            "The kitchen cooked salad"
+        );
+    }
+
+    #[test]
+    fn test_private_field_needs_a_constructor() {
+        // Outside back_of_house only the public field can be touched; describe reads the private one
+        let mut meal = Breakfast::summer("Rye");
+        meal.toast = String::from("Sourdough");
+        assert_eq!(meal.describe(), "Sourdough toast with peaches");
+    }
+
+    #[test]
+    fn test_path_attribute_module() {
+        // The module is named after the `mod` declaration, not after specials_of_the_day.rs
+        assert_eq!(specials::location(), "rust_concepts_restaurant::specials");
+        assert_eq!(specials::todays_special(), "Today's special is tomato soup");
+    }
+
+    #[test]
+    fn test_features_are_reported_consistently() {
+        // enabled_features must agree with the cfg flags this test was compiled with
+        let features = crate::enabled_features();
+        assert_eq!(features.contains(&"delivery"), cfg!(feature = "delivery"));
+        assert_eq!(features.contains(&"catering"), cfg!(feature = "catering"));
+    }
+
+    #[test]
+    fn test_eat_at_restaurant() {
+        // The full visit touches every visibility level from inside the crate
+        let log = crate::eat_at_restaurant("Ferris");
+        assert_eq!(log.len(), 5);
+        assert_eq!(log[0], "Ferris was added to the waitlist");
+        assert_eq!(
+            log[2],
+            "Serving Wheat toast with peaches, exactly as ordered"
+        );
+        assert_eq!(log[3], "The kitchen cooked soup");
+    }
+}
@@ -0,0 +1,41 @@
+// Build script for the Unsafe Code chapter (ch10_unsafe)
+// Cargo runs this before compiling the crate. It compiles c/ffi_harness.c with the system
+// C compiler into a static library and tells rustc to link it, without needing the `cc` crate
+
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn main() {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("cargo sets OUT_DIR"));
+    let source = "c/ffi_harness.c";
+    let object = out_dir.join("ffi_harness.o");
+    let library = out_dir.join("libffi_harness.a");
+
+    // Respect the usual CC and AR variables, falling back to the system tools
+    let cc = env::var("CC").unwrap_or_else(|_| String::from("cc"));
+    let ar = env::var("AR").unwrap_or_else(|_| String::from("ar"));
+
+    run(Command::new(&cc)
+        .args(["-c", "-O2", "-fPIC", "-Wall", "-Wextra", "-o"])
+        .arg(&object)
+        .arg(source));
+    run(Command::new(&ar).arg("crs").arg(&library).arg(&object));
+
+    println!("cargo:rustc-link-search=native={}", out_dir.display());
+    println!("cargo:rustc-link-lib=static=ffi_harness");
+    println!("cargo:rerun-if-changed={}", source);
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=CC");
+    println!("cargo:rerun-if-env-changed=AR");
+}
+
+// Runs a build tool and stops the build with a readable message if it fails
+fn run(command: &mut Command) {
+    let status = command
+        .status()
+        .unwrap_or_else(|error| panic!("failed to run {:?}: {}", command, error));
+    if !status.success() {
+        panic!("{:?} exited with {}", command, status);
+    }
+}
@@ -0,0 +1,44 @@
+// A small C test harness for the Unsafe Code chapter (ch10_unsafe)
+// build.rs compiles it with the system C compiler and links it into the Rust program
+//
+// The harness calls back into Rust: rust_concepts_adler32 is defined in ch10_unsafe.rs
+// with #[no_mangle] and extern "C", so C sees it as an ordinary C function
+
+#include <stddef.h>
+#include <stdint.h>
+#include <string.h>
+
+// Implemented in Rust
+uint32_t rust_concepts_adler32(const uint8_t *data, size_t len);
+
+// Computes the checksum of a NUL-terminated string by calling the Rust function
+uint32_t harness_checksum(const char *text) {
+    return rust_concepts_adler32((const uint8_t *)text, strlen(text));
+}
+
+// Checks the Rust function against known Adler-32 values
+// Returns the number of failed checks, so 0 means every check passed
+int harness_self_test(void) {
+    struct {
+        const char *input;
+        uint32_t expected;
+    } cases[] = {
+        {"", 0x00000001},
+        {"a", 0x00620062},
+        {"abc", 0x024d0127},
+        {"Wikipedia", 0x11e60398},
+    };
+    int failures = 0;
+
+    for (size_t i = 0; i < sizeof(cases) / sizeof(cases[0]); i++) {
+        if (harness_checksum(cases[i].input) != cases[i].expected) {
+            failures++;
+        }
+    }
+
+    // A null pointer with a zero length is allowed by the Rust side's safety contract
+    if (rust_concepts_adler32(NULL, 0) != 1) {
+        failures++;
+    }
+    return failures;
+}
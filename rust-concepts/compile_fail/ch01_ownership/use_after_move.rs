@@ -0,0 +1,8 @@
+// error[E0382]: borrow of moved value: `s1`
+// `let s2 = s1;` moves the String into s2, so s1 no longer owns anything and can't be read
+fn main() {
+    let s1 = String::from("hello");
+    let s2 = s1;
+    println!("{}", s1);
+    println!("s2: {}", s2);
+}
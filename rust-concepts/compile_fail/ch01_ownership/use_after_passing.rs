@@ -0,0 +1,11 @@
+// error[E0382]: borrow of moved value: `s5`
+// Passing a String by value moves it into the function, so the caller can't use it afterwards
+fn takes_ownership(some_string: String) {
+    println!("{}", some_string);
+}
+
+fn main() {
+    let s5 = String::from("hello");
+    takes_ownership(s5);
+    println!("{}", s5);
+}
@@ -0,0 +1,11 @@
+// error[E0106]: missing lifetime specifier
+// The returned reference would point at a String that is dropped when the function returns,
+// and there is no parameter it could borrow from instead
+fn dangle() -> &String {
+    let s = String::from("hello");
+    &s
+}
+
+fn main() {
+    println!("Reference: {}", dangle());
+}
@@ -0,0 +1,9 @@
+// error[E0502]: cannot borrow `s3` as mutable because it is also borrowed as immutable
+// r1 is still used after r2 is created, so the shared and the mutable borrow overlap
+fn main() {
+    let mut s3 = String::from("hello");
+    let r1 = &s3;
+    let r2 = &mut s3;
+    r2.push_str(", world");
+    println!("r1: {}", r1);
+}
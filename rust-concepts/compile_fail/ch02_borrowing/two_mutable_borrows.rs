@@ -0,0 +1,9 @@
+// error[E0499]: cannot borrow `s` as mutable more than once at a time
+// Only one mutable reference to a value may be live at any point
+fn main() {
+    let mut s = String::from("hello");
+    let r1 = &mut s;
+    let r2 = &mut s;
+    r1.push('!');
+    r2.push('?');
+}
@@ -0,0 +1,16 @@
+// error[E0597]: `novel` does not live long enough
+// An ImportantExcerpt<'a> borrows from the novel, so it can't be used once the novel is gone
+struct ImportantExcerpt<'a> {
+    part: &'a str,
+}
+
+fn main() {
+    let excerpt;
+    {
+        let novel = String::from("Call me Ishmael. Some years ago...");
+        excerpt = ImportantExcerpt {
+            part: novel.split('.').next().unwrap(),
+        };
+    }
+    println!("First sentence: {}", excerpt.part);
+}
@@ -0,0 +1,13 @@
+// error[E0106]: missing lifetime specifier
+// With two reference parameters, elision can't tell which one the result borrows from
+fn longest(x: &str, y: &str) -> &str {
+    if x.len() > y.len() {
+        x
+    } else {
+        y
+    }
+}
+
+fn main() {
+    println!("Longest string: {}", longest("short", "longer string"));
+}
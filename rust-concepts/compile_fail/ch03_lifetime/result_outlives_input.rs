@@ -0,0 +1,19 @@
+// error[E0597]: `string2` does not live long enough
+// `longest` ties its result to both arguments, so the result can't be used after string2 is dropped
+fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {
+    if x.len() > y.len() {
+        x
+    } else {
+        y
+    }
+}
+
+fn main() {
+    let string1 = String::from("short");
+    let result;
+    {
+        let string2 = String::from("longer string");
+        result = longest(&string1, &string2);
+    }
+    println!("Longest string: {}", result);
+}
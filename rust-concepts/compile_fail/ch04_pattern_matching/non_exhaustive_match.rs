@@ -0,0 +1,18 @@
+// error[E0004]: non-exhaustive patterns: `Message::Write(_)` not covered
+// A match must handle every variant, so forgetting one arm is a compile error, not a runtime surprise
+#[allow(dead_code)]
+enum Message {
+    Quit,
+    Move { x: i32, y: i32 },
+    Write(String),
+    ChangeColor(i32, i32, i32),
+}
+
+fn main() {
+    let msg = Message::ChangeColor(0, 160, 255);
+    match msg {
+        Message::Quit => println!("Quit"),
+        Message::Move { x, y } => println!("Move to x: {}, y: {}", x, y),
+        Message::ChangeColor(r, g, b) => println!("Change color to r: {}, g: {}, b: {}", r, g, b),
+    }
+}
@@ -0,0 +1,7 @@
+// error[E0005]: refutable pattern in local binding
+// let needs a pattern that always matches, and Some(x) doesn't match None
+fn main() {
+    let maybe: Option<i32> = None;
+    let Some(x) = maybe;
+    println!("Got {}", x);
+}
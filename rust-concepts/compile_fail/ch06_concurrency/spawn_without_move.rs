@@ -0,0 +1,9 @@
+// error[E0373]: closure may outlive the current function, but it borrows `names`, which is owned by the current function
+// Without `move` the closure only borrows names, but the thread may keep running after main's frame is gone
+use std::thread;
+
+fn main() {
+    let names = [String::from("Ferris"), String::from("Corro")];
+    let handle = thread::spawn(|| names.join(" & "));
+    println!("Greeting from a thread: hello, {}!", handle.join().unwrap());
+}
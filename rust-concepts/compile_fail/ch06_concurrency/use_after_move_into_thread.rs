@@ -0,0 +1,10 @@
+// error[E0382]: borrow of moved value: `names`
+// The `move` closure took ownership of names, so the spawning thread can't read it any more
+use std::thread;
+
+fn main() {
+    let names = [String::from("Ferris"), String::from("Corro")];
+    let handle = thread::spawn(move || names.join(" & "));
+    println!("Greeting from a thread: hello, {}!", handle.join().unwrap());
+    println!("{:?}", names);
+}
@@ -0,0 +1,6 @@
+// error[E0277]: the `?` operator can only be used in a function that returns `Result` or `Option` (or another type that implements `FromResidual`)
+// `?` returns the error to the caller, so the function has to return a Result that can hold it
+fn main() {
+    let port: u16 = "3000".parse()?;
+    println!("port = {}", port);
+}
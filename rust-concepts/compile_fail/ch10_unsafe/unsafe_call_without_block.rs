@@ -0,0 +1,13 @@
+// error[E0133]: call to unsafe function `get_unchecked` is unsafe and requires unsafe function or block
+// The caller of an unsafe fn promises to uphold its contract, and it has to say so with `unsafe { }`
+/// # Safety
+///
+/// `index` must be less than `slice.len()`
+unsafe fn get_unchecked(slice: &[i32], index: usize) -> i32 {
+    unsafe { *slice.as_ptr().add(index) }
+}
+
+fn main() {
+    let scores = [90, 85, 77];
+    println!("{}", get_unchecked(&scores, 1));
+}
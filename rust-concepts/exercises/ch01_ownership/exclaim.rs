@@ -0,0 +1,9 @@
+// Exercise: exclaim (chapter 01, Ownership)
+// Check your answer with `cargo run -- exercise ch01/exclaim`
+//
+// `exclaim` takes ownership of a String, adds a "!" to the end and gives the String back.
+// The caller's String is moved in and moved out again, so there is no need to clone it.
+
+pub fn exclaim(s: String) -> String {
+    todo!("append '!' to s and return it")
+}
@@ -0,0 +1,10 @@
+// Exercise: split_by_length (chapter 01, Ownership)
+// Check your answer with `cargo run -- exercise ch01/split_by_length`
+//
+// `split_by_length` takes ownership of a list of words and splits it into two lists:
+// the words with at most `max` characters, and the longer ones. Both lists keep the
+// original order. Every String should be moved into one of the two lists, not cloned.
+
+pub fn split_by_length(words: Vec<String>, max: usize) -> (Vec<String>, Vec<String>) {
+    todo!("move each word into the short or the long list")
+}
@@ -0,0 +1,10 @@
+// Exercise: capitalize_all (chapter 02, Borrowing)
+// Check your answer with `cargo run -- exercise ch02/capitalize_all`
+//
+// `capitalize_all` gets a mutable borrow of a list of words and changes them in place,
+// so that each word starts with an upper-case letter. It returns how many words it changed.
+// Words that are empty or already capitalized are left as they are.
+
+pub fn capitalize_all(words: &mut [String]) -> usize {
+    todo!("capitalize each word in place and count the changes")
+}
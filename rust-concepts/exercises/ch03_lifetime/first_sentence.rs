@@ -0,0 +1,20 @@
+// Exercise: first_sentence (chapter 03, Lifetimes)
+// Check your answer with `cargo run -- exercise ch03/first_sentence`
+//
+// An `Excerpt` holds a slice of some longer text, so it can't outlive that text.
+// `first_sentence` returns an excerpt with everything before the first '.', or the whole
+// text if there is none. `Excerpt::words` counts the whitespace-separated words in it.
+
+pub struct Excerpt<'a> {
+    pub part: &'a str,
+}
+
+impl<'a> Excerpt<'a> {
+    pub fn words(&self) -> usize {
+        todo!("count the words of the excerpt")
+    }
+}
+
+pub fn first_sentence(text: &str) -> Excerpt<'_> {
+    todo!("build an Excerpt that borrows the first sentence of text")
+}
@@ -0,0 +1,10 @@
+// Exercise: longest_word (chapter 03, Lifetimes)
+// Check your answer with `cargo run -- exercise ch03/longest_word`
+//
+// `longest_word` returns the longest whitespace-separated word of `text`. If several words
+// are equally long, the first one wins; an empty text gives an empty string.
+// The result borrows from `text` (lifetime elision ties them together), so don't allocate.
+
+pub fn longest_word(text: &str) -> &str {
+    todo!("return a slice of text holding its longest word")
+}
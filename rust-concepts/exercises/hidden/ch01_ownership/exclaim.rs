@@ -0,0 +1,22 @@
+#[cfg(test)]
+mod hidden_tests {
+    use super::*;
+
+    #[test]
+    fn adds_an_exclamation_mark() {
+        assert_eq!(exclaim(String::from("hello")), "hello!");
+    }
+
+    #[test]
+    fn works_on_an_empty_string() {
+        assert_eq!(exclaim(String::new()), "!");
+    }
+
+    #[test]
+    fn reuses_the_same_allocation() {
+        // Moving a String moves the pointer to its buffer, not the buffer itself
+        let s = String::with_capacity(16) + "wow";
+        let buffer = s.as_ptr();
+        assert_eq!(exclaim(s).as_ptr(), buffer);
+    }
+}
@@ -0,0 +1,31 @@
+#[cfg(test)]
+mod hidden_tests {
+    use super::*;
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn splits_and_keeps_the_order() {
+        let (short, long) = split_by_length(words(&["a", "crab", "is", "crustacean"]), 2);
+        assert_eq!(short, ["a", "is"]);
+        assert_eq!(long, ["crab", "crustacean"]);
+    }
+
+    #[test]
+    fn the_limit_is_inclusive() {
+        let (short, long) = split_by_length(words(&["four", "fives"]), 4);
+        assert_eq!(short, ["four"]);
+        assert_eq!(long, ["fives"]);
+    }
+
+    #[test]
+    fn moves_the_strings_instead_of_cloning_them() {
+        let list = words(&["ferris", "go"]);
+        let buffers: Vec<*const u8> = list.iter().map(|w| w.as_ptr()).collect();
+        let (short, long) = split_by_length(list, 3);
+        assert_eq!(short[0].as_ptr(), buffers[1]);
+        assert_eq!(long[0].as_ptr(), buffers[0]);
+    }
+}
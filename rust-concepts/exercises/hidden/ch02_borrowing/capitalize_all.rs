@@ -0,0 +1,26 @@
+#[cfg(test)]
+mod hidden_tests {
+    use super::*;
+
+    #[test]
+    fn capitalizes_in_place() {
+        let mut words = vec![String::from("ferris"), String::from("crab")];
+        assert_eq!(capitalize_all(&mut words), 2);
+        assert_eq!(words, ["Ferris", "Crab"]);
+    }
+
+    #[test]
+    fn leaves_capitalized_and_empty_words_alone() {
+        let mut words = vec![String::from("Rust"), String::new(), String::from("ok")];
+        assert_eq!(capitalize_all(&mut words), 1);
+        assert_eq!(words, ["Rust", "", "Ok"]);
+    }
+
+    #[test]
+    fn works_on_part_of_a_vector() {
+        // A mutable slice can borrow just some of the elements
+        let mut words = vec![String::from("a"), String::from("b"), String::from("c")];
+        assert_eq!(capitalize_all(&mut words[1..]), 2);
+        assert_eq!(words, ["a", "B", "C"]);
+    }
+}
@@ -0,0 +1,22 @@
+#[cfg(test)]
+mod hidden_tests {
+    use super::*;
+
+    #[test]
+    fn counts_lower_and_upper_case() {
+        assert_eq!(count_vowels("Ferris the crAb"), 4);
+    }
+
+    #[test]
+    fn no_vowels() {
+        assert_eq!(count_vowels("rhythm"), 0);
+        assert_eq!(count_vowels(""), 0);
+    }
+
+    #[test]
+    fn the_caller_keeps_the_string() {
+        let text = String::from("borrow");
+        assert_eq!(count_vowels(&text), 2);
+        assert_eq!(text, "borrow");
+    }
+}
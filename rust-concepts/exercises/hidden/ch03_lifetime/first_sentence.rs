@@ -0,0 +1,25 @@
+#[cfg(test)]
+mod hidden_tests {
+    use super::*;
+
+    #[test]
+    fn stops_at_the_first_period() {
+        let excerpt = first_sentence("Call me Ishmael. Some years ago...");
+        assert_eq!(excerpt.part, "Call me Ishmael");
+        assert_eq!(excerpt.words(), 3);
+    }
+
+    #[test]
+    fn without_a_period_the_whole_text_is_used() {
+        let excerpt = first_sentence("no period here");
+        assert_eq!(excerpt.part, "no period here");
+        assert_eq!(excerpt.words(), 3);
+    }
+
+    #[test]
+    fn the_excerpt_borrows_the_text() {
+        let novel = String::from("It was a dark and stormy night. Suddenly...");
+        let excerpt = first_sentence(&novel);
+        assert_eq!(excerpt.part.as_ptr(), novel.as_ptr());
+    }
+}
@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod hidden_tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_longest_word() {
+        assert_eq!(longest_word("the borrow checker is strict"), "checker");
+    }
+
+    #[test]
+    fn the_first_of_equal_words_wins() {
+        assert_eq!(longest_word("ab cd ef"), "ab");
+    }
+
+    #[test]
+    fn empty_and_blank_text() {
+        assert_eq!(longest_word(""), "");
+        assert_eq!(longest_word("   "), "");
+    }
+
+    #[test]
+    fn returns_a_slice_of_the_input() {
+        let text = String::from("lifetimes tie outputs to inputs");
+        let word = longest_word(&text);
+        assert_eq!(word, "lifetimes");
+        assert_eq!(word.as_ptr(), text.as_ptr());
+    }
+}
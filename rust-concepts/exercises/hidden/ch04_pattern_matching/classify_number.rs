@@ -0,0 +1,24 @@
+#[cfg(test)]
+mod hidden_tests {
+    use super::*;
+
+    #[test]
+    fn negative_and_zero() {
+        assert_eq!(classify_number(-1), "negative");
+        assert_eq!(classify_number(i64::MIN), "negative");
+        assert_eq!(classify_number(0), "zero");
+    }
+
+    #[test]
+    fn digits() {
+        assert_eq!(classify_number(1), "digit");
+        assert_eq!(classify_number(9), "digit");
+    }
+
+    #[test]
+    fn larger_numbers_are_even_or_odd() {
+        assert_eq!(classify_number(10), "even");
+        assert_eq!(classify_number(11), "odd");
+        assert_eq!(classify_number(i64::MAX), "odd");
+    }
+}
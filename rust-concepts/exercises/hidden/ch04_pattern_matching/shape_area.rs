@@ -0,0 +1,26 @@
+#[cfg(test)]
+mod hidden_tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn circle_and_rectangle() {
+        assert!(close(area(&Shape::Circle { radius: 1.0 }), std::f64::consts::PI));
+        assert!(close(area(&Shape::Rectangle { width: 2.0, height: 3.0 }), 6.0));
+    }
+
+    #[test]
+    fn triangle_from_its_sides() {
+        assert!(close(area(&Shape::Triangle(3.0, 4.0, 5.0)), 6.0));
+    }
+
+    #[test]
+    fn scaling_multiplies_the_area_by_the_square_of_the_factor() {
+        let square = Shape::Rectangle { width: 1.0, height: 1.0 };
+        let scaled = Shape::Scaled { factor: 3.0, shape: Box::new(square) };
+        assert!(close(area(&scaled), 9.0));
+    }
+}
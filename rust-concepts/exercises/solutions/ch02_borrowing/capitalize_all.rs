@@ -0,0 +1,20 @@
+// Exercise: capitalize_all (chapter 02, Borrowing)
+// Check your answer with `cargo run -- exercise ch02/capitalize_all`
+//
+// `capitalize_all` gets a mutable borrow of a list of words and changes them in place,
+// so that each word starts with an upper-case letter. It returns how many words it changed.
+// Words that are empty or already capitalized are left as they are.
+
+pub fn capitalize_all(words: &mut [String]) -> usize {
+    let mut changed = 0;
+    for word in words.iter_mut() {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            if !first.is_uppercase() {
+                *word = first.to_uppercase().chain(chars).collect();
+                changed += 1;
+            }
+        }
+    }
+    changed
+}
@@ -0,0 +1,11 @@
+// Exercise: count_vowels (chapter 02, Borrowing)
+// Check your answer with `cargo run -- exercise ch02/count_vowels`
+//
+// `count_vowels` only needs to read the text, so it borrows it instead of taking ownership.
+// Count the letters a, e, i, o and u, in upper or lower case.
+
+pub fn count_vowels(text: &str) -> usize {
+    text.chars()
+        .filter(|c| matches!(c.to_ascii_lowercase(), 'a' | 'e' | 'i' | 'o' | 'u'))
+        .count()
+}
@@ -0,0 +1,16 @@
+// Exercise: longest_word (chapter 03, Lifetimes)
+// Check your answer with `cargo run -- exercise ch03/longest_word`
+//
+// `longest_word` returns the longest whitespace-separated word of `text`. If several words
+// are equally long, the first one wins; an empty text gives an empty string.
+// The result borrows from `text` (lifetime elision ties them together), so don't allocate.
+
+pub fn longest_word(text: &str) -> &str {
+    let mut longest = "";
+    for word in text.split_whitespace() {
+        if word.len() > longest.len() {
+            longest = word;
+        }
+    }
+    longest
+}
@@ -0,0 +1,15 @@
+// Exercise: classify_number (chapter 04, Pattern Matching)
+// Check your answer with `cargo run -- exercise ch04/classify_number`
+//
+// `classify_number` describes an integer with a single `match`:
+// "negative" below zero, "zero", "digit" from 1 to 9, "even" or "odd" for anything larger.
+
+pub fn classify_number(n: i64) -> &'static str {
+    match n {
+        i64::MIN..=-1 => "negative",
+        0 => "zero",
+        1..=9 => "digit",
+        n if n % 2 == 0 => "even",
+        _ => "odd",
+    }
+}
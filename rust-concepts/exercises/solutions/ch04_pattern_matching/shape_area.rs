@@ -0,0 +1,25 @@
+// Exercise: shape_area (chapter 04, Pattern Matching)
+// Check your answer with `cargo run -- exercise ch04/shape_area`
+//
+// `area` destructures each variant of `Shape` to compute its area.
+// A triangle is given by its three side lengths (use Heron's formula), and a
+// `Scaled` shape is the inner shape with every side multiplied by `factor`.
+
+pub enum Shape {
+    Circle { radius: f64 },
+    Rectangle { width: f64, height: f64 },
+    Triangle(f64, f64, f64),
+    Scaled { factor: f64, shape: Box<Shape> },
+}
+
+pub fn area(shape: &Shape) -> f64 {
+    match shape {
+        Shape::Circle { radius } => std::f64::consts::PI * radius * radius,
+        Shape::Rectangle { width, height } => width * height,
+        Shape::Triangle(a, b, c) => {
+            let s = (a + b + c) / 2.0;
+            (s * (s - a) * (s - b) * (s - c)).sqrt()
+        }
+        Shape::Scaled { factor, shape } => factor * factor * area(shape),
+    }
+}
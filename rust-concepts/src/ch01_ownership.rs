@@ -1,83 +1,224 @@
-// This function demonstrates various aspects of Rust's ownership system
-pub fn demo() {
-    println!("Demonstrating Rust ownership!");
+use crate::chapter::{Chapter, Example};
+use std::io::{self, Write};
 
-    // Example 1: Move semantics
-    // In Rust, when we assign a value to another variable, the ownership is moved
+/// Registers this chapter with the CLI
+pub struct Ownership;
+
+impl Chapter for Ownership {
+    fn id(&self) -> &'static str {
+        "01"
+    }
+
+    fn slug(&self) -> &'static str {
+        "ownership"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ch01"]
+    }
+
+    fn title(&self) -> &'static str {
+        "Ownership"
+    }
+
+    fn summary(&self) -> &'static str {
+        "Move semantics, Clone, Copy and ownership across function calls"
+    }
+
+    fn source(&self) -> &'static str {
+        include_str!("ch01_ownership.rs")
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        demo(out)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+/// This function demonstrates various aspects of Rust's ownership system
+///
+/// ```
+/// let mut output = Vec::new();
+/// rust_concepts::ch01_ownership::demo(&mut output).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.starts_with("Demonstrating Rust ownership!"));
+/// ```
+pub fn demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Demonstrating Rust ownership!")?;
+
+    for example in EXAMPLES {
+        (example.run)(out)?;
+    }
+
+    Ok(())
+}
+
+/// The examples of this chapter, in the order `demo` runs them
+pub static EXAMPLES: &[Example] = &[
+    Example {
+        id: 1,
+        title: "Move semantics",
+        explanation: "In Rust, when we assign a value to another variable, the ownership is moved",
+        run: move_semantics,
+    },
+    Example {
+        id: 2,
+        title: "Clone",
+        explanation: "If we want to create a deep copy of the data, we can use the clone method",
+        run: clone,
+    },
+    Example {
+        id: 3,
+        title: "Copy for stack-only data",
+        explanation:
+            "For simple types that have a known size at compile time, the Copy trait is implemented",
+        run: copy_types,
+    },
+    Example {
+        id: 4,
+        title: "Ownership and functions",
+        explanation:
+            "When we pass a value to a function, the ownership is transferred to that function",
+        run: ownership_and_functions,
+    },
+    Example {
+        id: 5,
+        title: "Return values and scope",
+        explanation: "Functions can also transfer ownership of their return values",
+        run: return_values_and_scope,
+    },
+];
+
+// Example 1: Move semantics
+// In Rust, when we assign a value to another variable, the ownership is moved
+fn move_semantics(out: &mut dyn Write) -> io::Result<()> {
     let s1 = String::from("hello");
     let s2 = s1;
-    // println!("{}", s1); // This would cause a compile error because s1's ownership has been moved to s2
+    // writeln!(out, "{}", s1)?; // This would cause a compile error because s1's ownership has been moved to s2
     // The line above is commented out because it would cause a compile-time error.
     // After the move, s1 is no longer valid and attempting to use it would result in a "use of moved value" error.
-    println!("s2: {}", s2);
+    // compile_fail/ch01_ownership/use_after_move.rs checks that rustc really rejects it (E0382).
+    writeln!(out, "s2: {}", s2)?;
     // Result: s2: hello
 
-    // Example 2: Clone
-    // If we want to create a deep copy of the data, we can use the clone method
+    Ok(())
+}
+
+// Example 2: Clone
+// If we want to create a deep copy of the data, we can use the clone method
+fn clone(out: &mut dyn Write) -> io::Result<()> {
     let s3 = String::from("world");
     let s4 = s3.clone();
-    println!("s3: {}, s4: {}", s3, s4);
+    writeln!(out, "s3: {}, s4: {}", s3, s4)?;
     // Result: s3: world, s4: world
     // Both s3 and s4 are valid here because we created a new copy of the data
     // The clone method creates a new allocation in memory with the same contents,
     // allowing both variables to own their own independent data.
 
-    // Example 3: Copy for stack-only data
-    // For simple types that have a known size at compile time, the Copy trait is implemented
+    Ok(())
+}
+
+// Example 3: Copy for stack-only data
+// For simple types that have a known size at compile time, the Copy trait is implemented
+fn copy_types(out: &mut dyn Write) -> io::Result<()> {
     // This means that the value is copied instead of moved when assigned or passed to a function
     let x = 5;
     let y = x;
-    println!("x: {}, y: {}", x, y);
+    writeln!(out, "x: {}, y: {}", x, y)?;
     // Result: x: 5, y: 5
     // Both x and y are valid here because integers implement the Copy trait
     // Types that implement Copy are duplicated rather than moved, so the original variable remains valid.
 
-    // Example 4: Ownership and functions
-    // When we pass a value to a function, the ownership is transferred to that function
+    Ok(())
+}
+
+// Example 4: Ownership and functions
+// When we pass a value to a function, the ownership is transferred to that function
+fn ownership_and_functions(out: &mut dyn Write) -> io::Result<()> {
     let s5 = String::from("hello");
-    takes_ownership(s5);
+    takes_ownership(s5, out)?;
     // Result: hello
-    // println!("{}", s5); // This would cause a compile error because s5's ownership has been moved to the function
+    // writeln!(out, "{}", s5)?; // This would cause a compile error because s5's ownership has been moved to the function
     // After calling takes_ownership, s5 is no longer valid in this scope.
     // The ownership of the String has been transferred to the function.
+    // compile_fail/ch01_ownership/use_after_passing.rs checks that rustc really rejects it (E0382).
 
     // For types that implement Copy, the value is copied instead of moved
     let x = 5;
-    makes_copy(x);
+    makes_copy(x, out)?;
     // Result: 5
-    println!("x is still accessible: {}", x);
+    writeln!(out, "x is still accessible: {}", x)?;
     // Result: x is still accessible: 5
     // Since i32 implements Copy, x remains valid after being passed to makes_copy.
 
-    // Example 5: Return values and scope
-    // Functions can also transfer ownership of their return values
+    Ok(())
+}
+
+// Example 5: Return values and scope
+// Functions can also transfer ownership of their return values
+fn return_values_and_scope(out: &mut dyn Write) -> io::Result<()> {
     let s6 = gives_ownership();
-    println!("s6: {}", s6);
+    writeln!(out, "s6: {}", s6)?;
     // Result: s6: yours
     // s6 now owns the String returned by gives_ownership
 
     // We can also take ownership of a value, do something with it, and then return ownership
     let s7 = String::from("hello");
     let s8 = takes_and_gives_back(s7);
-    println!("s8: {}", s8);
+    writeln!(out, "s8: {}", s8)?;
     // Result: s8: hello
     // s7 is no longer valid here, but s8 is
     // The ownership of the String has been transferred from s7 to the function, then back to s8.
+
+    Ok(())
 }
 
-// This function takes ownership of the passed string
-fn takes_ownership(some_string: String) {
-    println!("{}", some_string);
+/// This function takes ownership of the passed string
+///
+/// ```
+/// use rust_concepts::ch01_ownership::takes_ownership;
+///
+/// let s = String::from("hello");
+/// let mut output = Vec::new();
+/// takes_ownership(s, &mut output).unwrap();
+/// // s was moved into the function, so it can't be used here anymore
+/// assert_eq!(output, b"hello\n");
+/// ```
+pub fn takes_ownership(some_string: String, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "{}", some_string)
 } // Here, some_string goes out of scope and `drop` is called, freeing the memory
   // The String's memory is automatically freed when some_string goes out of scope.
 
-// This function makes a copy of the passed integer
-fn makes_copy(some_integer: i32) {
-    println!("{}", some_integer);
+/// This function makes a copy of the passed integer
+///
+/// ```
+/// use rust_concepts::ch01_ownership::makes_copy;
+///
+/// let x = 5;
+/// let mut output = Vec::new();
+/// makes_copy(x, &mut output).unwrap();
+/// // i32 is Copy, so x is still usable
+/// assert_eq!(x, 5);
+/// assert_eq!(output, b"5\n");
+/// ```
+pub fn makes_copy(some_integer: i32, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "{}", some_integer)
 } // Here, some_integer goes out of scope, but nothing special happens because it's a Copy type
   // No special cleanup is needed for Copy types when they go out of scope.
 
-// This function will move its return value into the function that calls it
+/// This function will move its return value into the function that calls it
+/// The explicit binding is kept on purpose to show the value moving out of the function
+///
+/// ```
+/// use rust_concepts::ch01_ownership::gives_ownership;
+///
+/// let s = gives_ownership();
+/// assert_eq!(s, "yours");
+/// ```
+#[allow(clippy::let_and_return)]
 pub fn gives_ownership() -> String {
     let some_string = String::from("yours");
     some_string // This value is moved out to the calling function
@@ -85,7 +226,15 @@ pub fn gives_ownership() -> String {
                 // No need for an explicit return statement; the last expression is implicitly returned.
 }
 
-// This function takes a String and returns one
+/// This function takes a String and returns one
+///
+/// ```
+/// use rust_concepts::ch01_ownership::takes_and_gives_back;
+///
+/// let s1 = String::from("hello");
+/// let s2 = takes_and_gives_back(s1);
+/// assert_eq!(s2, "hello");
+/// ```
 pub fn takes_and_gives_back(a_string: String) -> String {
     a_string // This value is returned and moves out to the calling function
              // The function takes ownership of a_string when it's called,
@@ -1,89 +1,250 @@
-// This function demonstrates various aspects of Rust's borrowing system
-pub fn demo() {
-    println!("Demonstrating Rust borrowing!");
+use crate::chapter::{Chapter, Example};
+use std::io::{self, Write};
 
-    // Example 1: Borrowing with references
-    // Here we create a String and then borrow it immutably to calculate its length
+/// Registers this chapter with the CLI
+pub struct Borrowing;
+
+impl Chapter for Borrowing {
+    fn id(&self) -> &'static str {
+        "02"
+    }
+
+    fn slug(&self) -> &'static str {
+        "borrowing"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ch02"]
+    }
+
+    fn title(&self) -> &'static str {
+        "Borrowing"
+    }
+
+    fn summary(&self) -> &'static str {
+        "Shared and mutable references and the rules that prevent data races"
+    }
+
+    fn source(&self) -> &'static str {
+        include_str!("ch02_borrowing.rs")
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        demo(out)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+/// This function demonstrates various aspects of Rust's borrowing system
+///
+/// ```
+/// let mut output = Vec::new();
+/// rust_concepts::ch02_borrowing::demo(&mut output).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.starts_with("Demonstrating Rust borrowing!"));
+/// ```
+pub fn demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Demonstrating Rust borrowing!")?;
+
+    for example in EXAMPLES {
+        (example.run)(out)?;
+    }
+
+    writeln!(
+        out,
+        "🕺💃 Now, let's dance! The borrowing lesson is complete! 🎉🎊"
+    )?;
+
+    Ok(())
+}
+
+/// The examples of this chapter, in the order `demo` runs them
+pub static EXAMPLES: &[Example] = &[
+    Example {
+        id: 1,
+        title: "Borrowing with references",
+        explanation: "Here we create a String and then borrow it immutably to calculate its length",
+        run: references,
+    },
+    Example {
+        id: 2,
+        title: "Mutable references",
+        explanation: "We create a mutable String and then borrow it mutably to change its contents",
+        run: mutable_references,
+    },
+    Example {
+        id: 3,
+        title: "Multiple immutable references",
+        explanation: "Rust allows multiple immutable references to the same data",
+        run: multiple_immutable_references,
+    },
+    Example {
+        id: 4,
+        title: "Mutable and immutable references (not simultaneously)",
+        explanation: "Rust enforces the rule that you can have either one mutable reference or any number of immutable references, but not both at the same time",
+        run: mutable_and_immutable_references,
+    },
+    Example {
+        id: 5,
+        title: "Preventing dangling references",
+        explanation: "Rust's borrow checker ensures we don't create dangling references",
+        run: dangling_references,
+    },
+    Example {
+        id: 6,
+        title: "Lifetime annotations",
+        explanation: "Sometimes Rust needs help understanding how long references should live",
+        run: lifetime_annotations,
+    },
+    Example {
+        id: 7,
+        title: "Borrowing in loops",
+        explanation: "Iterating over `&mut vec` borrows each element mutably, so the loop can change it in place",
+        run: borrowing_in_loops,
+    },
+    Example {
+        id: 8,
+        title: "Self-referential structs (advanced topic)",
+        explanation: "A struct can't safely hold a plain reference into its own data, so this topic needs tools beyond basic borrowing",
+        run: self_referential_structs,
+    },
+];
+
+// Example 1: Borrowing with references
+// Here we create a String and then borrow it immutably to calculate its length
+fn references(out: &mut dyn Write) -> io::Result<()> {
     let s1 = String::from("hello");
     let len = calculate_length(&s1);
-    println!("The length of '{}' is {}.", s1, len);
+    writeln!(out, "The length of '{}' is {}.", s1, len)?;
     // Result: The length of 'hello' is 5.
     // Note that s1 is still valid here because we only borrowed it immutably
     // This demonstrates how immutable borrowing allows us to use data without taking ownership
 
-    // Example 2: Mutable references
-    // We create a mutable String and then borrow it mutably to change its contents
+    Ok(())
+}
+
+// Example 2: Mutable references
+// We create a mutable String and then borrow it mutably to change its contents
+fn mutable_references(out: &mut dyn Write) -> io::Result<()> {
     let mut s = String::from("hello");
     change(&mut s);
-    println!("After change: {}", s);
+    writeln!(out, "After change: {}", s)?;
     // Result: After change: hello, world
     // The change is reflected in the original variable because we used a mutable reference
     // This shows how mutable borrowing allows us to modify data in-place
 
-    // Example 3: Multiple immutable references
-    // Rust allows multiple immutable references to the same data
+    Ok(())
+}
+
+// Example 3: Multiple immutable references
+// Rust allows multiple immutable references to the same data
+fn multiple_immutable_references(out: &mut dyn Write) -> io::Result<()> {
     let s2 = String::from("hello");
     let r1 = &s2;
     let r2 = &s2;
-    println!("r1: {}, r2: {}", r1, r2);
+    writeln!(out, "r1: {}, r2: {}", r1, r2)?;
     // Result: r1: hello, r2: hello
     // Both r1 and r2 are valid here because they're immutable references
     // This demonstrates Rust's ability to have multiple readers of the same data
 
-    // Example 4: Mutable and immutable references (not simultaneously)
-    // Rust enforces the rule that you can have either one mutable reference
-    // or any number of immutable references, but not both at the same time
+    Ok(())
+}
+
+// Example 4: Mutable and immutable references (not simultaneously)
+// Rust enforces the rule that you can have either one mutable reference or any number of immutable references, but not both at the same time
+fn mutable_and_immutable_references(out: &mut dyn Write) -> io::Result<()> {
     let mut s3 = String::from("hello");
     {
         let r1 = &s3; // immutable borrow
-        println!("r1: {}", r1);
+        writeln!(out, "r1: {}", r1)?;
         // Result: r1: hello
     } // r1 goes out of scope here, so we can make a new reference
     let r2 = &mut s3; // mutable borrow
-    println!("r2: {}", r2);
+    writeln!(out, "r2: {}", r2)?;
     // Result: r2: hello
     // This is valid because the immutable borrow (r1) and the mutable borrow (r2) don't overlap
     // This example shows how Rust prevents data races by enforcing strict borrowing rules
 
-    // Example 5: Preventing dangling references
-    // Rust's borrow checker ensures we don't create dangling references
+    Ok(())
+}
+
+// Example 5: Preventing dangling references
+// Rust's borrow checker ensures we don't create dangling references
+fn dangling_references(out: &mut dyn Write) -> io::Result<()> {
     let reference_to_nothing = no_dangle();
-    println!("Reference: {}", reference_to_nothing);
+    writeln!(out, "Reference: {}", reference_to_nothing)?;
     // Result: Reference: hello
     // This function returns a String, not a reference, avoiding a potential dangling reference
     // It demonstrates how Rust's ownership system prevents common memory safety issues
 
-    // Example 6: Lifetime annotations
-    // Sometimes Rust needs help understanding how long references should live
+    Ok(())
+}
+
+// Example 6: Lifetime annotations
+// Sometimes Rust needs help understanding how long references should live
+fn lifetime_annotations(out: &mut dyn Write) -> io::Result<()> {
     let x = String::from("5");
     let y = String::from("10");
     let result = longest(&x, &y);
-    println!("Longest string: {}", result);
+    writeln!(out, "Longest string: {}", result)?;
     // Result: Longest string: 10
     // This example introduces lifetime annotations, which help the compiler understand
     // how long references should be valid
 
-    // Example 7: Borrowing in loops
+    Ok(())
+}
+
+// Example 7: Borrowing in loops
+// Iterating over `&mut vec` borrows each element mutably, so the loop can change it in place
+fn borrowing_in_loops(out: &mut dyn Write) -> io::Result<()> {
     let mut vec = vec![1, 2, 3, 4, 5];
     for i in &mut vec {
         *i *= 2;
     }
-    println!("Doubled vector: {:?}", vec);
+    writeln!(out, "Doubled vector: {:?}", vec)?;
     // Result: Doubled vector: [2, 4, 6, 8, 10]
     // This demonstrates how we can borrow and modify elements in a collection
     // The `&mut` before `vec` creates an iterator that yields mutable references
 
-    // Example 8: Self-referential structs (advanced topic)
-    println!("Self-referential structs are an advanced topic in Rust.");
-    println!("They often require special handling or crates like 'ouroboros'.");
+    Ok(())
+}
+
+// Example 8: Self-referential structs (advanced topic)
+// A struct can't safely hold a plain reference into its own data, so this topic needs tools beyond basic borrowing
+fn self_referential_structs(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "Self-referential structs are an advanced topic in Rust."
+    )?;
+    writeln!(
+        out,
+        "They often require special handling or crates like 'ouroboros'."
+    )?;
     // Self-referential structs are structures that contain references to their own fields
     // They are challenging in Rust due to the borrow checker's rules and often require
     // advanced techniques or external crates to implement safely
+    // Result:
+    // Self-referential structs are an advanced topic in Rust.
+    // They often require special handling or crates like 'ouroboros'.
 
-    println!("🕺💃 Now, let's dance! The borrowing lesson is complete! 🎉🎊");
+    Ok(())
 }
 
-// This function borrows a String immutably and returns its length
+/// This function borrows a String immutably and returns its length
+/// It takes &String rather than &str on purpose, to make the borrow of the String explicit
+///
+/// ```
+/// use rust_concepts::ch02_borrowing::calculate_length;
+///
+/// let s = String::from("hello");
+/// assert_eq!(calculate_length(&s), 5);
+/// // Only a reference was passed, so s still owns its String
+/// assert_eq!(s, "hello");
+/// ```
+#[allow(clippy::ptr_arg)]
 pub fn calculate_length(s: &String) -> usize {
     // s is a reference to a String
     s.len() // Return the length of the String
@@ -92,7 +253,15 @@ pub fn calculate_length(s: &String) -> usize {
             // By using an immutable reference, we can read the String's length without moving it
 }
 
-// This function takes a mutable reference to a String and modifies it
+/// This function takes a mutable reference to a String and modifies it
+///
+/// ```
+/// use rust_concepts::ch02_borrowing::change;
+///
+/// let mut s = String::from("hello");
+/// change(&mut s);
+/// assert_eq!(s, "hello, world");
+/// ```
 pub fn change(some_string: &mut String) {
     some_string.push_str(", world");
     // We can modify the String because we have a mutable reference to it
@@ -100,7 +269,14 @@ pub fn change(some_string: &mut String) {
     // Without taking ownership of the String, we can still modify its contents
 }
 
-// This function demonstrates how to avoid returning a dangling reference
+/// This function demonstrates how to avoid returning a dangling reference
+///
+/// ```
+/// use rust_concepts::ch02_borrowing::no_dangle;
+///
+/// assert_eq!(no_dangle(), "hello");
+/// ```
+#[allow(clippy::let_and_return)]
 pub fn no_dangle() -> String {
     let s = String::from("hello");
     s // We return s directly, transferring ownership to the caller
@@ -110,7 +286,15 @@ pub fn no_dangle() -> String {
       // By returning the String itself, we ensure that the caller gets a valid, owned value
 }
 
-// This function demonstrates lifetime annotations
+/// This function demonstrates lifetime annotations
+///
+/// ```
+/// use rust_concepts::ch02_borrowing::longest;
+///
+/// let short = String::from("abc");
+/// let long = String::from("abcd");
+/// assert_eq!(longest(&short, &long), "abcd");
+/// ```
 pub fn longest<'a>(x: &'a String, y: &'a String) -> &'a String {
     if x.len() > y.len() {
         x
@@ -1,89 +1,232 @@
-// This function demonstrates various aspects of Rust's lifetime system
-pub fn demo() {
-    println!("Demonstrating Rust lifetimes!");
+use crate::chapter::{Chapter, Example};
+use std::io::{self, Write};
 
-    // Example 1: Basic lifetime annotations
-    // Here we create two strings and pass references to them to the `longest` function
+/// Registers this chapter with the CLI
+pub struct Lifetimes;
+
+impl Chapter for Lifetimes {
+    fn id(&self) -> &'static str {
+        "03"
+    }
+
+    fn slug(&self) -> &'static str {
+        "lifetime"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ch03"]
+    }
+
+    fn title(&self) -> &'static str {
+        "Lifetimes"
+    }
+
+    fn summary(&self) -> &'static str {
+        "Lifetime annotations, elision rules and lifetimes in structs and traits"
+    }
+
+    fn source(&self) -> &'static str {
+        include_str!("ch03_lifetime.rs")
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        demo(out)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+/// This function demonstrates various aspects of Rust's lifetime system
+///
+/// ```
+/// let mut output = Vec::new();
+/// rust_concepts::ch03_lifetime::demo(&mut output).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.starts_with("Demonstrating Rust lifetimes!"));
+/// ```
+pub fn demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Demonstrating Rust lifetimes!")?;
+
+    for example in EXAMPLES {
+        (example.run)(out)?;
+    }
+
+    writeln!(out, "🕰️ Time's up! The lifetime lesson is complete! ⏳🎉")?;
+
+    Ok(())
+}
+
+/// The examples of this chapter, in the order `demo` runs them
+pub static EXAMPLES: &[Example] = &[
+    Example {
+        id: 1,
+        title: "Basic lifetime annotations",
+        explanation: "Here we create two strings and pass references to them to the `longest` function",
+        run: basic_annotations,
+    },
+    Example {
+        id: 2,
+        title: "Lifetime in struct definitions",
+        explanation: "We define a struct `ImportantExcerpt` that holds a reference to a string slice",
+        run: lifetimes_in_structs,
+    },
+    Example {
+        id: 3,
+        title: "Static lifetime",
+        explanation: "The 'static lifetime is a special lifetime that lasts for the entire duration of the program",
+        run: static_lifetime_example,
+    },
+    Example {
+        id: 4,
+        title: "Lifetime elision",
+        explanation: "Rust has lifetime elision rules that allow us to omit lifetime annotations in common cases",
+        run: lifetime_elision,
+    },
+    Example {
+        id: 5,
+        title: "Lifetime bounds on generic types",
+        explanation: "We can use lifetime annotations with generic types to ensure references live long enough",
+        run: bounds_on_generic_types,
+    },
+    Example {
+        id: 6,
+        title: "Lifetime subtyping",
+        explanation: "Demonstrates how one lifetime can outlive another",
+        run: lifetime_subtyping,
+    },
+    Example {
+        id: 7,
+        title: "Lifetime bounds on trait objects",
+        explanation: "Shows how to use lifetimes with trait objects",
+        run: bounds_on_trait_objects,
+    },
+];
+
+// Example 1: Basic lifetime annotations
+// Here we create two strings and pass references to them to the `longest` function
+fn basic_annotations(out: &mut dyn Write) -> io::Result<()> {
     // The `longest` function uses lifetime annotations to ensure the returned reference is valid
     let string1 = String::from("short");
     let string2 = String::from("longer string");
     let result = longest(&string1, &string2);
-    println!("Longest string: {}", result);
+    writeln!(out, "Longest string: {}", result)?;
     // Result: Longest string: longer string
     // The `result` reference is valid because it's tied to the lifetime of `string1` and `string2`
     // This demonstrates how Rust's borrow checker ensures that references are always valid
 
-    // Example 2: Lifetime in struct definitions
-    // We define a struct `ImportantExcerpt` that holds a reference to a string slice
+    Ok(())
+}
+
+// Example 2: Lifetime in struct definitions
+// We define a struct `ImportantExcerpt` that holds a reference to a string slice
+fn lifetimes_in_structs(out: &mut dyn Write) -> io::Result<()> {
     // The lifetime annotation ensures that the reference in the struct doesn't outlive the data it refers to
     let novel = String::from("Call me Ishmael. Some years ago...");
     let first_sentence = novel.split('.').next().expect("Could not find a '.'");
     let i = ImportantExcerpt {
         part: first_sentence,
     };
-    println!("First sentence: {}", i.part);
+    writeln!(out, "First sentence: {}", i.part)?;
     // Result: First sentence: Call me Ishmael
     // The `ImportantExcerpt` instance is valid as long as `novel` is valid
     // This shows how lifetimes can be used to ensure that struct fields referencing borrowed data remain valid
 
-    // Example 3: Static lifetime
-    // The 'static lifetime is a special lifetime that lasts for the entire duration of the program
+    Ok(())
+}
+
+// Example 3: Static lifetime
+// The 'static lifetime is a special lifetime that lasts for the entire duration of the program
+fn static_lifetime_example(out: &mut dyn Write) -> io::Result<()> {
     // String literals have a 'static lifetime by default
     let s: &'static str = "I have a static lifetime.";
-    println!("Static string: {}", s);
+    writeln!(out, "Static string: {}", s)?;
     // Result: Static string: I have a static lifetime.
     // This string will be available throughout the entire program's execution
     // 'static is useful for constants and other data that should live for the entire program runtime
 
-    // Example 4: Lifetime elision
-    // Rust has lifetime elision rules that allow us to omit lifetime annotations in common cases
+    Ok(())
+}
+
+// Example 4: Lifetime elision
+// Rust has lifetime elision rules that allow us to omit lifetime annotations in common cases
+fn lifetime_elision(out: &mut dyn Write) -> io::Result<()> {
     // Here, the compiler can infer the lifetimes without explicit annotations
     let s1 = String::from("hello");
     let len = calculate_length(&s1);
-    println!("The length of '{}' is {}.", s1, len);
+    writeln!(out, "The length of '{}' is {}.", s1, len)?;
     // Result: The length of 'hello' is 5.
     // The `calculate_length` function doesn't need explicit lifetime annotations due to elision rules
     // This demonstrates how Rust's compiler can often infer lifetimes, reducing boilerplate code
 
-    // Example 5: Lifetime bounds on generic types
-    // We can use lifetime annotations with generic types to ensure references live long enough
+    Ok(())
+}
+
+// Example 5: Lifetime bounds on generic types
+// We can use lifetime annotations with generic types to ensure references live long enough
+fn bounds_on_generic_types(out: &mut dyn Write) -> io::Result<()> {
     let x = 5;
     let y = 10;
     let point = Point { x: &x, y: &y };
-    println!("Point coordinates: ({}, {})", point.x, point.y);
+    writeln!(out, "Point coordinates: ({}, {})", point.x, point.y)?;
     // Result: Point coordinates: (5, 10)
     // The `Point` struct ensures that its references don't outlive the values they point to
     // This shows how lifetimes can be used with generic types to create flexible, safe abstractions
 
-    // Example 6: Lifetime subtyping
-    // Demonstrates how one lifetime can outlive another
+    Ok(())
+}
+
+// Example 6: Lifetime subtyping
+// Demonstrates how one lifetime can outlive another
+fn lifetime_subtyping(out: &mut dyn Write) -> io::Result<()> {
     let s1 = String::from("short");
     let s2 = String::from("longer");
     let announce = Announcer {
         message: &s1,
         data: &s2,
     };
-    println!(
+    writeln!(
+        out,
         "Announcement: {} (data: {})",
         announce.message, announce.data
-    );
+    )?;
+    // Result: Announcement: short (data: longer)
     // This example shows how different lifetimes can be related to each other
     // The 'announce lifetime must outlive both 'message and 'data lifetimes
 
-    // Example 7: Lifetime bounds on trait objects
-    // Shows how to use lifetimes with trait objects
+    Ok(())
+}
+
+// Example 7: Lifetime bounds on trait objects
+// Shows how to use lifetimes with trait objects
+fn bounds_on_trait_objects(out: &mut dyn Write) -> io::Result<()> {
     let s = String::from("Hello, world!");
     let obj: Box<dyn PrintWithLifetime> = Box::new(LifetimePrinter { s: &s });
-    obj.print();
+    obj.print(out)?;
+    // Result: Printing with lifetime: Hello, world!
     // This demonstrates how trait objects can use lifetimes
     // It allows for dynamic dispatch while still maintaining lifetime safety
 
-    println!("🕰️ Time's up! The lifetime lesson is complete! ⏳🎉");
+    Ok(())
 }
 
-// This function demonstrates explicit lifetime annotations
-// It takes two string slices and returns a reference to the longer one
-// The lifetime 'a ensures that the returned reference is valid for at least as long as both input references
+/// This function demonstrates explicit lifetime annotations
+/// It takes two string slices and returns a reference to the longer one
+/// The lifetime 'a ensures that the returned reference is valid for at least as long as both input references
+///
+/// ```
+/// use rust_concepts::ch03_lifetime::longest;
+///
+/// let string1 = String::from("long string is long");
+/// let result;
+/// {
+///     let string2 = String::from("xyz");
+///     result = longest(string1.as_str(), string2.as_str());
+///     // result can only be used while both string1 and string2 are alive
+///     assert_eq!(result, "long string is long");
+/// }
+/// ```
 pub fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {
     if x.len() > y.len() {
         x
@@ -96,35 +239,62 @@ pub fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {
 // 1. Both input references live at least as long as 'a
 // 2. The returned reference will not outlive either of the input references
 
-// This struct demonstrates lifetimes in struct definitions
-// The lifetime 'a ensures that the `part` reference doesn't outlive the data it refers to
+/// This struct demonstrates lifetimes in struct definitions
+/// The lifetime 'a ensures that the `part` reference doesn't outlive the data it refers to
+///
+/// ```
+/// use rust_concepts::ch03_lifetime::ImportantExcerpt;
+///
+/// let novel = String::from("Call me Ishmael. Some years ago...");
+/// let first_sentence = novel.split('.').next().unwrap();
+/// let excerpt = ImportantExcerpt { part: first_sentence };
+/// assert_eq!(excerpt.part, "Call me Ishmael");
+/// assert_eq!(excerpt.level(), 3);
+/// ```
 pub struct ImportantExcerpt<'a> {
-    pub(crate) part: &'a str,
+    pub part: &'a str,
 }
 // By including the lifetime 'a in the struct definition, we're saying:
 // "An ImportantExcerpt instance cannot outlive the reference it holds in its `part` field"
 
-// This function demonstrates lifetime elision rules
-// Even though it takes a reference and returns a value derived from that reference,
-// we don't need to specify lifetimes explicitly due to Rust's lifetime elision rules
+/// This function demonstrates lifetime elision rules
+/// Even though it takes a reference and returns a value derived from that reference,
+/// we don't need to specify lifetimes explicitly due to Rust's lifetime elision rules
+///
+/// ```
+/// use rust_concepts::ch03_lifetime::calculate_length;
+///
+/// assert_eq!(calculate_length("hello"), 5);
+/// ```
 pub fn calculate_length(s: &str) -> usize {
     s.len()
 }
 // Rust's lifetime elision rules allow us to omit explicit lifetime annotations in simple cases like this
 // The compiler can infer that the input reference 's' and the return value don't need explicit lifetime annotations
 
-// This struct demonstrates lifetime bounds on generic types
-// It holds two references that might have different lifetimes
+/// This struct demonstrates lifetime bounds on generic types
+/// It holds two references that might have different lifetimes
+///
+/// ```
+/// use rust_concepts::ch03_lifetime::Point;
+///
+/// let x = 5;
+/// let point;
+/// {
+///     let y = 10;
+///     point = Point { x: &x, y: &y };
+///     assert_eq!(*point.x + *point.y, 15);
+/// }
+/// ```
 pub struct Point<'a, 'b> {
-    pub(crate) x: &'a i32,
-    pub(crate) y: &'b i32,
+    pub x: &'a i32,
+    pub y: &'b i32,
 }
 // This struct shows how we can use different lifetimes for different fields
 // It allows for more flexible ownership patterns, where 'x' and 'y' can have independent lifetimes
 
 // Implement a method for ImportantExcerpt
 // This method doesn't need any additional lifetime annotations because it uses the lifetime from the impl block
-#[allow(dead_code)]
 impl<'a> ImportantExcerpt<'a> {
     pub fn level(&self) -> i32 {
         3
@@ -133,40 +303,65 @@ impl<'a> ImportantExcerpt<'a> {
 // This implementation demonstrates that methods on structs with lifetimes don't always need
 // additional lifetime annotations. The lifetime 'a is already part of the type ImportantExcerpt<'a>
 
-// This function demonstrates the 'static lifetime
-// It returns a string slice with a 'static lifetime, which means it's available for the entire program run
-#[allow(dead_code)]
+/// This function demonstrates the 'static lifetime
+/// It returns a string slice with a 'static lifetime, which means it's available for the entire program run
+///
+/// ```
+/// use rust_concepts::ch03_lifetime::static_lifetime;
+///
+/// let s: &'static str = static_lifetime();
+/// assert!(s.contains("'static"));
+/// ```
 pub fn static_lifetime() -> &'static str {
     "This string has a 'static lifetime"
 }
 // The 'static lifetime is special in Rust. It indicates that the reference is valid for the entire program run
 // String literals are 'static by default, as they're stored in the program's binary
 
-// This struct demonstrates lifetime subtyping
-// The 'announce lifetime must outlive the 'message and 'data lifetimes
+/// This struct demonstrates lifetime subtyping
+/// The 'announce lifetime must outlive the 'message and 'data lifetimes
+///
+/// ```
+/// use rust_concepts::ch03_lifetime::Announcer;
+///
+/// let message = "short";
+/// let data = String::from("longer");
+/// let announcer = Announcer { message, data: &data };
+/// assert_eq!(announcer.message, "short");
+/// assert_eq!(announcer.data, "longer");
+/// ```
 pub struct Announcer<'message, 'data> {
-    pub(crate) message: &'message str,
-    pub(crate) data: &'data str,
+    pub message: &'message str,
+    pub data: &'data str,
 }
 // This struct shows how we can relate different lifetimes to each other
 // It's saying that the Announcer struct can't outlive either of its references
 
-// This trait demonstrates lifetime bounds on trait objects
+/// This trait demonstrates lifetime bounds on trait objects
 pub trait PrintWithLifetime<'a> {
-    fn print(&self);
+    fn print(&self, out: &mut dyn Write) -> io::Result<()>;
 }
 // This trait shows how we can use lifetimes with traits
 // The lifetime 'a can be used to ensure that implementations of this trait
 // don't outlive any references they might be holding
 
-// This struct implements the PrintWithLifetime trait
+/// This struct implements the PrintWithLifetime trait
+///
+/// ```
+/// use rust_concepts::ch03_lifetime::{LifetimePrinter, PrintWithLifetime};
+///
+/// let printer = LifetimePrinter { s: "Hello, world!" };
+/// let mut output = Vec::new();
+/// printer.print(&mut output).unwrap();
+/// assert_eq!(output, b"Printing with lifetime: Hello, world!\n");
+/// ```
 pub struct LifetimePrinter<'a> {
-    pub(crate) s: &'a str,
+    pub s: &'a str,
 }
 
 impl<'a> PrintWithLifetime<'a> for LifetimePrinter<'a> {
-    fn print(&self) {
-        println!("Printing with lifetime: {}", self.s);
+    fn print(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "Printing with lifetime: {}", self.s)
     }
 }
 // This implementation shows how a struct with a lifetime can implement a trait with a lifetime
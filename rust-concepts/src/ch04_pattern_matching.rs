@@ -1,50 +1,217 @@
-// This function demonstrates various aspects of Rust's pattern matching
-pub fn demo() {
-    println!("Demonstrating Rust pattern matching!");
+use crate::chapter::{Chapter, Example};
+use std::io::{self, Write};
 
-    // Example 1: Basic match expression
+pub mod calc;
+
+/// Registers this chapter with the CLI
+pub struct PatternMatching;
+
+impl Chapter for PatternMatching {
+    fn id(&self) -> &'static str {
+        "04"
+    }
+
+    fn slug(&self) -> &'static str {
+        "pattern_matching"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ch04"]
+    }
+
+    fn title(&self) -> &'static str {
+        "Pattern Matching"
+    }
+
+    fn summary(&self) -> &'static str {
+        "Match expressions, guards, destructuring, slice and @ patterns, binding modes, let-else, matches! and an expression evaluator"
+    }
+
+    fn source(&self) -> &'static str {
+        include_str!("ch04_pattern_matching.rs")
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        demo(out)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+/// This function demonstrates various aspects of Rust's pattern matching
+///
+/// ```
+/// let mut output = Vec::new();
+/// rust_concepts::ch04_pattern_matching::demo(&mut output).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.starts_with("Demonstrating Rust pattern matching!"));
+/// ```
+pub fn demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Demonstrating Rust pattern matching!")?;
+
+    for example in EXAMPLES {
+        (example.run)(out)?;
+    }
+
+    writeln!(
+        out,
+        "🧩 Puzzle solved! The pattern matching lesson is complete! 🎭🎉"
+    )?;
+
+    Ok(())
+}
+
+/// The examples of this chapter, in the order `demo` runs them
+pub static EXAMPLES: &[Example] = &[
+    Example {
+        id: 1,
+        title: "Basic match expression",
+        explanation: "match compares a value against a series of patterns and runs the first arm that matches",
+        run: basic_match,
+    },
+    Example {
+        id: 2,
+        title: "Matching with guards",
+        explanation: "Guards allow for additional boolean conditions in match arms",
+        run: match_guards,
+    },
+    Example {
+        id: 3,
+        title: "Destructuring structs",
+        explanation: "Patterns can take a struct apart, binding or matching each of its fields",
+        run: destructuring_structs,
+    },
+    Example {
+        id: 4,
+        title: "Matching enums",
+        explanation: "A match on an enum has one arm per variant, and each arm can bind the data inside the variant",
+        run: matching_enums,
+    },
+    Example {
+        id: 5,
+        title: "Pattern matching with Option",
+        explanation: "Option is an enum that represents the presence or absence of a value",
+        run: matching_option,
+    },
+    Example {
+        id: 6,
+        title: "if let expressions",
+        explanation: "if let is a shorter way to match on a single pattern",
+        run: if_let,
+    },
+    Example {
+        id: 7,
+        title: "while let conditional loops",
+        explanation: "while let allows a loop to continue as long as a pattern matches",
+        run: while_let,
+    },
+    Example {
+        id: 8,
+        title: "Slice patterns",
+        explanation: "Slice patterns match on the length of a slice and bind its first, last or remaining elements",
+        run: slice_patterns,
+    },
+    Example {
+        id: 9,
+        title: "@ bindings",
+        explanation: "name @ pattern tests a value against a pattern and binds it at the same time",
+        run: at_bindings,
+    },
+    Example {
+        id: 10,
+        title: "Nested patterns",
+        explanation: "Patterns nest, so one arm can look inside an enum, the struct it holds and that struct's fields",
+        run: nested_patterns,
+    },
+    Example {
+        id: 11,
+        title: "ref, ref mut and binding modes",
+        explanation: "A pattern can borrow what it binds instead of moving it, either with ref or by matching on a reference",
+        run: binding_modes,
+    },
+    Example {
+        id: 12,
+        title: "Refutable and irrefutable patterns",
+        explanation: "let and function parameters need patterns that always match; if let, while let and match arms can fail",
+        run: refutability,
+    },
+    Example {
+        id: 13,
+        title: "let-else",
+        explanation: "let-else binds a refutable pattern and runs the else block, which must diverge, when it doesn't match",
+        run: let_else,
+    },
+    Example {
+        id: 14,
+        title: "The matches! macro",
+        explanation: "matches! turns a pattern, with an optional guard, into a bool",
+        run: matches_macro,
+    },
+    Example {
+        id: 15,
+        title: "Patterns in function parameters",
+        explanation: "Function and closure parameters are patterns, so they can take their arguments apart",
+        run: parameter_patterns,
+    },
+    Example {
+        id: 16,
+        title: "Capstone: an expression evaluator",
+        explanation: "An enum AST is parsed, folded, printed and evaluated with exhaustive matches, one arm per variant",
+        run: expression_evaluator,
+    },
+];
+
+// Example 1: Basic match expression
+// match compares a value against a series of patterns and runs the first arm that matches
+fn basic_match(out: &mut dyn Write) -> io::Result<()> {
     let number = 13;
     match number {
         // Match a single value
-        1 => println!("One!"),
+        1 => writeln!(out, "One!")?,
         // Match several values using the | (or) operator
         // This allows us to specify multiple patterns in a single match arm
-        2 | 3 | 5 | 7 | 11 => println!("This is a prime"),
+        2 | 3 | 5 | 7 | 11 => writeln!(out, "This is a prime")?,
         // Match an inclusive range using ..=
         // This matches any value from 13 to 19, inclusive
-        13..=19 => println!("A teen"),
+        13..=19 => writeln!(out, "A teen")?,
         // Handle all other cases with the wildcard pattern _
         // This is a catch-all pattern that matches any value not matched by previous patterns
-        _ => println!("Ain't special"),
+        _ => writeln!(out, "Ain't special")?,
     }
     // Result: A teen
 
-    // Example 2: Matching with guards
-    // Guards allow for additional boolean conditions in match arms
+    Ok(())
+}
+
+// Example 2: Matching with guards
+// Guards allow for additional boolean conditions in match arms
+fn match_guards(out: &mut dyn Write) -> io::Result<()> {
     let pair = (2, -2);
     match pair {
         // Check if both components are equal
         // The guard 'if x == y' adds an additional condition to the pattern
-        (x, y) if x == y => println!("These are twins"),
+        (x, y) if x == y => writeln!(out, "These are twins")?,
         // Check if the sum of components is zero
         // This demonstrates how we can use complex expressions in guards
-        (x, y) if x + y == 0 => println!("Antimatter, kaboom!"),
+        (x, y) if x + y == 0 => writeln!(out, "Antimatter, kaboom!")?,
         // Check if the first component is odd
         // The underscore _ in (x, _) means we don't care about the second value
-        (x, _) if x % 2 == 1 => println!("The first one is odd"),
+        (x, _) if x % 2 == 1 => writeln!(out, "The first one is odd")?,
         // Default case
         // This catches any pair that doesn't match the above patterns
-        _ => println!("No correlation..."),
+        _ => writeln!(out, "No correlation...")?,
     }
     // Result: Antimatter, kaboom!
 
-    // Example 3: Destructuring structs
-    // Define a simple Point struct with x and y coordinates
-    struct Point {
-        x: i32,
-        y: i32,
-    }
-    // Create a new Point instance
+    Ok(())
+}
+
+// Example 3: Destructuring structs
+// Patterns can take a struct apart, binding or matching each of its fields
+fn destructuring_structs(out: &mut dyn Write) -> io::Result<()> {
+    // Create a Point instance (Point is defined at the bottom of this file)
     let point = Point { x: 0, y: 7 };
     // Destructure the point into its components
     // This creates two new variables, x and y, with the values from point
@@ -52,69 +219,59 @@ pub fn demo() {
     // Verify that the destructuring worked correctly
     assert_eq!(0, x);
     assert_eq!(7, y);
-    // Match on different configurations of the Point struct
-    match point {
-        // Match points on the x-axis (y = 0)
-        // This demonstrates how we can match on specific field values
-        Point { x, y: 0 } => println!("On the x axis at {}", x),
-        // Match points on the y-axis (x = 0)
-        // Similar to the previous arm, but matching on x = 0
-        Point { x: 0, y } => println!("On the y axis at {}", y),
-        // Match any other point
-        // This is a catch-all for points not on either axis
-        Point { x, y } => println!("On neither axis: ({}, {})", x, y),
-    }
+    // Match on different configurations of the Point struct (see describe_point)
+    writeln!(out, "{}", describe_point(point))?;
     // Result: On the y axis at 7
 
-    // Example 4: Matching enums
-    // Define an enum with different variants
-    // This enum represents different types of messages in a system
-    #[allow(dead_code)]
-    enum Message {
-        Quit,
-        Move { x: i32, y: i32 },
-        Write(String),
-        ChangeColor(i32, i32, i32),
-    }
-    // Create an instance of the Message enum
+    Ok(())
+}
+
+// Example 4: Matching enums
+// A match on an enum has one arm per variant, and each arm can bind the data inside the variant
+fn matching_enums(out: &mut dyn Write) -> io::Result<()> {
+    // Create an instance of the Message enum (defined at the bottom of this file)
     let msg = Message::ChangeColor(0, 160, 255);
-    // Match on different enum variants
-    match msg {
-        // Simple variant with no data
-        Message::Quit => println!("Quit"),
-        // Struct-like variant: destructure to access fields
-        Message::Move { x, y } => println!("Move to x: {}, y: {}", x, y),
-        // Tuple-like variant: extract the String
-        Message::Write(text) => println!("Text message: {}", text),
-        // Tuple-like variant: destructure to access color components
-        Message::ChangeColor(r, g, b) => println!("Change color to r: {}, g: {}, b: {}", r, g, b),
-    }
+    // Match on different enum variants (see describe_message)
+    writeln!(out, "{}", describe_message(&msg))?;
     // Result: Change color to r: 0, g: 160, b: 255
 
-    // Example 5: Pattern matching with Option
-    // Option is an enum that represents the presence or absence of a value
+    Ok(())
+}
+
+// Example 5: Pattern matching with Option
+// Option is an enum that represents the presence or absence of a value
+fn matching_option(out: &mut dyn Write) -> io::Result<()> {
     let some_number = Some(5);
     let _some_string = Some("a string");
     let _absent_number: Option<i32> = None;
     // Match on the Option enum
     match some_number {
         // If the Option contains a value, bind it to 'i'
-        Some(i) => println!("Got an integer: {}", i),
+        Some(i) => writeln!(out, "Got an integer: {}", i)?,
         // If the Option is None, execute this arm
-        None => println!("No integer!"),
+        None => writeln!(out, "No integer!")?,
     }
     // Result: Got an integer: 5
 
-    // Example 6: if let expressions
-    // if let is a shorter way to match on a single pattern
+    Ok(())
+}
+
+// Example 6: if let expressions
+// if let is a shorter way to match on a single pattern
+fn if_let(out: &mut dyn Write) -> io::Result<()> {
     // It's particularly useful when you only care about one specific pattern
+    let some_number = Some(5);
     if let Some(i) = some_number {
-        println!("Matched {}", i);
+        writeln!(out, "Matched {}", i)?;
     }
     // Result: Matched 5
 
-    // Example 7: while let conditional loops
-    // while let allows a loop to continue as long as a pattern matches
+    Ok(())
+}
+
+// Example 7: while let conditional loops
+// while let allows a loop to continue as long as a pattern matches
+fn while_let(out: &mut dyn Write) -> io::Result<()> {
     // This is useful for iterating over values that implement the Iterator trait
     let mut stack = Vec::new();
     stack.push(1);
@@ -123,12 +280,443 @@ pub fn demo() {
     // Pop items from the stack while it's not empty
     // This demonstrates how while let can be used to process a sequence of values
     while let Some(top) = stack.pop() {
-        println!("{}", top);
+        writeln!(out, "{}", top)?;
     }
     // Result:
     // 3
     // 2
     // 1
 
-    println!("🧩 Puzzle solved! The pattern matching lesson is complete! 🎭🎉");
+    Ok(())
+}
+
+// Example 8: Slice patterns
+// Slice patterns match on the length of a slice and bind its first, last or remaining elements
+fn slice_patterns(out: &mut dyn Write) -> io::Result<()> {
+    // [first, .., last] needs at least two elements; .. matches whatever is in between
+    writeln!(out, "{}", describe_slice(&[1, 2, 3, 4]))?;
+    // Result: Starts with 1 and ends with 4
+    writeln!(out, "{}", describe_slice(&[]))?;
+    // Result: Empty
+    // [x, rest @ ..] splits off the first element and binds the rest as a smaller slice
+    writeln!(out, "Sum: {}", sum(&[1, 2, 3, 4]))?;
+    // Result: Sum: 10
+
+    Ok(())
+}
+
+// Example 9: @ bindings
+// name @ pattern tests a value against a pattern and binds it at the same time
+fn at_bindings(out: &mut dyn Write) -> io::Result<()> {
+    // Without @, the arm for 13..=19 couldn't say which age it matched
+    writeln!(out, "{}", classify_age(15))?;
+    // Result: 15 is a teenager
+    // @ also works inside other patterns, here on a field of an enum variant
+    let msg = Message::Move { x: 3, y: 40 };
+    if let Message::Move {
+        x: small @ 0..=9, ..
+    } = msg
+    {
+        writeln!(out, "Small step of {} along x", small)?;
+    }
+    // Result: Small step of 3 along x
+
+    Ok(())
+}
+
+// Example 10: Nested patterns
+// Patterns nest, so one arm can look inside an enum, the struct it holds and that struct's fields
+fn nested_patterns(out: &mut dyn Write) -> io::Result<()> {
+    // Shape holds Points, and describe_shape matches on the shape and the points in one go
+    let circle = Shape::Circle {
+        center: Point { x: 0, y: 0 },
+        radius: 5,
+    };
+    writeln!(out, "{}", describe_shape(&circle))?;
+    // Result: A circle of radius 5 at the origin
+    let line = Shape::Line(Point { x: 2, y: 0 }, Point { x: 2, y: 9 });
+    writeln!(out, "{}", describe_shape(&line))?;
+    // Result: A vertical line at x = 2
+    // Option<Message> nests an enum in an enum
+    let next: Option<Message> = Some(Message::Write(String::from("hi")));
+    if let Some(Message::Write(text)) = &next {
+        writeln!(out, "Next message says {}", text)?;
+    }
+    // Result: Next message says hi
+
+    Ok(())
+}
+
+// Example 11: ref, ref mut and binding modes
+// A pattern can borrow what it binds instead of moving it, either with ref or by matching on a reference
+fn binding_modes(out: &mut dyn Write) -> io::Result<()> {
+    let mut pair = (String::from("count"), 1);
+    // `ref` borrows the String instead of moving it out of pair, `ref mut` borrows mutably
+    let (ref name, ref mut count) = pair;
+    *count += 1;
+    writeln!(out, "{} is {}", name, count)?;
+    // Result: count is 2
+    // pair is still whole, since nothing was moved out of it
+    assert_eq!(pair.1, 2);
+    // Matching a reference against a non-reference pattern binds by reference automatically:
+    // in name_length, `Some(name)` on an &Option<String> makes name a &String
+    let nickname = Some(String::from("Ferris"));
+    writeln!(out, "Name length: {}", name_length(&nickname))?;
+    // Result: Name length: 6
+    // The same default binding mode gives &mut bindings when matching through &mut
+    let mut points = [Point { x: 1, y: 1 }, Point { x: 5, y: 0 }];
+    shift_right(&mut points);
+    writeln!(
+        out,
+        "Shifted to x = {} and x = {}",
+        points[0].x, points[1].x
+    )?;
+    // Result: Shifted to x = 2 and x = 6
+
+    Ok(())
+}
+
+// Example 12: Refutable and irrefutable patterns
+// let and function parameters need patterns that always match; if let, while let and match arms can fail
+fn refutability(out: &mut dyn Write) -> io::Result<()> {
+    // Irrefutable: a tuple pattern of bindings matches every (i32, i32)
+    let (a, b) = (1, 2);
+    writeln!(out, "a = {}, b = {}", a, b)?;
+    // Result: a = 1, b = 2
+    // Refutable: Some(x) doesn't match None, so it needs if let (or match, or let-else)
+    // `let Some(x) = maybe;` is an error: refutable pattern in local binding (E0005)
+    let maybe: Option<i32> = None;
+    if let Some(x) = maybe {
+        writeln!(out, "Got {}", x)?;
+    } else {
+        writeln!(out, "Got nothing")?;
+    }
+    // Result: Got nothing
+
+    Ok(())
+}
+
+// Example 13: let-else
+// let-else binds a refutable pattern and runs the else block, which must diverge, when it doesn't match
+fn let_else(out: &mut dyn Write) -> io::Result<()> {
+    // parse_pair returns early from its else blocks, so the happy path stays unindented
+    writeln!(out, "{:?}", parse_pair("3, 4"))?;
+    // Result: Ok((3, 4))
+    writeln!(out, "{:?}", parse_pair("3 4"))?;
+    // Result: Err("no comma in `3 4`")
+
+    Ok(())
+}
+
+// Example 14: The matches! macro
+// matches! turns a pattern, with an optional guard, into a bool
+fn matches_macro(out: &mut dyn Write) -> io::Result<()> {
+    let vowels = "pattern".chars().filter(|c| is_vowel(*c)).count();
+    writeln!(out, "Vowels in pattern: {}", vowels)?;
+    // Result: Vowels in pattern: 2
+    // A guard can follow the pattern, just like in a match arm
+    let msg = Message::Move { x: 3, y: 40 };
+    let moves_right = matches!(msg, Message::Move { x, .. } if x > 0);
+    writeln!(out, "Moves right: {}", moves_right)?;
+    // Result: Moves right: true
+
+    Ok(())
+}
+
+// Example 15: Patterns in function parameters
+// Function and closure parameters are patterns, so they can take their arguments apart
+fn parameter_patterns(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "{}", point_label(Point { x: 3, y: -1 }))?;
+    // Result: (3, -1)
+    writeln!(out, "Distance: {}", manhattan_distance(&(3, -4)))?;
+    // Result: Distance: 7
+    // Closure parameters work the same way: |&(a, b)| takes each &(i32, i32) apart
+    let pairs = [(1, 2), (3, 4)];
+    let sums: Vec<i32> = pairs.iter().map(|&(a, b)| a + b).collect();
+    writeln!(out, "Sums: {:?}", sums)?;
+    // Result: Sums: [3, 7]
+
+    Ok(())
+}
+
+// Example 16: Capstone: an expression evaluator
+// An enum AST is parsed, folded, printed and evaluated with exhaustive matches, one arm per variant
+fn expression_evaluator(out: &mut dyn Write) -> io::Result<()> {
+    let env = calc::Env::from([("x".to_string(), calc::Value::Int(4))]);
+    // Each line is parsed into a calc::Expr, then evaluated by matching on its variants
+    for input in [
+        "1 + 2 * (3 - x)",
+        "x > 2 && !(x == 5)",
+        "y + 1",
+        "10 / (x - 4)",
+    ] {
+        match calc::parse(input).and_then(|expr| calc::eval(&expr, &env)) {
+            Ok(value) => writeln!(out, "{} = {}", input, value)?,
+            Err(error) => writeln!(out, "{}: error: {}", input, error)?,
+        }
+    }
+    // Result:
+    // 1 + 2 * (3 - x) = -1
+    // x > 2 && !(x == 5) = true
+    // y + 1: error: unknown variable `y`
+    // 10 / (x - 4): error: division by zero
+
+    // Constant folding evaluates the parts that don't need x, and printing drops extra parentheses
+    if let Ok(expr) = calc::parse("((x)) * (2 + 3) - 10 / 2") {
+        writeln!(out, "Folded: {}", calc::fold(&expr))?;
+    }
+    // Result: Folded: x * 5 - 5
+
+    Ok(())
+}
+
+/// A point on a grid, taken apart by the struct patterns in this chapter
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::Point;
+///
+/// let Point { x, y } = Point { x: 0, y: 7 };
+/// assert_eq!((x, y), (0, 7));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// An enum with every kind of variant: unit, struct-like and tuple-like
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    Quit,
+    Move { x: i32, y: i32 },
+    Write(String),
+    ChangeColor(i32, i32, i32),
+}
+
+/// A shape made of points, for matching several levels deep at once
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Shape {
+    Circle { center: Point, radius: i32 },
+    Line(Point, Point),
+}
+
+/// Says which axis a point is on, matching on the values of its fields
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::{describe_point, Point};
+///
+/// assert_eq!(describe_point(Point { x: 3, y: 0 }), "On the x axis at 3");
+/// ```
+pub fn describe_point(point: Point) -> String {
+    match point {
+        // Match points on the x-axis (y = 0)
+        // This demonstrates how we can match on specific field values
+        Point { x, y: 0 } => format!("On the x axis at {}", x),
+        // Match points on the y-axis (x = 0)
+        // Similar to the previous arm, but matching on x = 0
+        Point { x: 0, y } => format!("On the y axis at {}", y),
+        // Match any other point
+        // This is a catch-all for points not on either axis
+        Point { x, y } => format!("On neither axis: ({}, {})", x, y),
+    }
+}
+
+/// Describes a message, with one arm per variant and no catch-all
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::{describe_message, Message};
+///
+/// assert_eq!(describe_message(&Message::Quit), "Quit");
+/// ```
+pub fn describe_message(msg: &Message) -> String {
+    match msg {
+        // Simple variant with no data
+        Message::Quit => "Quit".to_string(),
+        // Struct-like variant: destructure to access fields
+        Message::Move { x, y } => format!("Move to x: {}, y: {}", x, y),
+        // Tuple-like variant: extract the String
+        Message::Write(text) => format!("Text message: {}", text),
+        // Tuple-like variant: destructure to access color components
+        Message::ChangeColor(r, g, b) => {
+            format!("Change color to r: {}, g: {}, b: {}", r, g, b)
+        }
+    }
+}
+
+/// Describes a slice by its length and its ends, using slice patterns
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::describe_slice;
+///
+/// assert_eq!(describe_slice(&[7]), "Just 7");
+/// ```
+pub fn describe_slice(values: &[i32]) -> String {
+    match values {
+        [] => "Empty".to_string(),
+        [only] => format!("Just {}", only),
+        // .. matches any number of elements, including none
+        [first, .., last] => format!("Starts with {} and ends with {}", first, last),
+    }
+}
+
+/// Adds up a slice recursively: `[x, rest @ ..]` binds the first element and the rest
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::sum;
+///
+/// assert_eq!(sum(&[1, 2, 3]), 6);
+/// ```
+pub fn sum(values: &[i32]) -> i32 {
+    match values {
+        [] => 0,
+        [x, rest @ ..] => x + sum(rest),
+    }
+}
+
+/// Puts an age into a group, binding the age with `@` while matching its range
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::classify_age;
+///
+/// assert_eq!(classify_age(8), "8 is a child");
+/// ```
+pub fn classify_age(age: u32) -> String {
+    match age {
+        n @ 0..=12 => format!("{} is a child", n),
+        n @ 13..=19 => format!("{} is a teenager", n),
+        n => format!("{} is an adult", n),
+    }
+}
+
+/// Describes a shape, with patterns that reach into the points it's made of
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::{describe_shape, Point, Shape};
+///
+/// let line = Shape::Line(Point { x: 0, y: 1 }, Point { x: 5, y: 1 });
+/// assert_eq!(describe_shape(&line), "A horizontal line at y = 1");
+/// ```
+pub fn describe_shape(shape: &Shape) -> String {
+    match shape {
+        // A literal inside a struct inside a variant
+        Shape::Circle {
+            center: Point { x: 0, y: 0 },
+            radius,
+        } => format!("A circle of radius {} at the origin", radius),
+        // @ and a range on one field, bindings on the others
+        Shape::Circle {
+            center: Point { x, y },
+            radius: radius @ 1..=9,
+        } => format!("A small circle of radius {} at ({}, {})", radius, x, y),
+        Shape::Circle { center, radius } => format!(
+            "A circle of radius {} at ({}, {})",
+            radius, center.x, center.y
+        ),
+        // The same name can't be bound twice, so a guard compares the two x fields
+        Shape::Line(Point { x: x1, .. }, Point { x: x2, .. }) if x1 == x2 => {
+            format!("A vertical line at x = {}", x1)
+        }
+        Shape::Line(Point { y: y1, .. }, Point { y: y2, .. }) if y1 == y2 => {
+            format!("A horizontal line at y = {}", y1)
+        }
+        Shape::Line(start, end) => format!(
+            "A line from ({}, {}) to ({}, {})",
+            start.x, start.y, end.x, end.y
+        ),
+    }
+}
+
+/// The length of a name, if there is one
+///
+/// `name` is a reference, so `Some(name)` binds a `&String` that borrows from it; nothing
+/// is moved out of the caller's `Option`
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::name_length;
+///
+/// let name = Some(String::from("Ada"));
+/// assert_eq!(name_length(&name), 3);
+/// assert!(name.is_some());
+/// ```
+pub fn name_length(name: &Option<String>) -> usize {
+    match name {
+        Some(name) => name.len(),
+        None => 0,
+    }
+}
+
+/// Moves every point one step to the right
+///
+/// `iter_mut` yields `&mut Point`, so the pattern `Point { x, .. }` binds `x` as `&mut i32`
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::{shift_right, Point};
+///
+/// let mut points = [Point { x: 0, y: 0 }];
+/// shift_right(&mut points);
+/// assert_eq!(points[0].x, 1);
+/// ```
+pub fn shift_right(points: &mut [Point]) {
+    for Point { x, .. } in points.iter_mut() {
+        *x += 1;
+    }
+}
+
+/// Parses two comma-separated integers, giving up with let-else at the first problem
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::parse_pair;
+///
+/// assert_eq!(parse_pair("1,2"), Ok((1, 2)));
+/// assert_eq!(parse_pair("1,x"), Err("`x` isn't a number".to_string()));
+/// ```
+pub fn parse_pair(text: &str) -> Result<(i32, i32), String> {
+    let Some((left, right)) = text.split_once(',') else {
+        return Err(format!("no comma in `{}`", text));
+    };
+    let (left, right) = (left.trim(), right.trim());
+    let Ok(x) = left.parse() else {
+        return Err(format!("`{}` isn't a number", left));
+    };
+    let Ok(y) = right.parse() else {
+        return Err(format!("`{}` isn't a number", right));
+    };
+    Ok((x, y))
+}
+
+/// Whether a letter is a vowel, written as a `matches!` with an or-pattern
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::is_vowel;
+///
+/// assert!(is_vowel('E'));
+/// assert!(!is_vowel('x'));
+/// ```
+pub fn is_vowel(c: char) -> bool {
+    matches!(c.to_ascii_lowercase(), 'a' | 'e' | 'i' | 'o' | 'u')
+}
+
+/// Formats a point, taking it apart in the parameter list
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::{point_label, Point};
+///
+/// assert_eq!(point_label(Point { x: 1, y: 2 }), "(1, 2)");
+/// ```
+pub fn point_label(Point { x, y }: Point) -> String {
+    format!("({}, {})", x, y)
+}
+
+/// The distance from the origin when moving only along the axes
+///
+/// The parameter pattern `&(x, y)` matches the reference and the tuple inside it, so `x`
+/// and `y` are plain `i32`s
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::manhattan_distance;
+///
+/// assert_eq!(manhattan_distance(&(-2, 5)), 7);
+/// ```
+pub fn manhattan_distance(&(x, y): &(i32, i32)) -> i32 {
+    x.abs() + y.abs()
 }
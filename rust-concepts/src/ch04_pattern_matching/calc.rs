@@ -0,0 +1,741 @@
+//! Capstone: a calculator for arithmetic and boolean expressions
+//!
+//! Every stage is built on `match`. The tokenizer matches characters, the parser matches
+//! tokens, and evaluation, constant folding and printing each match on the [`Expr`] tree.
+//! None of the matches on [`Expr`], [`UnaryOp`] or [`BinaryOp`] has a `_` arm, so adding a
+//! variant makes the compiler point at every place that has to handle it.
+//!
+//! ```
+//! use rust_concepts::ch04_pattern_matching::calc::{self, Env, Value};
+//!
+//! let expr = calc::parse("1 + 2 * (3 - x)").unwrap();
+//! let env = Env::from([("x".to_string(), Value::Int(4))]);
+//! assert_eq!(calc::eval(&expr, &env), Ok(Value::Int(-1)));
+//! assert_eq!(calc::fold(&calc::parse("x * (2 + 3)").unwrap()).to_string(), "x * 5");
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+/// A value an expression evaluates to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value {
+    Int(i64),
+    Bool(bool),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(n) => write!(f, "{}", n),
+            Value::Bool(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+/// The values of the variables an expression may use
+pub type Env = HashMap<String, Value>;
+
+/// `-x` or `!x`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
+/// The operators between two operands, from `*` to `||`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+impl BinaryOp {
+    /// The operator as it's written
+    pub fn symbol(self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Rem => "%",
+            BinaryOp::Eq => "==",
+            BinaryOp::Ne => "!=",
+            BinaryOp::Lt => "<",
+            BinaryOp::Le => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::Ge => ">=",
+            BinaryOp::And => "&&",
+            BinaryOp::Or => "||",
+        }
+    }
+
+    /// How tightly the operator binds: `*` binds tighter than `+`, which binds tighter
+    /// than `==`, then `&&`, then `||`
+    pub fn precedence(self) -> u8 {
+        match self {
+            BinaryOp::Or => 1,
+            BinaryOp::And => 2,
+            BinaryOp::Eq
+            | BinaryOp::Ne
+            | BinaryOp::Lt
+            | BinaryOp::Le
+            | BinaryOp::Gt
+            | BinaryOp::Ge => 3,
+            BinaryOp::Add | BinaryOp::Sub => 4,
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem => 5,
+        }
+    }
+}
+
+// Unary operators bind tighter than any binary one, and literals tighter still
+const UNARY: u8 = 6;
+const ATOM: u8 = 7;
+
+/// An expression, as a tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Int(i64),
+    Bool(bool),
+    Var(String),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Int(_) | Expr::Bool(_) | Expr::Var(_) => ATOM,
+            Expr::Unary(..) => UNARY,
+            Expr::Binary(op, ..) => op.precedence(),
+        }
+    }
+}
+
+/// Prints the expression with only the parentheses it needs
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::calc;
+///
+/// let expr = calc::parse("((1 + 2)) * (3) - (4 - 5)").unwrap();
+/// assert_eq!(expr.to_string(), "(1 + 2) * 3 - (4 - 5)");
+/// ```
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Int(n) => write!(f, "{}", n),
+            Expr::Bool(b) => write!(f, "{}", b),
+            Expr::Var(name) => write!(f, "{}", name),
+            Expr::Unary(op, operand) => {
+                let symbol = match op {
+                    UnaryOp::Neg => "-",
+                    UnaryOp::Not => "!",
+                };
+                write!(f, "{}", symbol)?;
+                // Folding can leave a negative literal under `-`, and `--5` wouldn't read back
+                let negative = matches!(**operand, Expr::Int(n) if n < 0);
+                parenthesized(f, operand, negative || operand.precedence() < UNARY)
+            }
+            Expr::Binary(op, left, right) => {
+                let precedence = op.precedence();
+                // The operators are left-associative, and comparisons don't chain at all,
+                // so only the left operand of a non-comparison can share the precedence
+                let chains = precedence != BinaryOp::Eq.precedence();
+                let left_needs =
+                    left.precedence() < precedence || (!chains && left.precedence() == precedence);
+                parenthesized(f, left, left_needs)?;
+                write!(f, " {} ", op.symbol())?;
+                parenthesized(f, right, right.precedence() <= precedence)
+            }
+        }
+    }
+}
+
+fn parenthesized(f: &mut fmt::Formatter<'_>, expr: &Expr, needed: bool) -> fmt::Result {
+    if needed {
+        write!(f, "({})", expr)
+    } else {
+        write!(f, "{}", expr)
+    }
+}
+
+/// Everything that can go wrong between reading an expression and evaluating it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CalcError {
+    /// A character that starts no token, at a 0-based character position
+    UnexpectedChar {
+        found: char,
+        at: usize,
+    },
+    /// A token, or the end of the input, where something else had to be
+    UnexpectedToken {
+        expected: &'static str,
+        found: String,
+        at: usize,
+    },
+    /// `a < b < c`, which Rust doesn't allow either
+    ChainedComparison {
+        at: usize,
+    },
+    /// An integer literal that doesn't fit in an `i64`
+    NumberTooLarge {
+        at: usize,
+    },
+    /// An expression too large to work with without overflowing the stack: more than
+    /// [`MAX_DEPTH`] levels of parentheses, unary operators and chained binary operators
+    TooDeep {
+        at: usize,
+    },
+    UnknownVariable(String),
+    DivisionByZero,
+    Overflow,
+    /// An operator applied to a value of the wrong type, such as `1 + true`
+    TypeMismatch {
+        op: &'static str,
+        expected: &'static str,
+        found: Value,
+    },
+    /// A variable binding that isn't `name=value`
+    BadBinding(String),
+}
+
+impl CalcError {
+    /// Where in the input the error is, for the errors found while reading it
+    pub fn at(&self) -> Option<usize> {
+        match self {
+            CalcError::UnexpectedChar { at, .. }
+            | CalcError::UnexpectedToken { at, .. }
+            | CalcError::ChainedComparison { at }
+            | CalcError::NumberTooLarge { at }
+            | CalcError::TooDeep { at } => Some(*at),
+            CalcError::UnknownVariable(_)
+            | CalcError::DivisionByZero
+            | CalcError::Overflow
+            | CalcError::TypeMismatch { .. }
+            | CalcError::BadBinding(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalcError::UnexpectedChar { found, .. } => {
+                write!(f, "unexpected character `{}`", found)
+            }
+            CalcError::UnexpectedToken {
+                expected, found, ..
+            } => write!(f, "expected {}, found {}", expected, found),
+            CalcError::ChainedComparison { .. } => {
+                write!(f, "comparison operators can't be chained")
+            }
+            CalcError::NumberTooLarge { .. } => write!(f, "number too large"),
+            CalcError::TooDeep { .. } => {
+                write!(
+                    f,
+                    "expression too large: more than {} nested or chained operators",
+                    MAX_DEPTH
+                )
+            }
+            CalcError::UnknownVariable(name) => write!(f, "unknown variable `{}`", name),
+            CalcError::DivisionByZero => write!(f, "division by zero"),
+            CalcError::Overflow => write!(f, "arithmetic overflow"),
+            CalcError::TypeMismatch {
+                op,
+                expected,
+                found,
+            } => write!(f, "`{}` expects {}, found `{}`", op, expected, found),
+            CalcError::BadBinding(text) => {
+                write!(f, "expected a binding like `x=4`, found `{}`", text)
+            }
+        }
+    }
+}
+
+impl Error for CalcError {}
+
+/// A piece of an expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Int(i64),
+    /// A variable, `true` or `false`
+    Ident(String),
+    /// An operator or a parenthesis
+    Punct(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Int(n) => write!(f, "`{}`", n),
+            Token::Ident(name) => write!(f, "`{}`", name),
+            Token::Punct(punct) => write!(f, "`{}`", punct),
+        }
+    }
+}
+
+// Two-character operators come first, so `<=` isn't read as `<` and `=`
+const PUNCTUATION: &[&str] = &[
+    "&&", "||", "==", "!=", "<=", ">=", "+", "-", "*", "/", "%", "<", ">", "!", "(", ")",
+];
+
+/// Splits an expression into tokens, each with its 0-based character position
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::calc::{tokenize, Token};
+///
+/// let tokens = tokenize("x <= 10").unwrap();
+/// assert_eq!(tokens[1], (2, Token::Punct("<=")));
+/// ```
+pub fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, CalcError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        let token = match chars[i] {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '0'..='9' => {
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+                let digits: String = chars[start..i].iter().collect();
+                let value = digits
+                    .parse()
+                    .map_err(|_| CalcError::NumberTooLarge { at: start })?;
+                Token::Int(value)
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                Token::Ident(chars[start..i].iter().collect())
+            }
+            c => {
+                let rest: String = chars[i..].iter().take(2).collect();
+                let Some(punct) = PUNCTUATION.iter().find(|p| rest.starts_with(**p)) else {
+                    return Err(CalcError::UnexpectedChar { found: c, at: i });
+                };
+                i += punct.len();
+                Token::Punct(punct)
+            }
+        };
+        tokens.push((start, token));
+    }
+    Ok(tokens)
+}
+
+/// Parses an expression with one function per precedence level, lowest first
+///
+/// ```text
+/// or         = and ("||" and)*
+/// and        = comparison ("&&" comparison)*
+/// comparison = sum (("==" | "!=" | "<" | "<=" | ">" | ">=") sum)?
+/// sum        = product (("+" | "-") product)*
+/// product    = unary (("*" | "/" | "%") unary)*
+/// unary      = ("-" | "!") unary | atom
+/// atom       = integer | "true" | "false" | name | "(" or ")"
+/// ```
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::calc::{parse, BinaryOp, Expr};
+///
+/// let expr = parse("1 - 2 - 3").unwrap();
+/// // Left-associative: (1 - 2) - 3
+/// let Expr::Binary(BinaryOp::Sub, left, _) = expr else { panic!() };
+/// assert_eq!(left.to_string(), "1 - 2");
+/// assert_eq!(parse("1 +").unwrap_err().to_string(), "expected an expression, found the end of the input");
+/// ```
+pub fn parse(input: &str) -> Result<Expr, CalcError> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        pos: 0,
+        end: input.chars().count(),
+        depth: 0,
+    };
+    let expr = parser.or()?;
+    match parser.tokens.get(parser.pos) {
+        None => Ok(expr),
+        Some(_) => Err(parser.unexpected("an operator")),
+    }
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    // The position just past the input, where an unexpected end is reported
+    end: usize,
+    // How deep the tree being built is, counting parentheses, unary operators and operands
+    depth: usize,
+}
+
+/// How deeply an expression may nest before [`parse`] gives up with [`CalcError::TooDeep`]
+///
+/// Parsing, evaluating, folding and printing all recurse once per level, so a limit keeps
+/// input such as twenty thousand `-` signs from overflowing the stack. A chain such as
+/// `1 + 1 + 1` builds a tree one level deeper per operator, so long chains count too.
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::calc::{parse, CalcError, MAX_DEPTH};
+///
+/// let nested = format!("{}1{}", "(".repeat(MAX_DEPTH), ")".repeat(MAX_DEPTH));
+/// assert!(parse(&nested).is_ok());
+/// let too_deep = format!("{}1", "-".repeat(MAX_DEPTH + 1));
+/// assert_eq!(parse(&too_deep), Err(CalcError::TooDeep { at: MAX_DEPTH }));
+/// ```
+pub const MAX_DEPTH: usize = 256;
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, token)| token)
+    }
+
+    // Goes one level deeper, or fails at the current token past MAX_DEPTH
+    fn nest(&mut self) -> Result<(), CalcError> {
+        self.depth += 1;
+        if self.depth <= MAX_DEPTH {
+            return Ok(());
+        }
+        let at = self.tokens.get(self.pos).map_or(self.end, |(at, _)| *at);
+        Err(CalcError::TooDeep { at })
+    }
+
+    fn unexpected(&self, expected: &'static str) -> CalcError {
+        match self.tokens.get(self.pos) {
+            Some((at, token)) => CalcError::UnexpectedToken {
+                expected,
+                found: token.to_string(),
+                at: *at,
+            },
+            None => CalcError::UnexpectedToken {
+                expected,
+                found: "the end of the input".to_string(),
+                at: self.end,
+            },
+        }
+    }
+
+    // Parses `next (op next)*` for the operators `op_for` recognizes, grouping to the left
+    fn left_assoc(
+        &mut self,
+        next: fn(&mut Parser) -> Result<Expr, CalcError>,
+        op_for: fn(&str) -> Option<BinaryOp>,
+    ) -> Result<Expr, CalcError> {
+        let mut expr = next(self)?;
+        let mut levels = 0;
+        while let Some(op) = match self.peek() {
+            Some(Token::Punct(punct)) => op_for(punct),
+            _ => None,
+        } {
+            // Each operator in a chain puts the operands before it one level deeper
+            self.nest()?;
+            levels += 1;
+            self.pos += 1;
+            let right = next(self)?;
+            expr = Expr::Binary(op, Box::new(expr), Box::new(right));
+        }
+        self.depth -= levels;
+        Ok(expr)
+    }
+
+    fn or(&mut self) -> Result<Expr, CalcError> {
+        self.left_assoc(Parser::and, |punct| match punct {
+            "||" => Some(BinaryOp::Or),
+            _ => None,
+        })
+    }
+
+    fn and(&mut self) -> Result<Expr, CalcError> {
+        self.left_assoc(Parser::comparison, |punct| match punct {
+            "&&" => Some(BinaryOp::And),
+            _ => None,
+        })
+    }
+
+    fn comparison(&mut self) -> Result<Expr, CalcError> {
+        let op_for = |punct: &str| match punct {
+            "==" => Some(BinaryOp::Eq),
+            "!=" => Some(BinaryOp::Ne),
+            "<" => Some(BinaryOp::Lt),
+            "<=" => Some(BinaryOp::Le),
+            ">" => Some(BinaryOp::Gt),
+            ">=" => Some(BinaryOp::Ge),
+            _ => None,
+        };
+        let left = self.sum()?;
+        let op = match self.peek() {
+            Some(Token::Punct(punct)) => op_for(punct),
+            _ => None,
+        };
+        let Some(op) = op else {
+            return Ok(left);
+        };
+        self.pos += 1;
+        let right = self.sum()?;
+        if let Some((at, Token::Punct(punct))) = self.tokens.get(self.pos) {
+            if op_for(punct).is_some() {
+                return Err(CalcError::ChainedComparison { at: *at });
+            }
+        }
+        Ok(Expr::Binary(op, Box::new(left), Box::new(right)))
+    }
+
+    fn sum(&mut self) -> Result<Expr, CalcError> {
+        self.left_assoc(Parser::product, |punct| match punct {
+            "+" => Some(BinaryOp::Add),
+            "-" => Some(BinaryOp::Sub),
+            _ => None,
+        })
+    }
+
+    fn product(&mut self) -> Result<Expr, CalcError> {
+        self.left_assoc(Parser::unary, |punct| match punct {
+            "*" => Some(BinaryOp::Mul),
+            "/" => Some(BinaryOp::Div),
+            "%" => Some(BinaryOp::Rem),
+            _ => None,
+        })
+    }
+
+    fn unary(&mut self) -> Result<Expr, CalcError> {
+        let op = match self.peek() {
+            Some(Token::Punct("-")) => UnaryOp::Neg,
+            Some(Token::Punct("!")) => UnaryOp::Not,
+            _ => return self.atom(),
+        };
+        self.nest()?;
+        self.pos += 1;
+        let operand = self.unary()?;
+        self.depth -= 1;
+        Ok(Expr::Unary(op, Box::new(operand)))
+    }
+
+    fn atom(&mut self) -> Result<Expr, CalcError> {
+        let expr = match self.peek() {
+            Some(Token::Int(n)) => Expr::Int(*n),
+            Some(Token::Ident(name)) => match name.as_str() {
+                "true" => Expr::Bool(true),
+                "false" => Expr::Bool(false),
+                _ => Expr::Var(name.clone()),
+            },
+            Some(Token::Punct("(")) => {
+                self.nest()?;
+                self.pos += 1;
+                let inner = self.or()?;
+                self.depth -= 1;
+                if self.peek() != Some(&Token::Punct(")")) {
+                    return Err(self.unexpected("`)`"));
+                }
+                inner
+            }
+            Some(Token::Punct(_)) | None => return Err(self.unexpected("an expression")),
+        };
+        self.pos += 1;
+        Ok(expr)
+    }
+}
+
+/// Evaluates an expression, looking its variables up in `env`
+///
+/// `&&` and `||` only evaluate their right operand when the left one doesn't decide the
+/// result, as in Rust:
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::calc::{eval, parse, CalcError, Env, Value};
+///
+/// let env = Env::new();
+/// assert_eq!(eval(&parse("false && 1 / 0 == 0").unwrap(), &env), Ok(Value::Bool(false)));
+/// assert_eq!(eval(&parse("1 / 0 == 0").unwrap(), &env), Err(CalcError::DivisionByZero));
+/// ```
+pub fn eval(expr: &Expr, env: &Env) -> Result<Value, CalcError> {
+    match expr {
+        Expr::Int(n) => Ok(Value::Int(*n)),
+        Expr::Bool(b) => Ok(Value::Bool(*b)),
+        Expr::Var(name) => env
+            .get(name)
+            .copied()
+            .ok_or_else(|| CalcError::UnknownVariable(name.clone())),
+        Expr::Unary(op, operand) => unary(*op, eval(operand, env)?),
+        Expr::Binary(op @ (BinaryOp::And | BinaryOp::Or), left, right) => {
+            match (op, eval(left, env)?) {
+                (BinaryOp::And, Value::Bool(false)) => Ok(Value::Bool(false)),
+                (BinaryOp::Or, Value::Bool(true)) => Ok(Value::Bool(true)),
+                (_, Value::Bool(_)) => match eval(right, env)? {
+                    Value::Bool(b) => Ok(Value::Bool(b)),
+                    found => Err(bools_expected(*op, found)),
+                },
+                (_, found) => Err(bools_expected(*op, found)),
+            }
+        }
+        Expr::Binary(op, left, right) => binary(*op, eval(left, env)?, eval(right, env)?),
+    }
+}
+
+fn bools_expected(op: BinaryOp, found: Value) -> CalcError {
+    CalcError::TypeMismatch {
+        op: op.symbol(),
+        expected: "bools",
+        found,
+    }
+}
+
+fn unary(op: UnaryOp, value: Value) -> Result<Value, CalcError> {
+    match (op, value) {
+        (UnaryOp::Neg, Value::Int(n)) => n.checked_neg().map(Value::Int).ok_or(CalcError::Overflow),
+        (UnaryOp::Not, Value::Bool(b)) => Ok(Value::Bool(!b)),
+        (UnaryOp::Neg, found @ Value::Bool(_)) => Err(CalcError::TypeMismatch {
+            op: "-",
+            expected: "a number",
+            found,
+        }),
+        (UnaryOp::Not, found @ Value::Int(_)) => Err(CalcError::TypeMismatch {
+            op: "!",
+            expected: "a bool",
+            found,
+        }),
+    }
+}
+
+fn binary(op: BinaryOp, left: Value, right: Value) -> Result<Value, CalcError> {
+    use BinaryOp::*;
+    use Value::{Bool, Int};
+    let checked = |result: Option<i64>| result.map(Int).ok_or(CalcError::Overflow);
+    match (op, left, right) {
+        (Div | Rem, Int(_), Int(0)) => Err(CalcError::DivisionByZero),
+        (Add, Int(a), Int(b)) => checked(a.checked_add(b)),
+        (Sub, Int(a), Int(b)) => checked(a.checked_sub(b)),
+        (Mul, Int(a), Int(b)) => checked(a.checked_mul(b)),
+        (Div, Int(a), Int(b)) => checked(a.checked_div(b)),
+        (Rem, Int(a), Int(b)) => checked(a.checked_rem(b)),
+        (Lt, Int(a), Int(b)) => Ok(Bool(a < b)),
+        (Le, Int(a), Int(b)) => Ok(Bool(a <= b)),
+        (Gt, Int(a), Int(b)) => Ok(Bool(a > b)),
+        (Ge, Int(a), Int(b)) => Ok(Bool(a >= b)),
+        // Both sides must have the same type, as in Rust
+        (Eq, Int(a), Int(b)) => Ok(Bool(a == b)),
+        (Eq, Bool(a), Bool(b)) => Ok(Bool(a == b)),
+        (Ne, Int(a), Int(b)) => Ok(Bool(a != b)),
+        (Ne, Bool(a), Bool(b)) => Ok(Bool(a != b)),
+        (And, Bool(a), Bool(b)) => Ok(Bool(a && b)),
+        (Or, Bool(a), Bool(b)) => Ok(Bool(a || b)),
+        (Eq | Ne, Int(_), found) | (Eq | Ne, Bool(_), found) => Err(CalcError::TypeMismatch {
+            op: op.symbol(),
+            expected: "operands of the same type",
+            found,
+        }),
+        (And | Or, Int(_), _) => Err(bools_expected(op, left)),
+        (And | Or, _, found) => Err(bools_expected(op, found)),
+        (Add | Sub | Mul | Div | Rem | Lt | Le | Gt | Ge, _, _) => {
+            let found = match left {
+                Bool(_) => left,
+                Int(_) => right,
+            };
+            Err(CalcError::TypeMismatch {
+                op: op.symbol(),
+                expected: "numbers",
+                found,
+            })
+        }
+    }
+}
+
+/// Evaluates every part of an expression that doesn't depend on a variable
+///
+/// A part that would fail, such as `1 / 0`, is left as it is, so the error still comes
+/// up when the expression is evaluated. `false && x` and `true || x` fold to a constant
+/// because evaluation never looks at `x` either.
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::calc::{fold, parse};
+///
+/// let folded = fold(&parse("x * (2 + 3) - 10 / 2").unwrap());
+/// assert_eq!(folded.to_string(), "x * 5 - 5");
+/// assert_eq!(fold(&parse("true || x").unwrap()).to_string(), "true");
+/// assert_eq!(fold(&parse("x / (1 - 1)").unwrap()).to_string(), "x / 0");
+/// ```
+pub fn fold(expr: &Expr) -> Expr {
+    match expr {
+        Expr::Int(_) | Expr::Bool(_) | Expr::Var(_) => expr.clone(),
+        Expr::Unary(op, operand) => {
+            let operand = fold(operand);
+            match constant(&operand).map(|value| unary(*op, value)) {
+                Some(Ok(value)) => literal(value),
+                Some(Err(_)) | None => Expr::Unary(*op, Box::new(operand)),
+            }
+        }
+        Expr::Binary(op, left, right) => {
+            let left = fold(left);
+            match (op, constant(&left)) {
+                (BinaryOp::And, Some(Value::Bool(false))) => return Expr::Bool(false),
+                (BinaryOp::Or, Some(Value::Bool(true))) => return Expr::Bool(true),
+                _ => {}
+            }
+            let right = fold(right);
+            match (constant(&left), constant(&right)) {
+                (Some(l), Some(r)) => match binary(*op, l, r) {
+                    Ok(value) => literal(value),
+                    Err(_) => Expr::Binary(*op, Box::new(left), Box::new(right)),
+                },
+                _ => Expr::Binary(*op, Box::new(left), Box::new(right)),
+            }
+        }
+    }
+}
+
+// The value of a literal, or `None` for anything that needs evaluating
+fn constant(expr: &Expr) -> Option<Value> {
+    match expr {
+        Expr::Int(n) => Some(Value::Int(*n)),
+        Expr::Bool(b) => Some(Value::Bool(*b)),
+        Expr::Var(_) | Expr::Unary(..) | Expr::Binary(..) => None,
+    }
+}
+
+fn literal(value: Value) -> Expr {
+    match value {
+        Value::Int(n) => Expr::Int(n),
+        Value::Bool(b) => Expr::Bool(b),
+    }
+}
+
+/// Reads a variable binding such as `x=4` or `flag=true`
+///
+/// ```
+/// use rust_concepts::ch04_pattern_matching::calc::{binding, Value};
+///
+/// assert_eq!(binding("x=-4"), Ok(("x".to_string(), Value::Int(-4))));
+/// assert!(binding("x").is_err());
+/// ```
+pub fn binding(text: &str) -> Result<(String, Value), CalcError> {
+    let bad = || CalcError::BadBinding(text.to_string());
+    let Some((name, value)) = text.split_once('=') else {
+        return Err(bad());
+    };
+    let name = name.trim();
+    let valid_name = name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if !valid_name || matches!(name, "true" | "false") {
+        return Err(bad());
+    }
+    let value = match value.trim() {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        number => Value::Int(number.parse().map_err(|_| bad())?),
+    };
+    Ok((name.to_string(), value))
+}
@@ -0,0 +1,687 @@
+use crate::chapter::{Chapter, Example};
+use std::fmt::{self, Debug, Display};
+use std::io::{self, Write};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+/// Registers this chapter with the CLI
+pub struct Traits;
+
+impl Chapter for Traits {
+    fn id(&self) -> &'static str {
+        "05"
+    }
+
+    fn slug(&self) -> &'static str {
+        "traits"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ch05"]
+    }
+
+    fn title(&self) -> &'static str {
+        "Traits"
+    }
+
+    fn summary(&self) -> &'static str {
+        "Defining traits, trait bounds, impl Trait, trait objects and operator overloading"
+    }
+
+    fn source(&self) -> &'static str {
+        include_str!("ch05_traits.rs")
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        demo(out)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+/// This function demonstrates various aspects of Rust's trait system
+///
+/// ```
+/// let mut output = Vec::new();
+/// rust_concepts::ch05_traits::demo(&mut output).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.starts_with("Demonstrating Rust traits!"));
+/// ```
+pub fn demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Demonstrating Rust traits!")?;
+
+    for example in EXAMPLES {
+        (example.run)(out)?;
+    }
+
+    writeln!(
+        out,
+        "🧬 Traits implemented! The traits lesson is complete! 🧩🎉"
+    )?;
+
+    Ok(())
+}
+
+/// The examples of this chapter, in the order `demo` runs them
+pub static EXAMPLES: &[Example] = &[
+    Example {
+        id: 1,
+        title: "Defining and implementing a trait",
+        explanation: "A trait describes behavior that several types can share",
+        run: implementing_a_trait,
+    },
+    Example {
+        id: 2,
+        title: "Default methods",
+        explanation: "Tweet only implements summarize_author, and gets summarize for free from the trait",
+        run: default_methods,
+    },
+    Example {
+        id: 3,
+        title: "Trait bounds on generic functions",
+        explanation: "`T: PartialOrd + Copy` restricts largest to types that can be compared and copied",
+        run: trait_bounds,
+    },
+    Example {
+        id: 4,
+        title: "where clauses",
+        explanation: "When bounds get long, a where clause keeps the signature readable",
+        run: where_clauses,
+    },
+    Example {
+        id: 5,
+        title: "Returning impl Trait",
+        explanation: "The caller only knows that it gets \"some iterator of u32\", not the concrete type",
+        run: returning_impl_trait,
+    },
+    Example {
+        id: 6,
+        title: "Trait objects and vtables",
+        explanation: "A Vec can only hold one type, but Box<dyn Shape> lets it hold any type implementing Shape",
+        run: trait_objects,
+    },
+    Example {
+        id: 7,
+        title: "Associated types and consts",
+        explanation: "Container::Item is chosen once per implementation, and CAPACITY is a constant attached to the type",
+        run: associated_items,
+    },
+    Example {
+        id: 8,
+        title: "Supertraits",
+        explanation: "OutlinePrint requires Display, so its default method can rely on to_string()",
+        run: supertraits,
+    },
+    Example {
+        id: 9,
+        title: "Blanket implementations",
+        explanation: "Shout is implemented for every type that implements Display, all at once",
+        run: blanket_implementations,
+    },
+    Example {
+        id: 10,
+        title: "Operator overloading",
+        explanation: "Operators are traits from std::ops, so implementing Add lets us use `+` on our own types",
+        run: operator_overloading,
+    },
+];
+
+// Example 1: Defining and implementing a trait
+// A trait describes behavior that several types can share
+fn implementing_a_trait(out: &mut dyn Write) -> io::Result<()> {
+    // Both Tweet and NewsArticle implement Summary, each in its own way
+    let article = NewsArticle {
+        headline: String::from("Penguins win the Stanley Cup Championship!"),
+        location: String::from("Pittsburgh, PA, USA"),
+        author: String::from("Iceburgh"),
+    };
+    writeln!(out, "New article available! {}", article.summarize())?;
+    // Result: New article available! Penguins win the Stanley Cup Championship!, by Iceburgh (Pittsburgh, PA, USA)
+    // NewsArticle provides its own summarize, replacing the default one from the trait
+
+    Ok(())
+}
+
+// Example 2: Default methods
+// Tweet only implements summarize_author, and gets summarize for free from the trait
+fn default_methods(out: &mut dyn Write) -> io::Result<()> {
+    let tweet = Tweet {
+        username: String::from("horse_ebooks"),
+    };
+    writeln!(out, "1 new tweet: {}", tweet.summarize())?;
+    // Result: 1 new tweet: (Read more from @horse_ebooks...)
+    // Default methods can call other methods of the same trait, even ones without a default
+
+    Ok(())
+}
+
+// Example 3: Trait bounds on generic functions
+// `T: PartialOrd + Copy` restricts largest to types that can be compared and copied
+fn trait_bounds(out: &mut dyn Write) -> io::Result<()> {
+    let numbers = [34, 50, 25, 100, 65];
+    let chars = ['y', 'm', 'a', 'q'];
+    writeln!(
+        out,
+        "Largest number: {}, largest char: {}",
+        largest(&numbers),
+        largest(&chars)
+    )?;
+    // Result: Largest number: 100, largest char: y
+    // `&impl Summary` in argument position is shorthand for a generic parameter with a bound
+    let tweet = Tweet {
+        username: String::from("horse_ebooks"),
+    };
+    writeln!(out, "{}", notify(&tweet))?;
+    // Result: Breaking news! (Read more from @horse_ebooks...)
+    // The compiler generates a separate copy of each generic function for every type it's used with
+
+    Ok(())
+}
+
+// Example 4: where clauses
+// When bounds get long, a where clause keeps the signature readable
+fn where_clauses(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "{}", describe_pair(&"left", &vec![1, 2]))?;
+    // Result: left and [1, 2]
+    // The bounds are identical to writing `<T: Display, U: Debug>`, just moved after the signature
+
+    Ok(())
+}
+
+// Example 5: Returning impl Trait
+// The caller only knows that it gets "some iterator of u32", not the concrete type
+fn returning_impl_trait(out: &mut dyn Write) -> io::Result<()> {
+    let evens: Vec<u32> = even_numbers(5).collect();
+    writeln!(out, "First five even numbers: {:?}", evens)?;
+    // Result: First five even numbers: [0, 2, 4, 6, 8]
+    // The concrete type (a chain of iterator adapters) is still known to the compiler,
+    // so there's no dynamic dispatch or allocation involved
+
+    Ok(())
+}
+
+// Example 6: Trait objects and vtables
+// A Vec can only hold one type, but Box<dyn Shape> lets it hold any type implementing Shape
+fn trait_objects(out: &mut dyn Write) -> io::Result<()> {
+    let shapes: Vec<Box<dyn Shape>> = vec![
+        Box::new(Circle { radius: 1.0 }),
+        Box::new(Rectangle {
+            width: 2.0,
+            height: 3.0,
+        }),
+    ];
+    for shape in &shapes {
+        writeln!(out, "{} with area {:.2}", shape.name(), shape.area())?;
+    }
+    // Result:
+    // circle with area 3.14
+    // rectangle with area 6.00
+    writeln!(out, "Total area: {:.2}", total_area(&shapes))?;
+    // Result: Total area: 9.14
+    // A reference to a trait object is a "fat pointer": one pointer to the data and one to a vtable,
+    // a table of function pointers the program looks up at runtime to find the right `area`
+    writeln!(
+        out,
+        "&Circle is {} word(s), &dyn Shape is {} word(s)",
+        std::mem::size_of::<&Circle>() / std::mem::size_of::<usize>(),
+        std::mem::size_of::<&dyn Shape>() / std::mem::size_of::<usize>()
+    )?;
+    // Result: &Circle is 1 word(s), &dyn Shape is 2 word(s)
+
+    Ok(())
+}
+
+// Example 7: Associated types and consts
+// Container::Item is chosen once per implementation, and CAPACITY is a constant attached to the type
+fn associated_items(out: &mut dyn Write) -> io::Result<()> {
+    let mut shelf = Shelf::new();
+    for book in ["Dune", "Emma", "Ulysses", "Beloved"] {
+        if let Err(book) = shelf.add(String::from(book)) {
+            writeln!(
+                out,
+                "No room for {}: a shelf holds {} books",
+                book,
+                Shelf::CAPACITY
+            )?;
+        }
+    }
+    // Result: No room for Beloved: a shelf holds 3 books
+    writeln!(out, "Shelf: {:?}", shelf.items())?;
+    // Result: Shelf: ["Dune", "Emma", "Ulysses"]
+    // Iterator is the best-known trait with an associated type: Countdown sets `type Item = u32`
+    let countdown: Vec<u32> = Countdown::from(3).collect();
+    writeln!(out, "Countdown: {:?}", countdown)?;
+    // Result: Countdown: [3, 2, 1]
+
+    Ok(())
+}
+
+// Example 8: Supertraits
+// OutlinePrint requires Display, so its default method can rely on to_string()
+fn supertraits(out: &mut dyn Write) -> io::Result<()> {
+    // Implementing OutlinePrint for a type without Display would be a compile-time error
+    let v = Vector { x: 1, y: 3 };
+    write!(out, "{}", v.outline_print())?;
+    // Result:
+    // **********
+    // *        *
+    // * (1, 3) *
+    // *        *
+    // **********
+
+    Ok(())
+}
+
+// Example 9: Blanket implementations
+// Shout is implemented for every type that implements Display, all at once
+fn blanket_implementations(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "{} {}", "hello".shout(), 42.shout())?;
+    // Result: HELLO! 42!
+    // The standard library uses the same technique to implement ToString for every Display type
+
+    Ok(())
+}
+
+// Example 10: Operator overloading
+// Operators are traits from std::ops, so implementing Add lets us use `+` on our own types
+fn operator_overloading(out: &mut dyn Write) -> io::Result<()> {
+    let a = Vector { x: 1, y: 2 };
+    let b = Vector { x: 3, y: 4 };
+    writeln!(
+        out,
+        "a + b = {}, a - b = {}, a * 3 = {}, -a = {}",
+        a + b,
+        a - b,
+        a * 3,
+        -a
+    )?;
+    // Result: a + b = (4, 6), a - b = (-2, -2), a * 3 = (3, 6), -a = (-1, -2)
+    let mut c = a;
+    c += b;
+    writeln!(out, "c after c += b: {}", c)?;
+    // Result: c after c += b: (4, 6)
+    // Vector is Copy, so a and b are still usable after being passed to the operators
+
+    Ok(())
+}
+
+/// This trait defines shared behavior for anything that can be summarized
+/// summarize_author must be implemented, while summarize has a default implementation
+///
+/// ```
+/// use rust_concepts::ch05_traits::{Summary, Tweet};
+///
+/// let tweet = Tweet { username: String::from("ferris") };
+/// assert_eq!(tweet.summarize(), "(Read more from @ferris...)");
+/// ```
+pub trait Summary {
+    fn summarize_author(&self) -> String;
+
+    fn summarize(&self) -> String {
+        format!("(Read more from {}...)", self.summarize_author())
+    }
+}
+
+/// This struct overrides the default summarize method
+///
+/// ```
+/// use rust_concepts::ch05_traits::{NewsArticle, Summary};
+///
+/// let article = NewsArticle {
+///     headline: String::from("Rust 2024 released"),
+///     location: String::from("Internet"),
+///     author: String::from("The Rust Team"),
+/// };
+/// assert_eq!(article.summarize(), "Rust 2024 released, by The Rust Team (Internet)");
+/// ```
+pub struct NewsArticle {
+    pub headline: String,
+    pub location: String,
+    pub author: String,
+}
+
+impl Summary for NewsArticle {
+    fn summarize_author(&self) -> String {
+        self.author.clone()
+    }
+
+    fn summarize(&self) -> String {
+        format!("{}, by {} ({})", self.headline, self.author, self.location)
+    }
+}
+
+/// This struct relies on the default summarize method
+pub struct Tweet {
+    pub username: String,
+}
+
+impl Summary for Tweet {
+    fn summarize_author(&self) -> String {
+        format!("@{}", self.username)
+    }
+}
+
+/// This function demonstrates `impl Trait` in argument position
+/// It accepts a reference to any type that implements Summary
+///
+/// ```
+/// use rust_concepts::ch05_traits::{notify, Tweet};
+///
+/// let tweet = Tweet { username: String::from("ferris") };
+/// assert_eq!(notify(&tweet), "Breaking news! (Read more from @ferris...)");
+/// ```
+pub fn notify(item: &impl Summary) -> String {
+    format!("Breaking news! {}", item.summarize())
+}
+
+/// This function demonstrates trait bounds on a generic type
+/// PartialOrd lets us compare elements, and Copy lets us return one by value
+///
+/// # Panics
+///
+/// Panics if `list` is empty, because there is no first element to start from.
+///
+/// ```
+/// use rust_concepts::ch05_traits::largest;
+///
+/// assert_eq!(largest(&[34, 50, 25, 100, 65]), 100);
+/// assert_eq!(largest(&['y', 'm', 'a', 'q']), 'y');
+/// ```
+///
+/// ```should_panic
+/// rust_concepts::ch05_traits::largest::<i32>(&[]);
+/// ```
+pub fn largest<T: PartialOrd + Copy>(list: &[T]) -> T {
+    let mut largest = list[0];
+    for &item in list {
+        if item > largest {
+            largest = item;
+        }
+    }
+    largest
+}
+
+/// This function demonstrates a where clause
+/// The bounds are the same as `<T: Display, U: Debug>`, but listed after the signature
+///
+/// ```
+/// use rust_concepts::ch05_traits::describe_pair;
+///
+/// assert_eq!(describe_pair(&42, &"text"), "42 and \"text\"");
+/// ```
+pub fn describe_pair<T, U>(t: &T, u: &U) -> String
+where
+    T: Display,
+    U: Debug,
+{
+    format!("{} and {:?}", t, u)
+}
+
+/// This function demonstrates returning `impl Trait`
+/// The concrete iterator type is hidden from the caller
+///
+/// ```
+/// use rust_concepts::ch05_traits::even_numbers;
+///
+/// let evens: Vec<u32> = even_numbers(4).collect();
+/// assert_eq!(evens, [0, 2, 4, 6]);
+/// ```
+pub fn even_numbers(count: usize) -> impl Iterator<Item = u32> {
+    (0..).step_by(2).take(count)
+}
+
+/// This trait is used through trait objects (dyn Shape)
+/// Every method takes &self, so the trait is "dyn compatible" and can be put behind a pointer
+pub trait Shape {
+    fn area(&self) -> f64;
+    fn name(&self) -> &'static str;
+}
+
+pub struct Circle {
+    pub radius: f64,
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        std::f64::consts::PI * self.radius * self.radius
+    }
+
+    fn name(&self) -> &'static str {
+        "circle"
+    }
+}
+
+pub struct Rectangle {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Shape for Rectangle {
+    fn area(&self) -> f64 {
+        self.width * self.height
+    }
+
+    fn name(&self) -> &'static str {
+        "rectangle"
+    }
+}
+
+/// This function uses dynamic dispatch: each call to area goes through the shape's vtable
+///
+/// ```
+/// use rust_concepts::ch05_traits::{total_area, Rectangle, Shape};
+///
+/// let shapes: Vec<Box<dyn Shape>> = vec![
+///     Box::new(Rectangle { width: 2.0, height: 3.0 }),
+///     Box::new(Rectangle { width: 1.0, height: 4.0 }),
+/// ];
+/// assert_eq!(total_area(&shapes), 10.0);
+/// ```
+pub fn total_area(shapes: &[Box<dyn Shape>]) -> f64 {
+    shapes.iter().map(|shape| shape.area()).sum()
+}
+
+/// This trait demonstrates an associated type and an associated const
+/// Each implementation decides what it stores and how many items fit
+pub trait Container {
+    type Item;
+    const CAPACITY: usize;
+
+    fn items(&self) -> &[Self::Item];
+
+    fn is_full(&self) -> bool {
+        self.items().len() >= Self::CAPACITY
+    }
+}
+
+/// A bookshelf that can hold a fixed number of books
+///
+/// ```
+/// use rust_concepts::ch05_traits::{Container, Shelf};
+///
+/// let mut shelf = Shelf::new();
+/// for title in ["Dune", "Emma", "Ulysses"] {
+///     shelf.add(String::from(title)).unwrap();
+/// }
+/// assert!(shelf.is_full());
+/// // A full shelf hands the book back
+/// assert_eq!(shelf.add(String::from("Beloved")), Err(String::from("Beloved")));
+/// ```
+pub struct Shelf {
+    books: Vec<String>,
+}
+
+impl Shelf {
+    pub fn new() -> Self {
+        Shelf { books: Vec::new() }
+    }
+
+    /// Adds a book, handing it back if the shelf is already full
+    pub fn add(&mut self, book: String) -> Result<(), String> {
+        if self.is_full() {
+            return Err(book);
+        }
+        self.books.push(book);
+        Ok(())
+    }
+}
+
+impl Default for Shelf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Container for Shelf {
+    type Item = String;
+    const CAPACITY: usize = 3;
+
+    fn items(&self) -> &[String] {
+        &self.books
+    }
+}
+
+/// This iterator counts down to 1, demonstrating the associated type of Iterator
+///
+/// ```
+/// use rust_concepts::ch05_traits::Countdown;
+///
+/// let numbers: Vec<u32> = Countdown::from(3).collect();
+/// assert_eq!(numbers, [3, 2, 1]);
+/// ```
+pub struct Countdown {
+    remaining: u32,
+}
+
+impl From<u32> for Countdown {
+    fn from(start: u32) -> Self {
+        Countdown { remaining: start }
+    }
+}
+
+impl Iterator for Countdown {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        Some(self.remaining + 1)
+    }
+}
+
+/// This trait demonstrates a supertrait: only types that implement Display can implement it
+///
+/// ```
+/// use rust_concepts::ch05_traits::{OutlinePrint, Vector};
+///
+/// let outline = Vector { x: 1, y: 3 }.outline_print();
+/// assert_eq!(outline.lines().nth(2), Some("* (1, 3) *"));
+/// ```
+pub trait OutlinePrint: Display {
+    fn outline_print(&self) -> String {
+        let text = self.to_string();
+        let width = text.chars().count();
+        let border = "*".repeat(width + 4);
+        let blank = format!("*{}*", " ".repeat(width + 2));
+        format!("{border}\n{blank}\n* {text} *\n{blank}\n{border}\n")
+    }
+}
+
+/// This trait demonstrates a blanket implementation
+/// It's implemented below for every type that implements Display
+///
+/// ```
+/// use rust_concepts::ch05_traits::Shout;
+///
+/// assert_eq!("hello".shout(), "HELLO!");
+/// assert_eq!(42.shout(), "42!");
+/// ```
+pub trait Shout {
+    fn shout(&self) -> String;
+}
+
+impl<T: Display + ?Sized> Shout for T {
+    fn shout(&self) -> String {
+        format!("{}!", self.to_string().to_uppercase())
+    }
+}
+
+/// This struct demonstrates operator overloading
+/// It is Copy, so using it with an operator doesn't move it
+///
+/// ```
+/// use rust_concepts::ch05_traits::Vector;
+///
+/// let a = Vector { x: 1, y: 2 };
+/// let b = Vector { x: 3, y: 4 };
+/// assert_eq!(a + b, Vector { x: 4, y: 6 });
+/// assert_eq!(-(a * 2), Vector { x: -2, y: -4 });
+/// assert_eq!((b - a).to_string(), "(2, 2)");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Vector {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Display for Vector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl OutlinePrint for Vector {}
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, other: Vector) -> Vector {
+        Vector {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+
+    fn sub(self, other: Vector) -> Vector {
+        Vector {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+// The right-hand side of an operator doesn't have to be the same type
+impl Mul<i32> for Vector {
+    type Output = Vector;
+
+    fn mul(self, factor: i32) -> Vector {
+        Vector {
+            x: self.x * factor,
+            y: self.y * factor,
+        }
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Vector {
+        Vector {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+impl AddAssign for Vector {
+    fn add_assign(&mut self, other: Vector) {
+        *self = *self + other;
+    }
+}
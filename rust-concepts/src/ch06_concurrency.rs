@@ -0,0 +1,607 @@
+use crate::chapter::{Chapter, Example};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::thread;
+
+/// Registers this chapter with the CLI
+pub struct Concurrency;
+
+impl Chapter for Concurrency {
+    fn id(&self) -> &'static str {
+        "06"
+    }
+
+    fn slug(&self) -> &'static str {
+        "concurrency"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ch06", "parallelism"]
+    }
+
+    fn title(&self) -> &'static str {
+        "Concurrency"
+    }
+
+    fn summary(&self) -> &'static str {
+        "Threads, scoped threads, channels, locks, atomics and a parallel map-reduce"
+    }
+
+    fn source(&self) -> &'static str {
+        include_str!("ch06_concurrency.rs")
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        demo(out)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+/// This function demonstrates various aspects of Rust's concurrency story
+/// Threads run in an unpredictable order, so every example collects its results
+/// and prints them in a fixed order once all threads have finished
+///
+/// ```
+/// let mut output = Vec::new();
+/// rust_concepts::ch06_concurrency::demo(&mut output).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.starts_with("Demonstrating Rust concurrency!"));
+/// ```
+pub fn demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Demonstrating Rust concurrency!")?;
+
+    for example in EXAMPLES {
+        (example.run)(out)?;
+    }
+
+    writeln!(
+        out,
+        "🧵 All threads joined! The concurrency lesson is complete! 🔒🎉"
+    )?;
+
+    Ok(())
+}
+
+/// The examples of this chapter, in the order `demo` runs them
+pub static EXAMPLES: &[Example] = &[
+    Example {
+        id: 1,
+        title: "Spawning threads and joining them",
+        explanation: "thread::spawn starts a new OS thread and returns a JoinHandle",
+        run: spawning_threads,
+    },
+    Example {
+        id: 2,
+        title: "Moving data into a thread",
+        explanation: "A spawned thread may outlive the current function, so its closure must own what it uses",
+        run: move_closures,
+    },
+    Example {
+        id: 3,
+        title: "Scoped threads",
+        explanation: "thread::scope guarantees that every thread ends before the scope does, so the threads can borrow local data without `move` or Arc",
+        run: scoped_threads,
+    },
+    Example {
+        id: 4,
+        title: "Message passing with several producers",
+        explanation: "mpsc stands for \"multiple producer, single consumer\": the Sender can be cloned for each thread",
+        run: message_passing,
+    },
+    Example {
+        id: 5,
+        title: "Shared state with Arc<Mutex<T>>",
+        explanation: "Arc shares ownership between threads, Mutex makes sure only one thread changes the value at a time",
+        run: shared_state,
+    },
+    Example {
+        id: 6,
+        title: "Many readers, one writer with RwLock",
+        explanation: "RwLock allows any number of simultaneous readers, or a single writer",
+        run: rwlock,
+    },
+    Example {
+        id: 7,
+        title: "Waiting for a condition with Condvar",
+        explanation: "A Condvar lets a thread sleep until another thread signals that something changed",
+        run: condvar,
+    },
+    Example {
+        id: 8,
+        title: "Atomics and memory orderings",
+        explanation: "Atomic types can be updated from many threads without a lock",
+        run: atomics,
+    },
+    Example {
+        id: 9,
+        title: "Send and Sync",
+        explanation: "Send means ownership of a value can move to another thread; Sync means a shared reference (&T) can be used from several threads at once",
+        run: send_and_sync,
+    },
+    Example {
+        id: 10,
+        title: "A parallel map-reduce built only on std",
+        explanation: "Each worker maps and folds its own chunk, then the partial results are reduced in order",
+        run: parallel_map_reduce,
+    },
+];
+
+// Example 1: Spawning threads and joining them
+// thread::spawn starts a new OS thread and returns a JoinHandle
+fn spawning_threads(out: &mut dyn Write) -> io::Result<()> {
+    // join() waits for the thread to finish and hands back the value its closure returned
+    let squares = spawn_squares(5);
+    writeln!(out, "Squares computed on 5 threads: {:?}", squares)?;
+    // Result: Squares computed on 5 threads: [0, 1, 4, 9, 16]
+    // The threads may finish in any order, but we join the handles in order, so the results are ordered
+
+    Ok(())
+}
+
+// Example 2: Moving data into a thread
+// A spawned thread may outlive the current function, so its closure must own what it uses
+fn move_closures(out: &mut dyn Write) -> io::Result<()> {
+    // The `move` keyword transfers ownership of `names` into the thread
+    let names = [String::from("Ferris"), String::from("Corro")];
+    let handle = thread::spawn(move || names.join(" & "));
+    writeln!(
+        out,
+        "Greeting from a thread: hello, {}!",
+        handle.join().unwrap()
+    )?;
+    // Result: Greeting from a thread: hello, Ferris & Corro!
+    // println!("{:?}", names); // This would not compile: `names` was moved into the thread
+    // compile_fail/ch06_concurrency/ has this mistake (E0382) and a spawn that forgets `move` (E0373)
+
+    Ok(())
+}
+
+// Example 3: Scoped threads
+// thread::scope guarantees that every thread ends before the scope does, so the threads can borrow local data without `move` or Arc
+fn scoped_threads(out: &mut dyn Write) -> io::Result<()> {
+    let data: Vec<i64> = (1..=100).collect();
+    writeln!(
+        out,
+        "Sum of 1..=100 using 4 scoped threads: {}",
+        parallel_sum(&data, 4)
+    )?;
+    // Result: Sum of 1..=100 using 4 scoped threads: 5050
+    // `data` is still owned by this function and usable after the scope ends
+    writeln!(out, "data still has {} elements", data.len())?;
+    // Result: data still has 100 elements
+
+    Ok(())
+}
+
+// Example 4: Message passing with several producers
+// mpsc stands for "multiple producer, single consumer": the Sender can be cloned for each thread
+fn message_passing(out: &mut dyn Write) -> io::Result<()> {
+    let messages = collect_from_producers(3, 2);
+    writeln!(out, "Received {} messages", messages.len())?;
+    // Result: Received 6 messages
+    writeln!(out, "Sorted: {:?}", messages)?;
+    // Result: Sorted: ["producer 0: message 0", "producer 0: message 1", "producer 1: message 0", "producer 1: message 1", "producer 2: message 0", "producer 2: message 1"]
+    // The receiving loop ends once every Sender has been dropped, which is how the consumer knows it's done
+
+    Ok(())
+}
+
+// Example 5: Shared state with Arc<Mutex<T>>
+// Arc shares ownership between threads, Mutex makes sure only one thread changes the value at a time
+fn shared_state(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "Counter after 8 threads x 1000 increments: {}",
+        mutex_counter(8, 1000)
+    )?;
+    // Result: Counter after 8 threads x 1000 increments: 8000
+    // The lock is released automatically when the MutexGuard goes out of scope
+
+    Ok(())
+}
+
+// Example 6: Many readers, one writer with RwLock
+// RwLock allows any number of simultaneous readers, or a single writer
+fn rwlock(out: &mut dyn Write) -> io::Result<()> {
+    let sums = rwlock_readers(&[1, 2, 3], 3);
+    writeln!(out, "Each reader saw a total of: {:?}", sums)?;
+    // Result: Each reader saw a total of: [6, 6, 6]
+    // The writer finished before the readers started, so every reader sees the same data
+
+    Ok(())
+}
+
+// Example 7: Waiting for a condition with Condvar
+// A Condvar lets a thread sleep until another thread signals that something changed
+fn condvar(out: &mut dyn Write) -> io::Result<()> {
+    let latch = Arc::new(Latch::new());
+    let worker_latch = Arc::clone(&latch);
+    let worker = thread::spawn(move || {
+        worker_latch.wait();
+        "worker woke up after the latch opened"
+    });
+    latch.open();
+    writeln!(out, "{}", worker.join().unwrap())?;
+    // Result: worker woke up after the latch opened
+    writeln!(out, "Latch is open: {}", latch.is_open())?;
+    // Result: Latch is open: true
+    // Latch::wait checks the flag in a loop, so spurious wakeups and "open before wait" are both handled
+
+    Ok(())
+}
+
+// Example 8: Atomics and memory orderings
+// Atomic types can be updated from many threads without a lock
+fn atomics(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "Atomic counter after 8 threads x 1000 increments: {}",
+        atomic_counter(8, 1000)
+    )?;
+    // Result: Atomic counter after 8 threads x 1000 increments: 8000
+    // Relaxed is enough for a counter: we only need each increment to be atomic, not ordered
+    writeln!(
+        out,
+        "Value published with Release/Acquire: {}",
+        publish_with_release_acquire(42)
+    )?;
+    // Result: Value published with Release/Acquire: 42
+    // The Release store of the flag makes the earlier write of the value visible to
+    // any thread that reads the flag with Acquire and sees `true`
+
+    Ok(())
+}
+
+// Example 9: Send and Sync
+// Send means ownership of a value can move to another thread; Sync means a shared reference (&T) can be used from several threads at once
+fn send_and_sync(out: &mut dyn Write) -> io::Result<()> {
+    // The compiler checks these marker traits whenever a value crosses a thread boundary
+    assert_send::<Arc<Mutex<i32>>>();
+    assert_sync::<Arc<Mutex<i32>>>();
+    assert_send::<mpsc::Sender<i32>>();
+    // assert_send::<Rc<i32>>(); // This would not compile: Rc's reference count isn't atomic
+    let local = Rc::new(5);
+    writeln!(
+        out,
+        "Rc stays on one thread ({}), Arc<Mutex<T>> can be shared",
+        local
+    )?;
+    // Result: Rc stays on one thread (5), Arc<Mutex<T>> can be shared
+    // Types like Cell<T> are Send but not Sync, and MutexGuard is Sync but not Send
+
+    Ok(())
+}
+
+// Example 10: A parallel map-reduce built only on std
+// Each worker maps and folds its own chunk, then the partial results are reduced in order
+fn parallel_map_reduce(out: &mut dyn Write) -> io::Result<()> {
+    let lines = [
+        "the quick brown fox",
+        "jumps over the lazy dog",
+        "the dog sleeps",
+    ];
+    let counts = map_reduce(
+        &lines,
+        2,
+        BTreeMap::new(),
+        |line| word_counts(line),
+        merge_counts,
+    );
+    writeln!(out, "Word counts: {:?}", counts)?;
+    // Result: Word counts: {"brown": 1, "dog": 2, "fox": 1, "jumps": 1, "lazy": 1, "over": 1, "quick": 1, "sleeps": 1, "the": 3}
+    let data: Vec<i64> = (1..=100).collect();
+    let sum_of_squares = map_reduce(&data, 4, 0, |x| x * x, |a, b| a + b);
+    writeln!(out, "Sum of squares of 1..=100: {}", sum_of_squares)?;
+    // Result: Sum of squares of 1..=100: 338350
+    // Because reduce is associative, the result doesn't depend on how the work was split
+
+    Ok(())
+}
+
+/// This function spawns one thread per number and collects the squares in order
+///
+/// ```
+/// use rust_concepts::ch06_concurrency::spawn_squares;
+///
+/// assert_eq!(spawn_squares(4), [0, 1, 4, 9]);
+/// ```
+pub fn spawn_squares(count: u64) -> Vec<u64> {
+    let handles: Vec<thread::JoinHandle<u64>> =
+        (0..count).map(|i| thread::spawn(move || i * i)).collect();
+    // join() returns Err if the thread panicked
+    handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .collect()
+}
+
+/// This function sums a slice by splitting it between scoped threads
+/// The threads borrow `data` directly, which is only possible because the scope outlives them
+///
+/// ```
+/// use rust_concepts::ch06_concurrency::parallel_sum;
+///
+/// let data: Vec<i64> = (1..=100).collect();
+/// assert_eq!(parallel_sum(&data, 4), 5050);
+/// ```
+pub fn parallel_sum(data: &[i64], threads: usize) -> i64 {
+    let chunk_size = data.len().div_ceil(threads.max(1)).max(1);
+    thread::scope(|s| {
+        let handles: Vec<_> = data
+            .chunks(chunk_size)
+            .map(|chunk| s.spawn(move || chunk.iter().sum::<i64>()))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).sum()
+    })
+}
+
+/// This function starts several producer threads that send messages over one channel
+/// The messages arrive interleaved, so they are sorted before being returned
+///
+/// ```
+/// use rust_concepts::ch06_concurrency::collect_from_producers;
+///
+/// let messages = collect_from_producers(2, 1);
+/// assert_eq!(messages, ["producer 0: message 0", "producer 1: message 0"]);
+/// ```
+pub fn collect_from_producers(producers: usize, messages_each: usize) -> Vec<String> {
+    let (tx, rx) = mpsc::channel();
+    for id in 0..producers {
+        let tx = tx.clone();
+        thread::spawn(move || {
+            for n in 0..messages_each {
+                tx.send(format!("producer {}: message {}", id, n)).unwrap();
+            }
+        });
+    }
+    // Drop the original sender, otherwise the receiver would wait for it forever
+    drop(tx);
+
+    let mut received: Vec<String> = rx.iter().collect();
+    received.sort();
+    received
+}
+
+/// This function increments a counter shared between threads through `Arc<Mutex<usize>>`
+///
+/// ```
+/// use rust_concepts::ch06_concurrency::mutex_counter;
+///
+/// assert_eq!(mutex_counter(4, 100), 400);
+/// ```
+pub fn mutex_counter(threads: usize, increments: usize) -> usize {
+    let counter = Arc::new(Mutex::new(0));
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let counter = Arc::clone(&counter);
+            thread::spawn(move || {
+                for _ in 0..increments {
+                    *counter.lock().unwrap() += 1;
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    let total = *counter.lock().unwrap();
+    total
+}
+
+/// This function fills a RwLock-protected Vec from a writer thread,
+/// then lets several reader threads sum it at the same time
+///
+/// ```
+/// use rust_concepts::ch06_concurrency::rwlock_readers;
+///
+/// assert_eq!(rwlock_readers(&[1, 2, 3], 2), [6, 6]);
+/// ```
+pub fn rwlock_readers(values: &[i32], readers: usize) -> Vec<i32> {
+    let shared = RwLock::new(Vec::new());
+    thread::scope(|s| {
+        s.spawn(|| shared.write().unwrap().extend_from_slice(values))
+            .join()
+            .unwrap();
+        let handles: Vec<_> = (0..readers)
+            .map(|_| s.spawn(|| shared.read().unwrap().iter().sum::<i32>()))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    })
+}
+
+/// A one-shot gate: threads calling wait() block until some thread calls open()
+/// It pairs a `Mutex<bool>` holding the state with a Condvar used to signal changes
+///
+/// ```
+/// use rust_concepts::ch06_concurrency::Latch;
+/// use std::thread;
+///
+/// let latch = Latch::new();
+/// thread::scope(|s| {
+///     s.spawn(|| latch.wait());
+///     latch.open();
+/// });
+/// assert!(latch.is_open());
+/// ```
+pub struct Latch {
+    open: Mutex<bool>,
+    changed: Condvar,
+}
+
+impl Latch {
+    pub fn new() -> Self {
+        Latch {
+            open: Mutex::new(false),
+            changed: Condvar::new(),
+        }
+    }
+
+    /// Opens the latch and wakes every waiting thread
+    pub fn open(&self) {
+        *self.open.lock().unwrap() = true;
+        self.changed.notify_all();
+    }
+
+    /// Blocks until the latch is open
+    /// wait() releases the lock while sleeping and re-acquires it before returning
+    pub fn wait(&self) {
+        let mut open = self.open.lock().unwrap();
+        while !*open {
+            open = self.changed.wait(open).unwrap();
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        *self.open.lock().unwrap()
+    }
+}
+
+impl Default for Latch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// This function increments an AtomicUsize from several threads without any lock
+///
+/// ```
+/// use rust_concepts::ch06_concurrency::atomic_counter;
+///
+/// assert_eq!(atomic_counter(4, 250), 1000);
+/// ```
+pub fn atomic_counter(threads: usize, increments: usize) -> usize {
+    let counter = AtomicUsize::new(0);
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                for _ in 0..increments {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+    counter.load(Ordering::Relaxed)
+}
+
+/// This function publishes a value from one thread to another using Release/Acquire ordering
+/// The reader spins until it sees the flag, so it never reads the value too early
+///
+/// ```
+/// use rust_concepts::ch06_concurrency::publish_with_release_acquire;
+///
+/// assert_eq!(publish_with_release_acquire(42), 42);
+/// ```
+pub fn publish_with_release_acquire(value: u64) -> u64 {
+    let data = AtomicU64::new(0);
+    let ready = AtomicBool::new(false);
+    thread::scope(|s| {
+        let reader = s.spawn(|| {
+            while !ready.load(Ordering::Acquire) {
+                std::hint::spin_loop();
+            }
+            data.load(Ordering::Relaxed)
+        });
+        s.spawn(|| {
+            data.store(value, Ordering::Relaxed);
+            ready.store(true, Ordering::Release);
+        });
+        reader.join().unwrap()
+    })
+}
+
+/// These functions only compile if T implements Send or Sync
+/// They have no runtime effect: the check happens entirely in the type system
+///
+/// ```
+/// use rust_concepts::ch06_concurrency::{assert_send, assert_sync};
+/// use std::sync::Arc;
+///
+/// assert_send::<Arc<i32>>();
+/// assert_sync::<Arc<i32>>();
+/// ```
+pub fn assert_send<T: Send>() {}
+
+/// The Sync half of the pair above: compiles only if `&T` can be shared between threads
+///
+/// ```
+/// use rust_concepts::ch06_concurrency::assert_sync;
+///
+/// assert_sync::<std::sync::Mutex<i32>>();
+/// ```
+pub fn assert_sync<T: Sync>() {}
+
+/// This function splits `items` between worker threads, maps and folds each chunk,
+/// then reduces the partial results in chunk order
+/// `reduce` should be associative with `identity` as its neutral element
+///
+/// ```
+/// use rust_concepts::ch06_concurrency::map_reduce;
+///
+/// let numbers: Vec<u64> = (1..=10).collect();
+/// let sum_of_squares = map_reduce(&numbers, 3, 0, |n| n * n, |a, b| a + b);
+/// assert_eq!(sum_of_squares, 385);
+/// ```
+pub fn map_reduce<T, R, M, F>(items: &[T], workers: usize, identity: R, map: M, reduce: F) -> R
+where
+    T: Sync,
+    R: Send + Clone,
+    M: Fn(&T) -> R + Sync,
+    F: Fn(R, R) -> R + Sync,
+{
+    let chunk_size = items.len().div_ceil(workers.max(1)).max(1);
+    let (map, reduce) = (&map, &reduce);
+    let partials: Vec<R> = thread::scope(|s| {
+        let handles: Vec<_> = items
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let identity = identity.clone();
+                s.spawn(move || chunk.iter().map(map).fold(identity, reduce))
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    partials.into_iter().fold(identity, reduce)
+}
+
+/// Counts the words in one line of text (the "map" step of the word count)
+///
+/// ```
+/// use rust_concepts::ch06_concurrency::word_counts;
+///
+/// let counts = word_counts("the cat and The hat");
+/// assert_eq!(counts["the"], 2);
+/// assert_eq!(counts["cat"], 1);
+/// ```
+pub fn word_counts(line: &str) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for word in line.split_whitespace() {
+        *counts.entry(word.to_lowercase()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Merges two word counts (the "reduce" step of the word count)
+///
+/// ```
+/// use rust_concepts::ch06_concurrency::{merge_counts, word_counts};
+///
+/// let merged = merge_counts(word_counts("a b"), word_counts("b c"));
+/// assert_eq!(merged["b"], 2);
+/// assert_eq!(merged.len(), 3);
+/// ```
+pub fn merge_counts(
+    mut a: BTreeMap<String, usize>,
+    b: BTreeMap<String, usize>,
+) -> BTreeMap<String, usize> {
+    for (word, count) in b {
+        *a.entry(word).or_insert(0) += count;
+    }
+    a
+}
@@ -0,0 +1,594 @@
+use crate::chapter::{Chapter, Example};
+use std::cell::Cell;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::num::ParseIntError;
+use std::panic::{self, PanicHookInfo, UnwindSafe};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Registers this chapter with the CLI
+pub struct ErrorHandling;
+
+impl Chapter for ErrorHandling {
+    fn id(&self) -> &'static str {
+        "07"
+    }
+
+    fn slug(&self) -> &'static str {
+        "error_handling"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ch07", "errors"]
+    }
+
+    fn title(&self) -> &'static str {
+        "Error Handling"
+    }
+
+    fn summary(&self) -> &'static str {
+        "Result and Option, custom errors, the ? operator, error chains and panics"
+    }
+
+    fn source(&self) -> &'static str {
+        include_str!("ch07_error_handling.rs")
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        demo(out)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+/// This function demonstrates various aspects of Rust's error handling
+///
+/// ```
+/// let mut output = Vec::new();
+/// rust_concepts::ch07_error_handling::demo(&mut output).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.starts_with("Demonstrating Rust error handling!"));
+/// ```
+pub fn demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Demonstrating Rust error handling!")?;
+
+    for example in EXAMPLES {
+        (example.run)(out)?;
+    }
+
+    writeln!(
+        out,
+        "🛟 Errors handled! The error handling lesson is complete! ✅🎉"
+    )?;
+
+    Ok(())
+}
+
+/// The examples of this chapter, in the order `demo` runs them
+pub static EXAMPLES: &[Example] = &[
+    Example {
+        id: 1,
+        title: "Result and match",
+        explanation: "Functions that can fail return Result<T, E>: Ok(value) on success, Err(error) on failure",
+        run: result_and_match,
+    },
+    Example {
+        id: 2,
+        title: "The ? operator and From conversions",
+        explanation: "`?` returns early with the error if there is one, converting it with From::from on the way",
+        run: question_mark_operator,
+    },
+    Example {
+        id: 3,
+        title: "A custom error enum",
+        explanation: "Each variant describes one way things can go wrong, and Display turns it into a message",
+        run: custom_error_enum,
+    },
+    Example {
+        id: 4,
+        title: "Chains of errors with source()",
+        explanation: "A higher-level error can wrap the lower-level error that caused it",
+        run: error_chains,
+    },
+    Example {
+        id: 5,
+        title: "Box<dyn Error>",
+        explanation: "When a function can fail in several unrelated ways, Box<dyn Error> accepts any error type",
+        run: boxed_errors,
+    },
+    Example {
+        id: 6,
+        title: "Option and its combinators",
+        explanation: "Option<T> is for values that may be absent, where absence isn't an error",
+        run: option_combinators,
+    },
+    Example {
+        id: 7,
+        title: "Panics versus recoverable errors",
+        explanation: "A panic is for bugs: broken invariants the caller can't sensibly recover from",
+        run: panics_versus_errors,
+    },
+    Example {
+        id: 8,
+        title: "Catching a panic with catch_unwind",
+        explanation: "catch_unwind stops a panic from unwinding further and turns it into an Err",
+        run: catching_panics,
+    },
+];
+
+// Example 1: Result and match
+// Functions that can fail return Result<T, E>: Ok(value) on success, Err(error) on failure
+fn result_and_match(out: &mut dyn Write) -> io::Result<()> {
+    // The caller has to decide what to do with both cases before it can use the value
+    for input in ["8080", "http"] {
+        match parse_port(input) {
+            Ok(port) => writeln!(out, "Parsed port {}", port)?,
+            Err(e) => writeln!(out, "Could not parse {:?}: {}", input, error_chain(&e))?,
+        }
+    }
+    // Result:
+    // Parsed port 8080
+    // Could not parse "http": invalid number: invalid digit found in string
+
+    Ok(())
+}
+
+// Example 2: The ? operator and From conversions
+// `?` returns early with the error if there is one, converting it with From::from on the way
+fn question_mark_operator(out: &mut dyn Write) -> io::Result<()> {
+    // parse_config uses `?` on a ParseIntError, which becomes a ConfigError automatically
+    let config = parse_config("port = 3000\nworkers = 4");
+    writeln!(out, "Config: {:?}", config)?;
+    // Result: Config: Ok(Config { port: 3000, workers: 4 })
+    let config = parse_config("port = 3000\nworkers = many");
+    writeln!(out, "Config: {:?}", config.map_err(|e| error_chain(&e)))?;
+    // Result: Config: Err("invalid number: invalid digit found in string")
+    // io::Error gets the same treatment: read_config's `?` on read_to_string converts it to ConfigError::Io
+    let invalid_utf8: &[u8] = &[0xff, 0xfe];
+    let config = read_config(invalid_utf8);
+    writeln!(out, "Config: {:?}", config.map_err(|e| error_chain(&e)))?;
+    // Result: Config: Err("could not read config: stream did not contain valid UTF-8")
+
+    Ok(())
+}
+
+// Example 3: A custom error enum
+// Each variant describes one way things can go wrong, and Display turns it into a message
+fn custom_error_enum(out: &mut dyn Write) -> io::Result<()> {
+    // Callers can also match on the variants to handle specific failures differently
+    let errors = [
+        ConfigError::MissingKey(String::from("port")),
+        ConfigError::OutOfRange {
+            key: String::from("port"),
+            value: 70000,
+            max: 65535,
+        },
+    ];
+    for error in &errors {
+        writeln!(out, "Error: {}", error)?;
+    }
+    // Result:
+    // Error: missing key `port`
+    // Error: `port` is 70000, but must be at most 65535
+
+    Ok(())
+}
+
+// Example 4: Chains of errors with source()
+// A higher-level error can wrap the lower-level error that caused it
+fn error_chains(out: &mut dyn Write) -> io::Result<()> {
+    // Walking source() from the outermost error gives the whole story
+    // Each Display message only describes its own layer; the cause is reached through source(),
+    // otherwise the same text would be printed twice when the chain is rendered
+    let error = load_config("app.conf", "port = eighty").unwrap_err();
+    writeln!(out, "{}", error_chain(&error))?;
+    // Result: failed to load `app.conf`: invalid number: invalid digit found in string
+    let causes = std::iter::successors(Some(&error as &dyn Error), |&e| e.source()).count();
+    writeln!(out, "The chain has {} errors", causes)?;
+    // Result: The chain has 3 errors
+
+    Ok(())
+}
+
+// Example 5: Box<dyn Error>
+// When a function can fail in several unrelated ways, Box<dyn Error> accepts any error type
+fn boxed_errors(out: &mut dyn Write) -> io::Result<()> {
+    // This is also what our own main() returns
+    // The concrete type is still there at runtime: downcast_ref can recover it
+    for args in [["add", "2", "40"], ["add", "2", "x"], ["mul", "2", "3"]] {
+        match run_command(&args) {
+            Ok(value) => writeln!(out, "{} => {}", args.join(" "), value)?,
+            Err(e) => writeln!(out, "{} => error: {}", args.join(" "), e)?,
+        }
+    }
+    // Result:
+    // add 2 40 => 42
+    // add 2 x => error: invalid digit found in string
+    // mul 2 3 => error: unknown command `mul`
+    let error = run_command(&["add", "2", "x"]).unwrap_err();
+    writeln!(
+        out,
+        "Is it a ParseIntError? {}",
+        error.downcast_ref::<ParseIntError>().is_some()
+    )?;
+    // Result: Is it a ParseIntError? true
+
+    Ok(())
+}
+
+// Example 6: Option and its combinators
+// Option<T> is for values that may be absent, where absence isn't an error
+fn option_combinators(out: &mut dyn Write) -> io::Result<()> {
+    // Combinators like map, and_then, filter and unwrap_or avoid nested matches
+    writeln!(out, "Initials: {:?}", initials("Grace Brewster Hopper"))?;
+    // Result: Initials: Some("GBH")
+    writeln!(out, "Initials: {:?}", initials(""))?;
+    // Result: Initials: None
+    let scores = [7, 12, 5, 20];
+    writeln!(
+        out,
+        "First even score squared: {}",
+        first_even_squared(&scores).unwrap_or(0)
+    )?;
+    // Result: First even score squared: 144
+    // ok_or converts an Option into a Result, so `?` can be used with a custom error
+    writeln!(
+        out,
+        "Missing key: {:?}",
+        find_value("port = 80", "host").map_err(|e| e.to_string())
+    )?;
+    // Result: Missing key: Err("missing key `host`")
+
+    Ok(())
+}
+
+// Example 7: Panics versus recoverable errors
+// A panic is for bugs: broken invariants the caller can't sensibly recover from
+fn panics_versus_errors(out: &mut dyn Write) -> io::Result<()> {
+    // If failure is an expected outcome, return Result or Option instead
+    writeln!(out, "10 / 2 = {:?}", checked_divide(10, 2))?;
+    // Result: 10 / 2 = Some(5)
+    writeln!(out, "10 / 0 = {:?}", checked_divide(10, 0))?;
+    // Result: 10 / 0 = None
+    // divide_or_panic(10, 0) would stop the thread with "attempt to divide by zero"
+
+    Ok(())
+}
+
+// Example 8: Catching a panic with catch_unwind
+// catch_unwind stops a panic from unwinding further and turns it into an Err
+fn catching_panics(out: &mut dyn Write) -> io::Result<()> {
+    // It is meant for boundaries such as thread pools or FFI, not for normal error handling
+    // quietly keeps the default panic message off the terminal, see below
+    let caught = quietly(|| catch_panic(|| divide_or_panic(10, 0)));
+    let fine = catch_panic(|| divide_or_panic(10, 5));
+    writeln!(out, "Caught: {:?}", caught)?;
+    // Result: Caught: Err("attempt to divide by zero")
+    writeln!(out, "No panic: {:?}", fine)?;
+    // Result: No panic: Ok(2)
+
+    Ok(())
+}
+
+/// This enum lists every way reading our small config format can fail
+/// Holding the underlying error (ParseIntError, io::Error) lets callers inspect the cause
+#[derive(Debug)]
+pub enum ConfigError {
+    MissingKey(String),
+    InvalidNumber(ParseIntError),
+    OutOfRange { key: String, value: i64, max: i64 },
+    Io(io::Error),
+}
+
+// Display is the message shown to users
+// Variants that wrap another error leave its message to source(), see error_chain
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::MissingKey(key) => write!(f, "missing key `{}`", key),
+            ConfigError::InvalidNumber(_) => write!(f, "invalid number"),
+            ConfigError::OutOfRange { key, value, max } => {
+                write!(f, "`{}` is {}, but must be at most {}", key, value, max)
+            }
+            ConfigError::Io(_) => write!(f, "could not read config"),
+        }
+    }
+}
+
+// Implementing Error makes ConfigError usable as Box<dyn Error> and as a source() of other errors
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::InvalidNumber(e) => Some(e),
+            ConfigError::Io(e) => Some(e),
+            ConfigError::MissingKey(_) | ConfigError::OutOfRange { .. } => None,
+        }
+    }
+}
+
+// These From implementations are what let `?` convert lower-level errors into ConfigError
+impl From<ParseIntError> for ConfigError {
+    fn from(e: ParseIntError) -> Self {
+        ConfigError::InvalidNumber(e)
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(e: io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
+
+/// The settings read from a config file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub port: u16,
+    pub workers: u32,
+}
+
+/// This function parses a TCP port, using `?` to convert the ParseIntError
+///
+/// ```
+/// use rust_concepts::ch07_error_handling::{parse_port, ConfigError};
+///
+/// assert_eq!(parse_port(" 8080 ").unwrap(), 8080);
+/// assert!(matches!(parse_port("eighty"), Err(ConfigError::InvalidNumber(_))));
+/// assert!(matches!(parse_port("70000"), Err(ConfigError::OutOfRange { .. })));
+/// ```
+pub fn parse_port(text: &str) -> Result<u16, ConfigError> {
+    let value: i64 = text.trim().parse()?;
+    if !(0..=65535).contains(&value) {
+        return Err(ConfigError::OutOfRange {
+            key: String::from("port"),
+            value,
+            max: 65535,
+        });
+    }
+    Ok(value as u16)
+}
+
+/// This function looks up `key` in "key = value" lines
+/// ok_or turns the Option from find_map into a Result with a meaningful error
+///
+/// ```
+/// use rust_concepts::ch07_error_handling::find_value;
+///
+/// assert_eq!(find_value("port = 80", "port").unwrap(), "80");
+/// assert_eq!(
+///     find_value("port = 80", "workers").unwrap_err().to_string(),
+///     "missing key `workers`"
+/// );
+/// ```
+pub fn find_value<'a>(text: &'a str, key: &str) -> Result<&'a str, ConfigError> {
+    text.lines()
+        .filter_map(|line| line.split_once('='))
+        .find_map(|(k, v)| (k.trim() == key).then(|| v.trim()))
+        .ok_or_else(|| ConfigError::MissingKey(key.to_string()))
+}
+
+/// This function parses a whole config, propagating the first error with `?`
+///
+/// ```
+/// use rust_concepts::ch07_error_handling::{parse_config, Config};
+///
+/// let config = parse_config("port = 8080\nworkers = 4").unwrap();
+/// assert_eq!(config, Config { port: 8080, workers: 4 });
+/// ```
+pub fn parse_config(text: &str) -> Result<Config, ConfigError> {
+    let port = parse_port(find_value(text, "port")?)?;
+    let workers = find_value(text, "workers")?.parse()?;
+    Ok(Config { port, workers })
+}
+
+/// This function reads a config from any reader; an io::Error also converts through `?`
+///
+/// ```
+/// use rust_concepts::ch07_error_handling::read_config;
+///
+/// let file: &[u8] = b"port = 80\nworkers = 2";
+/// assert_eq!(read_config(file).unwrap().workers, 2);
+/// ```
+pub fn read_config(mut reader: impl Read) -> Result<Config, ConfigError> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    parse_config(&text)
+}
+
+/// A higher-level error that records which file failed and why
+#[derive(Debug)]
+pub struct LoadError {
+    pub path: String,
+    pub cause: ConfigError,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to load `{}`", self.path)
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.cause)
+    }
+}
+
+/// This function wraps any ConfigError with the path it came from
+/// map_err is used instead of `?` alone because the extra context has to be added by hand
+///
+/// ```
+/// use rust_concepts::ch07_error_handling::load_config;
+///
+/// let error = load_config("app.conf", "port = 80").unwrap_err();
+/// assert_eq!(error.path, "app.conf");
+/// assert_eq!(error.to_string(), "failed to load `app.conf`");
+/// ```
+pub fn load_config(path: &str, contents: &str) -> Result<Config, LoadError> {
+    parse_config(contents).map_err(|cause| LoadError {
+        path: path.to_string(),
+        cause,
+    })
+}
+
+/// This function renders an error followed by all of its sources, separated by ": "
+///
+/// ```
+/// use rust_concepts::ch07_error_handling::{error_chain, load_config};
+///
+/// let error = load_config("app.conf", "port = x\nworkers = 1").unwrap_err();
+/// assert_eq!(
+///     error_chain(&error),
+///     "failed to load `app.conf`: invalid number: invalid digit found in string"
+/// );
+/// ```
+pub fn error_chain(error: &dyn Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+/// This function demonstrates `Box<dyn Error>`: it mixes a ParseIntError with a String error
+/// `?` boxes any error type, and `.into()` turns a String into a `Box<dyn Error>`
+///
+/// ```
+/// use rust_concepts::ch07_error_handling::run_command;
+///
+/// assert_eq!(run_command(&["add", "2", "3"]).unwrap(), 5);
+/// assert_eq!(run_command(&["mul"]).unwrap_err().to_string(), "unknown command `mul`");
+/// ```
+pub fn run_command(args: &[&str]) -> Result<i64, Box<dyn Error>> {
+    match args {
+        ["add", a, b] => Ok(a.parse::<i64>()? + b.parse::<i64>()?),
+        [command, ..] => Err(format!("unknown command `{}`", command).into()),
+        [] => Err("no command given".into()),
+    }
+}
+
+/// This function uses `?` on Option values: it returns None as soon as any step is None
+///
+/// ```
+/// use rust_concepts::ch07_error_handling::initials;
+///
+/// assert_eq!(initials("grace brewster hopper"), Some(String::from("GBH")));
+/// assert_eq!(initials("   "), None);
+/// ```
+pub fn initials(name: &str) -> Option<String> {
+    let mut words = name.split_whitespace();
+    let first = words.next()?.chars().next()?;
+    let rest: String = words.filter_map(|word| word.chars().next()).collect();
+    Some(format!("{}{}", first, rest).to_uppercase())
+}
+
+/// This function chains Option combinators instead of writing a loop with a match
+///
+/// ```
+/// use rust_concepts::ch07_error_handling::first_even_squared;
+///
+/// assert_eq!(first_even_squared(&[1, 3, 4, 6]), Some(16));
+/// assert_eq!(first_even_squared(&[1, 3]), None);
+/// ```
+pub fn first_even_squared(values: &[i32]) -> Option<i32> {
+    values
+        .iter()
+        .find(|v| *v % 2 == 0)
+        .and_then(|v| v.checked_mul(*v))
+}
+
+/// This function reports division by zero as a recoverable None
+///
+/// ```
+/// use rust_concepts::ch07_error_handling::checked_divide;
+///
+/// assert_eq!(checked_divide(10, 2), Some(5));
+/// assert_eq!(checked_divide(1, 0), None);
+/// ```
+pub fn checked_divide(a: i32, b: i32) -> Option<i32> {
+    a.checked_div(b)
+}
+
+/// This function treats division by zero as a bug and panics
+///
+/// ```should_panic
+/// use rust_concepts::ch07_error_handling::divide_or_panic;
+///
+/// assert_eq!(divide_or_panic(10, 2), 5);
+/// divide_or_panic(1, 0); // panics: "attempt to divide by zero"
+/// ```
+pub fn divide_or_panic(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        panic!("attempt to divide by zero");
+    }
+    a / b
+}
+
+/// This function runs `f` and converts a panic into an Err holding the panic message
+/// The payload of a panic is usually a &str or a String, depending on how panic! was called
+///
+/// ```
+/// use rust_concepts::ch07_error_handling::catch_panic;
+///
+/// assert_eq!(catch_panic(|| 1 + 1), Ok(2));
+/// assert_eq!(catch_panic(|| panic!("boom")), Err::<(), _>(String::from("boom")));
+/// ```
+pub fn catch_panic<F, R>(f: F) -> Result<R, String>
+where
+    F: FnOnce() -> R + UnwindSafe,
+{
+    panic::catch_unwind(f).map_err(|payload| {
+        if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            String::from("unknown panic")
+        }
+    })
+}
+
+thread_local! {
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+type PanicHook = Box<dyn Fn(&PanicHookInfo<'_>) + Sync + Send + 'static>;
+
+// How many quiet calls are running, and the hook that was in place before the first of them
+static QUIET_CALLS: Mutex<(usize, Option<Arc<PanicHook>>)> = Mutex::new((0, None));
+
+// Runs `f` without printing the message of a panic on this thread
+// The panic hook is shared by every thread, so silencing it outright would also silence
+// panics on other threads. Instead, while any quiet call is running, a hook that checks a
+// thread-local flag is installed, and the previous hook is put back when the last one returns.
+fn quietly<R>(f: impl FnOnce() -> R) -> R {
+    {
+        let mut calls = QUIET_CALLS.lock().unwrap_or_else(PoisonError::into_inner);
+        if calls.0 == 0 {
+            let previous_hook = Arc::new(panic::take_hook());
+            calls.1 = Some(Arc::clone(&previous_hook));
+            panic::set_hook(Box::new(move |info| {
+                if !QUIET.with(Cell::get) {
+                    previous_hook(info);
+                }
+            }));
+        }
+        calls.0 += 1;
+    }
+    QUIET.with(|quiet| quiet.set(true));
+    let result = f();
+    QUIET.with(|quiet| quiet.set(false));
+    let mut calls = QUIET_CALLS.lock().unwrap_or_else(PoisonError::into_inner);
+    calls.0 -= 1;
+    if calls.0 == 0 {
+        if let Some(previous_hook) = calls.1.take() {
+            panic::set_hook(Box::new(move |info| previous_hook(info)));
+        }
+    }
+    result
+}
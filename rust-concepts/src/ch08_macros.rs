@@ -0,0 +1,494 @@
+use crate::chapter::{Chapter, Example};
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, Write};
+
+// The procedural macros live in their own crate (rust-concepts-macros), because a proc-macro
+// crate is compiled for the compiler to run, not into our program
+// Re-exporting the derive next to the trait means one `use` brings in both
+pub use rust_concepts_macros::{traced, Describe};
+
+// #[macro_export] places the macro_rules! macros below at the crate root (`rust_concepts::my_vec!`),
+// so other crates can use them; this re-export also makes them available from this module
+pub use crate::{add_ten, btreemap, count, max, my_vec, rpn};
+
+/// Registers this chapter with the CLI
+pub struct Macros;
+
+impl Chapter for Macros {
+    fn id(&self) -> &'static str {
+        "08"
+    }
+
+    fn slug(&self) -> &'static str {
+        "macros"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ch08"]
+    }
+
+    fn title(&self) -> &'static str {
+        "Macros"
+    }
+
+    fn summary(&self) -> &'static str {
+        "Declarative macro_rules! macros and procedural derive and attribute macros"
+    }
+
+    fn source(&self) -> &'static str {
+        include_str!("ch08_macros.rs")
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        demo(out)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+/// This function demonstrates declarative and procedural macros
+///
+/// ```
+/// let mut output = Vec::new();
+/// rust_concepts::ch08_macros::demo(&mut output).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.starts_with("Demonstrating Rust macros!"));
+/// ```
+pub fn demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Demonstrating Rust macros!")?;
+
+    for example in EXAMPLES {
+        (example.run)(out)?;
+    }
+
+    writeln!(
+        out,
+        "🪄 Code that writes code! The macros lesson is complete! 📜🎉"
+    )?;
+
+    Ok(())
+}
+
+/// The examples of this chapter, in the order `demo` runs them
+pub static EXAMPLES: &[Example] = &[
+    Example {
+        id: 1,
+        title: "Repetitions",
+        explanation: "`$( ... ),*` matches a comma-separated list, and `$( ... )*` in the output repeats code for each item",
+        run: repetitions,
+    },
+    Example {
+        id: 2,
+        title: "Recursion",
+        explanation: "A macro can expand to another call of itself, peeling off one argument at a time",
+        run: recursion,
+    },
+    Example {
+        id: 3,
+        title: "Hygiene",
+        explanation: "Variables introduced inside a macro can't clash with the caller's variables",
+        run: hygiene,
+    },
+    Example {
+        id: 4,
+        title: "Generating items",
+        explanation: "Macros can generate whole items: newtype! defines a struct and its Display implementation",
+        run: generating_items,
+    },
+    Example {
+        id: 5,
+        title: "Internal rules",
+        explanation: "Rules starting with `@name` are helpers that callers aren't supposed to use directly",
+        run: internal_rules,
+    },
+    Example {
+        id: 6,
+        title: "A tt-muncher",
+        explanation: "rpn! reads reverse Polish notation one token at a time, keeping a stack in its own input",
+        run: tt_muncher,
+    },
+    Example {
+        id: 7,
+        title: "A derive macro",
+        explanation: "#[derive(Describe)] is a procedural macro: Rust code that runs inside the compiler, reads the struct's tokens and generates an `impl Describe` for it",
+        run: derive_macro,
+    },
+    Example {
+        id: 8,
+        title: "An attribute macro",
+        explanation: "#[traced(record_call)] rewrites gcd so that every call, including the recursive ones, is reported to record_call together with its arguments and result",
+        run: attribute_macro,
+    },
+];
+
+// Example 1: Repetitions
+// `$( ... ),*` matches a comma-separated list, and `$( ... )*` in the output repeats code for each item
+fn repetitions(out: &mut dyn Write) -> io::Result<()> {
+    let numbers: Vec<i32> = my_vec![1, 2, 3];
+    writeln!(out, "my_vec![1, 2, 3] = {:?}", numbers)?;
+    // Result: my_vec![1, 2, 3] = [1, 2, 3]
+    let ages = btreemap! {
+        "Ferris" => 8,
+        "Corro" => 5,
+    };
+    writeln!(out, "btreemap! = {:?}", ages)?;
+    // Result: btreemap! = {"Corro": 5, "Ferris": 8}
+    // Unlike a function, a macro can take any number of arguments of any type
+
+    Ok(())
+}
+
+// Example 2: Recursion
+// A macro can expand to another call of itself, peeling off one argument at a time
+fn recursion(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "max!(3, 9, 4) = {}", max!(3, 9, 4))?;
+    // Result: max!(3, 9, 4) = 9
+    writeln!(out, "max!(2.5) = {}", max!(2.5))?;
+    // Result: max!(2.5) = 2.5
+    // The recursion happens at compile time: the final code is just nested comparisons
+
+    Ok(())
+}
+
+// Example 3: Hygiene
+// Variables introduced inside a macro can't clash with the caller's variables
+fn hygiene(out: &mut dyn Write) -> io::Result<()> {
+    // add_ten! declares its own `a`, but the `a` we pass in still refers to ours
+    let a = 1;
+    writeln!(out, "add_ten!(a * 2) = {}", add_ten!(a * 2))?;
+    // Result: add_ten!(a * 2) = 12
+    // Without hygiene, `a * 2` would see the macro's `a = 10` and the result would be 30
+
+    Ok(())
+}
+
+// Example 4: Generating items
+// Macros can generate whole items: newtype! defines a struct and its Display implementation
+fn generating_items(out: &mut dyn Write) -> io::Result<()> {
+    // The `ty` and `ident` fragment specifiers let the macro accept a type and a name
+    let distance = Meters(42.195);
+    let weight = Kilograms(3.5);
+    writeln!(
+        out,
+        "A marathon is {} and a cat weighs {}",
+        distance, weight
+    )?;
+    // Result: A marathon is 42.195 m and a cat weighs 3.5 kg
+
+    Ok(())
+}
+
+// Example 5: Internal rules
+// Rules starting with `@name` are helpers that callers aren't supposed to use directly
+fn internal_rules(out: &mut dyn Write) -> io::Result<()> {
+    // count! uses an internal rule to replace every token with `1` and add them up
+    writeln!(out, "count!(a b c d) = {}", count!(a b c d))?;
+    // Result: count!(a b c d) = 4
+    const LEN: usize = count!(x y z);
+    writeln!(out, "The count is a constant expression: {}", LEN)?;
+    // Result: The count is a constant expression: 3
+
+    Ok(())
+}
+
+// Example 6: A tt-muncher
+// rpn! reads reverse Polish notation one token at a time, keeping a stack in its own input
+fn tt_muncher(out: &mut dyn Write) -> io::Result<()> {
+    // Each step "munches" the next token and calls itself with the rest
+    writeln!(out, "rpn!(3 4 + 2 *) = {}", rpn!(3 4 + 2 *))?;
+    // Result: rpn!(3 4 + 2 *) = 14
+    writeln!(out, "rpn!(10 2 8 * + 3 -) = {}", rpn!(10 2 8 * + 3 -))?;
+    // Result: rpn!(10 2 8 * + 3 -) = 23
+
+    Ok(())
+}
+
+// Example 7: A derive macro
+// #[derive(Describe)] is a procedural macro: Rust code that runs inside the compiler, reads the struct's tokens and generates an `impl Describe` for it
+fn derive_macro(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "{}", Book::describe())?;
+    // Result: struct Book { title: String, pages: u32, tags: Vec<String> }
+    writeln!(out, "{}", Rgb::describe())?;
+    // Result: struct Rgb(u8, u8, u8)
+    writeln!(out, "{}", Command::describe())?;
+    // Result: enum Command { Quit, Move { x: i32, y: i32 }, Say(String) }
+    writeln!(
+        out,
+        "{} is a {} with {} fields",
+        Book::type_name(),
+        Book::kind(),
+        Book::fields().len()
+    )?;
+    // Result: Book is a struct with 3 fields
+    for (name, ty) in Book::fields() {
+        writeln!(out, "  {} has type {}", name, ty)?;
+    }
+    // Result:
+    //   title has type String
+    //   pages has type u32
+    //   tags has type Vec<String>
+
+    Ok(())
+}
+
+// Example 8: An attribute macro
+// #[traced(record_call)] rewrites gcd so that every call, including the recursive ones, is reported to record_call together with its arguments and result
+fn attribute_macro(out: &mut dyn Write) -> io::Result<()> {
+    let result = gcd(48, 18);
+    writeln!(out, "gcd(48, 18) = {}", result)?;
+    // Result: gcd(48, 18) = 6
+    for call in take_calls() {
+        writeln!(out, "  traced: {}", call)?;
+    }
+    // Result:
+    //   traced: gcd(6, 0) -> 6
+    //   traced: gcd(12, 6) -> 6
+    //   traced: gcd(18, 12) -> 6
+    //   traced: gcd(48, 18) -> 6
+
+    Ok(())
+}
+
+/// This macro builds a Vec from a list of expressions, like the standard vec! macro
+/// `$(,)?` accepts an optional trailing comma
+///
+/// ```
+/// let numbers: Vec<i32> = rust_concepts::my_vec![1, 2, 3];
+/// assert_eq!(numbers, [1, 2, 3]);
+/// let empty: Vec<i32> = rust_concepts::my_vec![];
+/// assert!(empty.is_empty());
+/// ```
+#[macro_export]
+macro_rules! my_vec {
+    () => {
+        ::std::vec::Vec::new()
+    };
+    ($($element:expr),+ $(,)?) => {{
+        // count! (defined below) works out the length at compile time
+        let mut v = ::std::vec::Vec::with_capacity($crate::count!($($element)+));
+        $(v.push($element);)+
+        v
+    }};
+}
+
+/// This macro builds a BTreeMap from `key => value` pairs
+///
+/// ```
+/// let ages = rust_concepts::btreemap! { "Ferris" => 8, "Corro" => 5 };
+/// assert_eq!(ages.keys().copied().collect::<Vec<_>>(), ["Corro", "Ferris"]);
+/// ```
+#[macro_export]
+macro_rules! btreemap {
+    ($($key:expr => $value:expr),* $(,)?) => {{
+        let mut map = ::std::collections::BTreeMap::new();
+        $(map.insert($key, $value);)*
+        map
+    }};
+}
+
+/// This macro finds the largest of its arguments by recursing on the rest of the list
+///
+/// ```
+/// assert_eq!(rust_concepts::max!(3, 9, 4), 9);
+/// assert_eq!(rust_concepts::max!(2.5), 2.5);
+/// ```
+#[macro_export]
+macro_rules! max {
+    ($x:expr) => {
+        $x
+    };
+    ($x:expr, $($rest:expr),+) => {{
+        let first = $x;
+        let rest = $crate::max!($($rest),+);
+        if first > rest { first } else { rest }
+    }};
+}
+
+/// This macro declares its own `a`, which hygiene keeps separate from any `a` in $e
+///
+/// ```
+/// let a = 1;
+/// // The macro's own `a` (10) doesn't replace ours
+/// assert_eq!(rust_concepts::add_ten!(a * 2), 12);
+/// ```
+#[macro_export]
+macro_rules! add_ten {
+    ($e:expr) => {{
+        let a = 10;
+        $e + a
+    }};
+}
+
+// This macro generates a tuple struct wrapping a number, plus a Display impl showing its unit
+macro_rules! newtype {
+    ($(#[$meta:meta])* $name:ident($inner:ty), $unit:literal) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+        pub struct $name(pub $inner);
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{} {}", self.0, $unit)
+            }
+        }
+    };
+}
+
+// Doc comments are attributes, so the `$(#[$meta:meta])*` part of newtype! passes them through
+newtype!(
+    /// A distance in meters
+    Meters(f64),
+    "m"
+);
+newtype!(
+    /// A mass in kilograms
+    Kilograms(f64),
+    "kg"
+);
+
+/// This macro counts the token trees it is given
+/// The `@one` internal rule turns any single token into the number 1
+///
+/// ```
+/// const LEN: usize = rust_concepts::count!(x y z);
+/// assert_eq!(LEN, 3);
+/// ```
+#[macro_export]
+macro_rules! count {
+    (@one $_token:tt) => {
+        1usize
+    };
+    ($($token:tt)*) => {
+        0usize $(+ $crate::count!(@one $token))*
+    };
+}
+
+/// This macro evaluates reverse Polish notation with a tt-muncher
+/// The `@eval [stack] tokens...` rules hold the stack (top first) and the unread tokens
+/// Operator rules come first so that `-` is read as subtraction rather than a negative literal
+///
+/// ```
+/// assert_eq!(rust_concepts::rpn!(3 4 + 2 *), 14);
+/// assert_eq!(rust_concepts::rpn!(10 2 8 * + 3 -), 23);
+/// ```
+#[macro_export]
+macro_rules! rpn {
+    (@eval [$result:expr]) => {
+        $result
+    };
+    (@eval [$b:expr, $a:expr $(, $stack:expr)*] + $($rest:tt)*) => {
+        $crate::rpn!(@eval [($a + $b) $(, $stack)*] $($rest)*)
+    };
+    (@eval [$b:expr, $a:expr $(, $stack:expr)*] - $($rest:tt)*) => {
+        $crate::rpn!(@eval [($a - $b) $(, $stack)*] $($rest)*)
+    };
+    (@eval [$b:expr, $a:expr $(, $stack:expr)*] * $($rest:tt)*) => {
+        $crate::rpn!(@eval [($a * $b) $(, $stack)*] $($rest)*)
+    };
+    (@eval [$b:expr, $a:expr $(, $stack:expr)*] / $($rest:tt)*) => {
+        $crate::rpn!(@eval [($a / $b) $(, $stack)*] $($rest)*)
+    };
+    (@eval [$($stack:expr),*] $number:literal $($rest:tt)*) => {
+        $crate::rpn!(@eval [$number $(, $stack)*] $($rest)*)
+    };
+    ($($tokens:tt)+) => {
+        $crate::rpn!(@eval [] $($tokens)+)
+    };
+}
+
+/// This trait is implemented by #[derive(Describe)]
+/// It exposes information about a type that is otherwise only known to the compiler
+///
+/// ```
+/// use rust_concepts::ch08_macros::Describe;
+///
+/// // The derive comes along with the trait
+/// #[derive(Describe)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// assert_eq!(Point::describe(), "struct Point { x: i32, y: i32 }");
+/// assert_eq!(Point::fields(), [("x", "i32"), ("y", "i32")]);
+/// ```
+pub trait Describe {
+    /// The name of the type, e.g. "Book"
+    fn type_name() -> &'static str;
+
+    /// "struct" or "enum"
+    fn kind() -> &'static str;
+
+    /// (name, type) for each field, or (variant, payload) for each enum variant
+    fn fields() -> &'static [(&'static str, &'static str)];
+
+    /// The type written out as Rust, e.g. "struct Rgb(u8, u8, u8)"
+    fn describe() -> &'static str;
+}
+
+/// A struct with named fields, described by the derive macro
+#[derive(Describe, Debug, Clone, PartialEq)]
+pub struct Book {
+    pub title: String,
+    pub pages: u32,
+    pub tags: Vec<String>,
+}
+
+/// A tuple struct, whose fields are described as "0", "1" and "2"
+#[derive(Describe, Debug, Clone, Copy, PartialEq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+/// An enum with unit, struct-like and tuple-like variants
+#[derive(Describe, Debug, Clone, PartialEq)]
+pub enum Command {
+    Quit,
+    Move { x: i32, y: i32 },
+    Say(String),
+}
+
+// The calls recorded by #[traced], kept per thread so that concurrent tests don't mix them up
+thread_local! {
+    static CALLS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The recorder function passed to #[traced]
+pub fn record_call(entry: String) {
+    CALLS.with(|calls| calls.borrow_mut().push(entry));
+}
+
+/// Returns the calls recorded so far on this thread and clears the list
+///
+/// ```
+/// use rust_concepts::ch08_macros::{record_call, take_calls};
+///
+/// record_call(String::from("first"));
+/// assert_eq!(take_calls(), ["first"]);
+/// assert!(take_calls().is_empty());
+/// ```
+pub fn take_calls() -> Vec<String> {
+    CALLS.with(|calls| calls.take())
+}
+
+/// This function computes the greatest common divisor with Euclid's algorithm
+/// The attribute macro adds the tracing; the body is exactly what we would write without it
+///
+/// ```
+/// use rust_concepts::ch08_macros::{gcd, take_calls};
+///
+/// assert_eq!(gcd(12, 8), 4);
+/// assert_eq!(
+///     take_calls(),
+///     ["gcd(4, 0) -> 4", "gcd(8, 4) -> 4", "gcd(12, 8) -> 4"]
+/// );
+/// ```
+#[traced(record_call)]
+pub fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
@@ -0,0 +1,291 @@
+use crate::chapter::{Chapter, Example};
+use std::io::{self, Write};
+
+// `restaurant` is a separate library crate in this workspace (rust-concepts-restaurant),
+// added to Cargo.toml as a path dependency and renamed to `restaurant`
+// A glob import brings in everything the crate's prelude re-exports
+use restaurant::prelude::*;
+
+/// Registers this chapter with the CLI
+pub struct Modules;
+
+impl Chapter for Modules {
+    fn id(&self) -> &'static str {
+        "09"
+    }
+
+    fn slug(&self) -> &'static str {
+        "modules"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ch09", "crates"]
+    }
+
+    fn title(&self) -> &'static str {
+        "Modules and Crates"
+    }
+
+    fn summary(&self) -> &'static str {
+        "Module trees, visibility, paths, re-exports, cargo features and library crates"
+    }
+
+    fn source(&self) -> &'static str {
+        include_str!("ch09_modules.rs")
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        demo(out)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+/// This function demonstrates how Rust code is split into modules and crates
+///
+/// ```
+/// let mut output = Vec::new();
+/// rust_concepts::ch09_modules::demo(&mut output).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.starts_with("Demonstrating Rust modules and crates!"));
+/// ```
+pub fn demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Demonstrating Rust modules and crates!")?;
+
+    for example in EXAMPLES {
+        (example.run)(out)?;
+    }
+
+    writeln!(
+        out,
+        "📦 Everything in its place! The modules lesson is complete! 🗂️🎉"
+    )?;
+
+    Ok(())
+}
+
+/// The examples of this chapter, in the order `demo` runs them
+pub static EXAMPLES: &[Example] = &[
+    Example {
+        id: 1,
+        title: "Using an external crate",
+        explanation: "Cargo builds the restaurant library first, then makes it available to us by its (renamed) name",
+        run: external_crates,
+    },
+    Example {
+        id: 2,
+        title: "Re-exports",
+        explanation: "front_of_house is private, but lib.rs re-exports its child with `pub use front_of_house::hosting;`",
+        run: re_exports,
+    },
+    Example {
+        id: 3,
+        title: "Private struct fields",
+        explanation: "A public struct can still have private fields, which forces callers to use its constructor",
+        run: private_fields,
+    },
+    Example {
+        id: 4,
+        title: "pub(crate), pub(super) and pub(in path)",
+        explanation: "eat_at_restaurant runs inside the library, so it may use the library's restricted items",
+        run: restricted_visibility,
+    },
+    Example {
+        id: 5,
+        title: "Glob imports",
+        explanation: "`use restaurant::prelude::*;` at the top of this file brought in todays_special, prepare, ...",
+        run: glob_imports,
+    },
+    Example {
+        id: 6,
+        title: "Cargo features",
+        explanation: "Our Cargo.toml enables the library's `delivery` feature, so restaurant::delivery exists",
+        run: cargo_features,
+    },
+    Example {
+        id: 7,
+        title: "Module files and #[path]",
+        explanation: "`mod front_of_house;` loads front_of_house.rs, and its `mod hosting;` loads front_of_house/hosting.rs",
+        run: module_files,
+    },
+];
+
+// Example 1: Using an external crate
+// Cargo builds the restaurant library first, then makes it available to us by its (renamed) name
+fn external_crates(out: &mut dyn Write) -> io::Result<()> {
+    // Paths into another crate start with the crate's name
+    writeln!(out, "{}", restaurant::hosting::add_to_waitlist("Ferris"))?;
+    // Result: Ferris was added to the waitlist
+    // Inside the library, the crate is still called rust_concepts_restaurant
+    writeln!(out, "hosting lives in {}", restaurant::hosting::location())?;
+    // Result: hosting lives in rust_concepts_restaurant::front_of_house::hosting
+
+    Ok(())
+}
+
+// Example 2: Re-exports
+// front_of_house is private, but lib.rs re-exports its child with `pub use front_of_house::hosting;`
+fn re_exports(out: &mut dyn Write) -> io::Result<()> {
+    // so `restaurant::hosting` works while `restaurant::front_of_house::hosting` doesn't
+    // restaurant::front_of_house::hosting::add_to_waitlist("Ferris"); // error[E0603]: module `front_of_house` is private
+    // Breakfast is re-exported at the crate root too, so both paths name the same type
+    let meal: restaurant::back_of_house::Breakfast = restaurant::Breakfast::summer("Rye");
+    writeln!(out, "Re-exported type: {}", meal.describe())?;
+    // Result: Re-exported type: Rye toast with peaches
+
+    Ok(())
+}
+
+// Example 3: Private struct fields
+// A public struct can still have private fields, which forces callers to use its constructor
+fn private_fields(out: &mut dyn Write) -> io::Result<()> {
+    let mut meal = Breakfast::summer("Rye");
+    meal.toast = String::from("Wheat");
+    // meal.seasonal_fruit = String::from("blueberries"); // error[E0616]: field `seasonal_fruit` is private
+    writeln!(out, "I'd like {} please", meal.describe())?;
+    // Result: I'd like Wheat toast with peaches please
+    // In contrast, every variant of a public enum is public
+    writeln!(out, "{}", prepare(Appetizer::Salad))?;
+    // Result: The kitchen cooked salad
+
+    Ok(())
+}
+
+// Example 4: pub(crate), pub(super) and pub(in path)
+// eat_at_restaurant runs inside the library, so it may use the library's restricted items
+fn restricted_visibility(out: &mut dyn Write) -> io::Result<()> {
+    // The same rules apply to modules inside our own crate, see `school` below
+    for line in restaurant::eat_at_restaurant("Corro") {
+        writeln!(out, "  {}", line)?;
+    }
+    // Result:
+    //   Corro was added to the waitlist
+    //   Corro was seated (a card is held at the front desk)
+    //   Serving Wheat toast with peaches, exactly as ordered
+    //   The kitchen cooked soup
+    //   The kitchen cooked a fresh order, then rust_concepts_restaurant::front_of_house::hosting
+    writeln!(out, "{}", school::enroll("Ada"))?;
+    // Result: Ada enrolled (room assigned by the office, checked by the registrar)
+    // school::office::assign_room(); // error[E0603]: module `office` is private
+    writeln!(out, "Office hours: {}", school::registrar::office_hours())?;
+    // Result: Office hours: 9 to 5
+
+    Ok(())
+}
+
+// Example 5: Glob imports
+// `use restaurant::prelude::*;` at the top of this file brought in todays_special, prepare, ...
+fn glob_imports(out: &mut dyn Write) -> io::Result<()> {
+    // Globs are handy for preludes but make it harder to see where a name comes from
+    writeln!(out, "{}", todays_special())?;
+    // Result: Today's special is tomato soup
+    writeln!(out, "{}", add_to_waitlist("Corro"))?;
+    // Result: Corro was added to the waitlist
+
+    Ok(())
+}
+
+// Example 6: Cargo features
+// Our Cargo.toml enables the library's `delivery` feature, so restaurant::delivery exists
+fn cargo_features(out: &mut dyn Write) -> io::Result<()> {
+    // Unless `--features catering` is passed, restaurant::catering isn't even compiled
+    // The list depends on how the crate was built, so only the delivery line is a fixed result
+    let features = restaurant::enabled_features();
+    writeln!(out, "Enabled features: {:?}", features)?;
+    writeln!(out, "Delivery enabled: {}", features.contains(&"delivery"))?;
+    // Result: Delivery enabled: true
+    writeln!(out, "{}", restaurant::delivery::deliver("42 Crab Lane"))?;
+    // Result: Order on its way to 42 Crab Lane
+
+    Ok(())
+}
+
+// Example 7: Module files and #[path]
+// `mod front_of_house;` loads front_of_house.rs, and its `mod hosting;` loads front_of_house/hosting.rs
+fn module_files(out: &mut dyn Write) -> io::Result<()> {
+    // back_of_house uses the older back_of_house/mod.rs layout, which still works
+    // #[path = "specials_of_the_day.rs"] loads a file whose name doesn't match the module
+    writeln!(
+        out,
+        "specials lives in {} (soup: {})",
+        restaurant::specials::location(),
+        restaurant::specials::SOUP_OF_THE_DAY
+    )?;
+    // Result: specials lives in rust_concepts_restaurant::specials (soup: tomato)
+    // Modules can also be written inline, like the `school` module at the bottom of this file
+    writeln!(out, "school lives in {}", school::location())?;
+    // Result: school lives in rust_concepts::ch09_modules::school
+
+    Ok(())
+}
+
+/// An inline module tree showing the visibility modifiers inside our own crate
+///
+/// Restricted items stay hidden from other crates, even though `school` itself is public:
+///
+/// ```compile_fail,E0603
+/// // registrar is pub(crate), so it can only be used inside rust_concepts
+/// rust_concepts::ch09_modules::school::registrar::check();
+/// ```
+pub mod school {
+    /// Public: usable from anywhere that can reach `school`
+    ///
+    /// ```
+    /// use rust_concepts::ch09_modules::school;
+    ///
+    /// assert_eq!(
+    ///     school::enroll("Ada"),
+    ///     "Ada enrolled (room assigned by the office, checked by the registrar)"
+    /// );
+    /// ```
+    pub fn enroll(student: &str) -> String {
+        format!(
+            "{} enrolled (room assigned by the {}, checked by the {})",
+            student,
+            office::assign_room(),
+            registrar::check()
+        )
+    }
+
+    /// Reports the module's full path, which starts with the crate name
+    ///
+    /// ```
+    /// assert_eq!(
+    ///     rust_concepts::ch09_modules::school::location(),
+    ///     "rust_concepts::ch09_modules::school"
+    /// );
+    /// ```
+    pub fn location() -> &'static str {
+        module_path!()
+    }
+
+    // A private module: only `school` and its descendants can use it
+    mod office {
+        // pub(super): visible in `school`, the parent module
+        pub(super) fn assign_room() -> &'static str {
+            "office"
+        }
+    }
+
+    // pub(crate): visible anywhere in this crate, but not to other crates
+    pub(crate) mod registrar {
+        pub(crate) fn check() -> &'static str {
+            // `super::` goes up to `school`, `self::` stays in this module
+            let _room = super::office::assign_room();
+            self::name()
+        }
+
+        // pub(in path): visible anywhere inside crate::ch09_modules, but not elsewhere in the
+        // crate. A visibility can't exceed its module's, which is why this lives in the
+        // pub(crate) registrar rather than the private office, where it would act like pub(super)
+        pub(in crate::ch09_modules) fn office_hours() -> &'static str {
+            "9 to 5"
+        }
+
+        fn name() -> &'static str {
+            "registrar"
+        }
+    }
+}
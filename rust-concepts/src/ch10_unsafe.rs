@@ -0,0 +1,519 @@
+use crate::chapter::{Chapter, Example};
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use std::{ptr, slice, thread};
+
+/// Registers this chapter with the CLI
+pub struct UnsafeCode;
+
+impl Chapter for UnsafeCode {
+    fn id(&self) -> &'static str {
+        "10"
+    }
+
+    fn slug(&self) -> &'static str {
+        "unsafe"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ch10", "ffi"]
+    }
+
+    fn title(&self) -> &'static str {
+        "Unsafe Code"
+    }
+
+    fn summary(&self) -> &'static str {
+        "Raw pointers, unsafe functions and safe abstractions, unions and FFI with C"
+    }
+
+    fn source(&self) -> &'static str {
+        include_str!("ch10_unsafe.rs")
+    }
+
+    fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+        demo(out)
+    }
+
+    fn examples(&self) -> &'static [Example] {
+        EXAMPLES
+    }
+}
+
+/// This function demonstrates unsafe Rust and calling to and from C
+///
+/// ```
+/// let mut output = Vec::new();
+/// rust_concepts::ch10_unsafe::demo(&mut output).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.starts_with("Demonstrating Rust unsafe code!"));
+/// ```
+pub fn demo(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Demonstrating Rust unsafe code!")?;
+
+    for example in EXAMPLES {
+        (example.run)(out)?;
+    }
+
+    writeln!(
+        out,
+        "☢️ Handled with care! The unsafe code lesson is complete! 🧤🎉"
+    )?;
+
+    Ok(())
+}
+
+/// The examples of this chapter, in the order `demo` runs them
+pub static EXAMPLES: &[Example] = &[
+    Example {
+        id: 1,
+        title: "Raw pointers",
+        explanation: "Creating a raw pointer is safe; only dereferencing it requires `unsafe`",
+        run: raw_pointers,
+    },
+    Example {
+        id: 2,
+        title: "Unsafe functions and their safety contracts",
+        explanation: "An `unsafe fn` has preconditions the compiler can't check; the caller promises to meet them",
+        run: unsafe_functions,
+    },
+    Example {
+        id: 3,
+        title: "A safe abstraction over unsafe code",
+        explanation: "The borrow checker can't tell that two halves of one slice don't overlap, so split_at_mut needs raw pointers inside, but its signature is completely safe",
+        run: safe_abstractions,
+    },
+    Example {
+        id: 4,
+        title: "`static mut` versus atomics",
+        explanation: "Every access to a `static mut` is unsafe, because any thread could be using it at the same time",
+        run: static_mut_and_atomics,
+    },
+    Example {
+        id: 5,
+        title: "Unions",
+        explanation: "All fields of a union share the same memory; reading a field reinterprets the bytes",
+        run: unions,
+    },
+    Example {
+        id: 6,
+        title: "Calling C functions",
+        explanation: "`extern \"C\"` declares functions from the C standard library, which Rust links by default",
+        run: calling_c,
+    },
+    Example {
+        id: 7,
+        title: "Passing a Rust callback to C",
+        explanation: "qsort takes a function pointer; any `extern \"C\" fn` written in Rust can be passed to it",
+        run: c_callbacks,
+    },
+    Example {
+        id: 8,
+        title: "Calling Rust from C",
+        explanation: "rust_concepts_adler32 is exported with #[no_mangle] and extern \"C\", so C code can call it",
+        run: calling_rust_from_c,
+    },
+];
+
+// Example 1: Raw pointers
+// Creating a raw pointer is safe; only dereferencing it requires `unsafe`
+fn raw_pointers(out: &mut dyn Write) -> io::Result<()> {
+    // Unlike references, raw pointers may be null, dangling or aliased, and the compiler won't check
+    let mut number = 5;
+    // &raw makes a pointer straight from the place, without a reference in between;
+    // `&mut number as *mut i32` would create a unique borrow that invalidates `shared`
+    let shared = &raw const number;
+    let exclusive = &raw mut number;
+    // SAFETY: both pointers come from a live local variable and no reference to it exists,
+    // so writing through one and then reading through the other is allowed aliasing
+    unsafe {
+        *exclusive += 1;
+        writeln!(out, "Through the raw pointer: {}", *shared)?;
+    }
+    // Result: Through the raw pointer: 6
+    let values = [10, 20, 30];
+    let first = values.as_ptr();
+    // SAFETY: index 2 is inside the array, so the pointer stays in bounds
+    let third = unsafe { *first.add(2) };
+    writeln!(out, "Pointer arithmetic: {}", third)?;
+    // Result: Pointer arithmetic: 30
+    // as_ref turns a possibly-null pointer back into an Option<&T>
+    let null: *const i32 = ptr::null();
+    // SAFETY: a null pointer becomes None, and `shared` points to a live value
+    let (from_null, from_shared) = unsafe { (null.as_ref(), shared.as_ref()) };
+    writeln!(out, "null: {:?}, shared: {:?}", from_null, from_shared)?;
+    // Result: null: None, shared: Some(6)
+
+    Ok(())
+}
+
+// Example 2: Unsafe functions and their safety contracts
+// An `unsafe fn` has preconditions the compiler can't check; the caller promises to meet them
+fn unsafe_functions(out: &mut dyn Write) -> io::Result<()> {
+    // The `# Safety` section of its documentation is the contract
+    let scores = [90, 85, 77];
+    // SAFETY: 1 < scores.len()
+    let second = unsafe { get_unchecked(&scores, 1) };
+    writeln!(out, "get_unchecked(&scores, 1) = {}", second)?;
+    // Result: get_unchecked(&scores, 1) = 85
+    // get_unchecked(&scores, 1); // error[E0133]: call to unsafe function is unsafe and requires unsafe block
+    // (compile_fail/ch10_unsafe/unsafe_call_without_block.rs checks this)
+    // A safe function can check the precondition itself and then call the unsafe one
+    writeln!(
+        out,
+        "checked_get(&scores, 7) = {:?}",
+        checked_get(&scores, 7)
+    )?;
+    // Result: checked_get(&scores, 7) = None
+
+    Ok(())
+}
+
+// Example 3: A safe abstraction over unsafe code
+// The borrow checker can't tell that two halves of one slice don't overlap, so split_at_mut needs raw pointers inside, but its signature is completely safe
+fn safe_abstractions(out: &mut dyn Write) -> io::Result<()> {
+    let mut data = [1, 2, 3, 4, 5, 6];
+    let (left, right) = split_at_mut(&mut data, 2);
+    left[0] = 100;
+    right[0] = 300;
+    writeln!(out, "left: {:?}, right: {:?}", left, right)?;
+    // Result: left: [100, 2], right: [300, 4, 5, 6]
+    writeln!(out, "data: {:?}", data)?;
+    // Result: data: [100, 2, 300, 4, 5, 6]
+
+    Ok(())
+}
+
+// Example 4: `static mut` versus atomics
+// Every access to a `static mut` is unsafe, because any thread could be using it at the same time
+fn static_mut_and_atomics(out: &mut dyn Write) -> io::Result<()> {
+    // The demo can run on several threads at once (the tests do that), so it holds COUNTERS_LOCK
+    // while it uses the counters, which is how it keeps bump_unsafe_counter's safety contract
+    let _guard = COUNTERS_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    // SAFETY: we hold COUNTERS_LOCK, so no other thread touches UNSAFE_COUNTER
+    unsafe { UNSAFE_COUNTER = 0 };
+    for _ in 0..3 {
+        // SAFETY: as above
+        unsafe { bump_unsafe_counter() };
+    }
+    // SAFETY: as above
+    let unsafe_count = unsafe { UNSAFE_COUNTER };
+    writeln!(out, "static mut counter: {}", unsafe_count)?;
+    // Result: static mut counter: 3
+    // An atomic static gives the same global counter with no unsafe code, even across threads
+    ATOMIC_COUNTER.store(0, Ordering::Relaxed);
+    thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..250 {
+                    ATOMIC_COUNTER.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+    writeln!(
+        out,
+        "atomic counter: {}",
+        ATOMIC_COUNTER.load(Ordering::Relaxed)
+    )?;
+    // Result: atomic counter: 1000
+    drop(_guard);
+
+    Ok(())
+}
+
+// Example 5: Unions
+// All fields of a union share the same memory; reading a field reinterprets the bytes
+fn unions(out: &mut dyn Write) -> io::Result<()> {
+    // Rust doesn't track which field was written last, so reading is unsafe
+    let bits = float_bits(1.0);
+    writeln!(out, "1.0f32 is stored as {:#010x}", bits)?;
+    // Result: 1.0f32 is stored as 0x3f800000
+    writeln!(out, "Same as f32::to_bits: {}", bits == 1.0f32.to_bits())?;
+    // Result: Same as f32::to_bits: true
+    writeln!(out, "-2.5f32 is stored as {:#010x}", float_bits(-2.5))?;
+    // Result: -2.5f32 is stored as 0xc0200000
+
+    Ok(())
+}
+
+// Example 6: Calling C functions
+// `extern "C"` declares functions from the C standard library, which Rust links by default
+fn calling_c(out: &mut dyn Write) -> io::Result<()> {
+    // C strings end with a NUL byte, so we use CString/CStr rather than String/&str
+    let greeting = CString::new("Hello, C!").expect("no interior NUL bytes");
+    // SAFETY: greeting is a valid NUL-terminated string that outlives the call
+    let length = unsafe { strlen(greeting.as_ptr()) };
+    writeln!(out, "strlen(\"Hello, C!\") = {}", length)?;
+    // Result: strlen("Hello, C!") = 9
+    writeln!(out, "Back in Rust: {}", string_from_c(&greeting))?;
+    // Result: Back in Rust: Hello, C!
+    // CString::new rejects interior NUL bytes, which C would treat as the end of the string
+    writeln!(
+        out,
+        "CString::new(\"a\\0b\") is an error: {}",
+        CString::new("a\0b").is_err()
+    )?;
+    // Result: CString::new("a\0b") is an error: true
+
+    Ok(())
+}
+
+// Example 7: Passing a Rust callback to C
+// qsort takes a function pointer; any `extern "C" fn` written in Rust can be passed to it
+fn c_callbacks(out: &mut dyn Write) -> io::Result<()> {
+    let mut numbers = [42, -7, 19, 0, 3];
+    sort_with_qsort(&mut numbers);
+    writeln!(out, "qsort: {:?}", numbers)?;
+    // Result: qsort: [-7, 0, 3, 19, 42]
+
+    Ok(())
+}
+
+// Example 8: Calling Rust from C
+// rust_concepts_adler32 is exported with #[no_mangle] and extern "C", so C code can call it
+fn calling_rust_from_c(out: &mut dyn Write) -> io::Result<()> {
+    // c/ffi_harness.c calls it back, and build.rs compiles that file with the system C compiler
+    let text = CString::new("Wikipedia").expect("no interior NUL bytes");
+    // SAFETY: text is a valid NUL-terminated string that outlives the call
+    let from_c = unsafe { harness_checksum(text.as_ptr()) };
+    writeln!(
+        out,
+        "C called Rust: adler32(\"Wikipedia\") = {:#010x}",
+        from_c
+    )?;
+    // Result: C called Rust: adler32("Wikipedia") = 0x11e60398
+    writeln!(out, "Called from Rust: {:#010x}", adler32(b"Wikipedia"))?;
+    // Result: Called from Rust: 0x11e60398
+    // SAFETY: harness_self_test takes no arguments and only calls rust_concepts_adler32 with valid data
+    let failures = unsafe { harness_self_test() };
+    writeln!(out, "C harness self-test failures: {}", failures)?;
+    // Result: C harness self-test failures: 0
+
+    Ok(())
+}
+
+/// Returns the element at `index` without checking the bounds.
+///
+/// # Safety
+///
+/// `index` must be less than `values.len()`. Otherwise the read is out of bounds,
+/// which is undefined behavior.
+///
+/// ```
+/// use rust_concepts::ch10_unsafe::get_unchecked;
+///
+/// let values = [1, 2, 3];
+/// // SAFETY: 2 < values.len()
+/// assert_eq!(unsafe { get_unchecked(&values, 2) }, 3);
+/// ```
+pub unsafe fn get_unchecked(values: &[i32], index: usize) -> i32 {
+    // SAFETY: the caller guarantees index < values.len()
+    unsafe { *values.as_ptr().add(index) }
+}
+
+/// This function wraps get_unchecked with the bounds check, so it is safe to call
+///
+/// ```
+/// use rust_concepts::ch10_unsafe::checked_get;
+///
+/// assert_eq!(checked_get(&[1, 2, 3], 1), Some(2));
+/// assert_eq!(checked_get(&[1, 2, 3], 3), None);
+/// ```
+pub fn checked_get(values: &[i32], index: usize) -> Option<i32> {
+    if index < values.len() {
+        // SAFETY: we just checked that index is in bounds
+        Some(unsafe { get_unchecked(values, index) })
+    } else {
+        None
+    }
+}
+
+/// This function splits one mutable slice into two non-overlapping mutable slices
+/// It is how the standard library's slice::split_at_mut is implemented
+///
+/// ```
+/// use rust_concepts::ch10_unsafe::split_at_mut;
+///
+/// let mut data = [1, 2, 3, 4];
+/// let (left, right) = split_at_mut(&mut data, 1);
+/// // Both halves can be changed at the same time
+/// left[0] = 10;
+/// right[0] = 20;
+/// assert_eq!(data, [10, 20, 3, 4]);
+/// ```
+pub fn split_at_mut(values: &mut [i32], mid: usize) -> (&mut [i32], &mut [i32]) {
+    let len = values.len();
+    // The assert is what makes the unsafe block below sound for every input
+    assert!(
+        mid <= len,
+        "mid ({}) is past the end of the slice ({})",
+        mid,
+        len
+    );
+    let start = values.as_mut_ptr();
+
+    // SAFETY: [0, mid) and [mid, len) are in bounds and don't overlap,
+    // and both slices borrow from `values`, so they can't outlive it
+    unsafe {
+        (
+            slice::from_raw_parts_mut(start, mid),
+            slice::from_raw_parts_mut(start.add(mid), len - mid),
+        )
+    }
+}
+
+/// A mutable global: every read and write needs `unsafe`
+pub static mut UNSAFE_COUNTER: u32 = 0;
+
+/// An atomic global: shared safely between threads without `unsafe`
+pub static ATOMIC_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+// Held by the demo while it resets and reads the two counters above
+static COUNTERS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Adds one to `UNSAFE_COUNTER`.
+///
+/// # Safety
+///
+/// No other thread may access `UNSAFE_COUNTER` at the same time, or the update is a data race.
+///
+/// ```
+/// use rust_concepts::ch10_unsafe::{bump_unsafe_counter, UNSAFE_COUNTER};
+///
+/// // SAFETY: this example is the only code using the counter
+/// let value = unsafe {
+///     bump_unsafe_counter();
+///     UNSAFE_COUNTER
+/// };
+/// assert_eq!(value, 1);
+/// ```
+pub unsafe fn bump_unsafe_counter() {
+    // SAFETY: the caller guarantees exclusive access
+    // `+=` on the static reads and writes it by value, without creating a reference
+    unsafe { UNSAFE_COUNTER += 1 };
+}
+
+/// Both fields occupy the same 4 bytes; #[repr(C)] lays the union out like a C union
+#[repr(C)]
+pub union FloatOrBits {
+    pub float: f32,
+    pub bits: u32,
+}
+
+/// This function reads the bit pattern of a float through a union
+///
+/// ```
+/// use rust_concepts::ch10_unsafe::float_bits;
+///
+/// assert_eq!(float_bits(1.0), 0x3f80_0000);
+/// assert_eq!(float_bits(-2.5), (-2.5f32).to_bits());
+/// ```
+pub fn float_bits(value: f32) -> u32 {
+    let union = FloatOrBits { float: value };
+    // SAFETY: every bit pattern is a valid u32, so reading `bits` is always defined
+    unsafe { union.bits }
+}
+
+// Functions provided by C: libc for strlen and qsort, and c/ffi_harness.c (linked by build.rs)
+// The block is `unsafe extern` because Rust has to trust that these signatures match the C code
+unsafe extern "C" {
+    fn strlen(s: *const c_char) -> usize;
+    fn qsort(
+        base: *mut c_void,
+        count: usize,
+        size: usize,
+        compare: extern "C" fn(*const c_void, *const c_void) -> c_int,
+    );
+    fn harness_checksum(text: *const c_char) -> u32;
+    fn harness_self_test() -> c_int;
+}
+
+// The comparison callback handed to qsort
+// It must use the C calling convention, because C code is what calls it
+extern "C" fn compare_i32(a: *const c_void, b: *const c_void) -> c_int {
+    // SAFETY: qsort only passes pointers to elements of the slice we gave it, which are i32s
+    let (a, b) = unsafe { (*(a as *const i32), *(b as *const i32)) };
+    a.cmp(&b) as c_int
+}
+
+/// This function sorts a slice with the C library's qsort
+///
+/// ```
+/// use rust_concepts::ch10_unsafe::sort_with_qsort;
+///
+/// let mut numbers = [42, -7, 19, 0, 3];
+/// sort_with_qsort(&mut numbers);
+/// assert_eq!(numbers, [-7, 0, 3, 19, 42]);
+/// ```
+pub fn sort_with_qsort(values: &mut [i32]) {
+    // SAFETY: the pointer, length and element size describe `values` exactly,
+    // and compare_i32 matches the element type
+    unsafe {
+        qsort(
+            values.as_mut_ptr() as *mut c_void,
+            values.len(),
+            size_of::<i32>(),
+            compare_i32,
+        );
+    }
+}
+
+/// This function computes the Adler-32 checksum of some bytes in safe Rust
+///
+/// ```
+/// use rust_concepts::ch10_unsafe::adler32;
+///
+/// assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+/// ```
+pub fn adler32(data: &[u8]) -> u32 {
+    const MOD_ADLER: u32 = 65521;
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + u32::from(byte)) % MOD_ADLER;
+        b = (b + a) % MOD_ADLER;
+    }
+    (b << 16) | a
+}
+
+/// Exports [`adler32`] to C as `uint32_t rust_concepts_adler32(const uint8_t *data, size_t len)`.
+///
+/// `#[no_mangle]` keeps the symbol name as written so the C linker can find it.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes. It may be null only when `len` is 0.
+///
+/// ```
+/// use rust_concepts::ch10_unsafe::rust_concepts_adler32;
+///
+/// let data = b"Wikipedia";
+/// // SAFETY: the pointer and length describe `data`
+/// let checksum = unsafe { rust_concepts_adler32(data.as_ptr(), data.len()) };
+/// assert_eq!(checksum, 0x11e6_0398);
+/// ```
+#[no_mangle]
+pub unsafe extern "C" fn rust_concepts_adler32(data: *const u8, len: usize) -> u32 {
+    if data.is_null() {
+        return adler32(&[]);
+    }
+    // SAFETY: the caller guarantees that data points to len readable bytes
+    let bytes = unsafe { slice::from_raw_parts(data, len) };
+    adler32(bytes)
+}
+
+/// This function turns a C string into an owned Rust String, replacing invalid UTF-8
+/// CStr borrows the bytes up to the NUL; to_string_lossy copies them into Rust-managed memory
+///
+/// ```
+/// use rust_concepts::ch10_unsafe::string_from_c;
+///
+/// assert_eq!(string_from_c(c"Hello, C!"), "Hello, C!");
+/// ```
+pub fn string_from_c(text: &CStr) -> String {
+    text.to_string_lossy().into_owned()
+}
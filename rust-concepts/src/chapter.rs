@@ -0,0 +1,122 @@
+use std::io::{self, Write};
+
+/// This trait describes a single chapter of the tutorial
+/// Every chapter module provides one implementation, and the registry collects them
+/// so that main.rs can dispatch, print help and resolve aliases without knowing about any chapter
+///
+/// ```
+/// use rust_concepts::chapter::{Chapter, Example};
+/// use std::io::{self, Write};
+///
+/// struct Hello;
+///
+/// impl Chapter for Hello {
+///     fn id(&self) -> &'static str { "99" }
+///     fn slug(&self) -> &'static str { "hello" }
+///     fn aliases(&self) -> &'static [&'static str] { &["ch99"] }
+///     fn title(&self) -> &'static str { "Hello World" }
+///     fn summary(&self) -> &'static str { "Printing a greeting" }
+///     fn source(&self) -> &'static str { "" }
+///     fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+///         for example in self.examples() {
+///             (example.run)(out)?;
+///         }
+///         Ok(())
+///     }
+///     fn examples(&self) -> &'static [Example] {
+///         &[Example {
+///             id: 1,
+///             title: "Greeting",
+///             explanation: "println! writes a line of text",
+///             run: |out| writeln!(out, "Hello, world!"),
+///         }]
+///     }
+/// }
+///
+/// assert!(Hello.matches("ch99"));
+/// assert_eq!(Hello.banner(), "Demonstrating Rust hello world!");
+/// assert_eq!(Hello.example(1).unwrap().title, "Greeting");
+/// ```
+pub trait Chapter: Sync {
+    /// The chapter number used on the command line, e.g. "01"
+    fn id(&self) -> &'static str;
+
+    /// The canonical name of the chapter, e.g. "ownership"
+    fn slug(&self) -> &'static str;
+
+    /// Any additional names the chapter can be selected by, e.g. "ch01"
+    fn aliases(&self) -> &'static [&'static str];
+
+    /// The human-readable title, e.g. "Ownership"
+    fn title(&self) -> &'static str;
+
+    /// A one-line description of what the chapter covers
+    fn summary(&self) -> &'static str;
+
+    /// The chapter's own source code, used to check its `// Result:` comments
+    fn source(&self) -> &'static str;
+
+    /// Runs the chapter's demo, writing everything it prints to `out`
+    fn run(&self, out: &mut dyn Write) -> io::Result<()>;
+
+    /// The chapter's numbered examples; `run` prints all of them, in this order
+    fn examples(&self) -> &'static [Example];
+
+    /// Looks up one example by its number
+    fn example(&self, id: u32) -> Option<&'static Example> {
+        self.examples().iter().find(|example| example.id == id)
+    }
+
+    /// The source of one example: its function, from the `fn` line to the closing brace
+    ///
+    /// ```
+    /// use rust_concepts::registry;
+    ///
+    /// let ownership = registry::find("01").unwrap();
+    /// let source = ownership.example_source(1).unwrap();
+    /// assert!(source.starts_with("fn move_semantics("));
+    /// assert!(source.ends_with("\n}"));
+    /// ```
+    fn example_source(&self, id: u32) -> Option<&'static str> {
+        let source = self.source();
+        let header = source.find(&format!("\n// Example {}: ", id))?;
+        let start = header + source[header..].find("\nfn ")? + 1;
+        let end = start + source[start..].find("\n}")? + 2;
+        Some(&source[start..end])
+    }
+
+    /// Returns true if `name` selects this chapter (by id, slug or alias)
+    fn matches(&self, name: &str) -> bool {
+        name == self.id() || name == self.slug() || self.aliases().contains(&name)
+    }
+
+    /// The banner printed before the chapter runs
+    fn banner(&self) -> String {
+        format!("Demonstrating Rust {}!", self.title().to_lowercase())
+    }
+
+    /// The line shown for this chapter in the usage help
+    fn usage(&self) -> String {
+        let mut names = vec![self.id()];
+        names.extend(self.aliases());
+        names.push(self.slug());
+        format!(
+            "cargo run -- {} (or {})\n    {}",
+            names[0],
+            names[1..].join(", or "),
+            self.summary()
+        )
+    }
+}
+
+/// One numbered example of a chapter, which can be run on its own with `--example N`
+pub struct Example {
+    /// The number after "Example" in the chapter source, starting at 1
+    pub id: u32,
+    /// A short title, e.g. "Move semantics"
+    pub title: &'static str,
+    /// One sentence on what the example shows, printed before it runs on its own
+    pub explanation: &'static str,
+    /// Prints the example's output
+    pub run: fn(&mut dyn Write) -> io::Result<()>,
+}
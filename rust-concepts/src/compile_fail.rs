@@ -0,0 +1,224 @@
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A small program that the compiler must reject, taken from `compile_fail/`
+///
+/// Each snippet is the broken variant of a chapter example: the line that the chapter
+/// leaves commented out, or the mistake its explanation warns about. The first line of
+/// the file names the error rustc is expected to report, for example
+/// ``// error[E0382]: borrow of moved value: `s1` ``.
+#[derive(Debug)]
+pub struct Snippet {
+    /// The id of the chapter the snippet belongs to, such as "01"
+    pub chapter: &'static str,
+    /// The number of the example in that chapter
+    pub example: u32,
+    /// The path of the snippet, relative to `compile_fail/`
+    pub path: &'static str,
+    /// The source of the snippet, included at compile time
+    pub source: &'static str,
+}
+
+// Registers a file from compile_fail/ and includes its source into the binary
+macro_rules! snippet {
+    ($chapter:literal, $example:literal, $path:literal) => {
+        Snippet {
+            chapter: $chapter,
+            example: $example,
+            path: $path,
+            source: include_str!(concat!("../compile_fail/", $path)),
+        }
+    };
+}
+
+/// Every compile-fail snippet, in chapter and example order
+pub static SNIPPETS: &[Snippet] = &[
+    snippet!("01", 1, "ch01_ownership/use_after_move.rs"),
+    snippet!("01", 4, "ch01_ownership/use_after_passing.rs"),
+    snippet!("02", 4, "ch02_borrowing/mutable_while_shared.rs"),
+    snippet!("02", 4, "ch02_borrowing/two_mutable_borrows.rs"),
+    snippet!("02", 5, "ch02_borrowing/dangle.rs"),
+    snippet!("03", 1, "ch03_lifetime/longest_without_lifetimes.rs"),
+    snippet!("03", 1, "ch03_lifetime/result_outlives_input.rs"),
+    snippet!("03", 2, "ch03_lifetime/excerpt_outlives_novel.rs"),
+    snippet!("04", 4, "ch04_pattern_matching/non_exhaustive_match.rs"),
+    snippet!("04", 12, "ch04_pattern_matching/refutable_let.rs"),
+    snippet!("06", 2, "ch06_concurrency/spawn_without_move.rs"),
+    snippet!("06", 2, "ch06_concurrency/use_after_move_into_thread.rs"),
+    snippet!("07", 2, "ch07_error_handling/question_mark_in_unit_fn.rs"),
+    snippet!("10", 2, "ch10_unsafe/unsafe_call_without_block.rs"),
+];
+
+impl Snippet {
+    /// The error code declared on the first line of the snippet, such as "E0382"
+    ///
+    /// ```
+    /// use rust_concepts::compile_fail::SNIPPETS;
+    ///
+    /// assert_eq!(SNIPPETS[0].expected_code(), Some("E0382"));
+    /// ```
+    pub fn expected_code(&self) -> Option<&'static str> {
+        let header = self.source.lines().next()?;
+        let code = header.strip_prefix("// error[")?;
+        code.split_once(']').map(|(code, _)| code)
+    }
+
+    /// The name of the snippet: its file name without the chapter directory or extension
+    ///
+    /// ```
+    /// use rust_concepts::compile_fail::SNIPPETS;
+    ///
+    /// assert_eq!(SNIPPETS[0].name(), "use_after_move");
+    /// ```
+    pub fn name(&self) -> &'static str {
+        let file_name = self.path.rsplit('/').next().unwrap_or(self.path);
+        file_name.strip_suffix(".rs").unwrap_or(file_name)
+    }
+
+    /// Why the compiler rejects the snippet: the comment lines below the error line
+    ///
+    /// ```
+    /// use rust_concepts::compile_fail::SNIPPETS;
+    ///
+    /// assert!(SNIPPETS[0].explanation().contains("moves the String into s2"));
+    /// ```
+    pub fn explanation(&self) -> String {
+        self.source
+            .lines()
+            .skip(1)
+            .map_while(|line| line.strip_prefix("// "))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Compiles the snippet with the local rustc
+    pub fn compile(&self) -> io::Result<Diagnostics> {
+        compile(self.name(), self.source)
+    }
+}
+
+/// Finds the snippets that break a given example of a chapter
+///
+/// ```
+/// use rust_concepts::compile_fail;
+///
+/// let snippets: Vec<_> = compile_fail::for_example("02", 4).collect();
+/// assert_eq!(snippets.len(), 2);
+/// ```
+pub fn for_example(chapter: &str, example: u32) -> impl Iterator<Item = &'static Snippet> + '_ {
+    SNIPPETS
+        .iter()
+        .filter(move |s| s.chapter == chapter && s.example == example)
+}
+
+/// What rustc said about a program
+#[derive(Debug)]
+pub struct Diagnostics {
+    /// Whether rustc accepted the program
+    pub success: bool,
+    /// The distinct error codes rustc reported, in the order they first appeared
+    pub codes: Vec<String>,
+    /// Everything rustc printed, as a human would see it in a terminal
+    pub rendered: String,
+}
+
+// Each compilation gets its own directory, so tests can compile snippets in parallel
+static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+
+/// Type-checks a single-file program with the local rustc and collects its diagnostics
+///
+/// The program is written to a throwaway crate that is removed again afterwards.
+pub fn compile(name: &str, source: &str) -> io::Result<Diagnostics> {
+    let dir = std::env::temp_dir().join(format!(
+        "rust-concepts-{}-{}",
+        process::id(),
+        NEXT_DIR.fetch_add(1, Ordering::Relaxed)
+    ));
+    let diagnostics = materialize(&dir, name, source).and_then(|()| compile_crate(&dir));
+    // Clean up before reporting a failure to write the crate or to start rustc.
+    // A failed cleanup is only reported when there's no earlier error it would hide
+    let cleanup = fs::remove_dir_all(&dir);
+    let diagnostics = diagnostics?;
+    cleanup?;
+    Ok(diagnostics)
+}
+
+/// Writes a program out as a standalone cargo crate, with the source as `src/main.rs`
+///
+/// The manifest has an empty `[workspace]` table, so the crate builds on its own even
+/// when `dir` is inside another workspace.
+pub fn materialize(dir: &Path, name: &str, source: &str) -> io::Result<()> {
+    fs::create_dir_all(dir.join("src"))?;
+    fs::write(
+        dir.join("Cargo.toml"),
+        format!(
+            "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[workspace]\n",
+            name
+        ),
+    )?;
+    fs::write(dir.join("src").join("main.rs"), source)
+}
+
+/// Type-checks a crate written by [`materialize`] with the local rustc
+///
+/// Only metadata is emitted, which runs the borrow checker without generating code.
+/// rustc runs from the crate directory, so diagnostics point at `src/main.rs` just like
+/// they would under `cargo build`. The compiler is taken from `$RUSTC`, falling back to
+/// `rustc` on the PATH.
+pub fn compile_crate(dir: &Path) -> io::Result<Diagnostics> {
+    // rustc doesn't create the directory of its output file
+    let target = dir.join("target");
+    fs::create_dir_all(&target)?;
+    let output = Command::new(rustc())
+        .current_dir(dir)
+        .args(["--edition", "2021", "--crate-type", "bin", "--crate-name"])
+        .arg("snippet")
+        .args(["--emit=metadata", "-A", "warnings", "--color", "never"])
+        .arg("-o")
+        .arg(target.join("snippet.rmeta"))
+        .arg(Path::new("src").join("main.rs"))
+        .output()?;
+
+    let rendered = String::from_utf8_lossy(&output.stderr).into_owned();
+    Ok(Diagnostics {
+        success: output.status.success(),
+        codes: error_codes(&rendered),
+        rendered,
+    })
+}
+
+// Cargo sets $RUSTC for build scripts; a plain `rustc` goes through rustup's toolchain selection
+pub(crate) fn rustc() -> PathBuf {
+    std::env::var_os("RUSTC")
+        .unwrap_or_else(|| OsString::from("rustc"))
+        .into()
+}
+
+/// Extracts the distinct error codes from rustc's output
+///
+/// ```
+/// use rust_concepts::compile_fail::error_codes;
+///
+/// let stderr = "error[E0382]: borrow of moved value: `s1`\n\
+///               error: aborting due to 1 previous error\n\
+///               For more information about this error, try `rustc --explain E0382`.";
+/// assert_eq!(error_codes(stderr), ["E0382"]);
+/// ```
+pub fn error_codes(stderr: &str) -> Vec<String> {
+    let mut codes: Vec<String> = Vec::new();
+    for line in stderr.lines() {
+        let Some(rest) = line.strip_prefix("error[") else {
+            continue;
+        };
+        if let Some((code, _)) = rest.split_once(']') {
+            if !codes.iter().any(|c| c == code) {
+                codes.push(code.to_string());
+            }
+        }
+    }
+    codes
+}
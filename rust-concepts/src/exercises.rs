@@ -0,0 +1,352 @@
+use crate::compile_fail::rustc;
+use crate::registry;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A practice exercise: a file with `todo!()` stubs for the learner to fill in
+///
+/// The learner edits `exercises/<chapter>/<name>.rs`. Grading appends the hidden tests
+/// from `exercises/hidden/` to that file, compiles it with `rustc --test` and runs them.
+/// A reference solution lives in `exercises/solutions/`, so the test suite can check
+/// that every exercise can actually be solved.
+#[derive(Debug)]
+pub struct Exercise {
+    /// The id of the chapter the exercise practices, such as "03"
+    pub chapter: &'static str,
+    /// The path of the exercise, relative to `exercises/`
+    pub path: &'static str,
+    /// Hints shown when the hidden tests fail, from gentle to explicit
+    pub hints: &'static [&'static str],
+    /// The hidden tests, included at compile time
+    pub tests: &'static str,
+    /// The reference solution, included at compile time
+    pub solution: &'static str,
+}
+
+// Registers an exercise and includes its hidden tests and reference solution
+macro_rules! exercise {
+    ($chapter:literal, $path:literal, $hints:expr) => {
+        Exercise {
+            chapter: $chapter,
+            path: $path,
+            hints: $hints,
+            tests: include_str!(concat!("../exercises/hidden/", $path)),
+            solution: include_str!(concat!("../exercises/solutions/", $path)),
+        }
+    };
+}
+
+/// Every exercise, in chapter order
+pub static EXERCISES: &[Exercise] = &[
+    exercise!(
+        "01",
+        "ch01_ownership/exclaim.rs",
+        &[
+            "A parameter can be declared `mut s: String` when the function wants to change what it owns.",
+            "String::push adds one character to the end; then return `s` itself, without a semicolon.",
+        ]
+    ),
+    exercise!(
+        "01",
+        "ch01_ownership/split_by_length.rs",
+        &[
+            "`for word in words` consumes the vector and hands you each String by value.",
+            "Push each word into one of two new vectors; `word.chars().count()` is its length in characters.",
+        ]
+    ),
+    exercise!(
+        "02",
+        "ch02_borrowing/count_vowels.rs",
+        &[
+            "`text.chars()` iterates over the characters without taking ownership of anything.",
+            "Lower-case each character with `to_ascii_lowercase` and compare it against the five vowels.",
+        ]
+    ),
+    exercise!(
+        "02",
+        "ch02_borrowing/capitalize_all.rs",
+        &[
+            "`words.iter_mut()` gives a `&mut String` for each word, so you can assign a new String through it.",
+            "Split a word into its first char and the rest with `let mut chars = word.chars(); chars.next()`.",
+            "`first.to_uppercase().chain(chars).collect()` builds the capitalized String.",
+        ]
+    ),
+    exercise!(
+        "03",
+        "ch03_lifetime/longest_word.rs",
+        &[
+            "`text.split_whitespace()` yields `&str` slices that borrow from `text`.",
+            "Start from `\"\"` and replace it only when a word is strictly longer, so ties keep the first word.",
+        ]
+    ),
+    exercise!(
+        "03",
+        "ch03_lifetime/first_sentence.rs",
+        &[
+            "`text.split('.').next()` is the part before the first period, and it borrows from `text`.",
+            "`Excerpt { part }` ties the excerpt's lifetime to `text`, just like ImportantExcerpt in chapter 03.",
+        ]
+    ),
+    exercise!(
+        "04",
+        "ch04_pattern_matching/classify_number.rs",
+        &[
+            "Range patterns such as `1..=9` match a whole span of values in one arm.",
+            "Arms are tried in order: handle the ranges first, then use a guard like `n if n % 2 == 0`.",
+        ]
+    ),
+    exercise!(
+        "04",
+        "ch04_pattern_matching/shape_area.rs",
+        &[
+            "Write one arm per variant, destructuring its fields: `Shape::Circle { radius } => ...`.",
+            "Heron's formula: with s = (a + b + c) / 2, the area is sqrt(s(s - a)(s - b)(s - c)).",
+            "For `Scaled`, call `area` recursively on the inner shape and multiply by factor squared.",
+        ]
+    ),
+];
+
+impl Exercise {
+    /// The name of the exercise: its file name without the chapter directory or extension
+    pub fn name(&self) -> &'static str {
+        let file_name = self.path.rsplit('/').next().unwrap_or(self.path);
+        file_name.strip_suffix(".rs").unwrap_or(file_name)
+    }
+
+    /// The id used on the command line, such as "ch03/longest_word"
+    ///
+    /// ```
+    /// use rust_concepts::exercises;
+    ///
+    /// let exercise = exercises::find("lifetime/longest_word").unwrap();
+    /// assert_eq!(exercise.id(), "ch03/longest_word");
+    /// ```
+    pub fn id(&self) -> String {
+        format!("ch{}/{}", self.chapter, self.name())
+    }
+
+    /// The file the learner edits, inside this package's `exercises/` directory
+    pub fn learner_file(&self) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("exercises")
+            .join(self.path)
+    }
+}
+
+/// Looks up an exercise by `<chapter>/<name>`, where the chapter can be any of its names
+///
+/// ```
+/// use rust_concepts::exercises;
+///
+/// assert!(exercises::find("ch03/longest_word").is_some());
+/// assert!(exercises::find("03/longest_word").is_some());
+/// assert!(exercises::find("ch04/longest_word").is_none());
+/// ```
+pub fn find(id: &str) -> Option<&'static Exercise> {
+    let (chapter, name) = id.split_once('/')?;
+    let chapter = registry::find(chapter)?;
+    EXERCISES
+        .iter()
+        .find(|e| e.chapter == chapter.id() && e.name() == name)
+}
+
+/// The outcome of one hidden test
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestResult {
+    /// The name of the test function
+    pub name: String,
+    pub passed: bool,
+    /// The panic message of a failed test
+    pub message: Option<String>,
+}
+
+/// The result of grading a learner's file
+#[derive(Debug)]
+pub enum Grade {
+    /// The file doesn't compile together with the hidden tests
+    DoesNotCompile { diagnostics: String },
+    /// The hidden tests ran to completion
+    Tested { results: Vec<TestResult> },
+    /// The hidden tests were still running when the time limit ran out
+    TimedOut,
+}
+
+impl Grade {
+    /// Whether the hidden tests ran and every one of them passed
+    pub fn passed(&self) -> bool {
+        match self {
+            Grade::Tested { results } => !results.is_empty() && results.iter().all(|r| r.passed),
+            Grade::DoesNotCompile { .. } | Grade::TimedOut => false,
+        }
+    }
+}
+
+/// How long the hidden tests may run before grading gives up, in case of an endless loop
+pub const TIME_LIMIT: Duration = Duration::from_secs(10);
+
+// Each grading gets its own directory, so tests can grade exercises in parallel
+static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+
+/// Grades a learner's version of an exercise against its hidden tests
+pub fn grade(exercise: &Exercise, source: &str) -> io::Result<Grade> {
+    grade_within(exercise, source, TIME_LIMIT)
+}
+
+/// Grades a learner's version of an exercise, giving the hidden tests `limit` to finish
+pub fn grade_within(exercise: &Exercise, source: &str, limit: Duration) -> io::Result<Grade> {
+    let dir = std::env::temp_dir().join(format!(
+        "rust-concepts-exercise-{}-{}",
+        process::id(),
+        NEXT_DIR.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::create_dir_all(&dir)?;
+    let grade = compile_and_test(&dir, exercise, source, limit);
+    // A failure to compile or run the tests matters more than a failure to clean up
+    let cleanup = fs::remove_dir_all(&dir);
+    let grade = grade?;
+    cleanup?;
+    Ok(grade)
+}
+
+fn compile_and_test(
+    dir: &Path,
+    exercise: &Exercise,
+    source: &str,
+    limit: Duration,
+) -> io::Result<Grade> {
+    // The file keeps the learner's file name, so diagnostics point at a name they recognize
+    let file_name = format!("{}.rs", exercise.name());
+    fs::write(
+        dir.join(&file_name),
+        format!("{}\n{}", source, exercise.tests),
+    )?;
+    let binary = dir.join(format!(
+        "{}{}",
+        exercise.name(),
+        std::env::consts::EXE_SUFFIX
+    ));
+    let output = Command::new(rustc())
+        .current_dir(dir)
+        .args([
+            "--edition",
+            "2021",
+            "--test",
+            "-A",
+            "warnings",
+            "--color",
+            "never",
+        ])
+        .arg("-o")
+        .arg(&binary)
+        .arg(&file_name)
+        .output()?;
+    if !output.status.success() {
+        return Ok(Grade::DoesNotCompile {
+            diagnostics: String::from_utf8_lossy(&output.stderr).into_owned(),
+        });
+    }
+
+    // One thread keeps the report in a stable order
+    let mut tests = Command::new(&binary);
+    tests
+        .args(["--test-threads=1", "--color", "never"])
+        .env("RUST_BACKTRACE", "0");
+    Ok(match run_with_limit(tests, limit)? {
+        Some(stdout) => Grade::Tested {
+            results: parse_test_output(&stdout),
+        },
+        None => Grade::TimedOut,
+    })
+}
+
+// Runs a command and returns its stdout, or None if it is still running after `limit`
+fn run_with_limit(mut command: Command, limit: Duration) -> io::Result<Option<String>> {
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    // Read stdout on another thread, so a chatty test can't block on a full pipe
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let reader = thread::spawn(move || {
+        let mut text = String::new();
+        stdout.read_to_string(&mut text).map(|_| text)
+    });
+
+    let start = Instant::now();
+    while child.try_wait()?.is_none() {
+        if start.elapsed() > limit {
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    reader
+        .join()
+        .expect("the reader thread doesn't panic")
+        .map(Some)
+}
+
+/// Reads the results of the hidden tests from the output of a libtest binary
+///
+/// ```
+/// use rust_concepts::exercises::parse_test_output;
+///
+/// let output = "\
+/// test hidden_tests::works ... ok
+/// test hidden_tests::broken ... FAILED
+///
+/// failures:
+///
+/// ---- hidden_tests::broken stdout ----
+///
+/// thread 'hidden_tests::broken' panicked at broken.rs:2:5:
+/// not yet implemented
+/// note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+/// ";
+/// let results = parse_test_output(output);
+/// assert_eq!(results[0].name, "works");
+/// assert!(results[0].passed);
+/// assert_eq!(results[1].message.as_deref(), Some("not yet implemented"));
+/// ```
+pub fn parse_test_output(output: &str) -> Vec<TestResult> {
+    let mut results: Vec<TestResult> = Vec::new();
+    let mut lines = output.lines();
+    while let Some(line) = lines.next() {
+        if let Some(rest) = line.strip_prefix("test ") {
+            let Some((path, status)) = rest.rsplit_once(" ... ") else {
+                continue;
+            };
+            results.push(TestResult {
+                name: short_name(path).to_string(),
+                passed: status == "ok",
+                message: None,
+            });
+        } else if let Some(path) = line
+            .strip_prefix("---- ")
+            .and_then(|rest| rest.strip_suffix(" stdout ----"))
+        {
+            // The panic message follows the "panicked at" line, up to a blank line or a note
+            let message: Vec<&str> = lines
+                .by_ref()
+                .skip_while(|line| !line.contains("panicked at"))
+                .skip(1)
+                .take_while(|line| !line.is_empty() && !line.starts_with("note:"))
+                .collect();
+            if let Some(result) = results.iter_mut().find(|r| r.name == short_name(path)) {
+                result.message = Some(message.join("\n"));
+            }
+        }
+    }
+    results
+}
+
+// "hidden_tests::works" -> "works"
+fn short_name(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path)
+}
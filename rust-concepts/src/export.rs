@@ -0,0 +1,459 @@
+use crate::chapter::{Chapter, Example};
+use crate::compile_fail;
+use crate::registry;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// What [`render`] produces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// A static site: `index.html`, one page per chapter and `style.css`
+    Html,
+    /// The `book.toml` and `src/` tree of an mdBook, which `mdbook build` turns into a site
+    MdBook,
+}
+
+impl Format {
+    /// Parses the value of `--format`
+    ///
+    /// ```
+    /// use rust_concepts::export::Format;
+    ///
+    /// assert_eq!(Format::parse("mdbook"), Some(Format::MdBook));
+    /// assert_eq!(Format::parse("pdf"), None);
+    /// ```
+    pub fn parse(name: &str) -> Option<Format> {
+        match name {
+            "html" => Some(Format::Html),
+            "mdbook" => Some(Format::MdBook),
+            _ => None,
+        }
+    }
+}
+
+/// One file of an exported book
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    /// The path of the file, relative to the export directory
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+/// Renders every chapter in the registry as a book, in memory
+///
+/// Each example shows its explanation, its source code and the output it printed when it
+/// was run for the export. Nothing is fetched from the network: the HTML pages only link
+/// to each other and to their stylesheet.
+pub fn render(format: Format) -> io::Result<Vec<Page>> {
+    let mut chapters = Vec::new();
+    for &chapter in registry::CHAPTERS {
+        let mut examples = Vec::new();
+        for example in chapter.examples() {
+            let mut output = Vec::new();
+            (example.run)(&mut output)?;
+            examples.push(Section {
+                example,
+                source: chapter.example_source(example.id),
+                output: String::from_utf8_lossy(&output).into_owned(),
+            });
+        }
+        chapters.push((chapter, examples));
+    }
+    Ok(match format {
+        Format::Html => render_html(&chapters),
+        Format::MdBook => render_mdbook(&chapters),
+    })
+}
+
+/// Renders the book and writes it to `dir`, returning the paths of the files written
+pub fn export(format: Format, dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    for page in render(format)? {
+        let path = dir.join(&page.path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, page.contents)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+// An example together with what the export found out about it
+struct Section {
+    example: &'static Example,
+    source: Option<&'static str>,
+    output: String,
+}
+
+type Chapters = [(&'static dyn Chapter, Vec<Section>)];
+
+// The name of a chapter's page, without its extension, such as "ch01-ownership"
+fn page_name(chapter: &dyn Chapter) -> String {
+    format!("ch{}-{}", chapter.id(), chapter.slug())
+}
+
+fn render_html(chapters: &Chapters) -> Vec<Page> {
+    let mut pages = vec![
+        Page {
+            path: PathBuf::from("style.css"),
+            contents: STYLE.to_string(),
+        },
+        Page {
+            path: PathBuf::from("index.html"),
+            contents: html_index(chapters),
+        },
+    ];
+    for (index, (chapter, sections)) in chapters.iter().enumerate() {
+        let mut body = format!(
+            "<h1>Chapter {}: {}</h1>\n<p class=\"summary\">{}</p>\n<p>Run it with <code>cargo run -- {}</code>.</p>\n",
+            chapter.id(),
+            escape(chapter.title()),
+            escape(chapter.summary()),
+            chapter.id()
+        );
+        for section in sections {
+            let example = section.example;
+            body += &format!(
+                "<section id=\"example-{}\">\n<h2>Example {}: {}</h2>\n<p>{}</p>\n",
+                example.id,
+                example.id,
+                escape(example.title),
+                escape(example.explanation)
+            );
+            if let Some(source) = section.source {
+                body += &format!(
+                    "<pre class=\"code\"><code>{}</code></pre>\n",
+                    highlight(source)
+                );
+            }
+            body += &format!(
+                "<p class=\"label\">Output</p>\n<pre class=\"output\">{}</pre>\n",
+                escape(&section.output)
+            );
+            for snippet in compile_fail::for_example(chapter.id(), example.id) {
+                body += &format!(
+                    "<p class=\"broken\">Broken variant: <code>compile_fail/{}</code> is rejected with {}. See it with <code>cargo run -- {} --break {}</code>.</p>\n",
+                    snippet.path,
+                    snippet.expected_code().unwrap_or("an error"),
+                    chapter.id(),
+                    example.id
+                );
+            }
+            body += "</section>\n";
+        }
+
+        let nav = html_nav(
+            index.checked_sub(1).map(|i| chapters[i].0),
+            chapters.get(index + 1).map(|(chapter, _)| *chapter),
+        );
+        pages.push(Page {
+            path: PathBuf::from(format!("{}.html", page_name(*chapter))),
+            contents: html_page(
+                &format!("{} - Rust Concepts", chapter.title()),
+                &format!("{}{}{}", nav, body, nav),
+            ),
+        });
+    }
+    pages
+}
+
+fn html_index(chapters: &Chapters) -> String {
+    let mut body = String::from(
+        "<h1>Rust Concepts</h1>\n<p>Examples of important Rust concepts, one chapter at a time. Every example shows its code and the output it prints.</p>\n<ol class=\"chapters\">\n",
+    );
+    for (chapter, sections) in chapters {
+        body += &format!(
+            "<li><a href=\"{}.html\">{}</a> - {} ({} examples)</li>\n",
+            page_name(*chapter),
+            escape(chapter.title()),
+            escape(chapter.summary()),
+            sections.len()
+        );
+    }
+    body += "</ol>\n";
+    html_page("Rust Concepts", &body)
+}
+
+// The links at the top and bottom of a chapter page
+fn html_nav(prev: Option<&dyn Chapter>, next: Option<&dyn Chapter>) -> String {
+    let link = |chapter: &dyn Chapter, rel: &str, text: String| {
+        format!(
+            "<a href=\"{}.html\" rel=\"{}\">{}</a>",
+            page_name(chapter),
+            rel,
+            text
+        )
+    };
+    let mut links = Vec::new();
+    if let Some(prev) = prev {
+        links.push(link(
+            prev,
+            "prev",
+            format!("&larr; {}", escape(prev.title())),
+        ));
+    }
+    links.push("<a href=\"index.html\">Contents</a>".to_string());
+    if let Some(next) = next {
+        links.push(link(
+            next,
+            "next",
+            format!("{} &rarr;", escape(next.title())),
+        ));
+    }
+    format!("<nav>{}</nav>\n", links.join(" | "))
+}
+
+fn html_page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<link rel=\"stylesheet\" href=\"style.css\">\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(title),
+        body
+    )
+}
+
+const STYLE: &str = "body { max-width: 50em; margin: 2em auto; padding: 0 1em; font-family: sans-serif; line-height: 1.5; }
+nav { margin: 1em 0; }
+pre { padding: 0.8em; overflow-x: auto; border-radius: 4px; }
+pre.code { background: #f6f8fa; }
+pre.output { background: #1e1e1e; color: #e0e0e0; }
+p.label { margin-bottom: 0; font-weight: bold; }
+p.broken { font-size: 0.9em; color: #8a1f11; }
+.kw { color: #a626a4; }
+.ty { color: #c18401; }
+.st { color: #50a14f; }
+.nu { color: #986801; }
+.co { color: #a0a1a7; font-style: italic; }
+.ma { color: #4078f2; }
+.lt { color: #e45649; }
+.at { color: #696c77; }
+";
+
+fn render_mdbook(chapters: &Chapters) -> Vec<Page> {
+    let mut summary = String::from("# Summary\n\n[Introduction](introduction.md)\n\n");
+    let mut pages = vec![
+        Page {
+            path: PathBuf::from("book.toml"),
+            contents: "[book]\ntitle = \"Rust Concepts\"\nlanguage = \"en\"\nsrc = \"src\"\n"
+                .to_string(),
+        },
+        Page {
+            path: Path::new("src").join("introduction.md"),
+            contents: "# Rust Concepts\n\nExamples of important Rust concepts, one chapter at a time. Every example shows its code and the output it prints.\n".to_string(),
+        },
+    ];
+    for (chapter, sections) in chapters {
+        let file = format!("{}.md", page_name(*chapter));
+        summary += &format!("- [{}](./{})\n", chapter.title(), file);
+
+        let mut text = format!(
+            "# Chapter {}: {}\n\n{}\n\nRun it with `cargo run -- {}`.\n",
+            chapter.id(),
+            chapter.title(),
+            chapter.summary(),
+            chapter.id()
+        );
+        for section in sections {
+            let example = section.example;
+            text += &format!(
+                "\n## Example {}: {}\n\n{}\n",
+                example.id, example.title, example.explanation
+            );
+            // The examples aren't complete programs, so mdbook test must not try to run them
+            if let Some(source) = section.source {
+                text += &format!("\n```rust,ignore\n{}\n```\n", source);
+            }
+            text += &format!("\nOutput:\n\n```text\n{}```\n", section.output);
+            for snippet in compile_fail::for_example(chapter.id(), example.id) {
+                text += &format!(
+                    "\n> Broken variant: `compile_fail/{}` is rejected with {}. See it with `cargo run -- {} --break {}`.\n",
+                    snippet.path,
+                    snippet.expected_code().unwrap_or("an error"),
+                    chapter.id(),
+                    example.id
+                );
+            }
+        }
+        pages.push(Page {
+            path: Path::new("src").join(file),
+            contents: text,
+        });
+    }
+    pages.insert(
+        1,
+        Page {
+            path: Path::new("src").join("SUMMARY.md"),
+            contents: summary,
+        },
+    );
+    pages
+}
+
+/// Escapes text for use in HTML
+///
+/// ```
+/// use rust_concepts::export::escape;
+///
+/// assert_eq!(escape("Vec<&str>"), "Vec&lt;&amp;str&gt;");
+/// ```
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+/// Highlights Rust source as HTML, wrapping each token in a `<span>` with a short class
+///
+/// The classes are `kw` (keywords), `ty` (capitalized names), `st` (string and character
+/// literals), `nu` (numbers), `co` (comments), `ma` (macro names), `lt` (lifetimes) and
+/// `at` (attributes). Everything else is escaped and left as it is.
+///
+/// ```
+/// use rust_concepts::export::highlight;
+///
+/// assert_eq!(
+///     highlight("let s: &'a str = \"<hi>\"; // done"),
+///     "<span class=\"kw\">let</span> s: &amp;<span class=\"lt\">'a</span> str = \
+///      <span class=\"st\">&quot;&lt;hi&gt;&quot;</span>; <span class=\"co\">// done</span>"
+/// );
+/// ```
+pub fn highlight(source: &str) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut html = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let start = i;
+        let class = if c == '/' && next == Some('/') {
+            i = end_of_line(&chars, i);
+            "co"
+        } else if c == '"' {
+            i = end_of_string(&chars, i + 1);
+            "st"
+        } else if c == '\'' {
+            match (next, chars.get(i + 2)) {
+                // '\n' and 'x' are characters, 'a is a lifetime
+                (Some('\\'), _) => {
+                    i = end_of_char(&chars, i + 2);
+                    "st"
+                }
+                (Some(_), Some('\'')) => {
+                    i += 3;
+                    "st"
+                }
+                _ => {
+                    i = end_of_word(&chars, i + 1);
+                    "lt"
+                }
+            }
+        } else if c == '#' && matches!(next, Some('[' | '!')) {
+            i = end_of_attribute(&chars, i);
+            "at"
+        } else if c.is_ascii_digit() {
+            i = end_of_number(&chars, i);
+            "nu"
+        } else if c.is_alphabetic() || c == '_' {
+            i = end_of_word(&chars, i);
+            let word: String = chars[start..i].iter().collect();
+            if chars.get(i) == Some(&'!') && chars.get(i + 1) != Some(&'=') {
+                i += 1;
+                "ma"
+            } else if KEYWORDS.contains(&word.as_str()) {
+                "kw"
+            } else if c.is_uppercase() {
+                "ty"
+            } else {
+                ""
+            }
+        } else {
+            i += 1;
+            ""
+        };
+
+        let text: String = chars[start..i].iter().collect();
+        if class.is_empty() {
+            html += &escape(&text);
+        } else {
+            html += &format!("<span class=\"{}\">{}</span>", class, escape(&text));
+        }
+    }
+    html
+}
+
+fn end_of_line(chars: &[char], from: usize) -> usize {
+    (from..chars.len())
+        .find(|&i| chars[i] == '\n')
+        .unwrap_or(chars.len())
+}
+
+// `from` is just after the opening quote; the end is just after the closing one
+fn end_of_string(chars: &[char], from: usize) -> usize {
+    let mut i = from;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    chars.len()
+}
+
+// `from` is just after the backslash of an escaped character literal
+fn end_of_char(chars: &[char], from: usize) -> usize {
+    (from + 1..chars.len())
+        .find(|&i| chars[i] == '\'')
+        .map_or(chars.len(), |i| i + 1)
+}
+
+fn end_of_word(chars: &[char], from: usize) -> usize {
+    (from..chars.len())
+        .find(|&i| !(chars[i].is_alphanumeric() || chars[i] == '_'))
+        .unwrap_or(chars.len())
+}
+
+// A number may contain letters (0xff, 1u8) and a decimal point, but `0..10` is a range
+fn end_of_number(chars: &[char], from: usize) -> usize {
+    let mut i = from;
+    while i < chars.len() {
+        let c = chars[i];
+        let decimal_point = c == '.' && chars.get(i + 1).is_some_and(|next| next.is_ascii_digit());
+        if !(c.is_alphanumeric() || c == '_' || decimal_point) {
+            break;
+        }
+        i += 1;
+    }
+    i
+}
+
+// Attributes end at the bracket matching their first one
+fn end_of_attribute(chars: &[char], from: usize) -> usize {
+    let mut depth = 0;
+    for (i, &c) in chars.iter().enumerate().skip(from) {
+        match c {
+            '[' => depth += 1,
+            ']' if depth == 1 => return i + 1,
+            ']' => depth -= 1,
+            '\n' => return i,
+            _ => {}
+        }
+    }
+    chars.len()
+}
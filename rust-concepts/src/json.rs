@@ -0,0 +1,228 @@
+use crate::chapter::Chapter;
+use crate::exercises;
+use crate::registry;
+use crate::verify;
+use std::fmt::{self, Write as _};
+use std::io;
+
+/// The version of the documents built by [`chapter`] and [`catalog`]
+///
+/// It only changes when a field is renamed, removed or changes its type, so tools can
+/// rely on it; new fields may be added without changing it.
+pub const SCHEMA_VERSION: i64 = 1;
+
+/// A JSON value, written by hand so the crate needs no serialization library
+///
+/// Objects keep their keys in the order they were added.
+///
+/// ```
+/// use rust_concepts::json::Json;
+///
+/// let value = Json::object([
+///     ("name", Json::from("Ferris")),
+///     ("tags", Json::Array(vec![Json::from(1), Json::Null])),
+/// ]);
+/// assert_eq!(value.to_string(), "{\n  \"name\": \"Ferris\",\n  \"tags\": [\n    1,\n    null\n  ]\n}");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(i64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Builds an object from its fields, in order
+    pub fn object<const N: usize>(fields: [(&str, Json); N]) -> Json {
+        Json::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    // Writes the value indented by `depth` levels of two spaces
+    fn write_pretty(&self, out: &mut String, depth: usize) {
+        let indent = |out: &mut String, depth: usize| out.push_str(&"  ".repeat(depth));
+        match self {
+            Json::Null => out.push_str("null"),
+            Json::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+            Json::Number(value) => out.push_str(&value.to_string()),
+            Json::String(value) => write_string(out, value),
+            Json::Array(items) if items.is_empty() => out.push_str("[]"),
+            Json::Object(fields) if fields.is_empty() => out.push_str("{}"),
+            Json::Array(items) => {
+                out.push_str("[\n");
+                for (index, item) in items.iter().enumerate() {
+                    indent(out, depth + 1);
+                    item.write_pretty(out, depth + 1);
+                    out.push_str(if index + 1 < items.len() { ",\n" } else { "\n" });
+                }
+                indent(out, depth);
+                out.push(']');
+            }
+            Json::Object(fields) => {
+                out.push_str("{\n");
+                for (index, (key, value)) in fields.iter().enumerate() {
+                    indent(out, depth + 1);
+                    write_string(out, key);
+                    out.push_str(": ");
+                    value.write_pretty(out, depth + 1);
+                    out.push_str(if index + 1 < fields.len() {
+                        ",\n"
+                    } else {
+                        "\n"
+                    });
+                }
+                indent(out, depth);
+                out.push('}');
+            }
+        }
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut text = String::new();
+        self.write_pretty(&mut text, 0);
+        f.write_str(&text)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Json {
+        Json::String(value.to_string())
+    }
+}
+
+impl From<u32> for Json {
+    fn from(value: u32) -> Json {
+        Json::Number(value.into())
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Json {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
+// Writes a string literal, escaping quotes, backslashes and control characters
+fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn strings<'a>(values: impl IntoIterator<Item = &'a str>) -> Json {
+    Json::Array(values.into_iter().map(Json::from).collect())
+}
+
+// The fields that describe a chapter in both documents
+fn chapter_fields(chapter: &dyn Chapter) -> Vec<(String, Json)> {
+    vec![
+        ("id".to_string(), Json::from(chapter.id())),
+        ("slug".to_string(), Json::from(chapter.slug())),
+        (
+            "aliases".to_string(),
+            strings(chapter.aliases().iter().copied()),
+        ),
+        ("title".to_string(), Json::from(chapter.title())),
+        ("summary".to_string(), Json::from(chapter.summary())),
+    ]
+}
+
+/// Runs every example of a chapter and describes it, with what it printed
+///
+/// Each example has its `id`, `title`, `explanation`, `source` (its function, or null),
+/// the `stdout` lines it printed and the `expected` lines of its `// Result:` comments.
+///
+/// ```
+/// use rust_concepts::{json, registry};
+///
+/// let document = json::chapter(registry::find("01").unwrap()).unwrap().to_string();
+/// assert!(document.starts_with("{\n  \"schema_version\": 1,\n  \"chapter\": {\n    \"id\": \"01\","));
+/// assert!(document.contains("\"stdout\": [\n        \"s2: hello\"\n      ],"));
+/// ```
+pub fn chapter(chapter: &dyn Chapter) -> io::Result<Json> {
+    let expectations = verify::parse_expectations(chapter.source());
+    let mut examples = Vec::new();
+    for example in chapter.examples() {
+        let mut output = Vec::new();
+        (example.run)(&mut output)?;
+        let output = String::from_utf8_lossy(&output);
+        let expected = expectations
+            .iter()
+            .filter(|e| e.example == example.id)
+            .map(|e| e.text.as_str());
+        examples.push(Json::object([
+            ("id", Json::from(example.id)),
+            ("title", Json::from(example.title)),
+            ("explanation", Json::from(example.explanation)),
+            ("source", Json::from(chapter.example_source(example.id))),
+            ("stdout", strings(output.lines())),
+            ("expected", strings(expected)),
+        ]));
+    }
+    Ok(Json::object([
+        ("schema_version", Json::Number(SCHEMA_VERSION)),
+        ("chapter", Json::Object(chapter_fields(chapter))),
+        ("examples", Json::Array(examples)),
+    ]))
+}
+
+/// Describes every chapter, its examples and its exercises, without running anything
+///
+/// ```
+/// use rust_concepts::json;
+///
+/// let catalog = json::catalog().to_string();
+/// assert!(catalog.contains("\"exercises\": [\n        \"ch01/exclaim\","));
+/// ```
+pub fn catalog() -> Json {
+    let chapters = registry::CHAPTERS
+        .iter()
+        .map(|&chapter| {
+            let mut fields = chapter_fields(chapter);
+            let examples = chapter
+                .examples()
+                .iter()
+                .map(|example| {
+                    Json::object([
+                        ("id", Json::from(example.id)),
+                        ("title", Json::from(example.title)),
+                        ("explanation", Json::from(example.explanation)),
+                    ])
+                })
+                .collect();
+            let exercises = exercises::EXERCISES
+                .iter()
+                .filter(|e| e.chapter == chapter.id())
+                .map(|e| Json::String(e.id()))
+                .collect();
+            fields.push(("examples".to_string(), Json::Array(examples)));
+            fields.push(("exercises".to_string(), Json::Array(exercises)));
+            Json::Object(fields)
+        })
+        .collect();
+    Json::object([
+        ("schema_version", Json::Number(SCHEMA_VERSION)),
+        ("chapters", Json::Array(chapters)),
+    ])
+}
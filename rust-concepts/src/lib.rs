@@ -0,0 +1,41 @@
+//! Examples of important Rust concepts, one module per chapter.
+//!
+//! Every chapter module has a `demo` function that prints the chapter's examples,
+//! plus the public functions and types those examples use, so they can also be
+//! called from tests, doctests or other crates:
+//!
+//! ```
+//! use rust_concepts::ch03_lifetime::{longest, ImportantExcerpt};
+//!
+//! let novel = String::from("Call me Ishmael. Some years ago...");
+//! let first_sentence = novel.split('.').next().unwrap();
+//! let excerpt = ImportantExcerpt { part: first_sentence };
+//! assert_eq!(longest(excerpt.part, "Ishmael"), "Call me Ishmael");
+//! ```
+//!
+//! The [`registry`] lists the chapters in study order, and the `rust-concepts`
+//! binary is a small command-line front end over it.
+
+pub mod ch01_ownership;
+pub mod ch02_borrowing;
+pub mod ch03_lifetime;
+pub mod ch04_pattern_matching;
+pub mod ch05_traits;
+pub mod ch06_concurrency;
+pub mod ch07_error_handling;
+pub mod ch08_macros;
+pub mod ch09_modules;
+pub mod ch10_unsafe;
+pub mod chapter;
+pub mod compile_fail;
+pub mod exercises;
+pub mod export;
+pub mod json;
+pub mod progress;
+pub mod quiz;
+pub mod registry;
+pub mod toy;
+pub mod verify;
+
+#[cfg(test)]
+mod tests;
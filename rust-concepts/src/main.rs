@@ -1,40 +1,912 @@
-mod ch01_ownership;
-mod ch02_borrowing;
-mod ch03_lifetime;
-mod ch04_pattern_matching;
-
-#[cfg(test)]
-mod tests;
-
-fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    match args.get(1).map(|s| s.as_str()) {
-        Some("01" | "ch01" | "ownership") => {
-            println!("Demonstrating Rust ownership!");
-            println!("--------------");
-            ch01_ownership::demo();
-        }
-        Some("02" | "ch02" | "borrowing") => {
-            println!("Demonstrating Rust borrowing!");
-            println!("--------------");
-            ch02_borrowing::demo();
-        }
-        Some("03" | "ch03" | "lifetime") => {
-            println!("Demonstrating Rust lifetimes!");
-            println!("--------------");
-            ch03_lifetime::demo();
-        }
-        Some("04" | "ch04" | "pattern_matching") => {
-            println!("Demonstrating Rust pattern matching!");
-            println!("--------------");
-            ch04_pattern_matching::demo();
+// The chapters live in the library crate (src/lib.rs); this binary is only the command-line front end
+use rust_concepts::ch04_pattern_matching::calc;
+use rust_concepts::chapter::Chapter;
+use rust_concepts::exercises::{self, Grade};
+use rust_concepts::export::{self, Format};
+use rust_concepts::progress::Progress;
+use rust_concepts::quiz::schedule::{Clock, SystemClock};
+use rust_concepts::quiz::{self, history::History};
+use rust_concepts::toy::{self, borrowck, ownership, parser, regions, usefulness};
+use rust_concepts::{compile_fail, json, registry, verify};
+use std::error::Error;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+// main can return any error type through Box<dyn Error> (see ch07_error_handling)
+// If it returns Err, the error is printed with Debug and the process exits with a failure code
+fn main() -> Result<ExitCode, Box<dyn Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let mut out = io::stdout().lock();
+
+    // Slice patterns (see ch04_pattern_matching) pick the command apart
+    let code = match args.as_slice() {
+        ["verify"] => run_verify(None, &mut out)?,
+        ["verify", chapter] => run_verify(Some(chapter), &mut out)?,
+        ["verify", ..] => {
+            print_usage(&mut out)?;
+            ExitCode::FAILURE
+        }
+        ["progress"] => show_progress(&mut out)?,
+        ["next"] => show_next(&mut out)?,
+        ["reset", rest @ ..] => reset_progress(rest.first().copied(), &mut out)?,
+        ["quiz", options @ ..] => run_quiz(options, false, &mut out)?,
+        ["review", options @ ..] => run_quiz(options, true, &mut out)?,
+        ["list"] => list_chapters(&mut out)?,
+        ["list", "--format", "json"] => {
+            writeln!(out, "{}", json::catalog())?;
+            ExitCode::SUCCESS
+        }
+        ["simulate", path] => simulate_file(path, &mut out)?,
+        ["borrowck", path] => borrowck_file(path, &mut out)?,
+        ["visualize", path] => visualize_file(path, &mut out)?,
+        ["matches", path] => check_matches_file(path, &mut out)?,
+        ["export", options @ ..] => run_export(options, &mut out)?,
+        ["exercise"] => list_exercises(&mut out)?,
+        ["exercise", id] => run_exercise(id, &mut out)?,
+        [name, options @ ..] => match registry::find(name) {
+            Some(chapter) => run_chapter(chapter, options, &mut out)?,
+            None => {
+                print_usage(&mut out)?;
+                ExitCode::SUCCESS
+            }
+        },
+        [] => {
+            print_usage(&mut out)?;
+            ExitCode::SUCCESS
         }
+    };
+    Ok(code)
+}
+
+// Prints the list of chapters and commands
+fn print_usage(out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Please specify a module to run. Examples:")?;
+    for chapter in registry::CHAPTERS {
+        writeln!(out, "{}", chapter.usage())?;
+    }
+    writeln!(out, "cargo run -- <chapter> --format json")?;
+    writeln!(
+        out,
+        "    Run a chapter and print its examples, their source and output as JSON"
+    )?;
+    writeln!(out, "cargo run -- list [--format json]")?;
+    writeln!(
+        out,
+        "    List the chapters with their examples and exercises"
+    )?;
+    writeln!(out, "cargo run -- <chapter> --list")?;
+    writeln!(out, "    List the numbered examples of a chapter")?;
+    writeln!(out, "cargo run -- <chapter> --example <number>")?;
+    writeln!(out, "    Run a single example of a chapter")?;
+    writeln!(out, "cargo run -- <chapter> --break <number>")?;
+    writeln!(
+        out,
+        "    Compile the broken variant of an example and show the real compiler error"
+    )?;
+    writeln!(out, "cargo run -- 01 --simulate [number]")?;
+    writeln!(
+        out,
+        "    Trace who owns each value while the examples run in a toy interpreter"
+    )?;
+    writeln!(out, "cargo run -- simulate <file>")?;
+    writeln!(
+        out,
+        "    Trace the ownership of values in your own program, written in the toy language"
+    )?;
+    writeln!(out, "cargo run -- 02 --borrowck [number]")?;
+    writeln!(
+        out,
+        "    Show how long each borrow in the examples lives, and where borrows conflict"
+    )?;
+    writeln!(out, "cargo run -- borrowck <file>")?;
+    writeln!(
+        out,
+        "    Borrow-check your own program, written in the toy language"
+    )?;
+    writeln!(out, "cargo run -- 03 --visualize [number]")?;
+    writeln!(
+        out,
+        "    Draw each variable's scope and each borrow's region in the examples as bars"
+    )?;
+    writeln!(out, "cargo run -- visualize <file>")?;
+    writeln!(
+        out,
+        "    Draw the scopes and regions of your own program, written in the toy language"
+    )?;
+    writeln!(out, "cargo run -- 04 --matches [number]")?;
+    writeln!(
+        out,
+        "    Check each match in the examples for values no arm covers and arms no value reaches"
+    )?;
+    writeln!(out, "cargo run -- matches <file>")?;
+    writeln!(out, "    Check the match expressions of your own Rust file")?;
+    writeln!(out, "cargo run -- 04 --calc <expression> [name=value ...]")?;
+    writeln!(
+        out,
+        "    Parse, fold and evaluate an expression such as \"1 + 2 * (3 - x)\" x=4"
+    )?;
+    writeln!(out, "cargo run -- exercise [chapter/name]")?;
+    writeln!(
+        out,
+        "    List the exercises, or check your solution to one of them"
+    )?;
+    writeln!(out, "cargo run -- quiz [chapter] [--count <number>]")?;
+    writeln!(
+        out,
+        "    Answer questions about the chapters; missed ones are asked again first"
+    )?;
+    writeln!(out, "cargo run -- review [chapter] [--count <number>]")?;
+    writeln!(
+        out,
+        "    Answer only the questions that are due for review today"
+    )?;
+    writeln!(
+        out,
+        "cargo run -- export <directory> [--format html|mdbook]"
+    )?;
+    writeln!(
+        out,
+        "    Write the chapters as a static website, or as the source of an mdBook"
+    )?;
+    writeln!(out, "cargo run -- progress")?;
+    writeln!(
+        out,
+        "    Show which chapters, examples and exercises you have completed"
+    )?;
+    writeln!(out, "cargo run -- next")?;
+    writeln!(out, "    Recommend the next unfinished lesson")?;
+    writeln!(out, "cargo run -- reset [chapter]")?;
+    writeln!(
+        out,
+        "    Forget your progress, or only the progress of one chapter"
+    )?;
+    writeln!(out, "cargo run -- verify [chapter]")?;
+    writeln!(
+        out,
+        "    Check every chapter's `// Result:` comments against its real output"
+    )
+}
+
+// Runs a whole chapter, one of its examples, or lists its examples
+fn run_chapter(
+    chapter: &dyn Chapter,
+    options: &[&str],
+    out: &mut dyn Write,
+) -> io::Result<ExitCode> {
+    match options {
+        [] => {
+            writeln!(out, "{}", chapter.banner())?;
+            writeln!(out, "--------------")?;
+            chapter.run(out)?;
+            record(|progress| progress.complete_chapter(chapter));
+        }
+        ["--list"] => {
+            writeln!(out, "ch{} {} examples:", chapter.id(), chapter.title())?;
+            for example in chapter.examples() {
+                // Examples with a broken variant can also be run with --break
+                let breakable = compile_fail::for_example(chapter.id(), example.id).next();
+                let marker = if breakable.is_some() {
+                    " [--break]"
+                } else {
+                    ""
+                };
+                writeln!(out, "{:>4}. {}{}", example.id, example.title, marker)?;
+                writeln!(out, "      {}", example.explanation)?;
+            }
+        }
+        ["--example", id] => {
+            let Some(example) = id.parse().ok().and_then(|id| chapter.example(id)) else {
+                writeln!(
+                    out,
+                    "ch{} has no example `{}`, see `cargo run -- {} --list`",
+                    chapter.id(),
+                    id,
+                    chapter.id()
+                )?;
+                return Ok(ExitCode::FAILURE);
+            };
+            writeln!(out, "{}", chapter.banner())?;
+            writeln!(out, "--------------")?;
+            writeln!(out, "Example {}: {}", example.id, example.title)?;
+            writeln!(out, "{}", example.explanation)?;
+            writeln!(out)?;
+            (example.run)(out)?;
+            record(|progress| progress.complete_example(chapter, example.id));
+        }
+        ["--break", id] => return run_break(chapter, id, out),
+        ["--simulate"] => return toy_examples(chapter, None, Tool::Simulator, out),
+        ["--simulate", id] => return toy_examples(chapter, Some(id), Tool::Simulator, out),
+        ["--borrowck"] => return toy_examples(chapter, None, Tool::BorrowChecker, out),
+        ["--borrowck", id] => return toy_examples(chapter, Some(id), Tool::BorrowChecker, out),
+        ["--visualize"] => return toy_examples(chapter, None, Tool::Visualizer, out),
+        ["--visualize", id] => return toy_examples(chapter, Some(id), Tool::Visualizer, out),
+        ["--matches"] => return toy_examples(chapter, None, Tool::MatchChecker, out),
+        ["--matches", id] => return toy_examples(chapter, Some(id), Tool::MatchChecker, out),
+        ["--calc", expression, bindings @ ..] if chapter.id() == "04" => {
+            return run_calc(expression, bindings, out)
+        }
+        // Tools read this instead of a learner, so it doesn't count as progress
+        ["--format", "json"] => writeln!(out, "{}", json::chapter(chapter)?)?,
         _ => {
-            println!("Please specify a module to run. Examples:");
-            println!("cargo run -- 01 (or ch01, or ownership)");
-            println!("cargo run -- 02 (or ch02, or borrowing)");
-            println!("cargo run -- 03 (or ch03, or lifetime)");
-            println!("cargo run -- 04 (or ch04, or pattern_matching)");
+            print_usage(out)?;
+            return Ok(ExitCode::FAILURE);
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+// Writes the broken variants of an example to a temp crate and shows what rustc says about them
+fn run_break(chapter: &dyn Chapter, id: &str, out: &mut dyn Write) -> io::Result<ExitCode> {
+    let example = id.parse().ok().and_then(|id| chapter.example(id));
+    let snippets: Vec<&compile_fail::Snippet> = match example {
+        Some(example) => compile_fail::for_example(chapter.id(), example.id).collect(),
+        None => Vec::new(),
+    };
+    let Some(example) = example.filter(|_| !snippets.is_empty()) else {
+        // SNIPPETS is in example order, so repeated ids are next to each other
+        let mut breakable: Vec<String> = compile_fail::SNIPPETS
+            .iter()
+            .filter(|s| s.chapter == chapter.id())
+            .map(|s| s.example.to_string())
+            .collect();
+        breakable.dedup();
+        writeln!(
+            out,
+            "ch{} example `{}` has no broken variant",
+            chapter.id(),
+            id
+        )?;
+        if breakable.is_empty() {
+            writeln!(out, "No example of ch{} has one yet", chapter.id())?;
+        } else {
+            writeln!(out, "Try one of these examples: {}", breakable.join(", "))?;
+        }
+        return Ok(ExitCode::FAILURE);
+    };
+
+    writeln!(out, "{}", chapter.banner())?;
+    writeln!(out, "--------------")?;
+    writeln!(out, "Example {}: {}", example.id, example.title)?;
+    writeln!(out, "{}", example.explanation)?;
+    for snippet in snippets {
+        // The crate is kept (and replaced on the next run), so it can be opened and fixed by hand
+        let dir = std::env::temp_dir()
+            .join("rust-concepts-break")
+            .join(format!("ch{}", chapter.id()))
+            .join(snippet.name());
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir)?;
+        }
+        compile_fail::materialize(&dir, snippet.name(), snippet.source)?;
+
+        writeln!(out)?;
+        writeln!(out, "Broken variant `{}`:", snippet.name())?;
+        writeln!(out)?;
+        for line in snippet.source.lines() {
+            writeln!(out, "    {}", line)?;
+        }
+        writeln!(out)?;
+        let diagnostics = match compile_fail::compile_crate(&dir) {
+            Ok(diagnostics) => diagnostics,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                writeln!(
+                    out,
+                    "rustc was not found; install a Rust toolchain or set $RUSTC"
+                )?;
+                return Ok(ExitCode::FAILURE);
+            }
+            Err(err) => return Err(err),
+        };
+        if diagnostics.success {
+            writeln!(
+                out,
+                "rustc accepted this program, although it should report {}",
+                snippet.expected_code().unwrap_or("an error")
+            )?;
+        } else {
+            writeln!(out, "rustc says:")?;
+            writeln!(out)?;
+            write!(out, "{}", diagnostics.rendered)?;
+        }
+        writeln!(out)?;
+        writeln!(out, "Why:")?;
+        writeln!(out, "{}", snippet.explanation())?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "The crate is in {}; run `cargo build` there to try a fix",
+            dir.display()
+        )?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+// Lists the exercises with the files to edit
+fn list_exercises(out: &mut dyn Write) -> io::Result<ExitCode> {
+    writeln!(
+        out,
+        "Exercises (edit the file, then run `cargo run -- exercise <id>`):"
+    )?;
+    for exercise in exercises::EXERCISES {
+        writeln!(out, "  {:<28} exercises/{}", exercise.id(), exercise.path)?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+// Grades the learner's file for one exercise and reports each hidden test
+fn run_exercise(id: &str, out: &mut dyn Write) -> io::Result<ExitCode> {
+    let Some(exercise) = exercises::find(id) else {
+        writeln!(
+            out,
+            "There is no exercise `{}`, see `cargo run -- exercise` for the list",
+            id
+        )?;
+        return Ok(ExitCode::FAILURE);
+    };
+    let source = std::fs::read_to_string(exercise.learner_file())?;
+    writeln!(
+        out,
+        "Exercise {}: exercises/{}",
+        exercise.id(),
+        exercise.path
+    )?;
+
+    match exercises::grade(exercise, &source)? {
+        Grade::DoesNotCompile { diagnostics } => {
+            writeln!(out, "Your file does not compile yet:")?;
+            writeln!(out)?;
+            write!(out, "{}", diagnostics)?;
+        }
+        Grade::TimedOut => {
+            writeln!(
+                out,
+                "The hidden tests did not finish within {} seconds. Is there an endless loop?",
+                exercises::TIME_LIMIT.as_secs()
+            )?;
+        }
+        Grade::Tested { results } => {
+            for result in &results {
+                let status = if result.passed { "ok" } else { "FAILED" };
+                writeln!(out, "  {:<7} {}", status, result.name)?;
+                if let Some(message) = &result.message {
+                    for line in message.lines() {
+                        writeln!(out, "          {}", line)?;
+                    }
+                }
+            }
+            let passed = results.iter().filter(|r| r.passed).count();
+            if passed == results.len() {
+                writeln!(out, "All {} hidden tests passed. Well done!", passed)?;
+                record(|progress| progress.complete_exercise(exercise));
+                return Ok(ExitCode::SUCCESS);
+            }
+            writeln!(out, "{} of {} hidden tests passed", passed, results.len())?;
+        }
+    }
+
+    writeln!(out)?;
+    writeln!(out, "Hints:")?;
+    for (number, hint) in exercise.hints.iter().enumerate() {
+        writeln!(out, "  {}. {}", number + 1, hint)?;
+    }
+    Ok(ExitCode::FAILURE)
+}
+
+// Runs a quiz on stdin and stdout, keeping the answers in the quiz history
+// A review asks only the questions that are due today, instead of the weakest ones
+fn run_quiz(options: &[&str], review: bool, out: &mut dyn Write) -> io::Result<ExitCode> {
+    let mut chapter = None;
+    let mut count = None;
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        match (*option, options.clone().next()) {
+            ("--count", Some(number)) if number.parse::<usize>().is_ok_and(|n| n > 0) => {
+                count = number.parse().ok();
+                options.next();
+            }
+            (name, _) if chapter.is_none() && registry::find(name).is_some() => {
+                chapter = registry::find(name);
+            }
+            _ => {
+                print_usage(out)?;
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+    }
+
+    let questions = match chapter {
+        Some(chapter) => quiz::questions::for_chapter(chapter),
+        None => quiz::all_questions(),
+    };
+    // Like progress, a history that can't be read is left alone rather than overwritten;
+    // the quiz still runs, but its answers aren't saved
+    let (mut history, path) = match History::default_path() {
+        Some(path) => match History::load(&path) {
+            Ok(history) => (history, Some(path)),
+            Err(err) => {
+                eprintln!(
+                    "note: answers won't be saved, could not read {}: {}",
+                    path.display(),
+                    err
+                );
+                (History::default(), None)
+            }
+        },
+        None => (History::default(), None),
+    };
+    let clock = SystemClock;
+    let selected = if review {
+        let mut due = quiz::due(&questions, &history, &clock);
+        if due.is_empty() {
+            print_next_review(&questions, &history, clock.today(), out)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+        due.truncate(count.unwrap_or(due.len()));
+        due
+    } else {
+        // The clock is only used to vary the order of questions between quizzes
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(1, |elapsed| elapsed.as_nanos() as u64);
+        quiz::select(
+            &questions,
+            &history,
+            count.unwrap_or(5),
+            &mut quiz::Rng::new(seed),
+        )
+    };
+
+    quiz::run(
+        &selected,
+        &mut history,
+        &clock,
+        &mut io::stdin().lock(),
+        out,
+    )?;
+    if let Some(path) = path {
+        if let Err(err) = history.save(&path) {
+            eprintln!(
+                "note: could not save the quiz history in {}: {}",
+                path.display(),
+                err
+            );
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+// Explains why there is nothing to review, and when there will be
+fn print_next_review(
+    questions: &[quiz::questions::Question],
+    history: &History,
+    today: u64,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let next = questions
+        .iter()
+        .filter_map(|question| history.get(&question.id))
+        .filter(|record| record.asked > 0)
+        .map(|record| record.due)
+        .min();
+    match next {
+        Some(due) => {
+            let days = due.saturating_sub(today);
+            writeln!(
+                out,
+                "Nothing to review today. The next review is due in {} day{}.",
+                days,
+                if days == 1 { "" } else { "s" }
+            )
+        }
+        None => writeln!(
+            out,
+            "Nothing to review yet. Start with `cargo run -- quiz`; every answer schedules a review."
+        ),
+    }
+}
+
+// The tools that check the examples' programs: the toy-language ones, and the match
+// checker, which reads the `match` fixtures instead
+#[derive(Clone, Copy)]
+enum Tool {
+    Simulator,
+    BorrowChecker,
+    Visualizer,
+    MatchChecker,
+}
+
+impl Tool {
+    fn name(self) -> &'static str {
+        match self {
+            Tool::Simulator => "the ownership simulator",
+            Tool::BorrowChecker => "the borrow checker",
+            Tool::Visualizer => "the lifetime visualizer",
+            Tool::MatchChecker => "the match checker",
+        }
+    }
+
+    // The programs the tool reads for an example, as (file name, source)
+    fn programs(self, chapter: &str, example: u32) -> Vec<(&'static str, &'static str)> {
+        match self {
+            Tool::MatchChecker => toy::matches_for(chapter, example),
+            _ => toy::programs_for(chapter, example),
+        }
+    }
+
+    // What the tool says about a program, or `None` if the program is beyond it
+    fn render(self, name: &str, source: &str) -> Option<String> {
+        match self {
+            Tool::Simulator => parser::parse(source)
+                .is_ok_and(|program| ownership::supports(&program))
+                .then(|| ownership::run(source).render(name, source)),
+            Tool::BorrowChecker => Some(borrowck::run(source).render(name, source)),
+            Tool::Visualizer => Some(regions::run(source).render(name, source)),
+            Tool::MatchChecker => Some(usefulness::run(source).render(name, source)),
+        }
+    }
+}
+
+// Runs the toy programs of a chapter's examples (or of one example) through a tool and
+// prints what it says
+fn toy_examples(
+    chapter: &dyn Chapter,
+    id: Option<&str>,
+    tool: Tool,
+    out: &mut dyn Write,
+) -> io::Result<ExitCode> {
+    let examples: Vec<_> = match id {
+        Some(id) => match id.parse().ok().and_then(|id| chapter.example(id)) {
+            Some(example) => vec![example],
+            None => Vec::new(),
+        },
+        None => chapter.examples().iter().collect(),
+    };
+    let mut ran = false;
+    for example in examples {
+        for (name, source) in tool.programs(chapter.id(), example.id) {
+            let Some(rendered) = tool.render(name, source) else {
+                continue;
+            };
+            if ran {
+                writeln!(out)?;
+            }
+            ran = true;
+            writeln!(out, "Example {}: {} ({})", example.id, example.title, name)?;
+            writeln!(out)?;
+            write!(out, "{}", rendered)?;
         }
     }
+    if !ran {
+        writeln!(
+            out,
+            "ch{} has no programs for {}{}",
+            chapter.id(),
+            tool.name(),
+            id.map(|id| format!(" in example `{}`", id))
+                .unwrap_or_default()
+        )?;
+        return Ok(ExitCode::FAILURE);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+// Runs a program written in the toy language and prints its trace
+fn simulate_file(path: &str, out: &mut dyn Write) -> io::Result<ExitCode> {
+    let source = std::fs::read_to_string(path)?;
+    let trace = ownership::run(&source);
+    write!(out, "{}", trace.render(path, &source))?;
+    Ok(if trace.error.is_some() {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+// Borrow-checks a program written in the toy language and prints its loans and conflicts
+fn borrowck_file(path: &str, out: &mut dyn Write) -> io::Result<ExitCode> {
+    let source = std::fs::read_to_string(path)?;
+    let check = borrowck::run(&source);
+    write!(out, "{}", check.render(path, &source))?;
+    Ok(if check.errors.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+// Draws the scopes and regions of a program written in the toy language
+fn visualize_file(path: &str, out: &mut dyn Write) -> io::Result<ExitCode> {
+    let source = std::fs::read_to_string(path)?;
+    let regions = regions::run(&source);
+    write!(out, "{}", regions.render(path, &source))?;
+    Ok(if regions.errors.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+// Checks the match expressions of a Rust file for missing patterns and unreachable arms
+fn check_matches_file(path: &str, out: &mut dyn Write) -> io::Result<ExitCode> {
+    let source = std::fs::read_to_string(path)?;
+    let check = usefulness::run(&source);
+    write!(out, "{}", check.render(path, &source))?;
+    Ok(if check.errors.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+// Runs the ch04 capstone calculator: prints the expression as parsed, folded and evaluated
+fn run_calc(expression: &str, bindings: &[&str], out: &mut dyn Write) -> io::Result<ExitCode> {
+    let env: Result<calc::Env, calc::CalcError> =
+        bindings.iter().map(|text| calc::binding(text)).collect();
+    let parsed = env.and_then(|env| Ok((env, calc::parse(expression)?)));
+    let (env, expr) = match parsed {
+        Ok(parsed) => parsed,
+        Err(error) => return calc_error(expression, &error, out),
+    };
+    writeln!(out, "Parsed: {}", expr)?;
+    writeln!(out, "Folded: {}", calc::fold(&expr))?;
+    match calc::eval(&expr, &env) {
+        Ok(value) => writeln!(out, "Value:  {}", value)?,
+        Err(error) => return calc_error(expression, &error, out),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+// Reading errors also point at where in the expression they are
+fn calc_error(
+    expression: &str,
+    error: &calc::CalcError,
+    out: &mut dyn Write,
+) -> io::Result<ExitCode> {
+    writeln!(out, "error: {}", error)?;
+    if let Some(at) = error.at() {
+        writeln!(out, "  {}", expression)?;
+        writeln!(out, "  {}^", " ".repeat(at))?;
+    }
+    Ok(ExitCode::FAILURE)
+}
+
+// Prints the catalog: every chapter with its number of examples and exercises
+fn list_chapters(out: &mut dyn Write) -> io::Result<ExitCode> {
+    for chapter in registry::CHAPTERS {
+        let exercises = exercises::EXERCISES
+            .iter()
+            .filter(|e| e.chapter == chapter.id())
+            .count();
+        writeln!(
+            out,
+            "ch{} {} ({} examples, {} exercises)",
+            chapter.id(),
+            chapter.title(),
+            chapter.examples().len(),
+            exercises
+        )?;
+        writeln!(out, "    {}", chapter.summary())?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+// Writes every chapter, with its code and output, as a book
+fn run_export(options: &[&str], out: &mut dyn Write) -> io::Result<ExitCode> {
+    let (dir, format) = match options {
+        [dir] => (dir, Some(Format::Html)),
+        [dir, "--format", format] | ["--format", format, dir] => (dir, Format::parse(format)),
+        _ => (&"", None),
+    };
+    let Some(format) = format else {
+        print_usage(out)?;
+        return Ok(ExitCode::FAILURE);
+    };
+    let dir = PathBuf::from(dir);
+    let written = export::export(format, &dir)?;
+    writeln!(out, "Wrote {} files to {}", written.len(), dir.display())?;
+    match format {
+        Format::Html => writeln!(
+            out,
+            "Open {} in a browser",
+            dir.join("index.html").display()
+        )?,
+        Format::MdBook => writeln!(out, "Build it with `mdbook build {}`", dir.display())?,
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+// Updates the progress file after a lesson
+// Progress is only a convenience, so a failure to record it is reported without failing the lesson
+fn record(update: impl FnOnce(&mut Progress)) {
+    let Some(path) = Progress::default_path() else {
+        return;
+    };
+    let result = Progress::load(&path).and_then(|mut progress| {
+        update(&mut progress);
+        progress.save(&path)
+    });
+    if let Err(err) = result {
+        eprintln!(
+            "note: could not record progress in {}: {}",
+            path.display(),
+            err
+        );
+    }
+}
+
+// Finds and reads the progress file for the progress, next and reset commands
+// Prints why it can't and returns None if there is no usable file
+fn load_progress(out: &mut dyn Write) -> io::Result<Option<(PathBuf, Progress)>> {
+    let Some(path) = Progress::default_path() else {
+        writeln!(
+            out,
+            "There is nowhere to store progress: set XDG_DATA_HOME or HOME"
+        )?;
+        return Ok(None);
+    };
+    match Progress::load(&path) {
+        Ok(progress) => Ok(Some((path, progress))),
+        Err(err) => {
+            writeln!(out, "Could not read {}: {}", path.display(), err)?;
+            writeln!(
+                out,
+                "Fix the file, or run `cargo run -- reset` to start over"
+            )?;
+            Ok(None)
+        }
+    }
+}
+
+// Shows what has been completed in each chapter, and what to do next
+fn show_progress(out: &mut dyn Write) -> io::Result<ExitCode> {
+    let Some((path, progress)) = load_progress(out)? else {
+        return Ok(ExitCode::FAILURE);
+    };
+    writeln!(out, "Progress (stored in {}):", path.display())?;
+    let mut completed = 0;
+    for &chapter in registry::CHAPTERS {
+        let examples = chapter.examples();
+        let examples_done = examples
+            .iter()
+            .filter(|e| progress.has_example(chapter, e.id))
+            .count();
+        let chapter_exercises: Vec<_> = exercises::EXERCISES
+            .iter()
+            .filter(|e| e.chapter == chapter.id())
+            .collect();
+        let exercises_done = chapter_exercises
+            .iter()
+            .filter(|e| progress.exercises.contains(&e.id()))
+            .count();
+
+        let mut line = format!(
+            "  ch{} {:<24} examples {:>2}/{:<2}",
+            chapter.id(),
+            chapter.title(),
+            examples_done,
+            examples.len()
+        );
+        if !chapter_exercises.is_empty() {
+            line += &format!("  exercises {}/{}", exercises_done, chapter_exercises.len());
+        }
+        if examples_done == examples.len() && exercises_done == chapter_exercises.len() {
+            completed += 1;
+            line += "  done";
+        }
+        writeln!(out, "{}", line.trim_end())?;
+    }
+    writeln!(
+        out,
+        "Completed {} of {} chapters",
+        completed,
+        registry::CHAPTERS.len()
+    )?;
+    print_next(&progress, out)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+// Recommends the next unfinished lesson
+fn show_next(out: &mut dyn Write) -> io::Result<ExitCode> {
+    let Some((_, progress)) = load_progress(out)? else {
+        return Ok(ExitCode::FAILURE);
+    };
+    print_next(&progress, out)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn print_next(progress: &Progress, out: &mut dyn Write) -> io::Result<()> {
+    match progress.next() {
+        Some(lesson) => {
+            writeln!(out, "Next: {}", lesson.description())?;
+            writeln!(out, "      {}", lesson.command())
+        }
+        None => writeln!(out, "You have completed every lesson. Well done!"),
+    }
+}
+
+// Forgets all progress, or the progress of one chapter
+fn reset_progress(name: Option<&str>, out: &mut dyn Write) -> io::Result<ExitCode> {
+    let chapter = match name.map(registry::find) {
+        Some(Some(chapter)) => Some(chapter),
+        Some(None) => {
+            print_usage(out)?;
+            return Ok(ExitCode::FAILURE);
+        }
+        None => None,
+    };
+    let Some(chapter) = chapter else {
+        let Some(path) = Progress::default_path() else {
+            return load_progress(out).map(|_| ExitCode::FAILURE);
+        };
+        match std::fs::remove_file(&path) {
+            Ok(()) => writeln!(out, "Progress reset, removed {}", path.display())?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                writeln!(out, "There is no progress to reset")?
+            }
+            Err(err) => return Err(err),
+        }
+        return Ok(ExitCode::SUCCESS);
+    };
+
+    let Some((path, mut progress)) = load_progress(out)? else {
+        return Ok(ExitCode::FAILURE);
+    };
+    progress.reset_chapter(chapter);
+    progress.save(&path)?;
+    writeln!(
+        out,
+        "Progress of ch{} {} reset",
+        chapter.id(),
+        chapter.title()
+    )?;
+    Ok(ExitCode::SUCCESS)
+}
+
+// Checks the `// Result:` comments of one chapter, or of all chapters if none is given
+fn run_verify(name: Option<&str>, out: &mut dyn Write) -> io::Result<ExitCode> {
+    let chapters: Vec<&dyn Chapter> = match name {
+        Some(name) => match registry::find(name) {
+            Some(chapter) => vec![chapter],
+            None => {
+                print_usage(out)?;
+                return Ok(ExitCode::FAILURE);
+            }
+        },
+        None => registry::CHAPTERS.to_vec(),
+    };
+
+    let mut failed = false;
+    for chapter in chapters {
+        let report = verify::verify_chapter(chapter)?;
+        if report.mismatches.is_empty() {
+            writeln!(
+                out,
+                "ch{} {}: {} results ok",
+                chapter.id(),
+                chapter.title(),
+                report.checked
+            )?;
+        } else {
+            failed = true;
+            writeln!(
+                out,
+                "ch{} {}: {} of {} results do not match",
+                chapter.id(),
+                chapter.title(),
+                report.mismatches.len(),
+                report.checked
+            )?;
+            for mismatch in &report.mismatches {
+                writeln!(out, "  {}", mismatch)?;
+            }
+        }
+    }
+
+    Ok(if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
 }
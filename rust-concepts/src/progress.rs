@@ -0,0 +1,330 @@
+use crate::chapter::{Chapter, Example};
+use crate::exercises::{self, Exercise};
+use crate::registry;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// What a learner has completed so far
+///
+/// Progress is stored as a small TOML file. Only the subset of TOML this file needs is
+/// supported (comments and `key = ["string", ...]` lines), so it is read and written by
+/// hand instead of pulling in a TOML crate:
+///
+/// ```text
+/// chapters = ["01"]
+/// examples = ["01/1", "01/2", "02/6"]
+/// exercises = ["ch01/exclaim"]
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Progress {
+    /// The ids of the chapters that were run in full or whose examples were all run
+    pub chapters: BTreeSet<String>,
+    /// The examples that were run, as (chapter id, example number)
+    pub examples: BTreeSet<(String, u32)>,
+    /// The ids of the exercises whose hidden tests all passed, such as "ch03/longest_word"
+    pub exercises: BTreeSet<String>,
+}
+
+/// The directory where the learner's files are kept: `$XDG_DATA_HOME/rust-concepts`
+///
+/// If `XDG_DATA_HOME` isn't set (or isn't an absolute path, which the XDG spec says to
+/// ignore), it defaults to `$HOME/.local/share`. Returns None if neither is available.
+pub fn data_dir() -> Option<PathBuf> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| Path::new(&home).join(".local").join("share"))
+        })?;
+    Some(data_home.join("rust-concepts"))
+}
+
+/// The next thing to study, as recommended by [`Progress::next`]
+pub enum Lesson {
+    Example {
+        chapter: &'static dyn Chapter,
+        example: &'static Example,
+    },
+    Exercise(&'static Exercise),
+}
+
+impl Lesson {
+    /// The command that starts the lesson
+    pub fn command(&self) -> String {
+        match self {
+            Lesson::Example { chapter, example } => {
+                format!("cargo run -- {} --example {}", chapter.id(), example.id)
+            }
+            Lesson::Exercise(exercise) => format!("cargo run -- exercise {}", exercise.id()),
+        }
+    }
+
+    /// A short description of the lesson
+    pub fn description(&self) -> String {
+        match self {
+            Lesson::Example { chapter, example } => {
+                format!(
+                    "ch{} example {}: {}",
+                    chapter.id(),
+                    example.id,
+                    example.title
+                )
+            }
+            Lesson::Exercise(exercise) => format!(
+                "exercise {}, edit exercises/{}",
+                exercise.id(),
+                exercise.path
+            ),
+        }
+    }
+}
+
+/// A line of the progress file that couldn't be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The 1-based line number in the file
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+impl Progress {
+    /// Where progress is stored: `progress.toml` in the [`data_dir`]
+    pub fn default_path() -> Option<PathBuf> {
+        Some(data_dir()?.join("progress.toml"))
+    }
+
+    /// Reads progress from a file; a missing file means nothing has been done yet
+    pub fn load(path: &Path) -> io::Result<Progress> {
+        match fs::read_to_string(path) {
+            Ok(text) => Progress::parse(&text)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Progress::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Writes progress to a file, creating its directory if needed
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.to_toml())
+    }
+
+    /// Parses the contents of a progress file
+    ///
+    /// Unknown keys are skipped, so files written by newer versions can still be read.
+    ///
+    /// ```
+    /// use rust_concepts::progress::Progress;
+    ///
+    /// let progress = Progress::parse("# mine\nexamples = [\"01/1\", \"01/2\"]\n").unwrap();
+    /// assert!(progress.examples.contains(&("01".to_string(), 2)));
+    ///
+    /// let err = Progress::parse("chapters = [01]").unwrap_err();
+    /// assert_eq!(err.to_string(), "line 1: expected a string, found `01`");
+    /// ```
+    pub fn parse(text: &str) -> Result<Progress, ParseError> {
+        let mut progress = Progress::default();
+        for (index, line) in text.lines().enumerate() {
+            let error = |message: String| ParseError {
+                line: index + 1,
+                message,
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(error(format!("expected `key = [...]`, found `{}`", line)));
+            };
+            let values = parse_string_array(value.trim()).map_err(error)?;
+            match key.trim() {
+                "chapters" => progress.chapters.extend(values),
+                "exercises" => progress.exercises.extend(values),
+                "examples" => {
+                    for value in values {
+                        let example = value.split_once('/').and_then(|(chapter, id)| {
+                            Some((chapter.to_string(), id.parse().ok()?))
+                        });
+                        let Some(example) = example else {
+                            return Err(error(format!(
+                                "expected an example like \"01/4\", found \"{}\"",
+                                value
+                            )));
+                        };
+                        progress.examples.insert(example);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(progress)
+    }
+
+    /// Serializes progress in the format [`Progress::parse`] reads
+    ///
+    /// ```
+    /// use rust_concepts::progress::Progress;
+    ///
+    /// let mut progress = Progress::default();
+    /// progress.exercises.insert("ch03/longest_word".to_string());
+    /// let text = progress.to_toml();
+    /// assert!(text.contains("exercises = [\"ch03/longest_word\"]\n"));
+    /// assert_eq!(Progress::parse(&text).unwrap(), progress);
+    /// ```
+    pub fn to_toml(&self) -> String {
+        let examples: Vec<String> = self
+            .examples
+            .iter()
+            .map(|(chapter, id)| format!("{}/{}", chapter, id))
+            .collect();
+        let mut text = String::from(
+            "# Progress through rust-concepts, run `cargo run -- reset` to start over\n",
+        );
+        for (key, values) in [
+            (
+                "chapters",
+                self.chapters.iter().cloned().collect::<Vec<_>>(),
+            ),
+            ("examples", examples),
+            ("exercises", self.exercises.iter().cloned().collect()),
+        ] {
+            let values: Vec<String> = values.iter().map(|v| quote(v)).collect();
+            text.push_str(&format!("{} = [{}]\n", key, values.join(", ")));
+        }
+        text
+    }
+
+    /// Records that a whole chapter was run, which includes all of its examples
+    pub fn complete_chapter(&mut self, chapter: &dyn Chapter) {
+        for example in chapter.examples() {
+            self.examples.insert((chapter.id().to_string(), example.id));
+        }
+        self.chapters.insert(chapter.id().to_string());
+    }
+
+    /// Records that one example was run; running the last one completes the chapter
+    pub fn complete_example(&mut self, chapter: &dyn Chapter, id: u32) {
+        self.examples.insert((chapter.id().to_string(), id));
+        if chapter
+            .examples()
+            .iter()
+            .all(|e| self.has_example(chapter, e.id))
+        {
+            self.chapters.insert(chapter.id().to_string());
+        }
+    }
+
+    /// Records that every hidden test of an exercise passed
+    pub fn complete_exercise(&mut self, exercise: &Exercise) {
+        self.exercises.insert(exercise.id());
+    }
+
+    /// Whether an example was run, on its own or as part of its whole chapter
+    pub fn has_example(&self, chapter: &dyn Chapter, id: u32) -> bool {
+        self.chapters.contains(chapter.id())
+            || self.examples.contains(&(chapter.id().to_string(), id))
+    }
+
+    /// Forgets everything about one chapter: the chapter, its examples and its exercises
+    pub fn reset_chapter(&mut self, chapter: &dyn Chapter) {
+        self.chapters.remove(chapter.id());
+        self.examples.retain(|(id, _)| id != chapter.id());
+        let prefix = format!("ch{}/", chapter.id());
+        self.exercises.retain(|id| !id.starts_with(&prefix));
+    }
+
+    /// The next lesson in study order: the chapters in registry order, and within a chapter
+    /// its examples first and then its exercises. Returns None once everything is done.
+    ///
+    /// ```
+    /// use rust_concepts::progress::Progress;
+    /// use rust_concepts::registry;
+    ///
+    /// let mut progress = Progress::default();
+    /// progress.complete_chapter(registry::find("01").unwrap());
+    /// let next = progress.next().unwrap();
+    /// assert_eq!(next.command(), "cargo run -- exercise ch01/exclaim");
+    /// ```
+    pub fn next(&self) -> Option<Lesson> {
+        for &chapter in registry::CHAPTERS {
+            if let Some(example) = chapter
+                .examples()
+                .iter()
+                .find(|e| !self.has_example(chapter, e.id))
+            {
+                return Some(Lesson::Example { chapter, example });
+            }
+            if let Some(exercise) = exercises::EXERCISES
+                .iter()
+                .filter(|e| e.chapter == chapter.id())
+                .find(|e| !self.exercises.contains(&e.id()))
+            {
+                return Some(Lesson::Exercise(exercise));
+            }
+        }
+        None
+    }
+}
+
+// Parses `["a", "b"]`; the strings may use the escapes \" and \\
+fn parse_string_array(text: &str) -> Result<Vec<String>, String> {
+    let Some(inner) = text.strip_prefix('[').and_then(|t| t.strip_suffix(']')) else {
+        return Err(format!(
+            "expected a list like [\"a\", \"b\"], found `{}`",
+            text
+        ));
+    };
+    let mut values = Vec::new();
+    let mut rest = inner.trim_start();
+    while !rest.is_empty() {
+        let (value, after) = parse_string(rest)?;
+        values.push(value);
+        rest = after.trim_start();
+        // Values are separated by commas, and a trailing comma is allowed
+        if let Some(after) = rest.strip_prefix(',') {
+            rest = after.trim_start();
+        } else if !rest.is_empty() {
+            return Err(format!("expected `,` between strings, found `{}`", rest));
+        }
+    }
+    Ok(values)
+}
+
+// Parses a quoted string at the start of `text`, returning it and the text after it
+pub(crate) fn parse_string(text: &str) -> Result<(String, &str), String> {
+    let Some(body) = text.strip_prefix('"') else {
+        return Err(format!("expected a string, found `{}`", text));
+    };
+    let mut value = String::new();
+    let mut chars = body.char_indices();
+    loop {
+        match chars.next() {
+            Some((i, '"')) => return Ok((value, &body[i + 1..])),
+            Some((_, '\\')) => match chars.next() {
+                Some((_, c @ ('"' | '\\'))) => value.push(c),
+                _ => return Err("unsupported escape in string".to_string()),
+            },
+            Some((_, c)) => value.push(c),
+            None => return Err("unterminated string".to_string()),
+        }
+    }
+}
+
+// Writes a string in the form parse_string reads
+pub(crate) fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
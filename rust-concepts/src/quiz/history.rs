@@ -0,0 +1,192 @@
+use super::schedule::{self, INITIAL_EASE};
+use crate::progress::{self, parse_string, quote};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// How one question has gone so far, and when it should be reviewed again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    /// How many times the question was asked
+    pub asked: u32,
+    /// How many of those answers were right
+    pub correct: u32,
+    /// How many times in a row it was answered right, up to now
+    pub streak: u32,
+    /// The SM-2 ease factor in hundredths, so 250 is an ease of 2.5
+    pub ease: u32,
+    /// The number of days between the last answer and the next review
+    pub interval: u32,
+    /// The day of the next review, in days since 1970-01-01 (see [`schedule::Clock`])
+    pub due: u64,
+}
+
+impl Default for Record {
+    fn default() -> Record {
+        Record {
+            asked: 0,
+            correct: 0,
+            streak: 0,
+            ease: INITIAL_EASE,
+            interval: 0,
+            due: 0,
+        }
+    }
+}
+
+impl Record {
+    /// Whether the last answer was wrong
+    pub fn missed_last_time(&self) -> bool {
+        self.asked > 0 && self.streak == 0
+    }
+
+    /// Whether the question was asked before and its next review is on `today` or earlier
+    pub fn is_due(&self, today: u64) -> bool {
+        self.asked > 0 && self.due <= today
+    }
+}
+
+/// The answers given to every question, kept between quizzes so missed questions come back
+///
+/// It is stored as one TOML inline table per question:
+///
+/// ```text
+/// "ch01/ex1/output1" = { asked = 3, correct = 2, streak = 1, ease = 250, interval = 1, due = 20378 }
+/// ```
+///
+/// Files written before scheduling was added have no `ease`, `interval` or `due`; their
+/// questions start with the initial ease and are due right away.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct History {
+    pub records: BTreeMap<String, Record>,
+}
+
+impl History {
+    /// Where the history is stored: `quiz.toml` in the [`progress::data_dir`]
+    pub fn default_path() -> Option<PathBuf> {
+        Some(progress::data_dir()?.join("quiz.toml"))
+    }
+
+    /// Reads the history from a file; a missing file means no question was asked yet
+    pub fn load(path: &Path) -> io::Result<History> {
+        match fs::read_to_string(path) {
+            Ok(text) => History::parse(&text)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, format!("line {}", err))),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(History::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Writes the history to a file, creating its directory if needed
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.to_toml())
+    }
+
+    /// Parses a history file; errors are "<line number>: <message>"
+    ///
+    /// Unknown fields are skipped, so files written by newer versions can still be read.
+    ///
+    /// ```
+    /// use rust_concepts::quiz::history::History;
+    ///
+    /// let history = History::parse("\"ch01/x\" = { asked = 2, correct = 1, streak = 0 }").unwrap();
+    /// assert_eq!(history.records["ch01/x"].asked, 2);
+    /// assert_eq!(History::parse(&history.to_toml()).unwrap(), history);
+    /// ```
+    pub fn parse(text: &str) -> Result<History, String> {
+        let mut history = History::default();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (id, record) = parse_line(line).map_err(|err| format!("{}: {}", index + 1, err))?;
+            history.records.insert(id, record);
+        }
+        Ok(history)
+    }
+
+    /// Serializes the history in the format [`History::parse`] reads
+    pub fn to_toml(&self) -> String {
+        let mut text =
+            String::from("# Quiz answers per question, used to ask missed questions again\n");
+        for (id, record) in &self.records {
+            text += &format!(
+                "{} = {{ asked = {}, correct = {}, streak = {}, ease = {}, interval = {}, due = {} }}\n",
+                quote(id),
+                record.asked,
+                record.correct,
+                record.streak,
+                record.ease,
+                record.interval,
+                record.due
+            );
+        }
+        text
+    }
+
+    /// The record of one question, if it was ever asked
+    pub fn get(&self, id: &str) -> Option<&Record> {
+        self.records.get(id)
+    }
+
+    /// Records an answer given on `today` and schedules the question's next review
+    pub fn record(&mut self, id: &str, correct: bool, today: u64) {
+        let record = self.records.entry(id.to_string()).or_default();
+        record.asked += 1;
+        if correct {
+            record.correct += 1;
+            record.streak += 1;
+        } else {
+            record.streak = 0;
+        }
+        schedule::review(record, correct, today);
+    }
+}
+
+// Parses `"id" = { name = value, ... }`
+fn parse_line(line: &str) -> Result<(String, Record), String> {
+    let (id, rest) = parse_string(line)?;
+    let fields = rest
+        .trim_start()
+        .strip_prefix('=')
+        .map(str::trim)
+        .and_then(|rest| rest.strip_prefix('{'))
+        .and_then(|rest| rest.strip_suffix('}'))
+        .ok_or_else(|| format!("expected `= {{ ... }}` after \"{}\"", id))?;
+
+    let mut record = Record::default();
+    for field in fields.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+        let Some((name, value)) = field.split_once('=') else {
+            return Err(format!("expected `name = value`, found `{}`", field));
+        };
+        let (name, value) = (name.trim(), value.trim());
+        let error = || format!("`{}` should be a whole number, found `{}`", name, value);
+        let number = || value.parse::<u32>().map_err(|_| error());
+        match name {
+            "asked" => record.asked = number()?,
+            "correct" => record.correct = number()?,
+            "streak" => record.streak = number()?,
+            "ease" => record.ease = number()?,
+            "interval" => record.interval = number()?,
+            // Days since the epoch, which outgrow the other counts
+            "due" => record.due = value.parse().map_err(|_| error())?,
+            _ => continue,
+        }
+    }
+    // A record is only written after an answer, and the quiz divides by `asked`
+    if record.asked == 0 {
+        return Err(format!("\"{}\" should have been asked at least once", id));
+    }
+    if record.correct > record.asked {
+        return Err(format!(
+            "\"{}\" has {} correct answers, but was only asked {} times",
+            id, record.correct, record.asked
+        ));
+    }
+    Ok((id, record))
+}
@@ -0,0 +1,177 @@
+//! The quiz: questions drawn from the chapters, answered on stdin and scored
+//!
+//! Every answer is kept in a [`history::History`], and questions that were missed last
+//! time are asked first in the next quiz. Each answer also schedules the question's next
+//! review with [`schedule`], and [`due`] picks the questions whose review has come.
+
+pub mod history;
+pub mod questions;
+pub mod schedule;
+
+use history::History;
+use questions::{option_letter, Answer, Question};
+use schedule::Clock;
+use std::io::{self, BufRead, Write};
+
+/// How a quiz went
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Score {
+    pub asked: usize,
+    pub correct: usize,
+}
+
+/// A small xorshift generator, enough to shuffle questions without an external crate
+pub struct Rng(u64);
+
+impl Rng {
+    /// A generator with a fixed seed, so the same seed gives the same quiz
+    pub fn new(seed: u64) -> Rng {
+        // xorshift gets stuck on zero
+        Rng(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+// (group, accuracy or misses, streak, random tie-breaker), smallest first
+type SortKey = (u8, u32, u32, u64);
+
+/// Picks up to `count` questions, weakest first
+///
+/// Questions missed last time come first (the most often missed ahead of the others),
+/// then questions never asked, then the rest by how rarely they were answered right.
+/// Ties are broken at random.
+pub fn select<'q>(
+    questions: &'q [Question],
+    history: &History,
+    count: usize,
+    rng: &mut Rng,
+) -> Vec<&'q Question> {
+    let mut keyed: Vec<(SortKey, &Question)> = questions
+        .iter()
+        .map(|question| {
+            let tie = rng.next_u64();
+            let key = match history.get(&question.id) {
+                Some(record) if record.missed_last_time() => {
+                    (0, u32::MAX - (record.asked - record.correct), 0, tie)
+                }
+                None => (1, 0, 0, tie),
+                // Lower accuracy first, in per mille so the key stays an integer
+                Some(record) => {
+                    let accuracy = u64::from(record.correct) * 1000 / u64::from(record.asked);
+                    (2, accuracy as u32, record.streak, tie)
+                }
+            };
+            (key, question)
+        })
+        .collect();
+    keyed.sort_by_key(|(key, _)| *key);
+    keyed.into_iter().take(count).map(|(_, q)| q).collect()
+}
+
+/// Picks the questions that are due for review on the clock's `today`, most overdue first
+///
+/// Questions that were never asked are left to the quiz.
+///
+/// ```
+/// use rust_concepts::quiz::{self, history::History, schedule::FixedClock};
+///
+/// let questions = quiz::all_questions();
+/// let mut history = History::default();
+/// history.record(&questions[0].id, true, 100);
+/// assert!(quiz::due(&questions, &history, &FixedClock(100)).is_empty());
+/// assert_eq!(quiz::due(&questions, &history, &FixedClock(101)).len(), 1);
+/// ```
+pub fn due<'q>(
+    questions: &'q [Question],
+    history: &History,
+    clock: &dyn Clock,
+) -> Vec<&'q Question> {
+    let today = clock.today();
+    let mut due: Vec<(u64, &Question)> = questions
+        .iter()
+        .filter_map(|question| {
+            let record = history.get(&question.id)?;
+            record.is_due(today).then_some((record.due, question))
+        })
+        .collect();
+    // A stable sort keeps chapter order among questions due on the same day
+    due.sort_by_key(|(day, _)| *day);
+    due.into_iter().map(|(_, q)| q).collect()
+}
+
+/// Asks the questions one by one, reading each answer as a line from `input`
+///
+/// Every answer is added to `history` and scheduled from the clock's `today`. The quiz
+/// ends early if `input` runs out.
+pub fn run(
+    questions: &[&Question],
+    history: &mut History,
+    clock: &dyn Clock,
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+) -> io::Result<Score> {
+    let mut score = Score::default();
+    for (number, question) in questions.iter().enumerate() {
+        writeln!(
+            out,
+            "Question {} of {} (ch{}, {})",
+            number + 1,
+            questions.len(),
+            question.chapter,
+            question.kind.label()
+        )?;
+        writeln!(out, "{}", question.prompt)?;
+        writeln!(out)?;
+        if let Answer::Choice { options, .. } = &question.answer {
+            for (index, option) in options.iter().enumerate() {
+                writeln!(out, "  {}) {}", option_letter(index), option)?;
+            }
+        }
+        write!(out, "Your answer: ")?;
+        out.flush()?;
+
+        let mut response = String::new();
+        if input.read_line(&mut response)? == 0 {
+            writeln!(out)?;
+            break;
+        }
+        let correct = question.check(&response);
+        history.record(&question.id, correct, clock.today());
+        score.asked += 1;
+        if correct {
+            score.correct += 1;
+            writeln!(out, "Correct!")?;
+        } else {
+            writeln!(
+                out,
+                "Not quite. The answer is: {}",
+                question.correct_answer()
+            )?;
+        }
+        writeln!(out, "{}", question.explanation)?;
+        writeln!(out)?;
+    }
+
+    writeln!(out, "Score: {} of {}", score.correct, score.asked)?;
+    if score.correct < score.asked {
+        writeln!(
+            out,
+            "The questions you missed will come up first in your next quiz."
+        )?;
+    }
+    Ok(score)
+}
+
+/// Every question about every chapter, in chapter order
+pub fn all_questions() -> Vec<Question> {
+    crate::registry::CHAPTERS
+        .iter()
+        .flat_map(|chapter| questions::for_chapter(*chapter))
+        .collect()
+}
@@ -0,0 +1,413 @@
+use crate::chapter::Chapter;
+use crate::compile_fail;
+use crate::verify;
+
+/// The kinds of question a quiz can ask
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Type the line an example prints, taken from its `// Result:` comment
+    Output,
+    /// Decide whether a piece of code compiles, using the compile-fail snippets
+    Compiles,
+    /// Pick the right option, from the hand-written questions below
+    Choice,
+}
+
+impl Kind {
+    /// How the kind is named in the quiz header
+    pub fn label(self) -> &'static str {
+        match self {
+            Kind::Output => "predict the output",
+            Kind::Compiles => "does it compile?",
+            Kind::Choice => "multiple choice",
+        }
+    }
+}
+
+/// What counts as a right answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    /// The exact text, compared with whitespace collapsed
+    Text(String),
+    /// One of several options, answered with its letter (a, b, ...) or number (1, 2, ...)
+    Choice {
+        options: Vec<String>,
+        correct: usize,
+    },
+}
+
+/// A single quiz question
+#[derive(Debug, Clone)]
+pub struct Question {
+    /// A stable id, used to keep the history of each question, such as "ch01/ex1/output1"
+    pub id: String,
+    /// The id of the chapter the question is about
+    pub chapter: &'static str,
+    pub kind: Kind,
+    /// The question, including any code it is about
+    pub prompt: String,
+    pub answer: Answer,
+    /// Shown after the question is answered, right or wrong
+    pub explanation: String,
+}
+
+impl Question {
+    /// Whether a response is right
+    ///
+    /// ```
+    /// use rust_concepts::quiz::questions::{Answer, Kind, Question};
+    ///
+    /// let question = Question {
+    ///     id: "demo".to_string(),
+    ///     chapter: "01",
+    ///     kind: Kind::Choice,
+    ///     prompt: "Is i32 Copy?".to_string(),
+    ///     answer: Answer::Choice { options: vec!["Yes".into(), "No".into()], correct: 0 },
+    ///     explanation: String::new(),
+    /// };
+    /// assert!(question.check("a"));
+    /// assert!(question.check(" 1 "));
+    /// assert!(!question.check("b"));
+    /// ```
+    pub fn check(&self, response: &str) -> bool {
+        match &self.answer {
+            Answer::Text(expected) => normalize(response) == normalize(expected),
+            Answer::Choice { options, correct } => {
+                choice_index(response, options.len()) == Some(*correct)
+            }
+        }
+    }
+
+    /// The right answer, as shown to a learner who got it wrong
+    pub fn correct_answer(&self) -> String {
+        match &self.answer {
+            Answer::Text(expected) => expected.clone(),
+            Answer::Choice { options, correct } => {
+                format!("{}) {}", option_letter(*correct), options[*correct])
+            }
+        }
+    }
+}
+
+/// The letter an option is answered with: 0 is "a", 1 is "b", ...
+pub fn option_letter(index: usize) -> char {
+    (b'a' + index as u8) as char
+}
+
+// Reads "b", "B" or "2" as the option with index 1
+fn choice_index(response: &str, count: usize) -> Option<usize> {
+    let response = response.trim().to_ascii_lowercase();
+    let index = match response.as_bytes() {
+        [letter @ b'a'..=b'z'] => (letter - b'a') as usize,
+        _ => response.parse::<usize>().ok()?.checked_sub(1)?,
+    };
+    (index < count).then_some(index)
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Every question about a chapter: predict-the-output questions from its examples,
+/// does-it-compile questions from its compile-fail snippets, and its multiple-choice questions
+pub fn for_chapter(chapter: &'static dyn Chapter) -> Vec<Question> {
+    let mut questions = output_questions(chapter);
+    questions.extend(compile_questions(chapter));
+    questions.extend(
+        CHOICES
+            .iter()
+            .filter(|choice| choice.chapter == chapter.id())
+            .map(Choice::to_question),
+    );
+    questions
+}
+
+// One question per single-line `// Result:` comment. The code is shown up to the comment,
+// which is replaced by `???`, so the earlier results are visible but the answer isn't
+fn output_questions(chapter: &'static dyn Chapter) -> Vec<Question> {
+    let source = chapter.source();
+    let lines: Vec<&str> = source.lines().collect();
+    let expectations = verify::parse_expectations(source);
+    let mut questions = Vec::new();
+
+    for example in chapter.examples() {
+        let Some(code) = chapter.example_source(example.id) else {
+            continue;
+        };
+        // The 1-based line of the chapter source where the example's function starts
+        let offset = code.as_ptr() as usize - source.as_ptr() as usize;
+        let first_line = source[..offset].lines().count() + 1;
+
+        let results: Vec<&verify::Expectation> = expectations
+            .iter()
+            .filter(|e| e.example == example.id)
+            .collect();
+        let mut number = 0;
+        for (i, result) in results.iter().enumerate() {
+            // Multi-line results are left out; they are tedious to type
+            let joined_to = |other: Option<&&verify::Expectation>| {
+                other.is_some_and(|o| o.source_line.abs_diff(result.source_line) == 1)
+            };
+            if joined_to(results.get(i + 1)) || i > 0 && joined_to(results.get(i - 1)) {
+                continue;
+            }
+            number += 1;
+            let line = lines[result.source_line - 1];
+            let mut prompt = String::from("What does this print in place of `???`?\n\n");
+            for code_line in &lines[first_line - 1..result.source_line - 1] {
+                prompt += &format!("    {}\n", code_line);
+            }
+            prompt += &format!("    {}// Result: ???", indentation(line));
+            questions.push(Question {
+                id: format!("ch{}/ex{}/output{}", chapter.id(), example.id, number),
+                chapter: chapter.id(),
+                kind: Kind::Output,
+                prompt,
+                answer: Answer::Text(result.text.clone()),
+                explanation: format!(
+                    "From example {} ({}): {}",
+                    example.id, example.title, example.explanation
+                ),
+            });
+        }
+    }
+    questions
+}
+
+fn indentation(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+// Each compile-fail snippet is a "no"; the example it breaks is a "yes"
+fn compile_questions(chapter: &'static dyn Chapter) -> Vec<Question> {
+    let options = vec![
+        "Yes, it compiles".to_string(),
+        "No, rustc rejects it".to_string(),
+    ];
+    let mut questions = Vec::new();
+    let mut examples = Vec::new();
+    for snippet in compile_fail::SNIPPETS
+        .iter()
+        .filter(|s| s.chapter == chapter.id())
+    {
+        // The leading comments name the error, so only the code is shown
+        let code: Vec<&str> = snippet
+            .source
+            .lines()
+            .skip_while(|line| line.starts_with("//"))
+            .collect();
+        questions.push(Question {
+            id: format!("ch{}/compile/{}", chapter.id(), snippet.name()),
+            chapter: chapter.id(),
+            kind: Kind::Compiles,
+            prompt: format!("Does this program compile?\n\n{}", indent(&code.join("\n"))),
+            answer: Answer::Choice {
+                options: options.clone(),
+                correct: 1,
+            },
+            explanation: format!(
+                "rustc reports error[{}]. {}",
+                snippet.expected_code().unwrap_or("?"),
+                snippet.explanation().replace('\n', " ")
+            ),
+        });
+        if !examples.contains(&snippet.example) {
+            examples.push(snippet.example);
+        }
+    }
+    for id in examples {
+        let (Some(example), Some(code)) = (chapter.example(id), chapter.example_source(id)) else {
+            continue;
+        };
+        questions.push(Question {
+            id: format!("ch{}/compile/ex{}", chapter.id(), id),
+            chapter: chapter.id(),
+            kind: Kind::Compiles,
+            prompt: format!("Does this function compile?\n\n{}", indent(code)),
+            answer: Answer::Choice {
+                options: options.clone(),
+                correct: 0,
+            },
+            explanation: format!(
+                "This is example {} ({}), which runs as part of chapter {}.",
+                id,
+                example.title,
+                chapter.id()
+            ),
+        });
+    }
+    questions
+}
+
+fn indent(code: &str) -> String {
+    code.lines()
+        .map(|line| format!("    {}", line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// A hand-written multiple-choice question
+struct Choice {
+    id: &'static str,
+    chapter: &'static str,
+    prompt: &'static str,
+    options: &'static [&'static str],
+    correct: usize,
+    explanation: &'static str,
+}
+
+impl Choice {
+    fn to_question(&self) -> Question {
+        Question {
+            id: format!("ch{}/{}", self.chapter, self.id),
+            chapter: self.chapter,
+            kind: Kind::Choice,
+            prompt: self.prompt.to_string(),
+            answer: Answer::Choice {
+                options: self.options.iter().map(|o| o.to_string()).collect(),
+                correct: self.correct,
+            },
+            explanation: self.explanation.to_string(),
+        }
+    }
+}
+
+static CHOICES: &[Choice] = &[
+    Choice {
+        id: "copy_integer",
+        chapter: "01",
+        prompt: "What happens here?\n\n    let x = 5;\n    let y = x;\n    println!(\"{} {}\", x, y);",
+        options: &[
+            "It prints \"5 5\"",
+            "It doesn't compile: x was moved into y",
+            "It prints \"5\" and then panics",
+        ],
+        correct: 0,
+        explanation: "i32 implements Copy, so `let y = x` copies the value and x stays usable.",
+    },
+    Choice {
+        id: "move_string",
+        chapter: "01",
+        prompt: "What happens here?\n\n    let s1 = String::from(\"hi\");\n    let s2 = s1;\n    println!(\"{} {}\", s1, s2);",
+        options: &[
+            "It prints \"hi hi\"",
+            "It doesn't compile: s1 was moved into s2",
+            "It prints \"hi\" twice, because s2 is a deep copy",
+        ],
+        correct: 1,
+        explanation: "String owns a heap buffer and isn't Copy, so `let s2 = s1` moves it (error E0382).",
+    },
+    Choice {
+        id: "which_is_copy",
+        chapter: "01",
+        prompt: "Which of these types implements Copy?",
+        options: &["String", "Vec<i32>", "(i32, bool)", "Box<i32>"],
+        correct: 2,
+        explanation: "A tuple is Copy when all of its fields are; the others own heap memory.",
+    },
+    Choice {
+        id: "clone_keeps_both",
+        chapter: "01",
+        prompt: "After `let s2 = s1.clone();`, which of the two Strings can still be used?",
+        options: &["Only s1", "Only s2", "Both", "Neither"],
+        correct: 2,
+        explanation: "clone makes a new allocation, so each variable owns its own data.",
+    },
+    Choice {
+        id: "one_mutable_reference",
+        chapter: "02",
+        prompt: "How many mutable references to the same value can be in use at the same time?",
+        options: &[
+            "Exactly one, and no shared references alongside it",
+            "One per thread",
+            "Any number, as long as none of them outlives the value",
+        ],
+        correct: 0,
+        explanation: "One writer or any number of readers, never both (errors E0499 and E0502).",
+    },
+    Choice {
+        id: "longest_signature",
+        chapter: "03",
+        prompt: "`longest` returns whichever of its two arguments is longer. Which signature compiles?",
+        options: &[
+            "fn longest(x: &str, y: &str) -> &str",
+            "fn longest<'a>(x: &'a str, y: &'a str) -> &'a str",
+            "fn longest<'a, 'b>(x: &'a str, y: &'b str) -> &'a str",
+            "fn longest(x: &str, y: &str) -> &'static str",
+        ],
+        correct: 1,
+        explanation: "The result may be either argument, so both must share the lifetime of the result. \
+                      Without annotations elision can't pick one (E0106).",
+    },
+    Choice {
+        id: "elision_one_input",
+        chapter: "03",
+        prompt: "Why does `fn first_word(s: &str) -> &str` compile without lifetime annotations?",
+        options: &[
+            "&str is always 'static",
+            "With exactly one input lifetime, elision gives the output that lifetime",
+            "The compiler looks at the body to infer the lifetime",
+            "The function returns a copy of the string",
+        ],
+        correct: 1,
+        explanation: "Elision rule 2: a single input lifetime is assigned to all output lifetimes.",
+    },
+    Choice {
+        id: "elision_self",
+        chapter: "03",
+        prompt: "In `fn announce(&self, announcement: &str) -> &str`, which lifetime does the result get?",
+        options: &[
+            "The lifetime of announcement",
+            "'static",
+            "The lifetime of the borrow of self",
+            "None: the method needs annotations to compile",
+        ],
+        correct: 2,
+        explanation: "Elision rule 3: in a method, the lifetime of &self is given to the output.",
+    },
+    Choice {
+        id: "struct_with_reference",
+        chapter: "03",
+        prompt: "Which struct can hold a slice borrowed from any String?",
+        options: &[
+            "struct Excerpt { part: &str }",
+            "struct Excerpt<'a> { part: &'a str }",
+            "struct Excerpt { part: &'static str }",
+        ],
+        correct: 1,
+        explanation: "A reference field needs a lifetime parameter (E0106 without it), and 'static \
+                      would only accept string literals and leaked data.",
+    },
+    Choice {
+        id: "static_str",
+        chapter: "03",
+        prompt: "Which of these has the type &'static str?",
+        options: &[
+            "\"hello\"",
+            "String::from(\"hello\").as_str()",
+            "&format!(\"{}\", 1)",
+        ],
+        correct: 0,
+        explanation: "String literals are stored in the program's binary, so they live for the whole run.",
+    },
+    Choice {
+        id: "guards_and_ranges",
+        chapter: "04",
+        prompt: "What does this evaluate to?\n\n    match 13 {\n        n if n < 13 => \"child\",\n        13..=19 => \"teen\",\n        _ => \"adult\",\n    }",
+        options: &["\"child\"", "\"teen\"", "\"adult\""],
+        correct: 1,
+        explanation: "The guard fails for 13, so matching continues with the range pattern.",
+    },
+    Choice {
+        id: "missing_variant",
+        chapter: "04",
+        prompt: "What happens if a match on an enum has no arm for one of the variants?",
+        options: &[
+            "It doesn't compile (E0004)",
+            "It panics when the missing variant shows up",
+            "Nothing happens for the missing variant",
+        ],
+        correct: 0,
+        explanation: "Matches must be exhaustive; add the arm or a `_` wildcard.",
+    },
+];
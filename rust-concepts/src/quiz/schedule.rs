@@ -0,0 +1,92 @@
+//! SM-2 spaced repetition: each answer decides when a question should be asked again
+//!
+//! A question answered right is asked again after 1 day, then 6 days, then after the
+//! previous interval times its ease factor. A wrong answer starts it over from 1 day.
+//! The ease factor starts at 2.5 and moves with every answer, so questions that are often
+//! missed come back more often; it never drops below 1.3. Intervals stop growing at
+//! [`MAXIMUM_INTERVAL`], about a hundred years.
+//!
+//! The scheduler reads the date from a [`Clock`], so tests can pick the day.
+
+use super::history::Record;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Where the scheduler gets today's date from
+pub trait Clock {
+    /// Today, as the number of days since 1970-01-01 (UTC)
+    fn today(&self) -> u64;
+}
+
+/// The system's clock
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn today(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs() / SECONDS_PER_DAY)
+    }
+}
+
+/// A clock that is stuck on one day
+pub struct FixedClock(pub u64);
+
+impl Clock for FixedClock {
+    fn today(&self) -> u64 {
+        self.0
+    }
+}
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// The ease factor of a question that was never asked, in hundredths
+pub const INITIAL_EASE: u32 = 250;
+
+/// The lowest ease factor, in hundredths
+pub const MINIMUM_EASE: u32 = 130;
+
+/// The longest interval between two reviews, in days
+pub const MAXIMUM_INTERVAL: u32 = 36_500;
+
+// SM-2 grades an answer from 0 (blackout) to 5 (perfect). A quiz answer is only right or
+// wrong, so it counts as one of two grades
+const RIGHT: u32 = 5;
+const WRONG: u32 = 2;
+
+/// Updates a question's schedule after an answer given on `today`
+///
+/// `record.streak` must already count this answer, as it is SM-2's repetition number.
+///
+/// ```
+/// use rust_concepts::quiz::history::Record;
+/// use rust_concepts::quiz::schedule;
+///
+/// let mut record = Record { asked: 1, correct: 1, streak: 1, ..Record::default() };
+/// schedule::review(&mut record, true, 100);
+/// assert_eq!((record.interval, record.due, record.ease), (1, 101, 260));
+/// ```
+pub fn review(record: &mut Record, correct: bool, today: u64) {
+    let quality = if correct { RIGHT } else { WRONG };
+    // EF' = EF + 0.1 - (5 - q) * (0.08 + (5 - q) * 0.02), in hundredths
+    let miss = 5 - quality;
+    let change = 10 - (miss * (8 + miss * 2)) as i64;
+    record.ease = (record.ease as i64 + change).clamp(MINIMUM_EASE as i64, u32::MAX as i64) as u32;
+
+    record.interval = match record.streak {
+        0 | 1 => 1,
+        2 => 6,
+        // Rounded to the nearest day. The ease has no upper limit, so a long streak would
+        // overflow u32 without the cap
+        // A file written before scheduling was added has no interval at all; such a record
+        // is past its second right answer, so it continues from the 6 days that gives
+        _ => {
+            let previous = match record.interval {
+                0 => 6,
+                interval => interval,
+            };
+            let next = (u64::from(previous) * u64::from(record.ease) + 50) / 100;
+            next.min(u64::from(MAXIMUM_INTERVAL)) as u32
+        }
+    };
+    record.due = today.saturating_add(u64::from(record.interval));
+}
@@ -0,0 +1,36 @@
+use crate::chapter::Chapter;
+use crate::{
+    ch01_ownership, ch02_borrowing, ch03_lifetime, ch04_pattern_matching, ch05_traits,
+    ch06_concurrency, ch07_error_handling, ch08_macros, ch09_modules, ch10_unsafe,
+};
+
+/// The central list of chapters, in the order they should be studied
+/// Adding a chapter only requires implementing `Chapter` in its module and listing it here
+pub static CHAPTERS: &[&dyn Chapter] = &[
+    &ch01_ownership::Ownership,
+    &ch02_borrowing::Borrowing,
+    &ch03_lifetime::Lifetimes,
+    &ch04_pattern_matching::PatternMatching,
+    &ch05_traits::Traits,
+    &ch06_concurrency::Concurrency,
+    &ch07_error_handling::ErrorHandling,
+    &ch08_macros::Macros,
+    &ch09_modules::Modules,
+    &ch10_unsafe::UnsafeCode,
+];
+
+/// Looks up a chapter by its id, slug or any of its aliases
+///
+/// ```
+/// use rust_concepts::registry;
+///
+/// let chapter = registry::find("ch03").unwrap();
+/// assert_eq!(chapter.slug(), "lifetime");
+/// assert!(registry::find("unknown").is_none());
+/// ```
+pub fn find(name: &str) -> Option<&'static dyn Chapter> {
+    CHAPTERS
+        .iter()
+        .copied()
+        .find(|chapter| chapter.matches(name))
+}
@@ -1,4 +1,5 @@
 use crate::ch01_ownership;
+use crate::tests::{capture, capture_example};
 
 #[cfg(test)]
 mod tests {
@@ -6,10 +7,35 @@ mod tests {
 
     #[test]
     fn test_ch01_ownership_demo() {
-        // This test calls the demo function from ch01_ownership module
-        // It doesn't assert anything specific, but ensures the function runs without panicking
-        // This is useful for demonstrating that the demo code compiles and executes successfully
-        ch01_ownership::demo();
+        // This test calls the demo function from ch01_ownership module and captures its output
+        // It checks that every example printed the value described in its "Result:" comment
+        let output = capture(ch01_ownership::demo);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "Demonstrating Rust ownership!");
+        assert!(lines.contains(&"s2: hello"));
+        assert!(lines.contains(&"s3: world, s4: world"));
+        assert!(lines.contains(&"x: 5, y: 5"));
+        assert!(lines.contains(&"x is still accessible: 5"));
+        assert_eq!(lines.last(), Some(&"s8: hello"));
+    }
+
+    #[test]
+    fn test_takes_ownership() {
+        // This test checks that takes_ownership prints the String it was given
+        // The String is moved into the function, so it can't be used here afterwards
+        let s = String::from("hello");
+        let output = capture(|out| ch01_ownership::takes_ownership(s, out));
+        assert_eq!(output, "hello\n");
+    }
+
+    #[test]
+    fn test_makes_copy() {
+        // This test checks that makes_copy prints the integer it was given
+        // Because i32 is Copy, x remains usable after the call
+        let x = 5;
+        let output = capture(|out| ch01_ownership::makes_copy(x, out));
+        assert_eq!(output, "5\n");
+        assert_eq!(x, 5);
     }
 
     #[test]
@@ -56,4 +82,11 @@ mod tests {
         // This means x is copied to y, and both can be used independently
         // This test shows the difference in behavior between Copy and non-Copy types
     }
+
+    #[test]
+    fn test_example_ownership_and_functions() {
+        // Passing a String moves it, while passing an i32 copies it and leaves x usable
+        let output = capture_example(&ch01_ownership::Ownership, 4);
+        assert_eq!(output, "hello\n5\nx is still accessible: 5\n");
+    }
 }
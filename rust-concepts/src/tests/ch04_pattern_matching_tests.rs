@@ -1,4 +1,6 @@
-use crate::ch04_pattern_matching;
+use crate::ch04_pattern_matching::calc::{self, BinaryOp, CalcError, Env, Expr, Value};
+use crate::ch04_pattern_matching::{self, Message, Point, Shape};
+use crate::tests::{capture, capture_example};
 
 #[cfg(test)]
 mod tests {
@@ -6,21 +8,338 @@ mod tests {
 
     #[test]
     fn test_ch04_pattern_matching_demo() {
-        // This test calls the demo function from ch04_pattern_matching module
-        // It doesn't assert anything specific, but ensures the function runs without panicking
-        // This approach is useful for testing functions that primarily produce side effects (like printing to console)
-        // or for initial smoke tests to ensure basic functionality
-        ch04_pattern_matching::demo();
-    }
-
-    // Additional tests can be added here to check specific pattern matching scenarios
-    // For example:
-    // - Test matching on literal values
-    // - Test matching with guards
-    // - Test destructuring structs and enums
-    // - Test matching on Option and Result types
-    // - Test if let and while let constructs
-    // - Test pattern matching in function parameters
-    // Each of these tests would involve setting up specific scenarios,
-    // calling functions that use pattern matching, and asserting on the results
+        // This test calls the demo function from ch04_pattern_matching module and captures its output
+        // Each match in the demo prints the arm it took, so the output shows which patterns matched
+        let output = capture(ch04_pattern_matching::demo);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[1..10],
+            [
+                "A teen",
+                "Antimatter, kaboom!",
+                "On the y axis at 7",
+                "Change color to r: 0, g: 160, b: 255",
+                "Got an integer: 5",
+                "Matched 5",
+                "3",
+                "2",
+                "1",
+            ]
+        );
+        assert_eq!(lines.len(), 36);
+        assert!(lines[35].contains("The pattern matching lesson is complete!"));
+    }
+
+    #[test]
+    fn test_example_match_guards() {
+        // The guard on the first arm fails for 13, so the next arm matches
+        let output = capture_example(&ch04_pattern_matching::PatternMatching, 2);
+        assert_eq!(output, "Antimatter, kaboom!\n");
+    }
+
+    #[test]
+    fn test_describe_point() {
+        // Each arm of the match, in order; (0, 0) is on both axes and takes the first arm
+        let describe = |x, y| ch04_pattern_matching::describe_point(Point { x, y });
+        assert_eq!(describe(3, 0), "On the x axis at 3");
+        assert_eq!(describe(0, 0), "On the x axis at 0");
+        assert_eq!(describe(0, 7), "On the y axis at 7");
+        assert_eq!(describe(2, -5), "On neither axis: (2, -5)");
+    }
+
+    #[test]
+    fn test_describe_message() {
+        // One arm per variant, and the match has no catch-all
+        let describe = ch04_pattern_matching::describe_message;
+        assert_eq!(describe(&Message::Quit), "Quit");
+        assert_eq!(
+            describe(&Message::Move { x: 1, y: -2 }),
+            "Move to x: 1, y: -2"
+        );
+        assert_eq!(
+            describe(&Message::Write(String::from("hello"))),
+            "Text message: hello"
+        );
+        assert_eq!(
+            describe(&Message::ChangeColor(0, 160, 255)),
+            "Change color to r: 0, g: 160, b: 255"
+        );
+    }
+
+    #[test]
+    fn test_slice_patterns() {
+        // [] and [only] match exact lengths, [first, .., last] everything longer
+        let describe = ch04_pattern_matching::describe_slice;
+        assert_eq!(describe(&[]), "Empty");
+        assert_eq!(describe(&[7]), "Just 7");
+        assert_eq!(describe(&[1, 2]), "Starts with 1 and ends with 2");
+        assert_eq!(describe(&[1, 2, 3, 4]), "Starts with 1 and ends with 4");
+
+        // [x, rest @ ..] peels off one element per call until [] ends the recursion
+        assert_eq!(ch04_pattern_matching::sum(&[]), 0);
+        assert_eq!(ch04_pattern_matching::sum(&[5]), 5);
+        assert_eq!(ch04_pattern_matching::sum(&[1, 2, 3, 4]), 10);
+    }
+
+    #[test]
+    fn test_classify_age() {
+        // The @ bindings report the age each range matched, including the edges of the ranges
+        let classify = ch04_pattern_matching::classify_age;
+        assert_eq!(classify(0), "0 is a child");
+        assert_eq!(classify(12), "12 is a child");
+        assert_eq!(classify(13), "13 is a teenager");
+        assert_eq!(classify(19), "19 is a teenager");
+        assert_eq!(classify(20), "20 is an adult");
+    }
+
+    #[test]
+    fn test_describe_shape() {
+        let describe = ch04_pattern_matching::describe_shape;
+        let circle = |x, y, radius| Shape::Circle {
+            center: Point { x, y },
+            radius,
+        };
+        let line = |x1, y1, x2, y2| Shape::Line(Point { x: x1, y: y1 }, Point { x: x2, y: y2 });
+        assert_eq!(
+            describe(&circle(0, 0, 20)),
+            "A circle of radius 20 at the origin"
+        );
+        assert_eq!(
+            describe(&circle(1, 2, 9)),
+            "A small circle of radius 9 at (1, 2)"
+        );
+        assert_eq!(
+            describe(&circle(1, 2, 10)),
+            "A circle of radius 10 at (1, 2)"
+        );
+        assert_eq!(describe(&line(2, 0, 2, 9)), "A vertical line at x = 2");
+        assert_eq!(describe(&line(0, 4, 6, 4)), "A horizontal line at y = 4");
+        assert_eq!(describe(&line(0, 0, 3, 4)), "A line from (0, 0) to (3, 4)");
+    }
+
+    #[test]
+    fn test_binding_modes() {
+        // Matching through a reference borrows, so the Option is still usable afterwards
+        let name = Some(String::from("Ferris"));
+        assert_eq!(ch04_pattern_matching::name_length(&name), 6);
+        assert_eq!(ch04_pattern_matching::name_length(&None), 0);
+        assert_eq!(name.as_deref(), Some("Ferris"));
+
+        // Through &mut, the bindings are &mut and write back into the points
+        let mut points = [Point { x: 1, y: 1 }, Point { x: -1, y: 0 }];
+        ch04_pattern_matching::shift_right(&mut points);
+        assert_eq!(points, [Point { x: 2, y: 1 }, Point { x: 0, y: 0 }]);
+
+        let output = capture_example(&ch04_pattern_matching::PatternMatching, 11);
+        assert!(output.starts_with("count is 2\n"));
+    }
+
+    #[test]
+    fn test_refutability() {
+        // The irrefutable let and the else branch of the refutable if let both run
+        let output = capture_example(&ch04_pattern_matching::PatternMatching, 12);
+        assert_eq!(output, "a = 1, b = 2\nGot nothing\n");
+    }
+
+    #[test]
+    fn test_parse_pair() {
+        // Each let-else returns early on its own kind of bad input
+        let parse = ch04_pattern_matching::parse_pair;
+        assert_eq!(parse("3, 4"), Ok((3, 4)));
+        assert_eq!(parse("-1,0"), Ok((-1, 0)));
+        assert_eq!(parse("3 4"), Err("no comma in `3 4`".to_string()));
+        assert_eq!(parse("x, 4"), Err("`x` isn't a number".to_string()));
+        assert_eq!(parse("3, y"), Err("`y` isn't a number".to_string()));
+    }
+
+    #[test]
+    fn test_matches_macro() {
+        assert!("aeiouAEIOU".chars().all(ch04_pattern_matching::is_vowel));
+        assert!(!"xyz".chars().any(ch04_pattern_matching::is_vowel));
+
+        // The guard inside matches! has to hold too
+        let output = capture_example(&ch04_pattern_matching::PatternMatching, 14);
+        assert_eq!(output, "Vowels in pattern: 2\nMoves right: true\n");
+    }
+
+    #[test]
+    fn test_parameter_patterns() {
+        assert_eq!(
+            ch04_pattern_matching::point_label(Point { x: 3, y: -1 }),
+            "(3, -1)"
+        );
+        assert_eq!(ch04_pattern_matching::manhattan_distance(&(0, 0)), 0);
+        assert_eq!(ch04_pattern_matching::manhattan_distance(&(-3, 4)), 7);
+    }
+
+    // Parses and evaluates an expression with x = 4 and flag = true
+    fn evaluate(input: &str) -> Result<Value, CalcError> {
+        let env = Env::from([
+            ("x".to_string(), Value::Int(4)),
+            ("flag".to_string(), Value::Bool(true)),
+        ]);
+        calc::eval(&calc::parse(input)?, &env)
+    }
+
+    #[test]
+    fn test_calc_precedence_and_associativity() {
+        assert_eq!(evaluate("1 + 2 * 3"), Ok(Value::Int(7)));
+        assert_eq!(evaluate("(1 + 2) * 3"), Ok(Value::Int(9)));
+        assert_eq!(evaluate("10 - 4 - 3"), Ok(Value::Int(3)));
+        assert_eq!(evaluate("17 % 5 * 2"), Ok(Value::Int(4)));
+        assert_eq!(evaluate("--x"), Ok(Value::Int(4)));
+        assert_eq!(evaluate("1 + 2 * (3 - x)"), Ok(Value::Int(-1)));
+        // || binds loosest, then &&, then comparisons
+        assert_eq!(evaluate("x < 1 || x > 3 && !flag"), Ok(Value::Bool(false)));
+        assert_eq!(evaluate("(x < 1 || x > 3) && flag"), Ok(Value::Bool(true)));
+        assert_eq!(
+            evaluate("x == 4 == flag"),
+            Err(CalcError::ChainedComparison { at: 7 })
+        );
+
+        let Ok(Expr::Binary(BinaryOp::Sub, left, right)) = calc::parse("10 - 4 - 3") else {
+            panic!("expected a subtraction");
+        };
+        assert_eq!(*left, calc::parse("10 - 4").unwrap());
+        assert_eq!(*right, Expr::Int(3));
+    }
+
+    #[test]
+    fn test_calc_syntax_errors() {
+        let error = |input| calc::parse(input).unwrap_err().to_string();
+        assert_eq!(
+            error("1 +"),
+            "expected an expression, found the end of the input"
+        );
+        assert_eq!(error("(1 + 2"), "expected `)`, found the end of the input");
+        assert_eq!(error("1 2"), "expected an operator, found `2`");
+        assert_eq!(error("1 $ 2"), "unexpected character `$`");
+        assert_eq!(error("99999999999999999999"), "number too large");
+        assert_eq!(calc::parse("(1 + 2").unwrap_err().at(), Some(6));
+        assert_eq!(calc::parse("1 $ 2").unwrap_err().at(), Some(2));
+    }
+
+    #[test]
+    fn test_calc_nesting_limit() {
+        // Up to the limit, every stage recurses through the tree without running out of stack
+        let depth = calc::MAX_DEPTH;
+        let nested = format!("{}x{}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(evaluate(&nested), Ok(Value::Int(4)));
+        let negated = format!("{}x", "-".repeat(depth));
+        assert_eq!(
+            calc::fold(&calc::parse(&negated).unwrap()).to_string(),
+            negated
+        );
+        let sum = vec!["1"; depth + 1].join(" + ");
+        assert_eq!(evaluate(&sum), Ok(Value::Int(depth as i64 + 1)));
+
+        // One more level is an error pointing at where it starts, not a stack overflow
+        let too_deep = format!("{}1", "-".repeat(20_000));
+        assert_eq!(
+            calc::parse(&too_deep),
+            Err(CalcError::TooDeep { at: depth })
+        );
+        // A long chain is a deep tree too, so it's reported as too large
+        let too_long = vec!["1"; depth + 2].join(" + ");
+        assert_eq!(
+            evaluate(&too_long).unwrap_err().to_string(),
+            format!(
+                "expression too large: more than {} nested or chained operators",
+                depth
+            )
+        );
+    }
+
+    #[test]
+    fn test_calc_runtime_errors() {
+        assert_eq!(
+            evaluate("y + 1"),
+            Err(CalcError::UnknownVariable("y".to_string()))
+        );
+        assert_eq!(evaluate("x / (x - 4)"), Err(CalcError::DivisionByZero));
+        assert_eq!(evaluate("x % 0"), Err(CalcError::DivisionByZero));
+        assert_eq!(
+            evaluate("9223372036854775807 + 1"),
+            Err(CalcError::Overflow)
+        );
+        assert_eq!(
+            evaluate("x + flag").unwrap_err().to_string(),
+            "`+` expects numbers, found `true`"
+        );
+        assert_eq!(
+            evaluate("!x").unwrap_err().to_string(),
+            "`!` expects a bool, found `4`"
+        );
+        assert_eq!(
+            evaluate("x && flag").unwrap_err().to_string(),
+            "`&&` expects bools, found `4`"
+        );
+        assert_eq!(
+            evaluate("x == flag").unwrap_err().to_string(),
+            "`==` expects operands of the same type, found `true`"
+        );
+        // The right side of && and || is only evaluated when it's needed
+        assert_eq!(evaluate("!flag && y"), Ok(Value::Bool(false)));
+        assert_eq!(evaluate("flag || 1 / 0 == 0"), Ok(Value::Bool(true)));
+        assert_eq!(
+            evaluate("flag && y"),
+            Err(CalcError::UnknownVariable("y".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_calc_fold() {
+        let fold = |input| calc::fold(&calc::parse(input).unwrap()).to_string();
+        assert_eq!(fold("1 + 2 * 3"), "7");
+        assert_eq!(fold("x * (2 + 3) - 10 / 2"), "x * 5 - 5");
+        assert_eq!(fold("-(2 - 5) < x"), "3 < x");
+        assert_eq!(fold("false && x"), "false");
+        assert_eq!(fold("x && false"), "x && false");
+        // Parts that would fail are kept, so evaluating still reports the error
+        assert_eq!(fold("x + 1 / 0"), "x + 1 / 0");
+        assert_eq!(fold("1 + true"), "1 + true");
+        // A negation that would overflow keeps its literal in parentheses, not as `--`
+        assert_eq!(
+            fold("-(0 - 9223372036854775807 - 1)"),
+            "-(-9223372036854775808)"
+        );
+    }
+
+    #[test]
+    fn test_calc_printing() {
+        // Printing keeps only the parentheses that change the meaning, so it parses back the same
+        for (input, printed) in [
+            ("((1 + 2)) * (3)", "(1 + 2) * 3"),
+            ("1 - (2 - 3)", "1 - (2 - 3)"),
+            ("(1 - 2) - 3", "1 - 2 - 3"),
+            ("-(x + 1)", "-(x + 1)"),
+            ("(1 < 2) == flag", "(1 < 2) == flag"),
+            ("!(a || b) && c", "!(a || b) && c"),
+        ] {
+            let expr = calc::parse(input).unwrap();
+            assert_eq!(expr.to_string(), printed);
+            assert_eq!(calc::parse(printed).unwrap(), expr);
+        }
+    }
+
+    #[test]
+    fn test_calc_bindings() {
+        assert_eq!(calc::binding("x=4"), Ok(("x".to_string(), Value::Int(4))));
+        assert_eq!(
+            calc::binding("done = false"),
+            Ok(("done".to_string(), Value::Bool(false)))
+        );
+        for text in ["x", "=4", "x=", "x=four", "1x=4", "true=1"] {
+            assert_eq!(
+                calc::binding(text),
+                Err(CalcError::BadBinding(text.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_example_expression_evaluator() {
+        let output = capture_example(&ch04_pattern_matching::PatternMatching, 16);
+        assert!(output.ends_with("10 / (x - 4): error: division by zero\nFolded: x * 5 - 5\n"));
+    }
 }
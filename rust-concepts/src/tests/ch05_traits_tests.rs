@@ -0,0 +1,140 @@
+use crate::ch05_traits::{self, Container, OutlinePrint, Shout, Summary};
+use crate::tests::{capture, capture_example};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ch05_traits_demo() {
+        // This test runs the whole chapter and checks a line from the first and last examples
+        let output = capture(ch05_traits::demo);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "Demonstrating Rust traits!");
+        assert!(lines.contains(&"Total area: 9.14"));
+        assert!(lines.contains(&"c after c += b: (4, 6)"));
+    }
+
+    #[test]
+    fn test_default_and_overridden_methods() {
+        // Tweet uses the default summarize, which calls the required summarize_author
+        // NewsArticle replaces the default with its own implementation
+        let tweet = ch05_traits::Tweet {
+            username: String::from("rustlang"),
+        };
+        assert_eq!(tweet.summarize_author(), "@rustlang");
+        assert_eq!(tweet.summarize(), "(Read more from @rustlang...)");
+
+        let article = ch05_traits::NewsArticle {
+            headline: String::from("Rust 2.0 released"),
+            location: String::from("Internet"),
+            author: String::from("Ferris"),
+        };
+        assert_eq!(
+            article.summarize(),
+            "Rust 2.0 released, by Ferris (Internet)"
+        );
+        assert_eq!(
+            ch05_traits::notify(&article),
+            "Breaking news! Rust 2.0 released, by Ferris (Internet)"
+        );
+    }
+
+    #[test]
+    fn test_largest_with_trait_bounds() {
+        // largest works for any type that is PartialOrd + Copy
+        assert_eq!(ch05_traits::largest(&[3, 9, 1]), 9);
+        assert_eq!(ch05_traits::largest(&[1.5, -2.0]), 1.5);
+        assert_eq!(ch05_traits::largest(&['a', 'z', 'm']), 'z');
+    }
+
+    #[test]
+    fn test_where_clause_and_impl_trait() {
+        // describe_pair formats its first argument with Display and its second with Debug
+        assert_eq!(ch05_traits::describe_pair(&5, &"five"), "5 and \"five\"");
+        // even_numbers returns an opaque iterator that we can still collect
+        let evens: Vec<u32> = ch05_traits::even_numbers(3).collect();
+        assert_eq!(evens, [0, 2, 4]);
+    }
+
+    #[test]
+    fn test_trait_objects() {
+        // Box<dyn Shape> lets different shapes share a Vec, and total_area dispatches through the vtable
+        let shapes: Vec<Box<dyn ch05_traits::Shape>> = vec![
+            Box::new(ch05_traits::Rectangle {
+                width: 2.0,
+                height: 5.0,
+            }),
+            Box::new(ch05_traits::Circle { radius: 2.0 }),
+        ];
+        let names: Vec<&str> = shapes.iter().map(|s| s.name()).collect();
+        assert_eq!(names, ["rectangle", "circle"]);
+        let expected = 10.0 + std::f64::consts::PI * 4.0;
+        assert!((ch05_traits::total_area(&shapes) - expected).abs() < 1e-9);
+        // A trait object reference carries a vtable pointer next to the data pointer
+        assert_eq!(
+            std::mem::size_of::<&dyn ch05_traits::Shape>(),
+            2 * std::mem::size_of::<usize>()
+        );
+    }
+
+    #[test]
+    fn test_associated_type_and_const() {
+        // Shelf::CAPACITY limits how many books fit; the rejected book is handed back
+        let mut shelf = ch05_traits::Shelf::new();
+        assert_eq!(ch05_traits::Shelf::CAPACITY, 3);
+        for book in ["a", "b", "c"] {
+            assert!(shelf.add(book.to_string()).is_ok());
+        }
+        assert!(shelf.is_full());
+        assert_eq!(shelf.add("d".to_string()), Err("d".to_string()));
+        assert_eq!(shelf.items(), ["a", "b", "c"]);
+
+        // Countdown's associated Item type is u32
+        let values: Vec<u32> = ch05_traits::Countdown::from(4).collect();
+        assert_eq!(values, [4, 3, 2, 1]);
+        assert_eq!(ch05_traits::Countdown::from(0).next(), None);
+    }
+
+    #[test]
+    fn test_supertrait_outline() {
+        // outline_print is built on the Display implementation required by the supertrait
+        let v = ch05_traits::Vector { x: 10, y: -2 };
+        assert_eq!(
+            v.outline_print(),
+            "************\n*          *\n* (10, -2) *\n*          *\n************\n"
+        );
+    }
+
+    #[test]
+    fn test_blanket_impl() {
+        // Every Display type gets shout, including str, integers and our own Vector
+        assert_eq!("hey".shout(), "HEY!");
+        assert_eq!(7.shout(), "7!");
+        assert_eq!(ch05_traits::Vector { x: 1, y: 2 }.shout(), "(1, 2)!");
+    }
+
+    #[test]
+    fn test_operator_overloading() {
+        // Each operator is backed by a trait from std::ops
+        use ch05_traits::Vector;
+        let a = Vector { x: 1, y: 2 };
+        let b = Vector { x: 3, y: 4 };
+        assert_eq!(a + b, Vector { x: 4, y: 6 });
+        assert_eq!(a - b, Vector { x: -2, y: -2 });
+        assert_eq!(a * 3, Vector { x: 3, y: 6 });
+        assert_eq!(-a, Vector { x: -1, y: -2 });
+        let mut c = a;
+        c += b;
+        assert_eq!(c, a + b);
+    }
+
+    #[test]
+    fn test_example_trait_objects() {
+        // A trait object is a fat pointer: data pointer plus vtable pointer
+        let output = capture_example(&ch05_traits::Traits, 6);
+        assert!(
+            output.ends_with("Total area: 9.14\n&Circle is 1 word(s), &dyn Shape is 2 word(s)\n")
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use crate::ch06_concurrency;
+use crate::tests::{capture, capture_example};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::thread;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ch06_concurrency_demo() {
+        // The demo only prints results after joining its threads, so its output is deterministic
+        let output = capture(ch06_concurrency::demo);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "Demonstrating Rust concurrency!");
+        assert!(lines.contains(&"Counter after 8 threads x 1000 increments: 8000"));
+        assert!(lines.contains(&"Sum of squares of 1..=100: 338350"));
+    }
+
+    #[test]
+    fn test_spawn_squares_keeps_order() {
+        // Joining the handles in the order they were spawned keeps the results ordered
+        assert_eq!(ch06_concurrency::spawn_squares(4), [0, 1, 4, 9]);
+        assert!(ch06_concurrency::spawn_squares(0).is_empty());
+    }
+
+    #[test]
+    fn test_parallel_sum() {
+        // The sum must not depend on how many threads the work is split between
+        let data: Vec<i64> = (1..=1000).collect();
+        for threads in [1, 2, 3, 7, 1000, 5000] {
+            assert_eq!(ch06_concurrency::parallel_sum(&data, threads), 500500);
+        }
+        assert_eq!(ch06_concurrency::parallel_sum(&[], 4), 0);
+    }
+
+    #[test]
+    fn test_every_message_from_every_producer_arrives() {
+        // All messages must arrive exactly once, and the receiver must stop once the senders are gone
+        let messages = ch06_concurrency::collect_from_producers(4, 25);
+        assert_eq!(messages.len(), 100);
+        for id in 0..4 {
+            for n in 0..25 {
+                let expected = format!("producer {}: message {}", id, n);
+                assert_eq!(messages.iter().filter(|m| **m == expected).count(), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mutex_and_atomic_counters_lose_no_updates() {
+        // Both a Mutex and an atomic make each increment indivisible, so no update is lost
+        assert_eq!(ch06_concurrency::mutex_counter(16, 500), 8000);
+        assert_eq!(ch06_concurrency::atomic_counter(16, 500), 8000);
+    }
+
+    #[test]
+    fn test_rwlock_readers_see_the_written_data() {
+        // Every reader runs after the writer has finished, so all of them see the full data
+        assert_eq!(ch06_concurrency::rwlock_readers(&[5, 10], 4), [15; 4]);
+    }
+
+    #[test]
+    fn test_latch_releases_waiting_threads() {
+        // Threads block on the latch until it opens; opening before waiting must not deadlock
+        let latch = Arc::new(ch06_concurrency::Latch::new());
+        assert!(!latch.is_open());
+        let waiters: Vec<_> = (0..3)
+            .map(|i| {
+                let latch = Arc::clone(&latch);
+                thread::spawn(move || {
+                    latch.wait();
+                    i
+                })
+            })
+            .collect();
+        latch.open();
+        let ids: Vec<i32> = waiters.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(ids, [0, 1, 2]);
+        // Waiting on an already-open latch returns immediately
+        latch.wait();
+        assert!(latch.is_open());
+    }
+
+    #[test]
+    fn test_release_acquire_publication() {
+        // The reader waits for the flag, so it always observes the published value
+        for value in [1, 7, u64::MAX] {
+            assert_eq!(ch06_concurrency::publish_with_release_acquire(value), value);
+        }
+    }
+
+    #[test]
+    fn test_map_reduce_matches_sequential_result() {
+        // For an associative reduce, splitting the work must not change the answer
+        let items: Vec<u64> = (0..257).collect();
+        let expected: u64 = items.iter().map(|x| x * 3).sum();
+        for workers in [1, 2, 5, 16, 300] {
+            let result = ch06_concurrency::map_reduce(&items, workers, 0, |x| x * 3, |a, b| a + b);
+            assert_eq!(result, expected);
+        }
+        // An empty input reduces to the identity
+        let empty: [u64; 0] = [];
+        assert_eq!(
+            ch06_concurrency::map_reduce(&empty, 4, 9, |x| *x, |a, b| a + b),
+            9
+        );
+    }
+
+    #[test]
+    fn test_map_reduce_word_count() {
+        // The word count merges per-line maps into one total
+        let lines = ["a b a", "B c"];
+        let counts = ch06_concurrency::map_reduce(
+            &lines,
+            2,
+            BTreeMap::new(),
+            |line| ch06_concurrency::word_counts(line),
+            ch06_concurrency::merge_counts,
+        );
+        let expected: BTreeMap<String, usize> = [
+            ("a".to_string(), 2),
+            ("b".to_string(), 2),
+            ("c".to_string(), 1),
+        ]
+        .into();
+        assert_eq!(counts, expected);
+    }
+
+    #[test]
+    fn test_example_shared_state() {
+        // No increment is lost, however the threads interleave
+        let output = capture_example(&ch06_concurrency::Concurrency, 5);
+        assert_eq!(output, "Counter after 8 threads x 1000 increments: 8000\n");
+    }
+}
@@ -0,0 +1,156 @@
+use crate::ch07_error_handling::{self, Config, ConfigError};
+use crate::tests::{capture, capture_example};
+use std::error::Error;
+use std::io::{self, Read};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A reader that always fails, used to exercise the io::Error conversion
+    struct BrokenReader;
+
+    impl Read for BrokenReader {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "pipe closed"))
+        }
+    }
+
+    #[test]
+    fn test_ch07_error_handling_demo() {
+        // The demo catches its own panic, so it must run to completion
+        let output = capture(ch07_error_handling::demo);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "Demonstrating Rust error handling!");
+        assert!(lines.contains(&"Caught: Err(\"attempt to divide by zero\")"));
+    }
+
+    #[test]
+    fn test_parse_port_paths() {
+        // Valid, non-numeric and out-of-range inputs each take a different path
+        assert_eq!(ch07_error_handling::parse_port(" 443 ").unwrap(), 443);
+        assert!(matches!(
+            ch07_error_handling::parse_port("https"),
+            Err(ConfigError::InvalidNumber(_))
+        ));
+        match ch07_error_handling::parse_port("65536") {
+            Err(ConfigError::OutOfRange { key, value, max }) => {
+                assert_eq!((key.as_str(), value, max), ("port", 65536, 65535));
+            }
+            other => panic!("expected OutOfRange, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_config_propagates_errors_with_question_mark() {
+        // Each `?` in parse_config returns the first error it meets
+        assert_eq!(
+            ch07_error_handling::parse_config("workers = 2\nport = 80").unwrap(),
+            Config {
+                port: 80,
+                workers: 2
+            }
+        );
+        let missing = ch07_error_handling::parse_config("port = 80").unwrap_err();
+        assert_eq!(missing.to_string(), "missing key `workers`");
+        assert!(missing.source().is_none());
+
+        let invalid = ch07_error_handling::parse_config("port = 80\nworkers = -1").unwrap_err();
+        assert_eq!(invalid.to_string(), "invalid number");
+        assert_eq!(
+            invalid.source().unwrap().to_string(),
+            "invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_io_error_converts_through_from() {
+        // read_config's `?` turns the io::Error into ConfigError::Io and keeps it as the source
+        let error = ch07_error_handling::read_config(BrokenReader).unwrap_err();
+        assert!(matches!(error, ConfigError::Io(_)));
+        assert_eq!(
+            ch07_error_handling::error_chain(&error),
+            "could not read config: pipe closed"
+        );
+        let ok = ch07_error_handling::read_config("port = 1\nworkers = 1".as_bytes());
+        assert!(ok.is_ok());
+    }
+
+    #[test]
+    fn test_error_chain_rendering() {
+        // LoadError -> ConfigError -> ParseIntError, rendered from the outside in
+        let error = ch07_error_handling::load_config("prod.conf", "port = x").unwrap_err();
+        assert_eq!(error.path, "prod.conf");
+        assert_eq!(
+            ch07_error_handling::error_chain(&error),
+            "failed to load `prod.conf`: invalid number: invalid digit found in string"
+        );
+        let range = ch07_error_handling::load_config("a", "port = 99999").unwrap_err();
+        assert_eq!(
+            ch07_error_handling::error_chain(&range),
+            "failed to load `a`: `port` is 99999, but must be at most 65535"
+        );
+    }
+
+    #[test]
+    fn test_box_dyn_error() {
+        // Errors of different types come back through the same Box<dyn Error>
+        assert_eq!(
+            ch07_error_handling::run_command(&["add", "1", "2"]).unwrap(),
+            3
+        );
+        let parse = ch07_error_handling::run_command(&["add", "one", "2"]).unwrap_err();
+        assert!(parse.downcast_ref::<std::num::ParseIntError>().is_some());
+        let unknown = ch07_error_handling::run_command(&["sub"]).unwrap_err();
+        assert_eq!(unknown.to_string(), "unknown command `sub`");
+        let empty = ch07_error_handling::run_command(&[]).unwrap_err();
+        assert_eq!(empty.to_string(), "no command given");
+    }
+
+    #[test]
+    fn test_option_combinators() {
+        // `?` on Option returns None as soon as a step has no value
+        assert_eq!(
+            ch07_error_handling::initials("ada lovelace").as_deref(),
+            Some("AL")
+        );
+        assert_eq!(ch07_error_handling::initials("   "), None);
+        assert_eq!(
+            ch07_error_handling::first_even_squared(&[1, 3, 4, 6]),
+            Some(16)
+        );
+        assert_eq!(ch07_error_handling::first_even_squared(&[1, 3]), None);
+        // checked_mul turns an overflow into None as well
+        assert_eq!(
+            ch07_error_handling::first_even_squared(&[i32::MAX - 1]),
+            None
+        );
+        assert_eq!(
+            ch07_error_handling::find_value("a=1\n b = 2 ", "b").unwrap(),
+            "2"
+        );
+    }
+
+    #[test]
+    fn test_panics_versus_recoverable_errors() {
+        // The checked version reports the problem; the other one panics, which catch_panic turns into Err
+        assert_eq!(ch07_error_handling::checked_divide(9, 3), Some(3));
+        assert_eq!(ch07_error_handling::checked_divide(9, 0), None);
+        assert_eq!(
+            ch07_error_handling::catch_panic(|| ch07_error_handling::divide_or_panic(1, 0)),
+            Err(String::from("attempt to divide by zero"))
+        );
+        assert_eq!(
+            ch07_error_handling::catch_panic(|| panic!("code {}", 7)),
+            Err::<(), _>(String::from("code 7"))
+        );
+        assert_eq!(ch07_error_handling::catch_panic(|| 5), Ok(5));
+    }
+
+    #[test]
+    fn test_example_error_chains() {
+        // The chain goes from the LoadError down to the ParseIntError
+        let output = capture_example(&ch07_error_handling::ErrorHandling, 4);
+        assert!(output.contains("The chain has 3 errors"));
+    }
+}
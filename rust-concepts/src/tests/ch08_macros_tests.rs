@@ -0,0 +1,166 @@
+use crate::ch08_macros::{
+    self, add_ten, btreemap, count, max, my_vec, rpn, traced, Book, Command, Describe, Rgb,
+};
+use crate::tests::{capture, capture_example};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A struct exercising the trickier field types the derive has to render
+    #[allow(dead_code)]
+    #[derive(Describe)]
+    struct Tricky {
+        /// Doc comments and attributes on fields are skipped
+        #[allow(unused)]
+        pub(crate) name: Option<&'static str>,
+        bytes: [u8; 4],
+        pair: (i32, String),
+        callback: fn(i32) -> i32,
+        map: std::collections::BTreeMap<String, Vec<u8>>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Describe)]
+    struct Marker;
+
+    // A traced function with a `mut` parameter and no return value
+    #[traced(crate::ch08_macros::record_call)]
+    fn shout(mut word: String) {
+        word.make_ascii_uppercase();
+        assert!(!word.is_empty());
+    }
+
+    #[test]
+    fn test_ch08_macros_demo() {
+        // The demo drains the trace log it fills, so it can run on any test thread
+        let output = capture(ch08_macros::demo);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "Demonstrating Rust macros!");
+        assert!(lines.contains(&"rpn!(3 4 + 2 *) = 14"));
+        assert!(lines.contains(&"  traced: gcd(48, 18) -> 6"));
+        assert!(ch08_macros::take_calls().is_empty());
+    }
+
+    #[test]
+    fn test_repetition_macros() {
+        // my_vec! and btreemap! accept any number of items, with or without a trailing comma
+        let empty: Vec<u8> = my_vec![];
+        assert!(empty.is_empty());
+        assert_eq!(my_vec!["a", "b",], ["a", "b"]);
+        let v = my_vec![1, 2, 3, 4, 5];
+        assert_eq!(v, [1, 2, 3, 4, 5]);
+        assert_eq!(v.capacity(), 5);
+        let map = btreemap! { 2 => "two", 1 => "one" };
+        assert_eq!(
+            map.into_iter().collect::<Vec<_>>(),
+            [(1, "one"), (2, "two")]
+        );
+    }
+
+    #[test]
+    fn test_recursive_max() {
+        // Each argument is evaluated exactly once, even though the macro recurses
+        let mut calls = 0;
+        let mut next = |x: i32| {
+            calls += 1;
+            x
+        };
+        assert_eq!(max!(next(1), next(7), next(3), next(5)), 7);
+        assert_eq!(calls, 4);
+        assert_eq!(max!("pear", "apple"), "pear");
+    }
+
+    #[test]
+    fn test_hygiene() {
+        // The macro's own `a` is invisible to the expression we pass in
+        let a = 5;
+        assert_eq!(add_ten!(a), 15);
+        assert_eq!(add_ten!(a * a), 35);
+    }
+
+    #[test]
+    fn test_internal_rules_and_tt_muncher() {
+        // count! counts token trees, so a bracketed group counts as one
+        assert_eq!(count!(), 0);
+        assert_eq!(count!(a [b c] 3), 3);
+        // rpn! evaluates left to right with a stack
+        assert_eq!(rpn!(2), 2);
+        assert_eq!(rpn!(5 1 2 + 4 * + 3 -), 14);
+        assert_eq!(rpn!(20 4 /), 5);
+        assert_eq!(rpn!(1.5 2.0 *), 3.0);
+    }
+
+    #[test]
+    fn test_generated_newtypes() {
+        // newtype! generated the struct, its derives and its Display implementation
+        let short = ch08_macros::Meters(1.5);
+        assert_eq!(short.to_string(), "1.5 m");
+        assert!(short < ch08_macros::Meters(2.0));
+        assert_eq!(ch08_macros::Kilograms(70.0).to_string(), "70 kg");
+    }
+
+    #[test]
+    fn test_describe_derive() {
+        // The derive reports names and types exactly as they were written
+        assert_eq!(Book::type_name(), "Book");
+        assert_eq!(Book::kind(), "struct");
+        assert_eq!(
+            Book::fields(),
+            [
+                ("title", "String"),
+                ("pages", "u32"),
+                ("tags", "Vec<String>")
+            ]
+        );
+        assert_eq!(Rgb::fields(), [("0", "u8"), ("1", "u8"), ("2", "u8")]);
+        assert_eq!(Rgb::describe(), "struct Rgb(u8, u8, u8)");
+        assert_eq!(Command::kind(), "enum");
+        assert_eq!(
+            Command::fields(),
+            [
+                ("Quit", ""),
+                ("Move", " { x: i32, y: i32 }"),
+                ("Say", "(String)")
+            ]
+        );
+        assert_eq!(Marker::describe(), "struct Marker");
+        assert!(Marker::fields().is_empty());
+    }
+
+    #[test]
+    fn test_describe_renders_complex_types() {
+        // Attributes and visibility are skipped, and types are printed without token spacing
+        assert_eq!(
+            Tricky::fields(),
+            [
+                ("name", "Option<&'static str>"),
+                ("bytes", "[u8; 4]"),
+                ("pair", "(i32, String)"),
+                ("callback", "fn(i32) -> i32"),
+                ("map", "std::collections::BTreeMap<String, Vec<u8>>"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_traced_attribute() {
+        // Every call is recorded after it returns, so the innermost recursive call comes first
+        ch08_macros::take_calls();
+        assert_eq!(ch08_macros::gcd(21, 6), 3);
+        assert_eq!(
+            ch08_macros::take_calls(),
+            ["gcd(3, 0) -> 3", "gcd(6, 3) -> 3", "gcd(21, 6) -> 3"]
+        );
+        // Arguments are formatted with Debug, and `()` is recorded for functions without a return type
+        shout(String::from("hi"));
+        assert_eq!(ch08_macros::take_calls(), ["shout(\"hi\") -> ()"]);
+    }
+
+    #[test]
+    fn test_example_tt_muncher() {
+        // Both rpn! expressions are evaluated at compile time
+        let output = capture_example(&ch08_macros::Macros, 6);
+        assert_eq!(output, "rpn!(3 4 + 2 *) = 14\nrpn!(10 2 8 * + 3 -) = 23\n");
+    }
+}
@@ -0,0 +1,77 @@
+use crate::ch09_modules::{self, school};
+use crate::tests::{capture, capture_example};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The features the restaurant library was compiled with: `delivery` always, because
+    // this crate asks for it, and `catering` when this crate's own `catering` feature is on
+    fn expected_features() -> Vec<&'static str> {
+        let mut features = vec!["delivery"];
+        if cfg!(feature = "catering") {
+            features.push("catering");
+        }
+        features
+    }
+
+    #[test]
+    fn test_demo() {
+        let output = capture(ch09_modules::demo);
+        assert!(output.contains("Ferris was added to the waitlist"));
+        assert!(output.contains(&format!("Enabled features: {:?}", expected_features())));
+    }
+
+    #[test]
+    fn test_renamed_dependency() {
+        // The dependency is called `restaurant` here, but module_path! reports the real crate name
+        assert_eq!(
+            restaurant::hosting::location(),
+            "rust_concepts_restaurant::front_of_house::hosting"
+        );
+        assert_eq!(
+            restaurant::specials::location(),
+            "rust_concepts_restaurant::specials"
+        );
+    }
+
+    #[test]
+    fn test_reexports_name_the_same_type() {
+        // A re-export is an alias, not a copy, so both paths accept the same value
+        let meal: restaurant::back_of_house::Breakfast = restaurant::Breakfast::summer("Rye");
+        let same: &restaurant::prelude::Breakfast = &meal;
+        assert_eq!(same.describe(), "Rye toast with peaches");
+    }
+
+    #[test]
+    fn test_features_enabled_by_this_crate() {
+        // Cargo unifies features: this crate asks for `delivery`, and `catering` only comes with our `catering` feature
+        assert_eq!(restaurant::enabled_features(), expected_features());
+        assert_eq!(
+            restaurant::delivery::deliver("here"),
+            "Order on its way to here"
+        );
+    }
+
+    #[test]
+    fn test_school_visibility() {
+        assert_eq!(
+            school::enroll("Ada"),
+            "Ada enrolled (room assigned by the office, checked by the registrar)"
+        );
+        // registrar is pub(crate), so this test module (in the same crate) can call it directly
+        assert_eq!(school::registrar::check(), "registrar");
+        assert_eq!(school::location(), "rust_concepts::ch09_modules::school");
+    }
+
+    #[test]
+    fn test_example_cargo_features() {
+        let output = capture_example(&ch09_modules::Modules, 6);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[0],
+            format!("Enabled features: {:?}", expected_features())
+        );
+        assert_eq!(lines[1], "Delivery enabled: true");
+    }
+}
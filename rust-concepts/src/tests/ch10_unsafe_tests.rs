@@ -0,0 +1,100 @@
+use crate::ch10_unsafe::{
+    self, adler32, checked_get, float_bits, rust_concepts_adler32, sort_with_qsort, split_at_mut,
+    string_from_c, FloatOrBits,
+};
+use crate::tests::{capture, capture_example};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_demo() {
+        let output = capture(ch10_unsafe::demo);
+        assert!(output.contains("qsort: [-7, 0, 3, 19, 42]"));
+        assert!(output.contains("C harness self-test failures: 0"));
+    }
+
+    #[test]
+    fn test_checked_get() {
+        let values = [1, 2, 3];
+        assert_eq!(checked_get(&values, 0), Some(1));
+        assert_eq!(checked_get(&values, 2), Some(3));
+        assert_eq!(checked_get(&values, 3), None);
+        assert_eq!(checked_get(&[], 0), None);
+    }
+
+    #[test]
+    fn test_split_at_mut_edges() {
+        let mut values = [1, 2, 3];
+        let (left, right) = split_at_mut(&mut values, 0);
+        assert!(left.is_empty());
+        assert_eq!(right, [1, 2, 3]);
+
+        let (left, right) = split_at_mut(&mut values, 3);
+        assert_eq!(left, [1, 2, 3]);
+        assert!(right.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "past the end")]
+    fn test_split_at_mut_checks_bounds() {
+        // Without the assert, this would build a slice pointing outside the array
+        let mut values = [1, 2, 3];
+        split_at_mut(&mut values, 4);
+    }
+
+    #[test]
+    fn test_union_matches_to_bits() {
+        for value in [0.0f32, -0.0, 1.5, f32::MAX, f32::INFINITY] {
+            assert_eq!(float_bits(value), value.to_bits());
+        }
+        // Writing one field and reading the other works in both directions
+        let union = FloatOrBits { bits: 0x4049_0fdb };
+        // SAFETY: every bit pattern is a valid f32
+        let pi = unsafe { union.float };
+        assert!((pi - std::f32::consts::PI).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_qsort_handles_duplicates_and_extremes() {
+        let mut values = [3, i32::MIN, 3, i32::MAX, 0, -1];
+        sort_with_qsort(&mut values);
+        assert_eq!(values, [i32::MIN, -1, 0, 3, 3, i32::MAX]);
+
+        let mut empty: [i32; 0] = [];
+        sort_with_qsort(&mut empty);
+    }
+
+    #[test]
+    fn test_adler32_known_values() {
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"abc"), 0x024d_0127);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        // The sums wrap around the modulus on long inputs
+        assert_eq!(adler32(&[0xff; 6000]), 0xa497_59ea);
+    }
+
+    #[test]
+    fn test_exported_function_called_directly() {
+        let data = b"Wikipedia";
+        // SAFETY: the pointer and length describe `data`
+        let checksum = unsafe { rust_concepts_adler32(data.as_ptr(), data.len()) };
+        assert_eq!(checksum, adler32(data));
+        // SAFETY: a null pointer is allowed when the length is 0
+        assert_eq!(unsafe { rust_concepts_adler32(std::ptr::null(), 0) }, 1);
+    }
+
+    #[test]
+    fn test_string_from_c() {
+        let text = c"caf\xc3\xa9 \xff";
+        assert_eq!(string_from_c(text), "café \u{fffd}");
+    }
+
+    #[test]
+    fn test_example_c_callback() {
+        // qsort calls back into compare_i32 for every comparison
+        let output = capture_example(&ch10_unsafe::UnsafeCode, 7);
+        assert_eq!(output, "qsort: [-7, 0, 3, 19, 42]\n");
+    }
+}
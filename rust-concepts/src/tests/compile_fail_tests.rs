@@ -0,0 +1,78 @@
+use crate::{compile_fail, registry};
+use std::path::Path;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippets_belong_to_registered_examples() {
+        // A snippet is the broken variant of an example, so that example must exist
+        for snippet in compile_fail::SNIPPETS {
+            let chapter = registry::find(snippet.chapter)
+                .unwrap_or_else(|| panic!("{} names an unknown chapter", snippet.path));
+            assert!(
+                chapter.example(snippet.example).is_some(),
+                "{} names an unknown example",
+                snippet.path
+            );
+            assert!(
+                snippet.expected_code().is_some(),
+                "{} does not declare its error code on the first line",
+                snippet.path
+            );
+            // `--break` prints the explanation next to the diagnostic
+            assert!(
+                !snippet.explanation().is_empty(),
+                "{} does not explain why it fails",
+                snippet.path
+            );
+        }
+    }
+
+    #[test]
+    fn test_every_snippet_file_is_registered() {
+        // A file dropped into compile_fail/ but missing from SNIPPETS would never be checked
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("compile_fail");
+        let mut files = Vec::new();
+        for dir in std::fs::read_dir(&root).unwrap() {
+            let dir = dir.unwrap().path();
+            for file in std::fs::read_dir(&dir).unwrap() {
+                let file = file.unwrap().path();
+                files.push(
+                    file.strip_prefix(&root)
+                        .unwrap()
+                        .to_string_lossy()
+                        .replace('\\', "/"),
+                );
+            }
+        }
+        files.sort();
+        let mut registered: Vec<&str> = compile_fail::SNIPPETS.iter().map(|s| s.path).collect();
+        registered.sort();
+        assert_eq!(files, registered);
+    }
+
+    #[test]
+    fn test_error_codes_are_distinct_and_ordered() {
+        // Repeated codes are reported once, and plain `error:` lines have no code at all
+        let stderr =
+            "error[E0499]: first\nerror[E0502]: second\nerror[E0499]: third\nerror: aborting";
+        assert_eq!(compile_fail::error_codes(stderr), ["E0499", "E0502"]);
+        assert!(compile_fail::error_codes("warning: unused variable").is_empty());
+    }
+
+    #[test]
+    fn test_materialized_crate_layout() {
+        // The broken variant is written out as a crate that `cargo build` accepts as is
+        let dir = std::env::temp_dir().join(format!("rust-concepts-layout-{}", std::process::id()));
+        let snippet = &compile_fail::SNIPPETS[0];
+        compile_fail::materialize(&dir, snippet.name(), snippet.source).unwrap();
+        let manifest = std::fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+        let main = std::fs::read_to_string(dir.join("src/main.rs")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(manifest.contains("name = \"use_after_move\""));
+        assert!(manifest.contains("[workspace]"));
+        assert_eq!(main, snippet.source);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::exercises::{self, TestResult};
+use crate::registry;
+use std::path::Path;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The paths of all files below `exercises/<tree>`, relative to it
+    fn files_in(tree: &str) -> Vec<String> {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join(tree);
+        let mut files = Vec::new();
+        for dir in std::fs::read_dir(&root).unwrap() {
+            let dir = dir.unwrap().path();
+            if !dir.file_name().unwrap().to_string_lossy().starts_with("ch") {
+                continue;
+            }
+            for file in std::fs::read_dir(&dir).unwrap() {
+                let file = file.unwrap().path();
+                files.push(
+                    file.strip_prefix(&root)
+                        .unwrap()
+                        .to_string_lossy()
+                        .replace('\\', "/"),
+                );
+            }
+        }
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_every_exercise_file_is_registered() {
+        // Each exercise is three files: the stub, its hidden tests and its solution
+        let mut registered: Vec<String> = exercises::EXERCISES
+            .iter()
+            .map(|e| e.path.to_string())
+            .collect();
+        registered.sort();
+        assert_eq!(files_in("exercises"), registered);
+        assert_eq!(files_in("exercises/hidden"), registered);
+        assert_eq!(files_in("exercises/solutions"), registered);
+    }
+
+    #[test]
+    fn test_exercises_belong_to_chapters_and_have_hints() {
+        for exercise in exercises::EXERCISES {
+            let chapter = registry::find(exercise.chapter).unwrap();
+            assert!(exercise.path.starts_with(&format!("ch{}_", chapter.id())));
+            assert!(!exercise.hints.is_empty(), "{} has no hints", exercise.id());
+            assert!(exercise.tests.contains("mod hidden_tests"));
+            assert_eq!(
+                exercises::find(&exercise.id()).map(|e| e.path),
+                Some(exercise.path)
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_rejects_malformed_ids() {
+        assert!(exercises::find("longest_word").is_none());
+        assert!(exercises::find("ch99/longest_word").is_none());
+        assert!(exercises::find("ch03/").is_none());
+    }
+
+    #[test]
+    fn test_parse_multi_line_failure_message() {
+        // An assert_eq! failure spans several lines, and all of them belong to the message
+        let output = "\
+running 2 tests
+test hidden_tests::a ... FAILED
+test hidden_tests::b ... ok
+
+failures:
+
+---- hidden_tests::a stdout ----
+
+thread 'hidden_tests::a' (42) panicked at a.rs:9:9:
+assertion `left == right` failed
+  left: 1
+ right: 2
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+
+failures:
+    hidden_tests::a
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out
+";
+        assert_eq!(
+            exercises::parse_test_output(output),
+            [
+                TestResult {
+                    name: "a".to_string(),
+                    passed: false,
+                    message: Some(
+                        "assertion `left == right` failed\n  left: 1\n right: 2".to_string()
+                    ),
+                },
+                TestResult {
+                    name: "b".to_string(),
+                    passed: true,
+                    message: None,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use crate::export::{self, highlight, Format, Page};
+use crate::registry;
+use std::path::Path;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page<'a>(pages: &'a [Page], path: &str) -> &'a str {
+        &pages
+            .iter()
+            .find(|page| page.path == Path::new(path))
+            .unwrap_or_else(|| panic!("no page {}", path))
+            .contents
+    }
+
+    #[test]
+    fn test_html_has_a_page_per_chapter() {
+        let pages = export::render(Format::Html).unwrap();
+        // The index, the stylesheet and one page per chapter
+        assert_eq!(pages.len(), registry::CHAPTERS.len() + 2);
+        let index = page(&pages, "index.html");
+        for chapter in registry::CHAPTERS {
+            let link = format!("<a href=\"ch{}-{}.html\">", chapter.id(), chapter.slug());
+            assert!(index.contains(&link), "the index has no {}", link);
+        }
+        // Everything works offline: nothing links to or loads from another site
+        assert!(!pages.iter().any(|page| page.contents.contains("://")));
+    }
+
+    #[test]
+    fn test_chapter_page_shows_code_and_output() {
+        let pages = export::render(Format::Html).unwrap();
+        let ownership = page(&pages, "ch01-ownership.html");
+        assert!(ownership.contains("<h2>Example 1: Move semantics</h2>"));
+        assert!(ownership.contains("<span class=\"kw\">fn</span> move_semantics("));
+        assert!(ownership.contains("<pre class=\"output\">s2: hello\n</pre>"));
+        assert!(ownership.contains("<code>cargo run -- 01 --break 1</code>"));
+        assert_eq!(ownership.matches("<section id=\"example-").count(), 5);
+    }
+
+    #[test]
+    fn test_navigation_links_neighbouring_chapters() {
+        let pages = export::render(Format::Html).unwrap();
+        let first = page(&pages, "ch01-ownership.html");
+        assert!(!first.contains("rel=\"prev\""));
+        assert!(first.contains("<a href=\"ch02-borrowing.html\" rel=\"next\">Borrowing &rarr;</a>"));
+
+        let middle = page(&pages, "ch03-lifetime.html");
+        assert!(
+            middle.contains("<a href=\"ch02-borrowing.html\" rel=\"prev\">&larr; Borrowing</a>")
+        );
+        assert!(middle.contains("rel=\"next\">Pattern Matching &rarr;</a>"));
+
+        let last = page(&pages, "ch10-unsafe.html");
+        assert!(last.contains("rel=\"prev\""));
+        assert!(!last.contains("rel=\"next\""));
+    }
+
+    #[test]
+    fn test_mdbook_tree() {
+        let pages = export::render(Format::MdBook).unwrap();
+        assert!(page(&pages, "book.toml").contains("src = \"src\""));
+        let summary = page(&pages, "src/SUMMARY.md");
+        assert!(summary
+            .contains("- [Ownership](./ch01-ownership.md)\n- [Borrowing](./ch02-borrowing.md)\n"));
+
+        let lifetimes = page(&pages, "src/ch03-lifetime.md");
+        assert!(lifetimes.starts_with("# Chapter 03: Lifetimes\n"));
+        assert!(lifetimes.contains("```rust,ignore\nfn basic_annotations("));
+        assert!(lifetimes.contains("Output:\n\n```text\nLongest string: longer string\n```\n"));
+    }
+
+    #[test]
+    fn test_highlight_tokens() {
+        let span = |class: &str, text: &str| format!("<span class=\"{}\">{}</span>", class, text);
+        assert_eq!(
+            highlight("#[derive(Debug)]"),
+            span("at", "#[derive(Debug)]")
+        );
+        assert_eq!(highlight("x != y"), "x != y");
+        assert_eq!(
+            highlight("vec![1.5, 0x1f]"),
+            format!(
+                "{}[{}, {}]",
+                span("ma", "vec!"),
+                span("nu", "1.5"),
+                span("nu", "0x1f")
+            )
+        );
+        // A range isn't a decimal number
+        assert_eq!(
+            highlight("0..10"),
+            format!("{}..{}", span("nu", "0"), span("nu", "10"))
+        );
+        // Characters and lifetimes both start with a quote
+        assert_eq!(
+            highlight("('\\n', 'x', &'static T)"),
+            format!(
+                "({}, {}, &amp;{} {})",
+                span("st", "'\\n'"),
+                span("st", "'x'"),
+                span("lt", "'static"),
+                span("ty", "T")
+            )
+        );
+        assert_eq!(
+            highlight("\"say \\\"hi\\\"\" // end\nok"),
+            format!(
+                "{} {}\nok",
+                span("st", "&quot;say \\&quot;hi\\&quot;&quot;"),
+                span("co", "// end")
+            )
+        );
+    }
+}
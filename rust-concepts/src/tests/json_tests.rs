@@ -0,0 +1,202 @@
+use crate::json::{self, Json, SCHEMA_VERSION};
+use crate::registry;
+
+// A small JSON reader, only to check that the documents are well-formed
+fn parse(text: &str) -> Json {
+    let mut chars = text.chars().peekable();
+    let value = parse_value(&mut chars);
+    skip_whitespace(&mut chars);
+    assert_eq!(chars.next(), None, "trailing text after the document");
+    value
+}
+
+type Chars<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+
+fn skip_whitespace(chars: &mut Chars) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn expect(chars: &mut Chars, expected: char) {
+    skip_whitespace(chars);
+    assert_eq!(chars.next(), Some(expected));
+}
+
+fn parse_value(chars: &mut Chars) -> Json {
+    skip_whitespace(chars);
+    match chars.peek().copied() {
+        Some('"') => Json::String(parse_string(chars)),
+        Some('[') => {
+            chars.next();
+            let mut items = Vec::new();
+            skip_whitespace(chars);
+            if chars.next_if_eq(&']').is_none() {
+                loop {
+                    items.push(parse_value(chars));
+                    skip_whitespace(chars);
+                    match chars.next() {
+                        Some(',') => continue,
+                        Some(']') => break,
+                        other => panic!("expected `,` or `]`, found {:?}", other),
+                    }
+                }
+            }
+            Json::Array(items)
+        }
+        Some('{') => {
+            chars.next();
+            let mut fields = Vec::new();
+            skip_whitespace(chars);
+            if chars.next_if_eq(&'}').is_none() {
+                loop {
+                    skip_whitespace(chars);
+                    let key = parse_string(chars);
+                    expect(chars, ':');
+                    fields.push((key, parse_value(chars)));
+                    skip_whitespace(chars);
+                    match chars.next() {
+                        Some(',') => continue,
+                        Some('}') => break,
+                        other => panic!("expected `,` or `}}`, found {:?}", other),
+                    }
+                }
+            }
+            Json::Object(fields)
+        }
+        _ => {
+            let mut word = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '-') {
+                word.push(c);
+            }
+            match word.as_str() {
+                "null" => Json::Null,
+                "true" => Json::Bool(true),
+                "false" => Json::Bool(false),
+                number => Json::Number(number.parse().expect("a number")),
+            }
+        }
+    }
+}
+
+fn parse_string(chars: &mut Chars) -> String {
+    assert_eq!(chars.next(), Some('"'));
+    let mut value = String::new();
+    loop {
+        match chars.next().expect("an unterminated string") {
+            '"' => return value,
+            '\\' => match chars.next() {
+                Some('n') => value.push('\n'),
+                Some('r') => value.push('\r'),
+                Some('t') => value.push('\t'),
+                Some('u') => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    value.push(char::from_u32(u32::from_str_radix(&hex, 16).unwrap()).unwrap());
+                }
+                Some(c @ ('"' | '\\' | '/')) => value.push(c),
+                other => panic!("unknown escape {:?}", other),
+            },
+            c => {
+                assert!(!c.is_control(), "control character in a string");
+                value.push(c);
+            }
+        }
+    }
+}
+
+// The value of a field of an object
+fn field<'a>(value: &'a Json, name: &str) -> &'a Json {
+    let Json::Object(fields) = value else {
+        panic!("not an object: {:?}", value);
+    };
+    &fields.iter().find(|(key, _)| key == name).unwrap().1
+}
+
+fn array(value: &Json) -> &[Json] {
+    let Json::Array(items) = value else {
+        panic!("not an array: {:?}", value);
+    };
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strings_are_escaped() {
+        let text = "quote \" backslash \\ newline \n tab \t bell \u{7} ü";
+        let written = Json::from(text).to_string();
+        assert_eq!(
+            written,
+            "\"quote \\\" backslash \\\\ newline \\n tab \\t bell \\u0007 ü\""
+        );
+        assert_eq!(parse(&written), Json::from(text));
+    }
+
+    #[test]
+    fn test_empty_containers_stay_on_one_line() {
+        let value = Json::object([("a", Json::Array(vec![])), ("b", Json::Object(vec![]))]);
+        assert_eq!(value.to_string(), "{\n  \"a\": [],\n  \"b\": {}\n}");
+    }
+
+    #[test]
+    fn test_every_chapter_document_is_well_formed() {
+        for chapter in registry::CHAPTERS {
+            let document = json::chapter(*chapter).unwrap();
+            assert_eq!(parse(&document.to_string()), document);
+            assert_eq!(
+                field(&document, "schema_version"),
+                &Json::Number(SCHEMA_VERSION)
+            );
+            assert_eq!(
+                field(field(&document, "chapter"), "id"),
+                &Json::from(chapter.id())
+            );
+
+            let examples = array(field(&document, "examples"));
+            assert_eq!(examples.len(), chapter.examples().len());
+            for example in examples {
+                assert!(matches!(field(example, "source"), Json::String(_)));
+                assert!(!array(field(example, "stdout")).is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_example_output_and_expectations() {
+        let document = json::chapter(registry::find("01").unwrap()).unwrap();
+        let example = &array(field(&document, "examples"))[3];
+        assert_eq!(
+            field(example, "title"),
+            &Json::from("Ownership and functions")
+        );
+        let lines = |name| array(field(example, name)).to_vec();
+        let printed = vec![
+            Json::from("hello"),
+            Json::from("5"),
+            Json::from("x is still accessible: 5"),
+        ];
+        assert_eq!(lines("stdout"), printed);
+        assert_eq!(lines("expected"), printed);
+    }
+
+    #[test]
+    fn test_catalog_lists_chapters_examples_and_exercises() {
+        let catalog = json::catalog();
+        assert_eq!(parse(&catalog.to_string()), catalog);
+        let chapters = array(field(&catalog, "chapters"));
+        assert_eq!(chapters.len(), registry::CHAPTERS.len());
+
+        let lifetimes = &chapters[2];
+        assert_eq!(field(lifetimes, "slug"), &Json::from("lifetime"));
+        assert_eq!(array(field(lifetimes, "examples")).len(), 7);
+        assert_eq!(
+            array(field(lifetimes, "exercises")),
+            [
+                Json::from("ch03/longest_word"),
+                Json::from("ch03/first_sentence")
+            ]
+        );
+        // Chapters without exercises still have the field
+        assert_eq!(array(field(&chapters[9], "exercises")), []);
+    }
+}
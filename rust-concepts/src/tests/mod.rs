@@ -2,6 +2,38 @@ mod ch01_ownership_tests;
 mod ch02_borrowing_tests;
 mod ch03_lifetime_tests;
 mod ch04_pattern_matching_tests;
+mod ch05_traits_tests;
+mod ch06_concurrency_tests;
+mod ch07_error_handling_tests;
+mod ch08_macros_tests;
+mod ch09_modules_tests;
+mod ch10_unsafe_tests;
+mod compile_fail_tests;
+mod exercises_tests;
+mod export_tests;
+mod json_tests;
+mod progress_tests;
+mod quiz_tests;
+mod registry_tests;
+mod toy_tests;
+mod verify_tests;
 
 // If you want to keep the existing tests here, you can,
 // or you can move them all to their respective files.
+
+// Runs a function that writes to an output sink and returns everything it wrote
+// This lets the chapter tests assert on what a demo prints instead of only checking that it runs
+pub(crate) fn capture(f: impl FnOnce(&mut dyn std::io::Write) -> std::io::Result<()>) -> String {
+    let mut buffer = Vec::new();
+    f(&mut buffer).expect("writing to a Vec<u8> never fails");
+    String::from_utf8(buffer).expect("demo output is valid UTF-8")
+}
+
+// Runs a single numbered example of a chapter and returns everything it wrote
+// Examples are independent of each other, so each one can be checked on its own
+pub(crate) fn capture_example(chapter: &dyn crate::chapter::Chapter, id: u32) -> String {
+    let example = chapter
+        .example(id)
+        .unwrap_or_else(|| panic!("ch{} has no example {}", chapter.id(), id));
+    capture(example.run)
+}
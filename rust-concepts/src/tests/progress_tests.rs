@@ -0,0 +1,110 @@
+use crate::progress::{Lesson, Progress};
+use crate::registry;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_with_escapes() {
+        // Ids never contain quotes today, but the serializer must not produce a file it can't read
+        let mut progress = Progress::default();
+        progress.chapters.insert("01".to_string());
+        progress.examples.insert(("02".to_string(), 10));
+        progress.examples.insert(("02".to_string(), 9));
+        progress
+            .exercises
+            .insert("odd \"name\" \\ here".to_string());
+        let text = progress.to_toml();
+        assert!(text.contains("examples = [\"02/9\", \"02/10\"]\n"));
+        assert_eq!(Progress::parse(&text).unwrap(), progress);
+    }
+
+    #[test]
+    fn test_parse_accepts_comments_spacing_and_unknown_keys() {
+        let text = "\n# comment\n  chapters=[ \"03\" , ]\nversion = [\"2\"]\nexamples = []\n";
+        let progress = Progress::parse(text).unwrap();
+        assert_eq!(progress.chapters.len(), 1);
+        assert!(progress.examples.is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors_name_the_line() {
+        let err = Progress::parse("chapters = []\nexamples = [\"01-4\"]").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert_eq!(
+            err.message,
+            "expected an example like \"01/4\", found \"01-4\""
+        );
+
+        let err = Progress::parse("chapters").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1: expected `key = [...]`, found `chapters`"
+        );
+
+        let err = Progress::parse("chapters = [\"01\" \"02\"]").unwrap_err();
+        assert_eq!(err.message, "expected `,` between strings, found `\"02\"`");
+    }
+
+    #[test]
+    fn test_last_example_completes_the_chapter() {
+        let ownership = registry::find("01").unwrap();
+        let mut progress = Progress::default();
+        for example in ownership.examples() {
+            assert!(progress.chapters.is_empty());
+            progress.complete_example(ownership, example.id);
+        }
+        assert!(progress.chapters.contains("01"));
+    }
+
+    #[test]
+    fn test_reset_chapter_forgets_only_that_chapter() {
+        let mut progress = Progress::default();
+        progress.complete_chapter(registry::find("01").unwrap());
+        progress.complete_chapter(registry::find("02").unwrap());
+        progress.exercises.insert("ch01/exclaim".to_string());
+        progress.exercises.insert("ch02/count_vowels".to_string());
+        progress.reset_chapter(registry::find("01").unwrap());
+        assert!(!progress.chapters.contains("01"));
+        assert!(progress.examples.iter().all(|(chapter, _)| chapter == "02"));
+        assert_eq!(progress.exercises.len(), 1);
+    }
+
+    #[test]
+    fn test_next_follows_the_study_order() {
+        // Examples of a chapter come first, then its exercises, then the next chapter
+        let mut progress = Progress::default();
+        let ownership = registry::find("01").unwrap();
+        progress.complete_example(ownership, 1);
+        assert_eq!(
+            progress.next().unwrap().command(),
+            "cargo run -- 01 --example 2"
+        );
+
+        progress.complete_chapter(ownership);
+        progress.exercises.insert("ch01/exclaim".to_string());
+        let next = progress.next().unwrap();
+        assert!(matches!(next, Lesson::Exercise(e) if e.id() == "ch01/split_by_length"));
+
+        progress
+            .exercises
+            .insert("ch01/split_by_length".to_string());
+        assert_eq!(
+            progress.next().unwrap().command(),
+            "cargo run -- 02 --example 1"
+        );
+    }
+
+    #[test]
+    fn test_nothing_next_when_everything_is_done() {
+        let mut progress = Progress::default();
+        for chapter in registry::CHAPTERS {
+            progress.complete_chapter(*chapter);
+        }
+        for exercise in crate::exercises::EXERCISES {
+            progress.complete_exercise(exercise);
+        }
+        assert!(progress.next().is_none());
+    }
+}
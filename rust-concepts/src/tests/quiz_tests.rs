@@ -0,0 +1,333 @@
+use crate::quiz::history::{History, Record};
+use crate::quiz::questions::{self, Answer, Kind, Question};
+use crate::quiz::schedule::{self, FixedClock};
+use crate::quiz::{self, Rng};
+use crate::registry;
+use std::collections::HashSet;
+use std::io::Cursor;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn choice(id: &str) -> Question {
+        Question {
+            id: id.to_string(),
+            chapter: "01",
+            kind: Kind::Choice,
+            prompt: format!("Question {}", id),
+            answer: Answer::Choice {
+                options: vec!["right".to_string(), "wrong".to_string()],
+                correct: 0,
+            },
+            explanation: "Because.".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_question_ids_are_unique() {
+        // The history is keyed by id, so two questions must never share one
+        let questions = quiz::all_questions();
+        let ids: HashSet<&str> = questions.iter().map(|q| q.id.as_str()).collect();
+        assert_eq!(ids.len(), questions.len());
+    }
+
+    #[test]
+    fn test_every_chapter_has_output_questions() {
+        for chapter in registry::CHAPTERS {
+            let questions = questions::for_chapter(*chapter);
+            assert!(
+                questions.iter().any(|q| q.kind == Kind::Output),
+                "ch{} has no predict-the-output questions",
+                chapter.id()
+            );
+            for question in &questions {
+                if let Answer::Choice { options, correct } = &question.answer {
+                    assert!(
+                        *correct < options.len(),
+                        "{} has no right option",
+                        question.id
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_output_question_hides_the_result() {
+        let questions = questions::for_chapter(registry::find("01").unwrap());
+        let question = questions
+            .iter()
+            .find(|q| q.id == "ch01/ex1/output1")
+            .unwrap();
+        assert!(question
+            .prompt
+            .contains("    fn move_semantics(out: &mut dyn Write)"));
+        assert!(question
+            .prompt
+            .ends_with("\n        writeln!(out, \"s2: {}\", s2)?;\n        // Result: ???"));
+        assert_eq!(question.answer, Answer::Text("s2: hello".to_string()));
+        assert!(question.check("  s2:   hello "));
+        assert!(!question.check("s2: world"));
+    }
+
+    #[test]
+    fn test_compile_questions_come_from_snippets_and_examples() {
+        let questions = questions::for_chapter(registry::find("01").unwrap());
+        let broken = questions
+            .iter()
+            .find(|q| q.id == "ch01/compile/use_after_move")
+            .unwrap();
+        assert!(broken.check("b"));
+        // The header of the snippet gives the answer away, so it isn't shown
+        assert!(!broken.prompt.contains("E0382"));
+        assert!(broken
+            .explanation
+            .starts_with("rustc reports error[E0382]."));
+        let working = questions
+            .iter()
+            .find(|q| q.id == "ch01/compile/ex1")
+            .unwrap();
+        assert!(working.check("a"));
+    }
+
+    #[test]
+    fn test_choice_answers_by_letter_or_number() {
+        let question = choice("q");
+        assert!(question.check("A"));
+        assert!(question.check("1"));
+        assert!(!question.check("2"));
+        assert!(!question.check("c"));
+        assert!(!question.check("0"));
+        assert!(!question.check(""));
+        assert_eq!(question.correct_answer(), "a) right");
+    }
+
+    #[test]
+    fn test_missed_questions_are_asked_first() {
+        let questions: Vec<Question> = ["new1", "new2", "right", "missed", "missed_twice"]
+            .into_iter()
+            .map(choice)
+            .collect();
+        let mut history = History::default();
+        history.record("right", true, 0);
+        history.record("missed", false, 0);
+        history.record("missed_twice", false, 0);
+        history.record("missed_twice", false, 0);
+
+        let selected = quiz::select(&questions, &history, 4, &mut Rng::new(7));
+        let ids: Vec<&str> = selected.iter().map(|q| q.id.as_str()).collect();
+        assert_eq!(ids[..2], ["missed_twice", "missed"]);
+        let mut new: Vec<&str> = ids[2..].to_vec();
+        new.sort();
+        assert_eq!(new, ["new1", "new2"]);
+
+        // Accuracy is worked out without overflowing, however long the history
+        let history = History::parse(
+            "\"right\" = { asked = 5000000, correct = 5000000, streak = 1 }\n\
+             \"missed\" = { asked = 5000000, correct = 2500000, streak = 1 }",
+        )
+        .unwrap();
+        let selected = quiz::select(&questions[2..4], &history, 2, &mut Rng::new(7));
+        let ids: Vec<&str> = selected.iter().map(|q| q.id.as_str()).collect();
+        assert_eq!(ids, ["missed", "right"]);
+    }
+
+    #[test]
+    fn test_same_seed_same_quiz() {
+        let questions = quiz::all_questions();
+        let history = History::default();
+        let first = quiz::select(&questions, &history, 5, &mut Rng::new(42));
+        let again = quiz::select(&questions, &history, 5, &mut Rng::new(42));
+        let ids = |qs: &[&Question]| qs.iter().map(|q| q.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&first), ids(&again));
+    }
+
+    #[test]
+    fn test_run_scores_and_records_answers() {
+        let questions = [choice("one"), choice("two"), choice("three")];
+        let selected: Vec<&Question> = questions.iter().collect();
+        let mut history = History::default();
+        let mut output = Vec::new();
+        // Only two answers: the quiz stops when the input runs out
+        let score = quiz::run(
+            &selected,
+            &mut history,
+            &FixedClock(0),
+            &mut Cursor::new("a\nb\n"),
+            &mut output,
+        )
+        .unwrap();
+        assert_eq!((score.correct, score.asked), (1, 2));
+        assert_eq!(history.get("one").unwrap().streak, 1);
+        assert!(history.get("two").unwrap().missed_last_time());
+        assert!(history.get("three").is_none());
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("Question 1 of 3 (ch01, multiple choice)\nQuestion one\n\n  a) right\n  b) wrong\nYour answer: Correct!\n"));
+        assert!(output.contains("Not quite. The answer is: a) right\nBecause.\n"));
+        assert!(output.ends_with(
+            "Score: 1 of 2\nThe questions you missed will come up first in your next quiz.\n"
+        ));
+    }
+
+    #[test]
+    fn test_right_answers_space_out_reviews() {
+        let mut history = History::default();
+        let mut days = Vec::new();
+        let mut today = 0;
+        for _ in 0..4 {
+            history.record("q", true, today);
+            let record = history.get("q").unwrap();
+            days.push((record.interval, record.ease));
+            today = record.due;
+        }
+        // 1 day, 6 days, then the last interval times the ease, which grows with each answer
+        assert_eq!(days, [(1, 260), (6, 270), (17, 280), (49, 290)]);
+        assert_eq!(today, 73);
+    }
+
+    #[test]
+    fn test_long_streak_stops_at_the_maximum_interval() {
+        // Without the cap, the interval times the ease overflowed u32 on the 15th right answer
+        let mut history = History::default();
+        let mut today = 0;
+        for _ in 0..100 {
+            history.record("q", true, today);
+            today = history.get("q").unwrap().due;
+        }
+        let record = history.get("q").unwrap();
+        assert_eq!(record.streak, 100);
+        assert_eq!(record.interval, schedule::MAXIMUM_INTERVAL);
+        assert_eq!(record.ease, 250 + 100 * 10);
+
+        // A hand-edited history can't overflow it either
+        let mut record = Record {
+            streak: 3,
+            interval: u32::MAX,
+            ease: u32::MAX,
+            ..Record::default()
+        };
+        schedule::review(&mut record, true, u64::MAX - 1);
+        assert_eq!(record.interval, schedule::MAXIMUM_INTERVAL);
+        assert_eq!(record.ease, u32::MAX);
+        assert_eq!(record.due, u64::MAX);
+    }
+
+    #[test]
+    fn test_wrong_answer_starts_over() {
+        let mut record = Record {
+            asked: 4,
+            correct: 4,
+            streak: 0,
+            ease: 290,
+            interval: 49,
+            due: 73,
+        };
+        schedule::review(&mut record, false, 73);
+        assert_eq!((record.interval, record.due, record.ease), (1, 74, 258));
+
+        // Missing over and over keeps the ease at its minimum
+        for day in 74..80 {
+            schedule::review(&mut record, false, day);
+        }
+        assert_eq!(record.ease, schedule::MINIMUM_EASE);
+        assert_eq!(record.due, 80);
+    }
+
+    #[test]
+    fn test_review_serves_only_due_questions() {
+        let questions: Vec<Question> = ["early", "late", "new"].into_iter().map(choice).collect();
+        let mut history = History::default();
+        history.record("late", true, 3);
+        history.record("early", false, 0);
+
+        let due_on = |day| {
+            quiz::due(&questions, &history, &FixedClock(day))
+                .iter()
+                .map(|q| q.id.clone())
+                .collect::<Vec<_>>()
+        };
+        assert!(due_on(0).is_empty());
+        assert_eq!(due_on(1), ["early"]);
+        // The most overdue question comes first, and a question never asked is never due
+        assert_eq!(due_on(9), ["early", "late"]);
+    }
+
+    #[test]
+    fn test_run_schedules_from_the_clock() {
+        let questions = [choice("one")];
+        let selected: Vec<&Question> = questions.iter().collect();
+        let mut history = History::default();
+        quiz::run(
+            &selected,
+            &mut history,
+            &FixedClock(20_000),
+            &mut Cursor::new("a\n"),
+            &mut Vec::new(),
+        )
+        .unwrap();
+        let record = history.get("one").unwrap();
+        assert_eq!(record.due, 20_001);
+        assert!(!record.is_due(20_000));
+        assert!(record.is_due(20_001));
+    }
+
+    #[test]
+    fn test_history_round_trip_and_errors() {
+        let mut history = History::default();
+        history.record("ch03/elision_self", true, 0);
+        history.record("ch03/elision_self", false, 0);
+        history.record("ch01/\"quoted\"", true, 0);
+        let text = history.to_toml();
+        assert!(text.contains(
+            "\"ch03/elision_self\" = { asked = 2, correct = 1, streak = 0, ease = 228, interval = 1, due = 1 }\n"
+        ));
+        assert_eq!(History::parse(&text).unwrap(), history);
+
+        // Fields added by later versions are skipped
+        let history =
+            History::parse("\"a\" = { asked = 1, correct = 1, streak = 1, lapses = 0 }").unwrap();
+        assert_eq!(history.get("a").unwrap().correct, 1);
+        // Files written before scheduling was added make their questions due right away
+        let record = history.get("a").unwrap();
+        assert_eq!(record.ease, schedule::INITIAL_EASE);
+        assert!(record.is_due(0));
+        // ... and their right answers keep spacing out, instead of staying due every day
+        let mut history = History::parse("\"a\" = { asked = 3, correct = 3, streak = 3 }").unwrap();
+        let mut today = 100;
+        let mut intervals = Vec::new();
+        for _ in 0..3 {
+            history.record("a", true, today);
+            let record = history.get("a").unwrap();
+            intervals.push(record.interval);
+            today = record.due;
+        }
+        assert_eq!(intervals, [16, 43, 120]);
+        // `due` is a day number, so it can be larger than the counts
+        let history =
+            History::parse("\"a\" = { asked = 1, correct = 1, due = 5000000000 }").unwrap();
+        assert_eq!(history.get("a").unwrap().due, 5_000_000_000);
+
+        assert_eq!(
+            History::parse("\"a\" = { asked = many }").unwrap_err(),
+            "1: `asked` should be a whole number, found `many`"
+        );
+        assert_eq!(
+            History::parse("\n\"a\" = 3").unwrap_err(),
+            "2: expected `= { ... }` after \"a\""
+        );
+        // Counts the quiz can't have written would break its accuracy math
+        assert_eq!(
+            History::parse("\"a\" = { asked = 0, correct = 0 }").unwrap_err(),
+            "1: \"a\" should have been asked at least once"
+        );
+        assert_eq!(
+            History::parse(
+                "\"a\" = { asked = 1, correct = 1 }\n\"b\" = { asked = 2, correct = 3 }"
+            )
+            .unwrap_err(),
+            "2: \"b\" has 3 correct answers, but was only asked 2 times"
+        );
+    }
+}
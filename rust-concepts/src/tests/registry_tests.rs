@@ -0,0 +1,75 @@
+use crate::registry;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_by_id_slug_and_alias() {
+        // Every chapter must be reachable by its number, its "chXX" alias and its name
+        // This mirrors the names accepted on the command line
+        for name in ["01", "ch01", "ownership"] {
+            assert_eq!(registry::find(name).map(|c| c.slug()), Some("ownership"));
+        }
+        assert_eq!(
+            registry::find("04").map(|c| c.slug()),
+            Some("pattern_matching")
+        );
+        assert!(registry::find("unknown").is_none());
+    }
+
+    #[test]
+    fn test_chapters_are_in_order_and_unique() {
+        // The registry defines the study order, so ids must be ascending and never repeat
+        let ids: Vec<&str> = registry::CHAPTERS.iter().map(|c| c.id()).collect();
+        let mut sorted = ids.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(ids, sorted);
+    }
+
+    #[test]
+    fn test_banner_and_usage() {
+        // The banner and usage lines are generated from the chapter metadata
+        let chapter = registry::find("03").unwrap();
+        assert_eq!(chapter.banner(), "Demonstrating Rust lifetimes!");
+        assert!(chapter
+            .usage()
+            .starts_with("cargo run -- 03 (or ch03, or lifetime)"));
+    }
+
+    #[test]
+    fn test_examples_are_numbered_from_one() {
+        // `--example N` looks examples up by number, so each chapter numbers them 1, 2, 3, ...
+        for chapter in registry::CHAPTERS {
+            let ids: Vec<u32> = chapter.examples().iter().map(|e| e.id).collect();
+            let expected: Vec<u32> = (1..=ids.len() as u32).collect();
+            assert_eq!(
+                ids,
+                expected,
+                "ch{} examples are out of order",
+                chapter.id()
+            );
+        }
+    }
+
+    #[test]
+    fn test_example_tables_match_the_source() {
+        // The title and explanation are written both in the EXAMPLES table and above the
+        // example's function, so the two copies must not drift apart
+        for chapter in registry::CHAPTERS {
+            for example in chapter.examples() {
+                let header = format!(
+                    "// Example {}: {}\n// {}\nfn ",
+                    example.id, example.title, example.explanation
+                );
+                assert!(
+                    chapter.source().contains(&header),
+                    "ch{} example {} does not match its source comments",
+                    chapter.id(),
+                    example.id
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,945 @@
+use crate::registry;
+use crate::toy::ast::{Expr, Stmt, Type};
+use crate::toy::borrowck;
+use crate::toy::ir::{self, Action};
+use crate::toy::lexer::{tokenize, TokenKind};
+use crate::toy::ownership::{self, Trace};
+use crate::toy::parser::parse;
+use crate::toy::patterns::{self, Pat, Ty};
+use crate::toy::regions::{self, Bar};
+use crate::toy::usefulness::{self, MatchReport};
+use crate::toy::{self, Span, MATCHES, SAMPLES};
+
+// The text of every step of a trace
+fn steps(trace: &Trace) -> Vec<&str> {
+    trace.steps.iter().map(|step| step.text.as_str()).collect()
+}
+
+// Runs a program that is expected to fail and returns its error code and message
+fn error(source: &str) -> (Option<&'static str>, String) {
+    let error = ownership::run(source)
+        .error
+        .expect("the program has an error");
+    (error.code, error.message)
+}
+
+// The bar of a timeline with the given label
+fn bar<'a>(bars: &'a [Bar], label: &str) -> &'a Bar {
+    bars.iter()
+        .find(|bar| bar.label == label)
+        .expect("the timeline has the bar")
+}
+
+// Borrow-checks a program that is expected to fail and returns its first error code and message
+fn borrow_error(source: &str) -> (Option<&'static str>, String) {
+    let errors = borrowck::run(source).errors;
+    let error = errors.first().expect("the program has an error");
+    (error.code, error.message.clone())
+}
+
+// Checks the only `match` of a file that is expected to type-check
+fn report(source: &str) -> MatchReport {
+    let check = usefulness::run(source);
+    assert_eq!(check.matches.len(), 1, "{:?}", check.errors);
+    check.matches[0].clone()
+}
+
+// Wraps a `match` on a variable of the given type into a program
+fn matching(ty: &str, arms: &str) -> String {
+    format!(
+        "enum Message {{\n    Quit,\n    Move {{ x: i32, y: i32 }},\n    Write(String),\n    ChangeColor(i32, i32, i32),\n}}\n\nfn main() {{\n    let value: {} = todo!();\n    match value {{\n{}\n    }}\n}}\n",
+        ty, arms
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_have_spans() {
+        let tokens = tokenize("fn f() -> String {\n    s1.clone() // done\n}").unwrap();
+        let kinds: Vec<&TokenKind> = tokens.iter().map(|t| &t.kind).collect();
+        assert_eq!(kinds[4], &TokenKind::Punct("->"));
+        assert_eq!(kinds[7], &TokenKind::Ident("s1".to_string()));
+        assert_eq!(
+            tokens[7].span,
+            Span {
+                line: 2,
+                column: 5,
+                len: 2
+            }
+        );
+        assert_eq!(tokens.len(), 13);
+
+        let err = tokenize("let s = \"open").unwrap_err();
+        assert_eq!(err.message, "unterminated string literal");
+        assert_eq!(
+            tokenize("let a = 1 @ 2").unwrap_err().message,
+            "unknown start of token: @"
+        );
+    }
+
+    #[test]
+    fn test_parse_statements() {
+        let program = parse(
+            "fn f(a: String, n: i32) -> String {\n    let mut b: String = a;\n    b = a.clone();\n    g(n, 1);\n    b\n}",
+        )
+        .unwrap();
+        let f = &program.functions[0];
+        assert_eq!(f.params.len(), 2);
+        assert_eq!(f.ret, Type::String);
+        assert!(matches!(
+            &f.body.stmts[0],
+            Stmt::Let {
+                mutable: true,
+                ty: Some(Type::String),
+                value: Some(Expr::Var(_)),
+                ..
+            }
+        ));
+        assert!(matches!(
+            &f.body.stmts[1],
+            Stmt::Assign {
+                value: Expr::Clone { .. },
+                ..
+            }
+        ));
+        assert!(matches!(&f.body.stmts[2], Stmt::Expr(Expr::Call { args, .. }) if args.len() == 2));
+        assert!(matches!(&f.body.tail, Some(Expr::Var(name)) if name.name == "b"));
+        assert_eq!(f.body.close.line, 6);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let message = |source: &str| parse(source).unwrap_err().message;
+        assert_eq!(
+            message("fn main() {"),
+            "expected `}`, found the end of the file"
+        );
+        assert_eq!(message("fn main(x: u8) {}"), "unsupported type `u8`");
+        assert_eq!(
+            message("fn main() { vec!(1); }"),
+            "unsupported macro `vec!`"
+        );
+        assert_eq!(
+            message("fn main() { let fn = 1; }"),
+            "expected a name, found `fn`"
+        );
+    }
+
+    #[test]
+    fn test_move_semantics_trace() {
+        let trace = ownership::run(SAMPLES[0].source);
+        assert_eq!(
+            steps(&trace),
+            [
+                "s1 owns \"hello\"",
+                "s1 moved into s2",
+                "print \"s2: hello\"",
+                "drop s2"
+            ]
+        );
+        assert_eq!(trace.output, ["s2: hello"]);
+        assert!(trace.error.is_none());
+    }
+
+    #[test]
+    fn test_function_calls_move_and_copy() {
+        let trace = ownership::run(SAMPLES[3].source);
+        assert_eq!(
+            steps(&trace),
+            [
+                "s5 owns \"hello\"",
+                "s5 moved into takes_ownership",
+                "call takes_ownership",
+                "some_string owns \"hello\"",
+                "print \"hello\"",
+                "drop some_string",
+                "x holds 5",
+                "x copied into makes_copy",
+                "call makes_copy",
+                "some_integer holds 5",
+                "print \"5\"",
+                "print \"x is still accessible: 5\"",
+            ]
+        );
+        // some_string is dropped at the closing brace of takes_ownership
+        assert_eq!(trace.steps[5].span.line, 5);
+    }
+
+    #[test]
+    fn test_return_values_move_out() {
+        let trace = ownership::run(SAMPLES[4].source);
+        let steps = steps(&trace);
+        assert_eq!(
+            steps[..4],
+            [
+                "call gives_ownership",
+                "some_string owns \"yours\"",
+                "some_string moved out of gives_ownership",
+                "s6 owns \"yours\""
+            ]
+        );
+        // Variables are dropped in the reverse order of their declaration; s7 was moved
+        assert_eq!(steps[steps.len() - 2..], ["drop s8", "drop s6"]);
+        assert_eq!(trace.output, ["s6: yours", "s8: hello"]);
+    }
+
+    #[test]
+    fn test_use_after_move_is_reported_with_spans() {
+        let source = toy::programs_for("01", 1)[1].1;
+        let trace = ownership::run(source);
+        assert_eq!(steps(&trace), ["s1 owns \"hello\"", "s1 moved into s2"]);
+        let error = trace.error.as_ref().unwrap();
+        assert_eq!(error.code, Some("E0382"));
+        assert_eq!(
+            error.render("use_after_move.rs", source),
+            "error[E0382]: borrow of moved value: `s1`
+ --> use_after_move.rs:6:20
+  |
+6 |     println!(\"{}\", s1);
+  |                    ^^ value borrowed here after move
+note: `s1` moved into `s2` here
+ --> use_after_move.rs:5:14
+  |
+5 |     let s2 = s1;
+  |              ^^
+"
+        );
+    }
+
+    #[test]
+    fn test_moving_a_moved_value() {
+        let source =
+            "fn main() {\n    let a = String::from(\"x\");\n    let b = a;\n    let c = a;\n}";
+        let error = ownership::run(source).error.unwrap();
+        assert_eq!(error.message, "use of moved value: `a`");
+        assert_eq!(error.label, "value used here after move");
+        assert_eq!(error.span.line, 4);
+        assert_eq!(error.notes[0].1, "`a` moved into `b` here");
+    }
+
+    #[test]
+    fn test_assignment_drops_the_old_value() {
+        let trace = ownership::run(
+            "fn main() {\n    let mut s = String::from(\"a\");\n    let t = s;\n    s = String::from(\"b\");\n    s = t;\n}",
+        );
+        assert!(trace.error.is_none());
+        // The new value is evaluated before the old one is dropped; the first assignment has
+        // nothing to drop, because s was moved into t
+        assert_eq!(
+            steps(&trace),
+            [
+                "s owns \"a\"",
+                "s moved into t",
+                "s owns \"b\"",
+                "t moved into s",
+                "drop the old value of s",
+                "drop s"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_other_errors() {
+        assert_eq!(
+            error("fn main() {\n    let s = String::from(\"a\");\n    s = String::from(\"b\");\n}"),
+            (
+                Some("E0384"),
+                "cannot assign twice to immutable variable `s`".to_string()
+            )
+        );
+        assert_eq!(
+            error("fn f(s: String) {}\nfn main() {\n    f(5);\n}"),
+            (Some("E0308"), "mismatched types".to_string())
+        );
+        assert_eq!(
+            error("fn main() {\n    println!(\"{}\", y);\n}"),
+            (
+                Some("E0425"),
+                "cannot find value `y` in this scope".to_string()
+            )
+        );
+        assert_eq!(
+            error("fn main() {\n    g();\n}"),
+            (
+                Some("E0425"),
+                "cannot find function `g` in this scope".to_string()
+            )
+        );
+        assert_eq!(
+            error("fn f() {}"),
+            (Some("E0601"), "`main` function not found".to_string())
+        );
+        assert_eq!(
+            error("fn main() {\n    main();\n}").1,
+            "`main` calls itself without end"
+        );
+    }
+
+    #[test]
+    fn test_every_ch01_example_has_a_program() {
+        let ownership = registry::find("01").unwrap();
+        for example in ownership.examples() {
+            let programs = toy::programs_for("01", example.id);
+            assert!(
+                !programs.is_empty(),
+                "example {} has no toy program",
+                example.id
+            );
+            // The sample runs cleanly and prints what the chapter's example prints
+            let trace = ownership::run(programs[0].1);
+            assert!(trace.error.is_none(), "{:?}", trace.error);
+            let printed = crate::tests::capture_example(ownership, example.id);
+            assert_eq!(trace.output, printed.lines().collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_tokens_chars_and_lifetimes() {
+        let tokens = tokenize("fn f<'a>(x: &'a String) { x.push('!'); x.push('\\''); }").unwrap();
+        let kinds: Vec<&TokenKind> = tokens.iter().map(|t| &t.kind).collect();
+        assert_eq!(kinds[3], &TokenKind::Lifetime("a".to_string()));
+        assert!(kinds.contains(&&TokenKind::Char('!')));
+        assert!(kinds.contains(&&TokenKind::Char('\'')));
+        assert_eq!(
+            tokenize("let c = ';").unwrap_err().message,
+            "unterminated character literal"
+        );
+    }
+
+    #[test]
+    fn test_parse_references_methods_and_blocks() {
+        let program = parse(
+            "fn longest<'a>(x: &'a String, y: &'a String) -> &'a String {\n    x\n}\nfn main() {\n    let mut s = String::from(\"a\");\n    {\n        let r = &mut s;\n        r.push_str(\"b\");\n    }\n}",
+        )
+        .unwrap();
+        let longest = &program.functions[0];
+        assert_eq!(longest.lifetimes[0].name, "a");
+        assert_eq!(longest.ret.to_string(), "&'a String");
+        assert_eq!(longest.params[1].ty.to_string(), "&'a String");
+        assert_eq!((longest.ret_span.column, longest.ret_span.len), (49, 10));
+        let Stmt::Block(block) = &program.functions[1].body.stmts[1] else {
+            panic!("expected a nested block");
+        };
+        assert!(matches!(
+            &block.stmts[0],
+            Stmt::Let {
+                value: Some(Expr::Ref { mutable: true, .. }),
+                ..
+            }
+        ));
+        assert!(matches!(
+            &block.stmts[1],
+            Stmt::Expr(Expr::Method { method, args, .. })
+                if method.name == "push_str" && matches!(args[..], [Expr::Str { .. }])
+        ));
+        assert_eq!(
+            parse("fn main() { s.trim(); }").unwrap_err().message,
+            "unsupported method `trim`"
+        );
+    }
+
+    #[test]
+    fn test_nested_blocks_drop_their_variables() {
+        let trace = ownership::run(
+            "fn main() {\n    let a = String::from(\"a\");\n    {\n        let b = String::from(\"b\");\n    }\n    println!(\"{}\", a);\n}",
+        );
+        assert_eq!(
+            steps(&trace),
+            [
+                "a owns \"a\"",
+                "b owns \"b\"",
+                "drop b",
+                "print \"a\"",
+                "drop a"
+            ]
+        );
+        // The inner variable is gone after its block
+        assert_eq!(
+            error("fn main() {\n    {\n        let b = 1;\n    }\n    println!(\"{}\", b);\n}").0,
+            Some("E0425")
+        );
+    }
+
+    #[test]
+    fn test_simulator_leaves_references_to_the_borrow_checker() {
+        let source = "fn main() {\n    let s = String::from(\"a\");\n    let r = &s;\n}";
+        assert!(!ownership::supports(&parse(source).unwrap()));
+        assert_eq!(
+            error(source).1,
+            "the ownership simulator doesn't follow references"
+        );
+        assert!(borrowck::run(source).errors.is_empty());
+    }
+
+    #[test]
+    fn test_loans_end_at_their_last_use() {
+        let source = "fn main() {\n    let mut s = String::from(\"a\");\n    let r1 = &s;\n    println!(\"{}\", r1);\n    let r2 = &mut s;\n    r2.push('!');\n}";
+        let check = borrowck::run(source);
+        assert!(check.errors.is_empty(), "{:?}", check.errors);
+        let loans: Vec<_> = check
+            .loans
+            .iter()
+            .filter_map(|loan| Some((loan.holder.as_deref()?, loan.mutable, loan.last_use?.line)))
+            .collect();
+        assert_eq!(loans, [("r1", false, 4), ("r2", true, 6)]);
+        assert_eq!(
+            check.render("demo.rs", source),
+            "Loans in `main`:\n  r1 = &s at 3:14, live until 4:20\n  r2 = &mut s at 5:14, live until 6:5\n\nNo borrow conflicts.\n"
+        );
+
+        // Reassigning a reference ends the loan it held
+        let source = "fn main() {\n    let mut a = String::from(\"a\");\n    let b = String::from(\"b\");\n    let mut r = &a;\n    r = &b;\n    a.push('!');\n    println!(\"{}\", r);\n}";
+        assert!(borrowck::run(source).errors.is_empty());
+    }
+
+    #[test]
+    fn test_conflicting_borrows_point_at_the_loan_and_the_use() {
+        let source = "fn main() {\n    let mut s = String::from(\"a\");\n    let r1 = &s;\n    s.push('!');\n    println!(\"{}\", r1);\n}";
+        let errors = borrowck::run(source).errors;
+        assert_eq!(errors.len(), 1);
+        let error = &errors[0];
+        assert_eq!(error.code, Some("E0502"));
+        assert_eq!(
+            (error.span.line, error.label.as_str()),
+            (4, "mutable borrow occurs here")
+        );
+        let notes: Vec<(usize, &str)> = error
+            .notes
+            .iter()
+            .map(|(span, note)| (span.line, note.as_str()))
+            .collect();
+        assert_eq!(
+            notes,
+            [
+                (3, "immutable borrow occurs here"),
+                (5, "immutable borrow later used here")
+            ]
+        );
+
+        assert_eq!(
+            borrow_error("fn main() {\n    let mut s = String::from(\"a\");\n    let r = &mut s;\n    println!(\"{}\", s);\n    r.push('!');\n}"),
+            (
+                Some("E0502"),
+                "cannot borrow `s` as immutable because it is also borrowed as mutable".to_string()
+            )
+        );
+        assert_eq!(
+            borrow_error("fn main() {\n    let mut s = String::from(\"a\");\n    let r = &mut s;\n    s.push('?');\n    r.push('!');\n}"),
+            (
+                Some("E0499"),
+                "cannot borrow `s` as mutable more than once at a time".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_loans_flow_through_calls() {
+        let longest = "fn longest<'a>(x: &'a String, y: &'a String) -> &'a String {\n    x\n}\n";
+        // The result borrows from both arguments, so neither can change while it is used
+        let source = format!("{}fn main() {{\n    let mut a = String::from(\"a\");\n    let b = String::from(\"b\");\n    let r = longest(&a, &b);\n    a.push('!');\n    println!(\"{{}}\", r);\n}}", longest);
+        let check = borrowck::run(&source);
+        assert_eq!(check.errors[0].code, Some("E0502"));
+        let held: Vec<_> = check
+            .loans
+            .iter()
+            .filter_map(|loan| loan.holder.as_deref())
+            .collect();
+        assert_eq!(held, ["r", "r"]);
+
+        // Without a lifetime, a reference can only come from the one reference parameter
+        let source = "fn first(x: &String, n: i32) -> &String {\n    x\n}\nfn main() {\n    let mut a = String::from(\"a\");\n    let r = first(&a, 1);\n    a.push('!');\n    println!(\"{}\", r);\n}";
+        assert_eq!(borrow_error(source).0, Some("E0502"));
+    }
+
+    #[test]
+    fn test_other_borrowck_errors() {
+        let errors = [
+            (
+                "fn main() {\n    let s = String::from(\"a\");\n    let r = &s;\n    let t = s;\n    println!(\"{}\", r);\n}",
+                "E0505",
+                "cannot move out of `s` because it is borrowed",
+            ),
+            (
+                "fn main() {\n    let mut s = String::from(\"a\");\n    let r = &s;\n    s = String::from(\"b\");\n    println!(\"{}\", r);\n}",
+                "E0506",
+                "cannot assign to `s` because it is borrowed",
+            ),
+            (
+                "fn main() {\n    let a = String::from(\"a\");\n    let mut r = &a;\n    {\n        let b = String::from(\"b\");\n        r = &b;\n    }\n    println!(\"{}\", r);\n}",
+                "E0597",
+                "`b` does not live long enough",
+            ),
+            (
+                "fn f<'a>(x: &'a String) -> &'a String {\n    let s = String::from(\"a\");\n    &s\n}\nfn main() {}",
+                "E0515",
+                "cannot return reference to local variable `s`",
+            ),
+            (
+                "fn main() {\n    let s = String::from(\"a\");\n    s.push('!');\n}",
+                "E0596",
+                "cannot borrow `s` as mutable, as it is not declared as mutable",
+            ),
+            (
+                "fn f(s: &String) {\n    s.push('!');\n}\nfn main() {}",
+                "E0596",
+                "cannot borrow `*s` as mutable, as it is behind a `&` reference",
+            ),
+            (
+                "fn main() {\n    let s = String::from(\"a\");\n    let t = s;\n    let n = s.len();\n}",
+                "E0382",
+                "borrow of moved value: `s`",
+            ),
+            (
+                "fn longest(x: &String, y: &String) -> &String {\n    x\n}\nfn main() {}",
+                "E0106",
+                "missing lifetime specifier",
+            ),
+        ];
+        for (source, code, message) in errors {
+            assert_eq!(
+                borrow_error(source),
+                (Some(code), message.to_string()),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn test_every_ch02_sample_borrow_checks() {
+        let samples: Vec<_> = SAMPLES.iter().filter(|s| s.chapter == "02").collect();
+        assert!(samples.len() >= 5);
+        for sample in samples {
+            let check = borrowck::run(sample.source);
+            assert!(
+                check.errors.is_empty(),
+                "{}: {:?}",
+                sample.path,
+                check.errors
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_structs_ifs_and_comparisons() {
+        let source = "struct Pair<'a> {\n    first: &'a str,\n    ok: bool,\n}\nfn main() {\n    let x;\n    if 1 <= 2 {\n        x = 1;\n    } else if true {\n        x = 2;\n    }\n    let p = Pair { first: \"a\", ok: false };\n    println!(\"{}\", p.first);\n}";
+        let program = parse(source).unwrap();
+        let pair = &program.structs[0];
+        assert_eq!(pair.lifetimes[0].name, "a");
+        assert_eq!(pair.field("first").unwrap().ty.to_string(), "&'a str");
+        let main = &program.functions[0];
+        assert!(matches!(&main.body.stmts[0], Stmt::Let { value: None, .. }));
+        let Stmt::Expr(Expr::If {
+            cond, otherwise, ..
+        }) = &main.body.stmts[1]
+        else {
+            panic!("expected an if statement");
+        };
+        assert!(matches!(**cond, Expr::Compare { op: "<=", .. }));
+        // `else if` is an else block holding another if
+        let otherwise = otherwise.as_ref().unwrap();
+        assert!(matches!(otherwise.tail, Some(Expr::If { .. })));
+        assert!(matches!(
+            &main.body.stmts[2],
+            Stmt::Let {
+                value: Some(Expr::Struct { .. }),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_simulator_runs_ifs_and_late_initialization() {
+        let source = "fn main() {\n    let s;\n    if 2 > 1 {\n        s = String::from(\"yes\");\n    } else {\n        s = String::from(\"no\");\n    }\n    println!(\"{}\", s);\n}";
+        let trace = ownership::run(source);
+        assert_eq!(trace.error, None);
+        assert_eq!(trace.output, ["yes"]);
+
+        let source = "fn main() {\n    let s: String;\n    println!(\"{}\", s);\n}";
+        assert_eq!(
+            error(source),
+            (
+                Some("E0381"),
+                "used binding `s` isn't initialized".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_if_arms_are_separate_paths() {
+        // Each arm moves `s` on its own path, but after the `if` it may be gone
+        let source = "fn take(s: String) {}\nfn main() {\n    let s = String::from(\"a\");\n    if true {\n        take(s);\n    } else {\n        take(s);\n    }\n}";
+        assert!(borrowck::run(source).errors.is_empty());
+        let moved = source.replace(
+            "take(s);\n    }\n}",
+            "take(s);\n    }\n    println!(\"{}\", s);\n}",
+        );
+        assert_eq!(
+            borrow_error(&moved),
+            (Some("E0382"), "borrow of moved value: `s`".to_string())
+        );
+        let one_arm = "fn take(s: String) {}\nfn main() {\n    let s = String::from(\"a\");\n    if true {\n        take(s);\n    }\n    take(s);\n}";
+        assert_eq!(borrow_error(one_arm).0, Some("E0382"));
+
+        // An `if` lowers to a branch over the arm it doesn't take and a jump past the else
+        let program = parse(source).unwrap();
+        let body = ir::lower_function(&program, &program.functions[1]).unwrap();
+        let branch = body
+            .points
+            .iter()
+            .position(|point| matches!(point.action, Action::Branch { .. }))
+            .unwrap();
+        let Action::Branch { otherwise } = body.points[branch].action else {
+            unreachable!()
+        };
+        assert_eq!(body.successors(branch), [branch + 1, otherwise]);
+        assert!(matches!(
+            body.points[otherwise - 1].action,
+            Action::Goto(end) if end > otherwise
+        ));
+    }
+
+    #[test]
+    fn test_late_initialization_errors() {
+        let errors = [
+            (
+                "fn main() {\n    let s: String;\n    let t = s;\n}",
+                "E0381",
+                "used binding `s` isn't initialized",
+            ),
+            (
+                "fn main() {\n    let x;\n    if true {\n        x = 1;\n    }\n    let y = x;\n}",
+                "E0381",
+                "used binding `x` isn't initialized",
+            ),
+            (
+                "fn main() {\n    let x;\n    x = 1;\n    x = 2;\n}",
+                "E0384",
+                "cannot assign twice to immutable variable `x`",
+            ),
+            (
+                "struct P<'a> {\n    x: &'a i32,\n}\nfn main() {\n    let p = P { y: 1 };\n}",
+                "E0560",
+                "struct `P` has no field named `y`",
+            ),
+        ];
+        for (source, code, message) in errors {
+            assert_eq!(
+                borrow_error(source),
+                (Some(code), message.to_string()),
+                "{}",
+                source
+            );
+        }
+        // Assigning once in each arm is fine
+        let source = "fn main() {\n    let x;\n    if true {\n        x = 1;\n    } else {\n        x = 2;\n    }\n    let y = x;\n}";
+        assert!(borrowck::run(source).errors.is_empty());
+    }
+
+    #[test]
+    fn test_structs_hold_the_loans_of_their_fields() {
+        let source = "struct Point<'a, 'b> {\n    x: &'a i32,\n    y: &'b i32,\n}\nfn main() {\n    let mut x = 5;\n    let y = 10;\n    let point = Point { x: &x, y: &y };\n    x = 6;\n    println!(\"{}\", point.y);\n}";
+        let check = borrowck::run(source);
+        assert_eq!(check.errors[0].code, Some("E0506"));
+        let held: Vec<_> = check
+            .loans
+            .iter()
+            .filter_map(|loan| loan.holder.as_deref())
+            .collect();
+        assert_eq!(held, ["point", "point"]);
+    }
+
+    #[test]
+    fn test_regions_of_longest() {
+        let sample = SAMPLES
+            .iter()
+            .find(|s| s.path == "ch03_lifetime/basic_annotations.rs")
+            .unwrap();
+        let regions = regions::run(sample.source);
+        assert!(regions.errors.is_empty());
+        let timeline = &regions.timelines[0];
+        assert_eq!(timeline.function, "main");
+        assert_eq!((timeline.first, timeline.last), (11, 16));
+        let string2 = bar(&timeline.bars, "string2");
+        assert_eq!((string2.first, string2.last), (13, 16));
+        let borrow = bar(&timeline.bars, "&string1");
+        assert_eq!(
+            (borrow.first, borrow.last, borrow.note.as_str()),
+            (14, 15, "held by result")
+        );
+        let a = bar(&timeline.bars, "'a");
+        assert_eq!((a.first, a.last), (14, 15));
+        assert_eq!(
+            a.note,
+            "'a of `longest`, within string1 and string2 (lines 13-16)"
+        );
+        let rendered = regions.render(sample.file_name(), sample.source);
+        assert!(rendered.contains("\n          11 12 13 14 15 16\nstring1     [=============]\n"));
+        assert!(rendered.ends_with("No borrow conflicts.\n"));
+    }
+
+    #[test]
+    fn test_regions_show_a_lifetime_that_outlives_its_borrows() {
+        let source = "fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {\n    x\n}\nfn main() {\n    let string1 = String::from(\"a\");\n    let result;\n    {\n        let string2 = String::from(\"b\");\n        result = longest(&string1, &string2);\n    }\n    println!(\"{}\", result);\n}";
+        let regions = regions::run(source);
+        assert_eq!(regions.errors[0].code, Some("E0597"));
+        let a = bar(&regions.timelines[0].bars, "'a");
+        assert_eq!((a.first, a.last), (9, 11));
+        assert!(a.note.ends_with("(lines 8-10), but needed until line 11"));
+
+        // A struct gets a bar for each of its lifetimes, and string literals are 'static
+        let source = "struct Announcer<'message, 'data> {\n    message: &'message str,\n    data: &'data str,\n}\nfn main() {\n    let s1 = \"short\";\n    let s2 = String::from(\"longer\");\n    let announce = Announcer { message: s1, data: &s2 };\n    println!(\"{}\", announce.data);\n}";
+        let bars = &regions::run(source).timelines[0].bars;
+        assert_eq!(
+            bar(bars, "'data").note,
+            "'data of `Announcer`, within s2 (lines 7-10)"
+        );
+        assert!(bars.iter().all(|bar| bar.label != "'message"));
+        let statics = bar(bars, "'static");
+        assert_eq!((statics.first, statics.last), (5, 10));
+    }
+
+    #[test]
+    fn test_every_ch03_sample_has_a_timeline() {
+        let samples: Vec<_> = SAMPLES.iter().filter(|s| s.chapter == "03").collect();
+        assert!(samples.len() >= 6);
+        for sample in samples {
+            let regions = regions::run(sample.source);
+            assert!(regions.errors.is_empty(), "{}", sample.path);
+            assert!(!regions.timelines.is_empty(), "{}", sample.path);
+        }
+    }
+
+    #[test]
+    fn test_parse_patterns() {
+        let source = matching(
+            "Message",
+            "        Message::Move { x: 0, .. } | Message::Quit => {}\n        Message::ChangeColor(r, 0..=9, -1) if r > 0 => {}\n        _ => {}",
+        );
+        let file = patterns::parse(&source).unwrap();
+        assert_eq!(file.adts[0].variants.len(), 4);
+        let found = &file.matches[0];
+        assert_eq!(found.scrutinee.name, "value");
+        assert_eq!(
+            found.ty,
+            Ty::Adt {
+                name: "Message".to_string(),
+                args: Vec::new()
+            }
+        );
+        assert!(matches!(&found.arms[0].pat, Pat::Or(alts) if alts.len() == 2));
+        assert_eq!(
+            found.arms[0].pat.to_string(),
+            "Message::Move { x: 0, .. } | Message::Quit"
+        );
+        assert_eq!(
+            found.arms[1].pat.to_string(),
+            "Message::ChangeColor(r, 0..=9, -1)"
+        );
+        assert!(found.arms[1].guard);
+        assert_eq!(
+            found.arms[1].pat.span(),
+            Span {
+                line: 12,
+                column: 9,
+                len: 20
+            }
+        );
+
+        // Variables get their types from annotations or from values that show them
+        let file = patterns::parse("fn main() {\n    let a = (1, true);\n    let b = a;\n    let c = Some(\"s\");\n    match b {\n        _ => {}\n    }\n    match c {\n        _ => {}\n    }\n}\n").unwrap();
+        assert_eq!(file.matches[0].ty.to_string(), "(i32, bool)");
+        assert_eq!(file.matches[1].ty.to_string(), "Option<&str>");
+        let error = patterns::parse(
+            "fn main() {\n    let n = f();\n    match n {\n        _ => {}\n    }\n}\n",
+        )
+        .unwrap_err();
+        assert_eq!(error.message, "can't tell the type of `n`");
+    }
+
+    #[test]
+    fn test_missing_patterns() {
+        let found = report(&matching(
+            "Message",
+            "        Message::Quit => {}\n        Message::Move { x: 0, y } => {}\n        Message::ChangeColor(..) => {}",
+        ));
+        assert_eq!(
+            found.missing,
+            [
+                "Message::Move { x: i32::MIN..=-1, .. }",
+                "Message::Move { x: 1..=i32::MAX, .. }",
+                "Message::Write(_)"
+            ]
+        );
+
+        // Ranges are split where the arms start and end, and the gaps are joined up again
+        let found = report(&matching(
+            "u8",
+            "        1 => {}\n        2 | 3 | 5 | 7 | 11 => {}\n        13..=19 => {}",
+        ));
+        assert_eq!(
+            found.missing,
+            ["0", "4", "6", "8..=10", "12", "20..=u8::MAX"]
+        );
+
+        // What's missing can be pasted back in as arms, however wide the type
+        for ty in ["i8", "i64", "u64"] {
+            let found = report(&matching(ty, "        0 => {}"));
+            let arms: String = found
+                .missing
+                .iter()
+                .map(|pat| format!("\n        {} => {{}}", pat))
+                .collect();
+            let found = report(&matching(ty, &format!("        0 => {{}}{}", arms)));
+            assert!(found.missing.is_empty(), "{}: {:?}", ty, found.missing);
+        }
+        let found = report(&matching(
+            "i64",
+            "        -9223372036854775808..=-1 => {}\n        0..=9223372036854775807 => {}",
+        ));
+        assert!(found.missing.is_empty());
+
+        let found = report(&matching(
+            "(bool, Option<bool>)",
+            "        (true, _) => {}\n        (false, Some(true)) => {}",
+        ));
+        assert_eq!(found.missing, ["(false, None)", "(false, Some(false))"]);
+
+        // Guarded arms don't count towards covering anything
+        let found = report(&matching(
+            "bool",
+            "        b if b => {}\n        false => {}",
+        ));
+        assert_eq!(found.missing, ["true"]);
+
+        // Values with too many constructors to list are only covered by a catch-all
+        let found = report(&matching("&str", "        \"a\" => {}"));
+        assert_eq!(found.missing, ["_"]);
+    }
+
+    #[test]
+    fn test_unreachable_arms() {
+        let check = usefulness::run(&matching(
+            "i32",
+            "        0..=9 => {}\n        5 => {}\n        10 | 3 | 11 => {}\n        _ => {}\n        x => {}",
+        ));
+        let arms = &check.matches[0].arms;
+        let reachable: Vec<bool> = arms.iter().map(|arm| arm.reachable).collect();
+        assert_eq!(reachable, [true, false, true, true, false]);
+        assert_eq!(arms[2].unreachable, ["3"]);
+        assert!(check.errors.is_empty());
+        let lines: Vec<usize> = check.warnings.iter().map(|w| w.span.line).collect();
+        assert_eq!(lines, [12, 13, 15]);
+
+        // A guarded arm doesn't hide the arms after it
+        let found = report(&matching(
+            "Option<i32>",
+            "        Some(x) if x > 0 => {}\n        Some(_) => {}\n        None => {}",
+        ));
+        assert!(found.arms.iter().all(|arm| arm.reachable));
+        assert!(found.missing.is_empty());
+    }
+
+    #[test]
+    fn test_program_nesting_limit() {
+        // main's block is the first level, and the `println!` argument in the innermost block
+        // is an expression inside an expression, so this is as deep as its blocks may go
+        let depth = toy::parser::MAX_DEPTH - 3;
+        let nested = |depth| {
+            format!(
+                "fn main() {{\n{}let s = String::from(\"x\");\nlet t = s;\nprintln!(\"{{}}\", t);\n{}}}\n",
+                "{\n".repeat(depth),
+                "}\n".repeat(depth)
+            )
+        };
+        // The interpreter, the borrow checker and the visualizer all fit on a test thread's stack
+        let source = nested(depth);
+        assert!(ownership::run(&source).error.is_none());
+        assert!(borrowck::run(&source).errors.is_empty());
+        assert!(regions::run(&source).errors.is_empty());
+
+        // One more level is an error rather than a stack overflow, and so is a long `else if` chain
+        assert!(parse(&nested(depth + 1)).is_err());
+        // The block that goes past the limit is the one reported, on its own line
+        let error = parse(&nested(toy::parser::MAX_DEPTH)).unwrap_err();
+        assert_eq!(
+            error.message,
+            format!("nested more than {} levels deep", toy::parser::MAX_DEPTH)
+        );
+        assert_eq!(error.span.line, toy::parser::MAX_DEPTH + 1);
+        let chain = format!(
+            "fn main() {{\nlet x = 1;\nif x == 1 {{}}{}\n}}\n",
+            " else if x == 2 {}".repeat(2000)
+        );
+        assert!(parse(&chain).is_err());
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        // At the limit, reading and checking still fit on the stack of a test thread
+        let depth = patterns::MAX_DEPTH - 1;
+        let ty = format!("{}bool{}", "Option<".repeat(depth), ">".repeat(depth));
+        let pat = format!("{}true{}", "Some(".repeat(depth), ")".repeat(depth));
+        let report = report(&matching(&ty, &format!("        {} => {{}}", pat)));
+        assert_eq!(report.arms.len(), 1);
+        assert_eq!(report.missing.len(), depth + 1);
+
+        // Deeper patterns and types are an error rather than a stack overflow
+        let pat = format!("{}_{}", "Some(".repeat(3000), ")".repeat(3000));
+        let check = usefulness::run(&matching(
+            "Option<i32>",
+            &format!("        {} => {{}}", pat),
+        ));
+        assert_eq!(
+            check.errors[0].message,
+            format!("nested more than {} levels deep", patterns::MAX_DEPTH)
+        );
+        let ty = format!("{}i32{}", "Option<".repeat(3000), ">".repeat(3000));
+        let error = patterns::parse(&matching(&ty, "        _ => {}")).unwrap_err();
+        assert_eq!(error.span.line, 9);
+    }
+
+    #[test]
+    fn test_pattern_type_errors() {
+        let code = |ty: &str, arm: &str| {
+            let check = usefulness::run(&matching(ty, &format!("        {} => {{}}", arm)));
+            assert!(check.matches.is_empty());
+            check.errors[0].code
+        };
+        assert_eq!(code("Message", "Message::Write(a, b)"), Some("E0023"));
+        assert_eq!(code("Message", "Message::Move { x }"), Some("E0027"));
+        assert_eq!(code("Message", "Message::Move { z, .. }"), Some("E0026"));
+        assert_eq!(code("Message", "Message::Stop"), Some("E0599"));
+        assert_eq!(code("Message", "Message::Write"), Some("E0533"));
+        assert_eq!(code("Message", "Some(x)"), Some("E0308"));
+        assert_eq!(code("(i32, i32)", "(a, b, c)"), Some("E0308"));
+        assert_eq!(code("i32", "9..=1"), Some("E0030"));
+        assert_eq!(code("u8", "256"), None);
+    }
+
+    #[test]
+    fn test_every_ch04_fixture_is_exhaustive() {
+        assert_eq!(MATCHES.len(), 5);
+        for fixture in MATCHES {
+            let check = usefulness::run(fixture.source);
+            assert_eq!(check.matches.len(), 1, "{}", fixture.path);
+            assert!(
+                check.errors.is_empty(),
+                "{}: {:?}",
+                fixture.path,
+                check.errors
+            );
+            assert!(check.warnings.is_empty(), "{}", fixture.path);
+        }
+        // ... and the compile_fail variant of example 4 misses the variant rustc names
+        let files = toy::matches_for("04", 4);
+        let check = usefulness::run(files[1].1);
+        assert_eq!(check.matches[0].missing, ["Message::Write(_)"]);
+        let rendered = check.render(files[1].0, files[1].1);
+        assert!(rendered
+            .contains("error[E0004]: non-exhaustive patterns: `Message::Write(_)` not covered\n"));
+    }
+}
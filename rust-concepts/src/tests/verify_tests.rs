@@ -0,0 +1,138 @@
+use crate::chapter::{Chapter, Example};
+use crate::{registry, verify};
+use std::io::{self, Write};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+    // Example 1: A single line
+    println!("a");
+    // Result: a
+
+    // Example 2: Several lines
+    println!("b");
+    println!("c");
+    // Result:
+    // b
+    // c
+    let _ = 1;
+    // The block ends at the first line of code, so this is just an explanation
+"#;
+
+    #[test]
+    fn test_every_chapter_matches_its_result_comments() {
+        // This is the golden-output check: every chapter's demo is run and its output
+        // is compared with the `// Result:` comments in its source
+        for chapter in registry::CHAPTERS {
+            let report = verify::verify_chapter(*chapter).unwrap();
+            assert!(report.checked > 0, "ch{} has no annotations", chapter.id());
+            let messages: Vec<String> = report.mismatches.iter().map(|m| m.to_string()).collect();
+            assert!(messages.is_empty(), "{}", messages.join("\n"));
+        }
+    }
+
+    #[test]
+    fn test_parse_single_and_multi_line_results() {
+        // Both the one-line form and the multi-line form should be picked up,
+        // each tagged with its example number and source line
+        let expectations = verify::parse_expectations(SOURCE);
+        let summary: Vec<(u32, usize, &str)> = expectations
+            .iter()
+            .map(|e| (e.example, e.source_line, e.text.as_str()))
+            .collect();
+        assert_eq!(summary, [(1, 4, "a"), (2, 10, "b"), (2, 11, "c"),]);
+    }
+
+    #[test]
+    fn test_compare_ignores_unannotated_lines() {
+        // Lines such as banners aren't annotated, so they must not count as mismatches
+        let expectations = verify::parse_expectations("// Example 1: x\n// Result: hello");
+        let mismatches = verify::compare("01", &expectations, "Banner\nhello\nGoodbye\n");
+        assert!(mismatches.is_empty());
+    }
+
+    #[test]
+    fn test_compare_reports_drifted_line() {
+        // When the output drifts from the comment, the mismatch names the chapter,
+        // the example and the line that was printed instead
+        let source = "// Example 3: x\n// Result: s2: hello\n// Result: done";
+        let expectations = verify::parse_expectations(source);
+        let mismatches = verify::compare("01", &expectations, "Banner\ns2: world\ndone\n");
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(
+            mismatches[0].to_string(),
+            "ch01 example 3 (line 2): expected `s2: hello`, got `s2: world`"
+        );
+    }
+
+    #[test]
+    fn test_compare_reports_missing_output() {
+        // If nothing was printed where the comment expects a line, there is no candidate to show
+        let expectations =
+            verify::parse_expectations("// Example 1: x\n// Result: a\n// Result: b");
+        let mismatches = verify::compare("02", &expectations, "a\n");
+        assert_eq!(
+            mismatches[0].to_string(),
+            "ch02 example 1 (line 3): expected `b`, got no corresponding output"
+        );
+    }
+
+    // A chapter whose source annotates an example it doesn't register
+    struct Orphaned;
+
+    impl Chapter for Orphaned {
+        fn id(&self) -> &'static str {
+            "99"
+        }
+        fn slug(&self) -> &'static str {
+            "orphaned"
+        }
+        fn aliases(&self) -> &'static [&'static str] {
+            &[]
+        }
+        fn title(&self) -> &'static str {
+            "Orphaned"
+        }
+        fn summary(&self) -> &'static str {
+            "An annotation without an example"
+        }
+        fn source(&self) -> &'static str {
+            "// Example 1: Kept\n// Result: one\n// Example 2: Removed\n// Result: two"
+        }
+        fn run(&self, out: &mut dyn Write) -> io::Result<()> {
+            writeln!(out, "one")
+        }
+        fn examples(&self) -> &'static [Example] {
+            &[Example {
+                id: 1,
+                title: "Kept",
+                explanation: "Prints one",
+                run: |out| writeln!(out, "one"),
+            }]
+        }
+    }
+
+    #[test]
+    fn test_verify_single_example() {
+        // An example is run on its own and only checked against its own annotations
+        let chapter = registry::find("02").unwrap();
+        let report = verify::verify_example(chapter, chapter.example(6).unwrap()).unwrap();
+        assert_eq!(report.checked, 1);
+        assert!(report.mismatches.is_empty());
+    }
+
+    #[test]
+    fn test_annotation_without_example_is_reported() {
+        // If an example is dropped from the table but its `// Result:` comment stays behind,
+        // the comment can never be checked, so it counts as a mismatch
+        let report = verify::verify_chapter(&Orphaned).unwrap();
+        assert_eq!(report.checked, 2);
+        let messages: Vec<String> = report.mismatches.iter().map(|m| m.to_string()).collect();
+        assert_eq!(
+            messages,
+            ["ch99 example 2 (line 4): expected `two`, got no corresponding output"]
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use super::Span;
+use std::fmt;
+
+/// A whole toy program: its structs and functions, in source order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    pub structs: Vec<Struct>,
+    pub functions: Vec<Function>,
+}
+
+impl Program {
+    /// Looks up a function by name
+    pub fn function(&self, name: &str) -> Option<&Function> {
+        self.functions.iter().find(|f| f.name.name == name)
+    }
+
+    /// Looks up a struct by name
+    pub fn find_struct(&self, name: &str) -> Option<&Struct> {
+        self.structs.iter().find(|s| s.name.name == name)
+    }
+}
+
+/// A name and where it was written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ident {
+    pub name: String,
+    pub span: Span,
+}
+
+/// `fn name<'a>(params) -> ret { body }`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Function {
+    pub name: Ident,
+    /// The lifetime parameters, such as `'a`, without their quote
+    pub lifetimes: Vec<Ident>,
+    pub params: Vec<Param>,
+    /// The return type; a function without `->` returns `()`
+    pub ret: Type,
+    /// Where the return type was written, or the closing parenthesis of the parameters
+    pub ret_span: Span,
+    pub body: Block,
+}
+
+/// `struct Name<'a> { fields }`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Struct {
+    pub name: Ident,
+    /// The lifetime parameters, such as `'a`, without their quote
+    pub lifetimes: Vec<Ident>,
+    pub fields: Vec<Param>,
+}
+
+impl Struct {
+    /// Looks up a field by name
+    pub fn field(&self, name: &str) -> Option<&Param> {
+        self.fields.iter().find(|f| f.name.name == name)
+    }
+}
+
+/// A function parameter or a struct field, `name: Type`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Param {
+    pub name: Ident,
+    pub ty: Type,
+}
+
+/// The types of the toy language
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Type {
+    /// `()`, the type of a function without a return value
+    Unit,
+    I32,
+    /// The type of `.len()`
+    Usize,
+    Char,
+    Bool,
+    /// `str`, the text behind a `&str`
+    Str,
+    String,
+    /// A struct, with the lifetimes it was given, such as `Announcer<'a, 'b>`; they are
+    /// empty when the type was inferred
+    Struct {
+        name: String,
+        lifetimes: Vec<String>,
+    },
+    /// `&'a T` or `&'a mut T`; the lifetime is usually left out
+    Ref {
+        lifetime: Option<String>,
+        mutable: bool,
+        target: Box<Type>,
+    },
+}
+
+impl Type {
+    /// Whether values of the type are copied instead of moved
+    ///
+    /// ```
+    /// use rust_concepts::toy::ast::Type;
+    ///
+    /// let shared = Type::Ref { lifetime: None, mutable: false, target: Box::new(Type::String) };
+    /// assert!(shared.is_copy());
+    /// assert!(!Type::String.is_copy());
+    /// ```
+    pub fn is_copy(&self) -> bool {
+        match self {
+            Type::Unit | Type::I32 | Type::Usize | Type::Char | Type::Bool => true,
+            Type::Ref { mutable, .. } => !mutable,
+            Type::Str | Type::String | Type::Struct { .. } => false,
+        }
+    }
+
+    /// Whether the type is a reference
+    pub fn is_ref(&self) -> bool {
+        matches!(self, Type::Ref { .. })
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Unit => f.write_str("()"),
+            Type::I32 => f.write_str("i32"),
+            Type::Usize => f.write_str("usize"),
+            Type::Char => f.write_str("char"),
+            Type::Bool => f.write_str("bool"),
+            Type::Str => f.write_str("str"),
+            Type::String => f.write_str("String"),
+            Type::Struct { name, lifetimes } if lifetimes.is_empty() => f.write_str(name),
+            Type::Struct { name, lifetimes } => {
+                let lifetimes: Vec<String> = lifetimes.iter().map(|l| format!("'{}", l)).collect();
+                write!(f, "{}<{}>", name, lifetimes.join(", "))
+            }
+            Type::Ref {
+                lifetime,
+                mutable,
+                target,
+            } => {
+                f.write_str("&")?;
+                if let Some(lifetime) = lifetime {
+                    write!(f, "'{} ", lifetime)?;
+                }
+                if *mutable {
+                    f.write_str("mut ")?;
+                }
+                write!(f, "{}", target)
+            }
+        }
+    }
+}
+
+/// `{ statements; tail }`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    pub stmts: Vec<Stmt>,
+    /// The expression at the end of the block without a `;`, which is its value
+    pub tail: Option<Expr>,
+    /// The closing brace, where the block's variables go out of scope
+    pub close: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Stmt {
+    /// `let [mut] name[: Type] = value;`, or `let name;` to assign the value later
+    Let {
+        mutable: bool,
+        name: Ident,
+        ty: Option<Type>,
+        value: Option<Expr>,
+    },
+    /// `name = value;`
+    Assign { name: Ident, value: Expr },
+    /// `expr;`
+    Expr(Expr),
+    /// `{ ... }`, a nested scope
+    Block(Block),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    /// An integer literal
+    Int { value: i64, span: Span },
+    /// A character literal, such as `'!'`
+    Char { value: char, span: Span },
+    /// `true` or `false`
+    Bool { value: bool, span: Span },
+    /// A string literal, which borrows text built into the program
+    Str { text: String, span: Span },
+    /// `String::from("text")`
+    StringFrom { text: String, span: Span },
+    /// A variable
+    Var(Ident),
+    /// `receiver.clone()`
+    Clone { receiver: Ident, span: Span },
+    /// `&place` or `&mut place`
+    Ref {
+        mutable: bool,
+        place: Ident,
+        span: Span,
+    },
+    /// `receiver.method(args)`: `len`, `push` or `push_str`
+    Method {
+        receiver: Ident,
+        method: Ident,
+        args: Vec<Expr>,
+        span: Span,
+    },
+    /// `name(args)`
+    Call { name: Ident, args: Vec<Expr> },
+    /// `lhs op rhs`, where `op` compares two numbers: `==`, `!=`, `<`, `<=`, `>` or `>=`
+    Compare {
+        op: &'static str,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    /// `if cond { then } else { otherwise }`; `else if` is an `otherwise` block holding
+    /// another `if`
+    If {
+        cond: Box<Expr>,
+        then: Box<Block>,
+        otherwise: Option<Box<Block>>,
+        span: Span,
+    },
+    /// `Name { field: value, ... }`
+    Struct {
+        name: Ident,
+        fields: Vec<(Ident, Expr)>,
+        span: Span,
+    },
+    /// `base.field`
+    Field {
+        base: Ident,
+        field: Ident,
+        span: Span,
+    },
+    /// `println!("format", args)`, where the format only uses `{}`
+    Print {
+        format: String,
+        args: Vec<Expr>,
+        span: Span,
+    },
+}
+
+impl Expr {
+    /// Where the expression starts, for diagnostics
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Int { span, .. }
+            | Expr::Char { span, .. }
+            | Expr::Bool { span, .. }
+            | Expr::Str { span, .. }
+            | Expr::StringFrom { span, .. }
+            | Expr::Clone { span, .. }
+            | Expr::Ref { span, .. }
+            | Expr::Method { span, .. }
+            | Expr::If { span, .. }
+            | Expr::Struct { span, .. }
+            | Expr::Field { span, .. }
+            | Expr::Print { span, .. } => *span,
+            Expr::Var(ident) | Expr::Call { name: ident, .. } => ident.span,
+            Expr::Compare { lhs, .. } => lhs.span(),
+        }
+    }
+}
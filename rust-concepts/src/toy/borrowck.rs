@@ -0,0 +1,609 @@
+use std::collections::BTreeSet;
+
+use super::ast::{Program, Type};
+use super::ir::{self, Access, Action, Body, LocalId};
+use super::parser::parse;
+use super::{Diagnostic, Span};
+
+/// A borrow made by a toy program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Loan {
+    /// The function that borrows
+    pub function: String,
+    /// The borrowed variable
+    pub place: String,
+    pub mutable: bool,
+    /// The `&place` or `&mut place`, or the method call or `println!` argument that borrows
+    pub span: Span,
+    /// The first variable the reference was stored in; `None` for a reference that only
+    /// lived in a temporary, such as the one `println!` makes
+    pub holder: Option<String>,
+    /// The last use of the reference, where the loan ends; `None` if it was never used
+    pub last_use: Option<Span>,
+}
+
+impl Loan {
+    // "&s3" or "&mut s3"
+    fn describe(&self) -> String {
+        if self.mutable {
+            format!("&mut {}", self.place)
+        } else {
+            format!("&{}", self.place)
+        }
+    }
+}
+
+/// What the borrow checker found in a toy program
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BorrowCheck {
+    /// Every loan, in the order the functions make them
+    pub loans: Vec<Loan>,
+    /// Uses of moved values, and conflicts between loans and the code that runs while they
+    /// are live
+    pub errors: Vec<Diagnostic>,
+}
+
+impl BorrowCheck {
+    /// Renders the loans kept in variables, with where each one ends, followed by the errors
+    ///
+    /// ```
+    /// use rust_concepts::toy::borrowck;
+    ///
+    /// let source = "fn main() {\n    let s = String::from(\"hi\");\n    let r = &s;\n    println!(\"{}\", r);\n}\n";
+    /// let rendered = borrowck::run(source).render("demo.rs", source);
+    /// assert_eq!(rendered, "Loans in `main`:\n  r = &s at 3:13, live until 4:20\n\nNo borrow conflicts.\n");
+    /// ```
+    pub fn render(&self, name: &str, source: &str) -> String {
+        let mut text = String::new();
+        let mut function = None;
+        for loan in &self.loans {
+            let Some(holder) = &loan.holder else {
+                continue;
+            };
+            if function != Some(&loan.function) {
+                if function.is_some() {
+                    text += "\n";
+                }
+                text += &format!("Loans in `{}`:\n", loan.function);
+                function = Some(&loan.function);
+            }
+            let end = match loan.last_use {
+                Some(span) => format!("live until {}:{}", span.line, span.column),
+                None => "never used".to_string(),
+            };
+            text += &format!(
+                "  {} = {} at {}:{}, {}\n",
+                holder,
+                loan.describe(),
+                loan.span.line,
+                loan.span.column,
+                end
+            );
+        }
+        if function.is_some() {
+            text += "\n";
+        }
+        if self.errors.is_empty() {
+            text += "No borrow conflicts.\n";
+        }
+        for error in &self.errors {
+            text += &error.render(name, source);
+        }
+        text
+    }
+}
+
+/// Parses and borrow-checks a toy program
+///
+/// A loan lasts from the borrow to the last use of the reference, not to the end of the
+/// reference's scope, so a shared borrow that is no longer used doesn't stop a mutable one:
+///
+/// ```
+/// use rust_concepts::toy::borrowck;
+///
+/// let fine = "fn main() {\n    let mut s = String::from(\"hi\");\n    let r1 = &s;\n    println!(\"{}\", r1);\n    let r2 = &mut s;\n    r2.push('!');\n}\n";
+/// assert!(borrowck::run(fine).errors.is_empty());
+///
+/// let overlap = fine.replace("r2.push('!');", "r2.push('!');\n    println!(\"{}\", r1);");
+/// let errors = borrowck::run(&overlap).errors;
+/// assert_eq!(errors[0].code, Some("E0502"));
+/// assert_eq!(errors[0].message, "cannot borrow `s` as mutable because it is also borrowed as immutable");
+/// ```
+pub fn run(source: &str) -> BorrowCheck {
+    match parse(source) {
+        Ok(program) => check(&program),
+        Err(error) => BorrowCheck {
+            loans: Vec::new(),
+            errors: vec![error],
+        },
+    }
+}
+
+/// Borrow-checks every function of a parsed toy program
+///
+/// Each function is checked on its own, trusting the signatures of the functions it calls.
+pub fn check(program: &Program) -> BorrowCheck {
+    let mut result = BorrowCheck::default();
+    for function in &program.functions {
+        match ir::lower_function(program, function) {
+            Ok(body) => check_body(&body, &mut result),
+            Err(error) => result.errors.push(error),
+        }
+    }
+    result
+}
+
+// A loan while checking a body: the public part and where it sits among the points
+struct Tracked {
+    loan: Loan,
+    place: LocalId,
+    // The points that use a local holding the loan, in order
+    uses: Vec<usize>,
+}
+
+impl Tracked {
+    // The first use of the loan after point `p`
+    fn used_after(&self, p: usize) -> Option<usize> {
+        self.uses.iter().copied().find(|&used| used > p)
+    }
+}
+
+// Why a local may have no value when a point runs
+#[derive(Debug, Clone, Copy)]
+enum Gone {
+    Uninit,
+    Moved(Span),
+}
+
+// What may be true of the locals when a point runs, over every path that reaches it
+#[derive(Clone)]
+struct State {
+    // The loans each local's value may hold
+    holds: Vec<BTreeSet<usize>>,
+    // Locals that may have no value
+    gone: Vec<Option<Gone>>,
+    // Locals that may have been given a value already
+    assigned: Vec<bool>,
+}
+
+impl State {
+    // The state of a body's first point: only the parameters have values
+    fn entry(body: &Body) -> State {
+        let count = body.locals.len();
+        let mut state = State {
+            holds: vec![BTreeSet::new(); count],
+            gone: vec![Some(Gone::Uninit); count],
+            assigned: vec![false; count],
+        };
+        for local in 1..=body.params {
+            state.gone[local] = None;
+            state.assigned[local] = true;
+        }
+        state
+    }
+
+    fn join(&mut self, other: &State) {
+        for (holds, other) in self.holds.iter_mut().zip(&other.holds) {
+            holds.extend(other.iter().copied());
+        }
+        for (gone, other) in self.gone.iter_mut().zip(&other.gone) {
+            *gone = gone.or(*other);
+        }
+        for (assigned, other) in self.assigned.iter_mut().zip(&other.assigned) {
+            *assigned |= *other;
+        }
+    }
+
+    // Gives `local` a value holding `held`
+    fn define(&mut self, local: LocalId, held: BTreeSet<usize>) {
+        self.holds[local] = held;
+        self.gone[local] = None;
+        self.assigned[local] = true;
+    }
+}
+
+// The local an action gives a new value, or takes out of scope
+fn defines(action: &Action) -> Option<LocalId> {
+    match *action {
+        Action::Init(local)
+        | Action::Borrow { dest: local, .. }
+        | Action::Assign { dest: local, .. }
+        | Action::StorageDead(local) => Some(local),
+        _ => None,
+    }
+}
+
+// The locals whose values an action uses, with the loans they hold
+fn uses(action: &Action) -> Vec<LocalId> {
+    match action {
+        Action::Use(local, access) if *access != Access::Write => vec![*local],
+        Action::Assign { sources, .. } => sources.clone(),
+        // Borrowing a value that holds references keeps them in use
+        Action::Borrow { place, .. } => vec![*place],
+        _ => Vec::new(),
+    }
+}
+
+fn check_body(body: &Body, result: &mut BorrowCheck) {
+    let (loans, states) = loans(body);
+    let live = live_after(body);
+    for (p, point) in body.points.iter().enumerate() {
+        let Some(state) = &states[p] else {
+            continue;
+        };
+        // The loans of `place` that a reference still used after this point holds
+        let defined = defines(&point.action);
+        let live = &live[p];
+        let live_loans = |place: LocalId| {
+            loans.iter().enumerate().filter(move |(index, loan)| {
+                loan.place == place
+                    && live
+                        .iter()
+                        .any(|&local| Some(local) != defined && state.holds[local].contains(index))
+            })
+        };
+        let later = |loan: &Tracked| loan.used_after(p).map(|used| body.points[used].span);
+        let without_value = match point.action {
+            Action::Borrow { place, .. } => state.gone[place].map(|gone| (place, gone, "borrow")),
+            Action::Use(local, access) if access != Access::Write => {
+                state.gone[local].map(|gone| (local, gone, "use"))
+            }
+            _ => None,
+        };
+        if let Some((local, gone, noun)) = without_value {
+            result
+                .errors
+                .push(no_value(body, local, gone, noun, point.span));
+            continue;
+        }
+        let error = match point.action {
+            Action::Borrow { place, mutable, .. } => {
+                if mutable && !body.locals[place].mutable {
+                    Some(not_mutable(body, place, point.span))
+                } else {
+                    live_loans(place)
+                        .find(|(_, loan)| mutable || loan.loan.mutable)
+                        .map(|(_, loan)| {
+                            conflicting_borrow(&loan.loan, later(loan), mutable, point.span)
+                        })
+                }
+            }
+            Action::Use(local, Access::Mutate) => match &body.locals[local].ty {
+                Type::Ref { mutable: false, .. } => Some(behind_shared(body, local, point.span)),
+                _ => None,
+            },
+            Action::Use(local, Access::Write)
+                if !body.locals[local].mutable && state.assigned[local] =>
+            {
+                Some(assigned_twice(body, local, point.span))
+            }
+            Action::Use(local, access) => {
+                let mut loans = live_loans(local).map(|(_, loan)| loan);
+                let loan = match access {
+                    Access::Read => loans.find(|loan| loan.loan.mutable),
+                    _ => loans.next(),
+                };
+                loan.map(|loan| conflicting_use(&loan.loan, later(loan), access, point.span))
+            }
+            Action::StorageDead(local) => live_loans(local).next().map(|(_, loan)| {
+                let used = loan.used_after(p);
+                let returned =
+                    used.is_some_and(|used| matches!(body.points[used].action, Action::Use(0, _)));
+                dropped_while_borrowed(&loan.loan, later(loan), returned, point.span)
+            }),
+            Action::Init(_) | Action::Assign { .. } | Action::Branch { .. } | Action::Goto(_) => {
+                None
+            }
+        };
+        result.errors.extend(error);
+    }
+    result
+        .loans
+        .extend(loans.into_iter().map(|tracked| tracked.loan));
+}
+
+// Finds every loan of a body and where it is used, and what may be true of the locals when
+// each point runs. The loans a local holds flow along with its value, and every use of a
+// local uses the loans it holds at that point. Jumps only go forward, so one pass in point
+// order sees every path into a point before the point itself.
+fn loans(body: &Body) -> (Vec<Tracked>, Vec<Option<State>>) {
+    let mut loans: Vec<Tracked> = Vec::new();
+    let mut states: Vec<Option<State>> = vec![None; body.points.len()];
+    if let Some(first) = states.first_mut() {
+        *first = Some(State::entry(body));
+    }
+    for (p, point) in body.points.iter().enumerate() {
+        let Some(mut state) = states[p].clone() else {
+            continue;
+        };
+        for local in uses(&point.action) {
+            for &index in &state.holds[local] {
+                if loans[index].uses.last() != Some(&p) {
+                    loans[index].uses.push(p);
+                }
+            }
+        }
+        match &point.action {
+            Action::Borrow {
+                dest,
+                place,
+                mutable,
+            } => {
+                loans.push(Tracked {
+                    loan: Loan {
+                        function: body.function.clone(),
+                        place: name(body, *place),
+                        mutable: *mutable,
+                        span: point.span,
+                        holder: body.locals[*dest].name.clone(),
+                        last_use: None,
+                    },
+                    place: *place,
+                    uses: Vec::new(),
+                });
+                // A reference to a value also holds the loans the value holds
+                let mut held = state.holds[*place].clone();
+                held.insert(loans.len() - 1);
+                state.define(*dest, held);
+            }
+            Action::Assign { dest, sources } => {
+                let held: BTreeSet<usize> = sources
+                    .iter()
+                    .flat_map(|source| state.holds[*source].iter().copied())
+                    .collect();
+                if let Some(holder) = &body.locals[*dest].name {
+                    for &index in &held {
+                        loans[index]
+                            .loan
+                            .holder
+                            .get_or_insert_with(|| holder.clone());
+                    }
+                }
+                state.define(*dest, held);
+            }
+            Action::Init(local) => state.define(*local, BTreeSet::new()),
+            Action::Use(local, Access::Move) => state.gone[*local] = Some(Gone::Moved(point.span)),
+            Action::StorageDead(local) => state.holds[*local].clear(),
+            Action::Use(..) | Action::Branch { .. } | Action::Goto(_) => {}
+        }
+        for next in body.successors(p) {
+            match &mut states[next] {
+                Some(existing) => existing.join(&state),
+                empty => *empty = Some(state.clone()),
+            }
+        }
+    }
+    for tracked in &mut loans {
+        tracked.loan.last_use = tracked.uses.last().map(|&last| body.points[last].span);
+    }
+    (loans, states)
+}
+
+// The locals whose values are used again after each point, found by walking the points
+// backwards
+fn live_after(body: &Body) -> Vec<BTreeSet<LocalId>> {
+    let mut live_before: Vec<BTreeSet<LocalId>> = vec![BTreeSet::new(); body.points.len()];
+    let mut live_after = live_before.clone();
+    for p in (0..body.points.len()).rev() {
+        let mut live: BTreeSet<LocalId> = body
+            .successors(p)
+            .into_iter()
+            .flat_map(|next| live_before[next].iter().copied())
+            .collect();
+        live_after[p] = live.clone();
+        if let Some(local) = defines(&body.points[p].action) {
+            live.remove(&local);
+        }
+        live.extend(uses(&body.points[p].action));
+        live_before[p] = live;
+    }
+    live_after
+}
+
+fn name(body: &Body, local: LocalId) -> String {
+    body.locals[local]
+        .name
+        .clone()
+        .unwrap_or_else(|| "a temporary".to_string())
+}
+
+// A use of a local that was moved away or never given a value
+fn no_value(body: &Body, local: LocalId, gone: Gone, noun: &str, span: Span) -> Diagnostic {
+    let name = name(body, local);
+    let participle = if noun == "borrow" { "borrowed" } else { "used" };
+    match gone {
+        Gone::Moved(at) => Diagnostic {
+            code: Some("E0382"),
+            notes: vec![(at, "value moved here".to_string())],
+            ..Diagnostic::new(
+                format!("{} of moved value: `{}`", noun, name),
+                span,
+                format!("value {} here after move", participle),
+            )
+        },
+        Gone::Uninit => Diagnostic {
+            code: Some("E0381"),
+            notes: vec![(
+                body.locals[local].span,
+                "binding declared here but left uninitialized".to_string(),
+            )],
+            ..Diagnostic::new(
+                format!("{} binding `{}` isn't initialized", participle, name),
+                span,
+                format!("`{}` {} here but it isn't initialized", name, participle),
+            )
+        },
+    }
+}
+
+// The notes every conflict has: where the earlier loan was made and where it is used later
+fn loan_notes(loan: &Loan, later: Option<Span>, made: &str, used: &str) -> Vec<(Span, String)> {
+    let mut notes = vec![(loan.span, made.to_string())];
+    if let Some(span) = later {
+        notes.push((span, used.to_string()));
+    }
+    notes
+}
+
+fn conflicting_borrow(loan: &Loan, later: Option<Span>, mutable: bool, span: Span) -> Diagnostic {
+    let place = &loan.place;
+    let (code, message, label, made, used) = match (loan.mutable, mutable) {
+        (true, true) => (
+            "E0499",
+            format!(
+                "cannot borrow `{}` as mutable more than once at a time",
+                place
+            ),
+            "second mutable borrow occurs here",
+            "first mutable borrow occurs here",
+            "first borrow later used here",
+        ),
+        (false, _) => (
+            "E0502",
+            format!(
+                "cannot borrow `{}` as mutable because it is also borrowed as immutable",
+                place
+            ),
+            "mutable borrow occurs here",
+            "immutable borrow occurs here",
+            "immutable borrow later used here",
+        ),
+        (true, false) => (
+            "E0502",
+            format!(
+                "cannot borrow `{}` as immutable because it is also borrowed as mutable",
+                place
+            ),
+            "immutable borrow occurs here",
+            "mutable borrow occurs here",
+            "mutable borrow later used here",
+        ),
+    };
+    Diagnostic {
+        code: Some(code),
+        notes: loan_notes(loan, later, made, used),
+        ..Diagnostic::new(message, span, label)
+    }
+}
+
+fn conflicting_use(loan: &Loan, later: Option<Span>, access: Access, span: Span) -> Diagnostic {
+    let place = &loan.place;
+    let (code, message, label) = match access {
+        Access::Move => (
+            "E0505",
+            format!("cannot move out of `{}` because it is borrowed", place),
+            format!("move out of `{}` occurs here", place),
+        ),
+        Access::Write => (
+            "E0506",
+            format!("cannot assign to `{}` because it is borrowed", place),
+            format!(
+                "`{}` is assigned to here but it was already borrowed",
+                place
+            ),
+        ),
+        Access::Read | Access::Mutate => (
+            "E0503",
+            format!("cannot use `{}` because it was mutably borrowed", place),
+            format!("use of borrowed `{}`", place),
+        ),
+    };
+    Diagnostic {
+        code: Some(code),
+        notes: loan_notes(
+            loan,
+            later,
+            &format!("`{}` is borrowed here", place),
+            "borrow later used here",
+        ),
+        ..Diagnostic::new(message, span, label)
+    }
+}
+
+fn dropped_while_borrowed(
+    loan: &Loan,
+    later: Option<Span>,
+    returned: bool,
+    span: Span,
+) -> Diagnostic {
+    let place = &loan.place;
+    if returned {
+        return Diagnostic {
+            code: Some("E0515"),
+            notes: vec![(span, format!("`{}` is dropped here", place))],
+            ..Diagnostic::new(
+                format!("cannot return reference to local variable `{}`", place),
+                loan.span,
+                "returns a reference to data owned by the current function",
+            )
+        };
+    }
+    let mut notes = vec![(
+        span,
+        format!("`{}` dropped here while still borrowed", place),
+    )];
+    if let Some(later) = later {
+        notes.push((later, "borrow later used here".to_string()));
+    }
+    Diagnostic {
+        code: Some("E0597"),
+        notes,
+        ..Diagnostic::new(
+            format!("`{}` does not live long enough", place),
+            loan.span,
+            "borrowed value does not live long enough",
+        )
+    }
+}
+
+fn not_mutable(body: &Body, place: LocalId, span: Span) -> Diagnostic {
+    let place = name(body, place);
+    Diagnostic {
+        code: Some("E0596"),
+        ..Diagnostic::new(
+            format!(
+                "cannot borrow `{}` as mutable, as it is not declared as mutable",
+                place
+            ),
+            span,
+            format!(
+                "cannot borrow as mutable; declare it with `let mut {}`",
+                place
+            ),
+        )
+    }
+}
+
+fn behind_shared(body: &Body, local: LocalId, span: Span) -> Diagnostic {
+    let reference = name(body, local);
+    Diagnostic {
+        code: Some("E0596"),
+        ..Diagnostic::new(
+            format!(
+                "cannot borrow `*{}` as mutable, as it is behind a `&` reference",
+                reference
+            ),
+            span,
+            format!(
+                "`{}` is a `&` reference, so the data it refers to can't be changed",
+                reference
+            ),
+        )
+    }
+}
+
+fn assigned_twice(body: &Body, local: LocalId, span: Span) -> Diagnostic {
+    let name = name(body, local);
+    Diagnostic {
+        code: Some("E0384"),
+        notes: vec![(
+            body.locals[local].span,
+            format!("consider making it mutable: `let mut {}`", name),
+        )],
+        ..Diagnostic::new(
+            format!("cannot assign twice to immutable variable `{}`", name),
+            span,
+            "cannot assign twice to immutable variable",
+        )
+    }
+}
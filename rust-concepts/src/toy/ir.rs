@@ -0,0 +1,651 @@
+use super::ast::{Block, Expr, Function, Ident, Program, Stmt, Struct, Type};
+use super::{Diagnostic, Span};
+
+/// The index of a local in [`Body::locals`]
+pub type LocalId = usize;
+
+/// A function lowered for the borrow checker: its locals, and what happens to them in the
+/// order it happens
+///
+/// A body is a list of points that run one after the other, except that an `if` jumps
+/// over the arm it doesn't take. Every jump goes forward, since the toy language has no
+/// loops.
+///
+/// ```
+/// use rust_concepts::toy::ir::{lower, Access, Action};
+/// use rust_concepts::toy::parser::parse;
+///
+/// let program = parse("fn main() {\n    let s = String::from(\"hi\");\n    let r = &s;\n}\n").unwrap();
+/// let body = &lower(&program).unwrap()[0];
+/// let actions: Vec<&Action> = body.points.iter().map(|point| &point.action).collect();
+/// assert_eq!(
+///     actions,
+///     [
+///         &Action::Init(1),
+///         &Action::Borrow { dest: 2, place: 1, mutable: false },
+///         &Action::Init(0),
+///         &Action::StorageDead(2),
+///         &Action::StorageDead(1),
+///         &Action::Use(0, Access::Move),
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Body {
+    pub function: String,
+    /// Local 0 holds the return value, the parameters come next
+    pub locals: Vec<Local>,
+    /// The number of parameters, which are locals 1 to `params`
+    pub params: usize,
+    pub points: Vec<Point>,
+}
+
+impl Body {
+    /// The points that can run right after point `p`, in order
+    pub fn successors(&self, p: usize) -> Vec<usize> {
+        let next = match self.points[p].action {
+            Action::Branch { otherwise } => vec![p + 1, otherwise],
+            Action::Goto(target) => vec![target],
+            _ => vec![p + 1],
+        };
+        next.into_iter()
+            .filter(|&next| next < self.points.len())
+            .collect()
+    }
+}
+
+/// A variable, parameter or temporary of a lowered function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Local {
+    /// The name of a variable or parameter; temporaries and the return value have none
+    pub name: Option<String>,
+    pub mutable: bool,
+    pub ty: Type,
+    /// Where a variable was declared, or the expression a temporary holds
+    pub span: Span,
+}
+
+/// An action and the code it comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Point {
+    pub span: Span,
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// The local gets a value that holds no references, such as a literal
+    Init(LocalId),
+    /// `dest = &place` or `dest = &mut place`, which creates a loan of `place`
+    Borrow {
+        dest: LocalId,
+        place: LocalId,
+        mutable: bool,
+    },
+    /// `dest` gets a value made from `sources`, and holds the references they hold
+    Assign {
+        dest: LocalId,
+        sources: Vec<LocalId>,
+    },
+    /// The local is used
+    Use(LocalId, Access),
+    /// The local goes out of scope
+    StorageDead(LocalId),
+    /// Runs the next point if the condition used just before holds, or jumps to
+    /// `otherwise` if it doesn't
+    Branch { otherwise: usize },
+    /// Jumps to another point, past the `else` arm of an `if`
+    Goto(usize),
+}
+
+/// How a local is used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// Read without moving it: a copy, or a read through a reference
+    Read,
+    /// Moved out of
+    Move,
+    /// Overwritten by an assignment
+    Write,
+    /// Changed through the reference it holds, as `r.push('!')` does
+    Mutate,
+}
+
+/// Lowers every function of a program, stopping at the first function that can't be lowered
+pub fn lower(program: &Program) -> Result<Vec<Body>, Diagnostic> {
+    program
+        .functions
+        .iter()
+        .map(|function| lower_function(program, function))
+        .collect()
+}
+
+/// Lowers one function of a program
+///
+/// Fails if the function calls something that doesn't exist, or if its signature returns a
+/// reference without saying which parameter it borrows from.
+pub fn lower_function(program: &Program, function: &Function) -> Result<Body, Diagnostic> {
+    check_signature(function)?;
+    let mut builder = Builder {
+        program,
+        locals: Vec::new(),
+        points: Vec::new(),
+        scope: Vec::new(),
+    };
+    builder.local(None, false, function.ret.clone(), function.ret_span);
+    for param in &function.params {
+        let id = builder.local(
+            Some(&param.name.name),
+            false,
+            param.ty.clone(),
+            param.name.span,
+        );
+        builder.scope.push((param.name.name.clone(), id));
+    }
+    builder.block(&function.body, 0)?;
+    for id in (1..=function.params.len()).rev() {
+        builder.point(function.body.close, Action::StorageDead(id));
+    }
+    // The caller gets the return value after every local is gone
+    let span = function
+        .body
+        .tail
+        .as_ref()
+        .map_or(function.body.close, Expr::span);
+    builder.point(span, Action::Use(0, Access::Move));
+    Ok(Body {
+        function: function.name.name.clone(),
+        locals: builder.locals,
+        params: function.params.len(),
+        points: builder.points,
+    })
+}
+
+// A function that returns a reference must say which parameter it borrows from, unless it
+// only has one reference parameter
+fn check_signature(function: &Function) -> Result<(), Diagnostic> {
+    let Type::Ref { lifetime: None, .. } = function.ret else {
+        return Ok(());
+    };
+    let references = function.params.iter().filter(|p| p.ty.is_ref()).count();
+    if references == 1 {
+        return Ok(());
+    }
+    let label = if references == 0 {
+        "expected named lifetime parameter; there is no parameter to borrow from"
+    } else {
+        "expected named lifetime parameter; it could borrow from more than one parameter"
+    };
+    Err(Diagnostic {
+        code: Some("E0106"),
+        ..Diagnostic::new("missing lifetime specifier", function.ret_span, label)
+    })
+}
+
+struct Builder<'p> {
+    program: &'p Program,
+    locals: Vec<Local>,
+    points: Vec<Point>,
+    // The variables in scope, innermost last; later `let`s shadow earlier ones
+    scope: Vec<(String, LocalId)>,
+}
+
+impl<'p> Builder<'p> {
+    fn local(&mut self, name: Option<&str>, mutable: bool, ty: Type, span: Span) -> LocalId {
+        self.locals.push(Local {
+            name: name.map(str::to_string),
+            mutable,
+            ty,
+            span,
+        });
+        self.locals.len() - 1
+    }
+
+    fn point(&mut self, span: Span, action: Action) {
+        self.points.push(Point { span, action });
+    }
+
+    fn lookup(&self, name: &Ident) -> Result<LocalId, Diagnostic> {
+        self.scope
+            .iter()
+            .rev()
+            .find(|(n, _)| *n == name.name)
+            .map(|(_, id)| *id)
+            .ok_or_else(|| Diagnostic {
+                code: Some("E0425"),
+                ..Diagnostic::new(
+                    format!("cannot find value `{}` in this scope", name.name),
+                    name.span,
+                    "not found in this scope",
+                )
+            })
+    }
+
+    fn function(&self, name: &Ident) -> Result<&'p Function, Diagnostic> {
+        self.program.function(&name.name).ok_or_else(|| Diagnostic {
+            code: Some("E0425"),
+            ..Diagnostic::new(
+                format!("cannot find function `{}` in this scope", name.name),
+                name.span,
+                "not found in this scope",
+            )
+        })
+    }
+
+    // Lowers a block with its value going to `dest`; its variables die at the closing brace
+    fn block(&mut self, block: &Block, dest: LocalId) -> Result<Type, Diagnostic> {
+        let start = self.scope.len();
+        for stmt in &block.stmts {
+            match stmt {
+                Stmt::Let {
+                    mutable,
+                    name,
+                    ty,
+                    value,
+                } => {
+                    let declared = ty.clone().unwrap_or(Type::Unit);
+                    let id = self.local(Some(&name.name), *mutable, declared, name.span);
+                    // The value is lowered before the name is in scope, so `let s = s;` works
+                    if let Some(value) = value {
+                        let value_ty = self.lower(id, value)?;
+                        if ty.is_none() {
+                            self.locals[id].ty = value_ty;
+                        }
+                    }
+                    self.scope.push((name.name.clone(), id));
+                }
+                Stmt::Assign { name, value } => {
+                    let id = self.lookup(name)?;
+                    let temp = self.temporary(value)?;
+                    // `let x;` leaves the type to the first assignment
+                    if self.locals[id].ty == Type::Unit {
+                        self.locals[id].ty = self.locals[temp].ty.clone();
+                    }
+                    self.point(name.span, Action::Use(id, Access::Write));
+                    self.point(
+                        name.span,
+                        Action::Assign {
+                            dest: id,
+                            sources: vec![temp],
+                        },
+                    );
+                }
+                Stmt::Expr(expr) => {
+                    self.temporary(expr)?;
+                }
+                Stmt::Block(inner) => {
+                    let temp = self.local(None, false, Type::Unit, inner.close);
+                    self.block(inner, temp)?;
+                }
+            }
+        }
+        let ty = match &block.tail {
+            Some(tail) => self.lower(dest, tail)?,
+            None => {
+                self.point(block.close, Action::Init(dest));
+                Type::Unit
+            }
+        };
+        for (_, id) in self.scope.split_off(start).into_iter().rev() {
+            self.point(block.close, Action::StorageDead(id));
+        }
+        Ok(ty)
+    }
+
+    // Lowers an expression into a new temporary
+    fn temporary(&mut self, expr: &Expr) -> Result<LocalId, Diagnostic> {
+        let temp = self.local(None, false, Type::Unit, expr.span());
+        self.locals[temp].ty = self.lower(temp, expr)?;
+        Ok(temp)
+    }
+
+    // Lowers an expression whose value goes to `dest`, and returns its type
+    fn lower(&mut self, dest: LocalId, expr: &Expr) -> Result<Type, Diagnostic> {
+        Ok(match expr {
+            Expr::Int { span, .. } => self.init(dest, *span, Type::I32),
+            Expr::Char { span, .. } => self.init(dest, *span, Type::Char),
+            Expr::Bool { span, .. } => self.init(dest, *span, Type::Bool),
+            Expr::StringFrom { span, .. } => self.init(dest, *span, Type::String),
+            // A string literal borrows text that lives as long as the program
+            Expr::Str { span, .. } => {
+                let ty = Type::Ref {
+                    lifetime: Some("static".to_string()),
+                    mutable: false,
+                    target: Box::new(Type::Str),
+                };
+                self.init(dest, *span, ty)
+            }
+            Expr::Var(name) => {
+                let id = self.lookup(name)?;
+                let ty = self.locals[id].ty.clone();
+                let access = if ty.is_copy() {
+                    Access::Read
+                } else {
+                    Access::Move
+                };
+                self.point(name.span, Action::Use(id, access));
+                self.point(
+                    name.span,
+                    Action::Assign {
+                        dest,
+                        sources: vec![id],
+                    },
+                );
+                ty
+            }
+            Expr::Clone { receiver, span } => {
+                self.read(receiver, *span)?;
+                let ty = match &self.locals[self.lookup(receiver)?].ty {
+                    Type::Ref { target, .. } => (**target).clone(),
+                    ty => ty.clone(),
+                };
+                self.init(dest, *span, ty)
+            }
+            Expr::Ref {
+                mutable,
+                place,
+                span,
+            } => {
+                let place = self.lookup(place)?;
+                self.point(
+                    *span,
+                    Action::Borrow {
+                        dest,
+                        place,
+                        mutable: *mutable,
+                    },
+                );
+                Type::Ref {
+                    lifetime: None,
+                    mutable: *mutable,
+                    target: Box::new(self.locals[place].ty.clone()),
+                }
+            }
+            Expr::Method {
+                receiver,
+                method,
+                args,
+                span,
+            } => {
+                if method.name == "len" {
+                    self.read(receiver, *span)?;
+                } else {
+                    self.mutate(receiver, *span)?;
+                }
+                for arg in args {
+                    self.operand(arg)?;
+                }
+                let ty = if method.name == "len" {
+                    Type::Usize
+                } else {
+                    Type::Unit
+                };
+                self.init(dest, *span, ty)
+            }
+            Expr::Call { name, args } => {
+                let function = self.function(name)?;
+                if args.len() != function.params.len() {
+                    return Err(Diagnostic {
+                        code: Some("E0061"),
+                        ..Diagnostic::new(
+                            format!(
+                                "this function takes {} arguments but {} were supplied",
+                                function.params.len(),
+                                args.len()
+                            ),
+                            name.span,
+                            "",
+                        )
+                    });
+                }
+                let ret = function.ret.clone();
+                let borrowed_from: Vec<bool> = function
+                    .params
+                    .iter()
+                    .map(|param| returns_borrow_of(function, &param.ty))
+                    .collect();
+                let mut sources = Vec::new();
+                for (arg, borrowed_from) in args.iter().zip(borrowed_from) {
+                    let temp = self.operand(arg)?;
+                    if borrowed_from {
+                        sources.push(temp);
+                    }
+                }
+                let action = if ret.is_ref() {
+                    Action::Assign { dest, sources }
+                } else {
+                    Action::Init(dest)
+                };
+                self.point(name.span, action);
+                ret
+            }
+            Expr::Compare { lhs, rhs, .. } => {
+                self.operand(lhs)?;
+                self.operand(rhs)?;
+                self.init(dest, expr.span(), Type::Bool)
+            }
+            Expr::If {
+                cond,
+                then,
+                otherwise,
+                span,
+            } => {
+                self.operand(cond)?;
+                let branch = self.points.len();
+                self.point(*span, Action::Branch { otherwise: 0 });
+                let ty = self.block(then, dest)?;
+                let goto = self.points.len();
+                self.point(then.close, Action::Goto(0));
+                let else_start = self.points.len();
+                match otherwise {
+                    Some(otherwise) => {
+                        self.block(otherwise, dest)?;
+                    }
+                    None => self.point(*span, Action::Init(dest)),
+                }
+                self.points[branch].action = Action::Branch {
+                    otherwise: else_start,
+                };
+                self.points[goto].action = Action::Goto(self.points.len());
+                ty
+            }
+            Expr::Struct { name, fields, span } => {
+                let def = self.struct_def(name)?;
+                let ty = Type::Struct {
+                    name: def.name.name.clone(),
+                    lifetimes: def.lifetimes.iter().map(|l| l.name.clone()).collect(),
+                };
+                for (field, _) in fields {
+                    if def.field(&field.name).is_none() {
+                        return Err(Diagnostic {
+                            code: Some("E0560"),
+                            ..Diagnostic::new(
+                                format!(
+                                    "struct `{}` has no field named `{}`",
+                                    name.name, field.name
+                                ),
+                                field.span,
+                                "unknown field",
+                            )
+                        });
+                    }
+                }
+                if let Some(missing) = def
+                    .fields
+                    .iter()
+                    .find(|f| fields.iter().all(|(field, _)| field.name != f.name.name))
+                {
+                    return Err(Diagnostic {
+                        code: Some("E0063"),
+                        ..Diagnostic::new(
+                            format!(
+                                "missing field `{}` in initializer of `{}`",
+                                missing.name.name, name.name
+                            ),
+                            name.span,
+                            format!("missing `{}`", missing.name.name),
+                        )
+                    });
+                }
+                // The struct holds every reference its fields hold
+                let mut sources = Vec::new();
+                for (_, value) in fields {
+                    sources.push(self.operand(value)?);
+                }
+                self.point(*span, Action::Assign { dest, sources });
+                ty
+            }
+            Expr::Field { base, field, span } => {
+                let id = self.lookup(base)?;
+                let ty = self.field_type(id, field)?;
+                if !ty.is_copy() {
+                    return Err(Diagnostic::new(
+                        format!("the borrow checker can't move `{}` out of a struct", ty),
+                        *span,
+                        "only fields that are `Copy` can be used on their own",
+                    ));
+                }
+                // A reference copied out of a field may hold any of the struct's loans
+                self.point(*span, Action::Use(id, Access::Read));
+                self.point(
+                    *span,
+                    Action::Assign {
+                        dest,
+                        sources: vec![id],
+                    },
+                );
+                ty
+            }
+            Expr::Print { args, span, .. } => {
+                for arg in args {
+                    // println! only borrows its arguments
+                    match arg {
+                        Expr::Var(name) => self.read(name, name.span)?,
+                        Expr::Field { base, field, span } => {
+                            let id = self.lookup(base)?;
+                            self.field_type(id, field)?;
+                            self.read(base, *span)?;
+                        }
+                        arg => {
+                            self.operand(arg)?;
+                        }
+                    }
+                }
+                self.init(dest, *span, Type::Unit)
+            }
+        })
+    }
+
+    // Gives `dest` a value that holds no references
+    fn init(&mut self, dest: LocalId, span: Span, ty: Type) -> Type {
+        self.point(span, Action::Init(dest));
+        ty
+    }
+
+    // Lowers an argument into a temporary and uses it
+    fn operand(&mut self, expr: &Expr) -> Result<LocalId, Diagnostic> {
+        let temp = self.temporary(expr)?;
+        let access = if self.locals[temp].ty.is_copy() {
+            Access::Read
+        } else {
+            Access::Move
+        };
+        self.point(expr.span(), Action::Use(temp, access));
+        Ok(temp)
+    }
+
+    // Reads a variable in place: through it if it is a reference, else through a shared
+    // borrow made at `span`
+    fn read(&mut self, name: &Ident, span: Span) -> Result<(), Diagnostic> {
+        self.through(name, span, false)
+    }
+
+    // Changes a variable in place: through it if it is a reference, else through a mutable
+    // borrow made at `span`
+    fn mutate(&mut self, name: &Ident, span: Span) -> Result<(), Diagnostic> {
+        self.through(name, span, true)
+    }
+
+    fn through(&mut self, name: &Ident, span: Span, mutable: bool) -> Result<(), Diagnostic> {
+        let id = self.lookup(name)?;
+        let access = if mutable {
+            Access::Mutate
+        } else {
+            Access::Read
+        };
+        if self.locals[id].ty.is_ref() {
+            self.point(name.span, Action::Use(id, access));
+            return Ok(());
+        }
+        let ty = Type::Ref {
+            lifetime: None,
+            mutable,
+            target: Box::new(self.locals[id].ty.clone()),
+        };
+        let temp = self.local(None, false, ty, span);
+        self.point(
+            span,
+            Action::Borrow {
+                dest: temp,
+                place: id,
+                mutable,
+            },
+        );
+        self.point(span, Action::Use(temp, access));
+        Ok(())
+    }
+
+    fn struct_def(&self, name: &Ident) -> Result<&'p Struct, Diagnostic> {
+        self.program
+            .find_struct(&name.name)
+            .ok_or_else(|| Diagnostic {
+                code: Some("E0422"),
+                ..Diagnostic::new(
+                    format!("cannot find struct `{}` in this scope", name.name),
+                    name.span,
+                    "not found in this scope",
+                )
+            })
+    }
+
+    // The type of `field` in the struct local `id` holds
+    fn field_type(&self, id: LocalId, field: &Ident) -> Result<Type, Diagnostic> {
+        let ty = &self.locals[id].ty;
+        let found = match ty {
+            Type::Struct { name, .. } => self
+                .program
+                .find_struct(name)
+                .and_then(|def| def.field(&field.name)),
+            _ => None,
+        };
+        found
+            .map(|param| param.ty.clone())
+            .ok_or_else(|| Diagnostic {
+                code: Some("E0609"),
+                ..Diagnostic::new(
+                    format!("no field `{}` on type `{}`", field.name, ty),
+                    field.span,
+                    "unknown field",
+                )
+            })
+    }
+}
+
+// Whether the reference a function returns may borrow from a parameter of type `param`: the
+// parameter has the same lifetime as the return type, or is its only reference parameter
+fn returns_borrow_of(function: &Function, param: &Type) -> bool {
+    match (&function.ret, param) {
+        (
+            Type::Ref {
+                lifetime: Some(ret),
+                ..
+            },
+            Type::Ref {
+                lifetime: Some(lifetime),
+                ..
+            },
+        ) => ret == lifetime,
+        (Type::Ref { lifetime: None, .. }, Type::Ref { .. }) => true,
+        _ => false,
+    }
+}
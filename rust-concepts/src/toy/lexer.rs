@@ -0,0 +1,180 @@
+use super::{Diagnostic, Span};
+
+/// What a token is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenKind {
+    /// A name or keyword, such as `let` or `s1`
+    Ident(String),
+    /// An integer literal, without a sign
+    Int(u64),
+    /// A string literal, with its escapes resolved
+    Str(String),
+    /// A character literal, such as `'!'`
+    Char(char),
+    /// A lifetime, such as `'a`, without its quote
+    Lifetime(String),
+    /// Punctuation, such as `(`, `->` or `::`
+    Punct(&'static str),
+}
+
+/// A token and where it was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+// Longer punctuation comes first, so `->` isn't read as `-` and `>`
+const PUNCTUATION: &[&str] = &[
+    "..=", "->", "=>", "::", "==", "!=", "<=", ">=", "..", "(", ")", "{", "}", "[", "]", ",", ";",
+    ":", "=", ".", "!", "&", "|", "#", "<", ">", "+", "-", "*", "/", "%",
+];
+
+/// Splits a toy program into tokens, skipping whitespace and `//` comments
+///
+/// ```
+/// use rust_concepts::toy::lexer::{tokenize, TokenKind};
+///
+/// let tokens = tokenize("let s = \"hi\"; // a comment").unwrap();
+/// assert_eq!(tokens.len(), 5);
+/// assert_eq!(tokens[3].kind, TokenKind::Str("hi".to_string()));
+/// assert_eq!((tokens[3].span.column, tokens[3].span.len), (9, 4));
+/// ```
+pub fn tokenize(source: &str) -> Result<Vec<Token>, Diagnostic> {
+    let mut tokens = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            let start = i;
+            let span = |end: usize| Span {
+                line: index + 1,
+                column: start + 1,
+                len: end - start,
+            };
+            let kind = if c.is_whitespace() {
+                i += 1;
+                continue;
+            } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+                break;
+            } else if c.is_alphabetic() || c == '_' {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                TokenKind::Ident(chars[start..i].iter().collect())
+            } else if c.is_ascii_digit() {
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '_') {
+                    i += 1;
+                }
+                let digits: String = chars[start..i].iter().filter(|c| **c != '_').collect();
+                let value = digits
+                    .parse()
+                    .map_err(|_| Diagnostic::new("integer literal is too large", span(i), ""))?;
+                TokenKind::Int(value)
+            } else if c == '"' {
+                let mut value = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        Some('"') => break,
+                        Some('\\') => {
+                            let escaped = match chars.get(i + 1).copied().and_then(unescape) {
+                                Some(escaped) => escaped,
+                                None => {
+                                    return Err(Diagnostic::new(
+                                        "unknown character escape",
+                                        Span {
+                                            line: index + 1,
+                                            column: i + 1,
+                                            len: 2,
+                                        },
+                                        "",
+                                    ))
+                                }
+                            };
+                            value.push(escaped);
+                            i += 2;
+                        }
+                        Some(c) => {
+                            value.push(*c);
+                            i += 1;
+                        }
+                        None => {
+                            return Err(Diagnostic::new(
+                                "unterminated string literal",
+                                span(i),
+                                "strings can't span lines in the toy language",
+                            ))
+                        }
+                    }
+                }
+                i += 1;
+                TokenKind::Str(value)
+            } else if c == '\'' {
+                // `'x'` is a character, `'a` without a closing quote is a lifetime
+                let (value, len) = match chars.get(i + 1..i + 3) {
+                    Some(['\\', escaped]) => (unescape(*escaped), 3),
+                    Some([value, _]) => (Some(*value), 2),
+                    _ => (None, 0),
+                };
+                if len > 0 && chars.get(i + len) == Some(&'\'') {
+                    let Some(value) = value else {
+                        return Err(Diagnostic::new(
+                            "unknown character escape",
+                            Span {
+                                line: index + 1,
+                                column: i + 2,
+                                len: 2,
+                            },
+                            "",
+                        ));
+                    };
+                    i += len + 1;
+                    TokenKind::Char(value)
+                } else if chars
+                    .get(i + 1)
+                    .is_some_and(|c| c.is_alphabetic() || *c == '_')
+                {
+                    i += 1;
+                    while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                        i += 1;
+                    }
+                    TokenKind::Lifetime(chars[start + 1..i].iter().collect())
+                } else {
+                    return Err(Diagnostic::new(
+                        "unterminated character literal",
+                        span(i + 1),
+                        "",
+                    ));
+                }
+            } else {
+                let rest: String = chars[i..].iter().take(3).collect();
+                let Some(punct) = PUNCTUATION.iter().find(|p| rest.starts_with(**p)) else {
+                    return Err(Diagnostic::new(
+                        format!("unknown start of token: {}", c),
+                        span(i + 1),
+                        "",
+                    ));
+                };
+                i += punct.len();
+                TokenKind::Punct(punct)
+            };
+            tokens.push(Token {
+                kind,
+                span: span(i),
+            });
+        }
+    }
+    Ok(tokens)
+}
+
+// The character an escape such as `\n` stands for
+fn unescape(c: char) -> Option<char> {
+    match c {
+        'n' => Some('\n'),
+        't' => Some('\t'),
+        '"' | '\'' | '\\' => Some(c),
+        _ => None,
+    }
+}
@@ -0,0 +1,249 @@
+//! A toy Rust-like language for watching ownership happen
+//!
+//! The language is a small subset of Rust: structs, functions, `let` (optionally `mut`),
+//! assignment, nested blocks, `if`/`else`, comparisons, calls, `println!`, `String::from`,
+//! `.clone()`, `.len()`, `.push()`, `.push_str()`, references, integers, booleans,
+//! characters, string literals and `String`s. Programs written in it are real Rust, so
+//! rustc can check them too.
+//!
+//! [`lexer`] and [`parser`] turn source text into an [`ast::Program`]. [`ownership`] runs
+//! it while tracking who owns each value, and [`borrowck`] lowers it to the [`ir`] to find
+//! how long each borrow lives and where borrows conflict. [`regions`] draws those borrows
+//! as bars over the source lines:
+//!
+//! ```
+//! use rust_concepts::toy::ownership;
+//!
+//! let trace = ownership::run("fn main() {\n    let s1 = String::from(\"hi\");\n    let s2 = s1;\n}\n");
+//! let steps: Vec<&str> = trace.steps.iter().map(|step| step.text.as_str()).collect();
+//! assert_eq!(steps, ["s1 owns \"hi\"", "s1 moved into s2", "drop s2"]);
+//! ```
+//!
+//! [`patterns`] and [`usefulness`] work on a different slice of Rust: the enums, structs
+//! and `match` expressions of a file, checked for values no arm matches and arms no value
+//! reaches, the way rustc checks them.
+
+pub mod ast;
+pub mod borrowck;
+pub mod ir;
+pub mod lexer;
+pub mod ownership;
+pub mod parser;
+pub mod patterns;
+pub mod regions;
+pub mod usefulness;
+
+use crate::compile_fail;
+
+/// A place in a toy program's source
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Span {
+    /// The 1-based line number
+    pub line: usize,
+    /// The 1-based column, counted in characters
+    pub column: usize,
+    /// The length in characters; spans don't cross lines
+    pub len: usize,
+}
+
+/// An error in a toy program, rendered like rustc renders its diagnostics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The rustc error code for the same mistake, such as "E0382"
+    pub code: Option<&'static str>,
+    pub message: String,
+    pub span: Span,
+    /// The text under the `^^^` marker
+    pub label: String,
+    /// Other places worth pointing at, such as where a value was moved
+    pub notes: Vec<(Span, String)>,
+}
+
+impl Diagnostic {
+    /// A diagnostic without a code or notes
+    pub fn new(message: impl Into<String>, span: Span, label: impl Into<String>) -> Diagnostic {
+        Diagnostic {
+            code: None,
+            message: message.into(),
+            span,
+            label: label.into(),
+            notes: Vec::new(),
+        }
+    }
+
+    /// Renders the diagnostic against the program's source, with `name` as its file name
+    ///
+    /// ```
+    /// use rust_concepts::toy::{Diagnostic, Span};
+    ///
+    /// let span = Span { line: 1, column: 5, len: 1 };
+    /// let rendered = Diagnostic::new("expected `;`", span, "here").render("demo.rs", "let x");
+    /// assert_eq!(rendered, "error: expected `;`\n --> demo.rs:1:5\n  |\n1 | let x\n  |     ^ here\n");
+    /// ```
+    pub fn render(&self, name: &str, source: &str) -> String {
+        self.render_as("error", name, source)
+    }
+
+    /// Renders the diagnostic as a warning, such as an unreachable `match` arm
+    pub fn render_warning(&self, name: &str, source: &str) -> String {
+        self.render_as("warning", name, source)
+    }
+
+    fn render_as(&self, level: &str, name: &str, source: &str) -> String {
+        let mut text = match self.code {
+            Some(code) => format!("{}[{}]: {}\n", level, code, self.message),
+            None => format!("{}: {}\n", level, self.message),
+        };
+        text += &snippet(name, source, self.span, &self.label);
+        for (span, note) in &self.notes {
+            text += &format!("note: {}\n", note);
+            text += &snippet(name, source, *span, "");
+        }
+        text
+    }
+}
+
+// The ` --> file:line:column` part of a diagnostic, with the line and a marker under the span
+fn snippet(name: &str, source: &str, span: Span, label: &str) -> String {
+    let line = source
+        .lines()
+        .nth(span.line.saturating_sub(1))
+        .unwrap_or("");
+    let number = span.line.to_string();
+    let gutter = " ".repeat(number.len());
+    let marker = format!(
+        "{}{} {}",
+        " ".repeat(span.column.saturating_sub(1)),
+        "^".repeat(span.len.max(1)),
+        label
+    );
+    format!(
+        "{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {}\n",
+        gutter,
+        name,
+        span.line,
+        span.column,
+        gutter,
+        number,
+        line,
+        gutter,
+        marker.trim_end()
+    )
+}
+
+/// A built-in toy program: a chapter example rewritten as a standalone program
+#[derive(Debug)]
+pub struct Sample {
+    /// The id of the chapter the program comes from, such as "01"
+    pub chapter: &'static str,
+    /// The number of the example it mirrors
+    pub example: u32,
+    /// The path of the program, relative to `toy/`
+    pub path: &'static str,
+    /// The source of the program, included at compile time
+    pub source: &'static str,
+}
+
+// Registers a file from toy/ and includes its source into the binary
+macro_rules! sample {
+    ($chapter:literal, $example:literal, $path:literal) => {
+        Sample {
+            chapter: $chapter,
+            example: $example,
+            path: $path,
+            source: include_str!(concat!("../../toy/", $path)),
+        }
+    };
+}
+
+/// Every built-in toy program, in chapter and example order
+pub static SAMPLES: &[Sample] = &[
+    sample!("01", 1, "ch01_ownership/move_semantics.rs"),
+    sample!("01", 2, "ch01_ownership/clone.rs"),
+    sample!("01", 3, "ch01_ownership/copy_types.rs"),
+    sample!("01", 4, "ch01_ownership/ownership_and_functions.rs"),
+    sample!("01", 5, "ch01_ownership/return_values_and_scope.rs"),
+    sample!("02", 1, "ch02_borrowing/references.rs"),
+    sample!("02", 2, "ch02_borrowing/mutable_references.rs"),
+    sample!("02", 3, "ch02_borrowing/multiple_immutable_references.rs"),
+    sample!(
+        "02",
+        4,
+        "ch02_borrowing/mutable_and_immutable_references.rs"
+    ),
+    sample!("02", 4, "ch02_borrowing/without_a_block.rs"),
+    sample!("02", 5, "ch02_borrowing/dangling_references.rs"),
+    sample!("03", 1, "ch03_lifetime/basic_annotations.rs"),
+    sample!("03", 1, "ch03_lifetime/longest_in_a_block.rs"),
+    sample!("03", 3, "ch03_lifetime/static_lifetime_example.rs"),
+    sample!("03", 4, "ch03_lifetime/lifetime_elision.rs"),
+    sample!("03", 5, "ch03_lifetime/bounds_on_generic_types.rs"),
+    sample!("03", 6, "ch03_lifetime/lifetime_subtyping.rs"),
+];
+
+/// Every built-in `match` fixture for the [`usefulness`] checker, in chapter and example
+/// order. They aren't toy programs: they're read with [`patterns`] instead of [`parser`]
+pub static MATCHES: &[Sample] = &[
+    sample!("04", 1, "ch04_pattern_matching/basic_match.rs"),
+    sample!("04", 2, "ch04_pattern_matching/match_guards.rs"),
+    sample!("04", 3, "ch04_pattern_matching/destructuring_structs.rs"),
+    sample!("04", 4, "ch04_pattern_matching/matching_enums.rs"),
+    sample!("04", 5, "ch04_pattern_matching/matching_option.rs"),
+];
+
+impl Sample {
+    /// The file name of the program, such as "move_semantics.rs"
+    pub fn file_name(&self) -> &'static str {
+        self.path.rsplit('/').next().unwrap_or(self.path)
+    }
+}
+
+/// The toy programs for one example: its sample, then the broken variants from
+/// `compile_fail/` that are written in the toy language, as (file name, source)
+///
+/// ```
+/// use rust_concepts::toy;
+///
+/// let programs = toy::programs_for("01", 1);
+/// assert_eq!(programs[0].0, "move_semantics.rs");
+/// assert_eq!(programs[1].0, "use_after_move.rs");
+/// ```
+pub fn programs_for(chapter: &str, example: u32) -> Vec<(&'static str, &'static str)> {
+    let samples = SAMPLES
+        .iter()
+        .filter(|s| s.chapter == chapter && s.example == example)
+        .map(|s| (s.file_name(), s.source));
+    let snippets = compile_fail::for_example(chapter, example)
+        .filter(|snippet| parser::parse(snippet.source).is_ok())
+        .map(|snippet| {
+            let file_name = snippet.path.rsplit('/').next().unwrap_or(snippet.path);
+            (file_name, snippet.source)
+        });
+    samples.chain(snippets).collect()
+}
+
+/// The `match` fixtures for one example: its fixture, then the broken variants from
+/// `compile_fail/` that the pattern reader understands, as (file name, source)
+///
+/// ```
+/// use rust_concepts::toy;
+///
+/// let files = toy::matches_for("04", 4);
+/// assert_eq!(files[0].0, "matching_enums.rs");
+/// assert_eq!(files[1].0, "non_exhaustive_match.rs");
+/// ```
+pub fn matches_for(chapter: &str, example: u32) -> Vec<(&'static str, &'static str)> {
+    let fixtures = MATCHES
+        .iter()
+        .filter(|s| s.chapter == chapter && s.example == example)
+        .map(|s| (s.file_name(), s.source));
+    let snippets = compile_fail::for_example(chapter, example)
+        .filter(|snippet| {
+            patterns::parse(snippet.source).is_ok_and(|file| !file.matches.is_empty())
+        })
+        .map(|snippet| {
+            let file_name = snippet.path.rsplit('/').next().unwrap_or(snippet.path);
+            (file_name, snippet.source)
+        });
+    fixtures.chain(snippets).collect()
+}
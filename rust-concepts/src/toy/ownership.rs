@@ -0,0 +1,660 @@
+use super::ast::{Block, Expr, Function, Ident, Program, Stmt, Type};
+use super::parser::parse;
+use super::{Diagnostic, Span};
+use std::fmt;
+
+/// A value of the toy language
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Unit,
+    Int(i64),
+    Char(char),
+    Bool(bool),
+    Str(String),
+}
+
+impl Value {
+    pub fn ty(&self) -> Type {
+        match self {
+            Value::Unit => Type::Unit,
+            Value::Int(_) => Type::I32,
+            Value::Char(_) => Type::Char,
+            Value::Bool(_) => Type::Bool,
+            Value::Str(_) => Type::String,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Unit => write!(f, "()"),
+            Value::Int(value) => write!(f, "{}", value),
+            Value::Char(value) => write!(f, "{:?}", value),
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Str(text) => write!(f, "{:?}", text),
+        }
+    }
+}
+
+/// One thing that happened to a value while the program ran
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// The code that made it happen
+    pub span: Span,
+    /// What happened, such as "s1 moved into s2"
+    pub text: String,
+}
+
+/// Everything that happened while a toy program ran
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trace {
+    pub steps: Vec<Step>,
+    /// The lines the program printed
+    pub output: Vec<String>,
+    /// The error that stopped the program, if any
+    pub error: Option<Diagnostic>,
+}
+
+impl Trace {
+    /// Renders the trace: each source line that ran, followed by the steps it caused
+    ///
+    /// ```
+    /// use rust_concepts::toy::ownership;
+    ///
+    /// let source = "fn main() {\n    let x = 5;\n}\n";
+    /// let trace = ownership::run(source);
+    /// assert_eq!(trace.render("demo.rs", source), "2 | let x = 5;\n       x holds 5\n");
+    /// ```
+    pub fn render(&self, name: &str, source: &str) -> String {
+        let lines: Vec<&str> = source.lines().collect();
+        let width = self
+            .steps
+            .iter()
+            .map(|step| step.span.line.to_string().len())
+            .max()
+            .unwrap_or(1);
+        let mut text = String::new();
+        let mut current = None;
+        for step in &self.steps {
+            if current != Some(step.span.line) {
+                current = Some(step.span.line);
+                let line = lines.get(step.span.line - 1).copied().unwrap_or("");
+                text += &format!("{:>width$} | {}\n", step.span.line, line.trim());
+            }
+            text += &format!("{}      {}\n", " ".repeat(width), step.text);
+        }
+        if let Some(error) = &self.error {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text += &error.render(name, source);
+        }
+        text
+    }
+}
+
+/// Parses and runs a toy program, starting at its `main` function
+pub fn run(source: &str) -> Trace {
+    match parse(source) {
+        Ok(program) => simulate(&program),
+        Err(error) => Trace {
+            error: Some(error),
+            ..Trace::default()
+        },
+    }
+}
+
+/// Runs a parsed toy program, starting at its `main` function
+///
+/// The program stops at the first error, such as using a moved value; the steps up to
+/// there are kept in the trace.
+pub fn simulate(program: &Program) -> Trace {
+    let mut simulator = Simulator {
+        program,
+        trace: Trace::default(),
+        depth: 0,
+    };
+    let result = match program.function("main") {
+        Some(main) => simulator.call(main, Vec::new(), main.name.span),
+        None => Err(Diagnostic {
+            code: Some("E0601"),
+            ..Diagnostic::new(
+                "`main` function not found",
+                Span {
+                    line: 1,
+                    column: 1,
+                    len: 1,
+                },
+                "add `fn main() { ... }` to run the program",
+            )
+        }),
+    };
+    simulator.trace.error = result.err();
+    simulator.trace
+}
+
+/// Whether the simulator can run a program: it doesn't follow references or run structs,
+/// and of the methods it only runs `clone`
+///
+/// ```
+/// use rust_concepts::toy::{ownership, parser::parse};
+///
+/// assert!(ownership::supports(&parse("fn main() { let s = String::from(\"hi\"); }").unwrap()));
+/// assert!(!ownership::supports(&parse("fn main() { let s = String::from(\"hi\"); let r = &s; }").unwrap()));
+/// ```
+pub fn supports(program: &Program) -> bool {
+    program.structs.is_empty()
+        && program.functions.iter().all(|function| {
+            function.params.iter().all(|param| !param.ty.is_ref())
+                && !function.ret.is_ref()
+                && runs_block(&function.body)
+        })
+}
+
+fn runs_block(block: &Block) -> bool {
+    block.stmts.iter().all(|stmt| match stmt {
+        Stmt::Let { value, .. } => value.as_ref().is_none_or(runs),
+        Stmt::Assign { value, .. } | Stmt::Expr(value) => runs(value),
+        Stmt::Block(block) => runs_block(block),
+    }) && block.tail.as_ref().is_none_or(runs)
+}
+
+fn runs(expr: &Expr) -> bool {
+    match expr {
+        Expr::Ref { .. }
+        | Expr::Str { .. }
+        | Expr::Method { .. }
+        | Expr::Struct { .. }
+        | Expr::Field { .. } => false,
+        Expr::Call { args, .. } | Expr::Print { args, .. } => args.iter().all(runs),
+        Expr::Compare { lhs, rhs, .. } => runs(lhs) && runs(rhs),
+        Expr::If {
+            cond,
+            then,
+            otherwise,
+            ..
+        } => runs(cond) && runs_block(then) && otherwise.as_deref().is_none_or(runs_block),
+        _ => true,
+    }
+}
+
+// Calls nested deeper than this are assumed to be endless recursion
+const MAX_DEPTH: usize = 64;
+
+struct Simulator<'p> {
+    program: &'p Program,
+    trace: Trace,
+    depth: usize,
+}
+
+// A variable of a function that is running
+struct Var {
+    name: String,
+    mutable: bool,
+    // `None` for a `let x;` without a type until it is assigned
+    ty: Option<Type>,
+    state: State,
+}
+
+enum State {
+    // Declared with `let x;` and not assigned yet
+    Uninit,
+    Owned(Value),
+    // Where the value went and the code that moved it, for the use-after-move error
+    Moved { to: String, span: Span },
+}
+
+// Where an evaluated value goes, which decides how a move or copy is described
+#[derive(Clone, Copy)]
+enum Dest<'a> {
+    Var(&'a str),
+    Call(&'a str),
+    Caller(&'a str),
+    Temporary,
+}
+
+impl Dest<'_> {
+    // "s2", "takes_ownership", ...
+    fn describe(&self) -> String {
+        match self {
+            Dest::Var(name) | Dest::Call(name) => format!("`{}`", name),
+            Dest::Caller(function) => format!("the caller of `{}`", function),
+            Dest::Temporary => "a temporary".to_string(),
+        }
+    }
+
+    fn step(&self, from: &str, verb: &str) -> String {
+        match self {
+            Dest::Var(name) | Dest::Call(name) => format!("{} {} into {}", from, verb, name),
+            Dest::Caller(function) => format!("{} {} out of {}", from, verb, function),
+            Dest::Temporary => format!("{} {} into a temporary", from, verb),
+        }
+    }
+}
+
+type Frame = Vec<Var>;
+
+impl Simulator<'_> {
+    fn step(&mut self, span: Span, text: String) {
+        self.trace.steps.push(Step { span, text });
+    }
+
+    fn call(
+        &mut self,
+        function: &Function,
+        args: Vec<Value>,
+        span: Span,
+    ) -> Result<Value, Diagnostic> {
+        if self.depth == MAX_DEPTH {
+            return Err(Diagnostic::new(
+                format!("`{}` calls itself without end", function.name.name),
+                span,
+                "every call makes another call before it returns",
+            ));
+        }
+        self.depth += 1;
+        let mut frame = Frame::new();
+        for (param, value) in function.params.iter().zip(args) {
+            self.bind(&mut frame, &param.name, false, value, true);
+        }
+        let value = self.block(
+            &mut frame,
+            &function.body,
+            Dest::Caller(&function.name.name),
+        )?;
+        // The parameters outlive the body's own variables
+        self.drop_vars(&frame, function.body.close);
+        if value.ty() != function.ret {
+            let span = function
+                .body
+                .tail
+                .as_ref()
+                .map_or(function.body.close, Expr::span);
+            return Err(mismatch(function.ret.clone(), value.ty(), span));
+        }
+        self.depth -= 1;
+        Ok(value)
+    }
+
+    // Runs a block and drops the variables it declared; returns the value of its tail
+    // expression, which goes to `tail`
+    fn block(&mut self, frame: &mut Frame, block: &Block, tail: Dest) -> Result<Value, Diagnostic> {
+        let start = frame.len();
+        for stmt in &block.stmts {
+            match stmt {
+                Stmt::Let {
+                    mutable,
+                    name,
+                    ty,
+                    value: None,
+                } => frame.push(Var {
+                    name: name.name.clone(),
+                    mutable: *mutable,
+                    ty: ty.clone(),
+                    state: State::Uninit,
+                }),
+                Stmt::Let {
+                    mutable,
+                    name,
+                    ty,
+                    value: Some(value),
+                } => {
+                    let value = self.eval(frame, value, Dest::Var(&name.name))?;
+                    if let Some(ty) = ty {
+                        if *ty != value.ty() {
+                            return Err(mismatch(ty.clone(), value.ty(), stmt_value_span(stmt)));
+                        }
+                    }
+                    self.bind(frame, name, *mutable, value, announces(stmt));
+                }
+                Stmt::Assign { name, value } => {
+                    let index = lookup(frame, name)?;
+                    let uninit = matches!(frame[index].state, State::Uninit);
+                    if !frame[index].mutable && !uninit {
+                        return Err(Diagnostic {
+                            code: Some("E0384"),
+                            ..Diagnostic::new(
+                                format!(
+                                    "cannot assign twice to immutable variable `{}`",
+                                    name.name
+                                ),
+                                name.span,
+                                "cannot assign twice to immutable variable",
+                            )
+                        });
+                    }
+                    let value = self.eval(frame, value, Dest::Var(&name.name))?;
+                    match &frame[index].ty {
+                        Some(ty) if *ty != value.ty() => {
+                            return Err(mismatch(ty.clone(), value.ty(), stmt_value_span(stmt)));
+                        }
+                        Some(_) => {}
+                        None => frame[index].ty = Some(value.ty()),
+                    }
+                    let old =
+                        std::mem::replace(&mut frame[index].state, State::Owned(value.clone()));
+                    if let State::Owned(Value::Str(_)) = old {
+                        self.step(name.span, format!("drop the old value of {}", name.name));
+                    }
+                    if announces(stmt) {
+                        self.step(name.span, owns(&name.name, &value));
+                    }
+                }
+                Stmt::Expr(expr) => {
+                    let value = self.eval(frame, expr, Dest::Temporary)?;
+                    if let (Value::Str(_), Expr::Call { name, .. }) = (&value, expr) {
+                        self.step(
+                            expr.span(),
+                            format!("drop the String returned by {}", name.name),
+                        );
+                    }
+                }
+                Stmt::Block(inner) => {
+                    let value = self.block(frame, inner, Dest::Temporary)?;
+                    if value != Value::Unit {
+                        let span = inner.tail.as_ref().map_or(inner.close, Expr::span);
+                        return Err(mismatch(Type::Unit, value.ty(), span));
+                    }
+                }
+            }
+        }
+        let value = match &block.tail {
+            Some(expr) => self.eval(frame, expr, tail)?,
+            None => Value::Unit,
+        };
+        self.drop_vars(&frame[start..], block.close);
+        frame.truncate(start);
+        Ok(value)
+    }
+
+    // Variables are dropped in the reverse order of their declaration
+    fn drop_vars(&mut self, vars: &[Var], span: Span) {
+        for var in vars.iter().rev() {
+            if let State::Owned(Value::Str(_)) = var.state {
+                self.step(span, format!("drop {}", var.name));
+            }
+        }
+    }
+
+    fn bind(
+        &mut self,
+        frame: &mut Frame,
+        name: &Ident,
+        mutable: bool,
+        value: Value,
+        announce: bool,
+    ) {
+        if announce {
+            self.step(name.span, owns(&name.name, &value));
+        }
+        frame.push(Var {
+            name: name.name.clone(),
+            mutable,
+            ty: Some(value.ty()),
+            state: State::Owned(value),
+        });
+    }
+
+    fn eval(&mut self, frame: &mut Frame, expr: &Expr, dest: Dest) -> Result<Value, Diagnostic> {
+        match expr {
+            Expr::Int { value, .. } => Ok(Value::Int(*value)),
+            Expr::Char { value, .. } => Ok(Value::Char(*value)),
+            Expr::Bool { value, .. } => Ok(Value::Bool(*value)),
+            Expr::Compare { op, lhs, rhs } => {
+                let left = self.eval(frame, lhs, Dest::Temporary)?;
+                let right = self.eval(frame, rhs, Dest::Temporary)?;
+                let (Value::Int(left), Value::Int(right)) = (&left, &right) else {
+                    let found = if let Value::Int(_) = left {
+                        right
+                    } else {
+                        left
+                    };
+                    return Err(mismatch(Type::I32, found.ty(), expr.span()));
+                };
+                Ok(Value::Bool(match *op {
+                    "==" => left == right,
+                    "!=" => left != right,
+                    "<" => left < right,
+                    "<=" => left <= right,
+                    ">" => left > right,
+                    _ => left >= right,
+                }))
+            }
+            Expr::If {
+                cond,
+                then,
+                otherwise,
+                ..
+            } => {
+                let Value::Bool(cond_value) = self.eval(frame, cond, Dest::Temporary)? else {
+                    let found = self.eval(frame, cond, Dest::Temporary)?.ty();
+                    return Err(mismatch(Type::Bool, found, cond.span()));
+                };
+                let value = match (cond_value, otherwise) {
+                    (true, _) => self.block(frame, then, dest)?,
+                    (false, Some(otherwise)) => self.block(frame, otherwise, dest)?,
+                    (false, None) => Value::Unit,
+                };
+                if otherwise.is_none() && value != Value::Unit {
+                    let span = then.tail.as_ref().map_or(then.close, Expr::span);
+                    return Err(mismatch(Type::Unit, value.ty(), span));
+                }
+                Ok(value)
+            }
+            Expr::Struct { span, .. } | Expr::Field { span, .. } => Err(Diagnostic::new(
+                "the ownership simulator doesn't run structs",
+                *span,
+                "check borrows with `cargo run -- borrowck <file>` instead",
+            )),
+            Expr::StringFrom { text, .. } => Ok(Value::Str(text.clone())),
+            Expr::Var(name) => {
+                let value = read(frame, name, "use", "used")?;
+                let verb = if value.ty().is_copy() {
+                    "copied"
+                } else {
+                    let index = lookup(frame, name)?;
+                    frame[index].state = State::Moved {
+                        to: dest.describe(),
+                        span: name.span,
+                    };
+                    "moved"
+                };
+                self.step(name.span, dest.step(&name.name, verb));
+                Ok(value)
+            }
+            Expr::Clone { receiver, span } => {
+                let value = read(frame, receiver, "borrow", "borrowed")?;
+                let verb = if value.ty().is_copy() {
+                    "copied"
+                } else {
+                    "cloned"
+                };
+                self.step(*span, dest.step(&receiver.name, verb));
+                Ok(value)
+            }
+            Expr::Call { name, args } => {
+                let Some(function) = self.program.function(&name.name) else {
+                    return Err(Diagnostic {
+                        code: Some("E0425"),
+                        ..Diagnostic::new(
+                            format!("cannot find function `{}` in this scope", name.name),
+                            name.span,
+                            "not found in this scope",
+                        )
+                    });
+                };
+                if args.len() != function.params.len() {
+                    return Err(Diagnostic {
+                        code: Some("E0061"),
+                        ..Diagnostic::new(
+                            format!(
+                                "this function takes {} arguments but {} were supplied",
+                                function.params.len(),
+                                args.len()
+                            ),
+                            name.span,
+                            "",
+                        )
+                    });
+                }
+                let mut values = Vec::new();
+                for (arg, param) in args.iter().zip(&function.params) {
+                    let value = self.eval(frame, arg, Dest::Call(&name.name))?;
+                    if value.ty() != param.ty {
+                        return Err(mismatch(param.ty.clone(), value.ty(), arg.span()));
+                    }
+                    values.push(value);
+                }
+                self.step(name.span, format!("call {}", name.name));
+                self.call(function, values, name.span)
+            }
+            Expr::Str { span, .. } => Err(Diagnostic::new(
+                "the ownership simulator doesn't follow references",
+                *span,
+                "a string literal is a `&str`; use `String::from` to own the text",
+            )),
+            Expr::Ref { span, .. } => Err(Diagnostic::new(
+                "the ownership simulator doesn't follow references",
+                *span,
+                "check borrows with `cargo run -- borrowck <file>` instead",
+            )),
+            Expr::Method { method, .. } => Err(Diagnostic::new(
+                format!("the ownership simulator can't run `{}`", method.name),
+                method.span,
+                "it only runs `clone`",
+            )),
+            Expr::Print { format, args, span } => {
+                let mut values = Vec::new();
+                for arg in args {
+                    // println! only borrows its arguments
+                    let value = match arg {
+                        Expr::Var(name) => read(frame, name, "borrow", "borrowed")?,
+                        arg => self.eval(frame, arg, Dest::Temporary)?,
+                    };
+                    values.push(value);
+                }
+                let line = substitute(format, &values, *span)?;
+                self.step(*span, format!("print {:?}", line));
+                self.trace.output.push(line);
+                Ok(Value::Unit)
+            }
+        }
+    }
+}
+
+// Reads a variable without moving it, failing if its value was moved away
+fn read(frame: &Frame, name: &Ident, noun: &str, participle: &str) -> Result<Value, Diagnostic> {
+    let var = &frame[lookup(frame, name)?];
+    match &var.state {
+        State::Uninit => Err(Diagnostic {
+            code: Some("E0381"),
+            ..Diagnostic::new(
+                format!("used binding `{}` isn't initialized", name.name),
+                name.span,
+                format!("`{}` used here but it isn't initialized", name.name),
+            )
+        }),
+        State::Owned(value) => Ok(value.clone()),
+        State::Moved { to, span } => Err(Diagnostic {
+            code: Some("E0382"),
+            notes: vec![(*span, format!("`{}` moved into {} here", name.name, to))],
+            ..Diagnostic::new(
+                format!("{} of moved value: `{}`", noun, name.name),
+                name.span,
+                format!("value {} here after move", participle),
+            )
+        }),
+    }
+}
+
+// The index of the innermost variable called `name`; later `let`s shadow earlier ones
+fn lookup(frame: &Frame, name: &Ident) -> Result<usize, Diagnostic> {
+    frame
+        .iter()
+        .rposition(|var| var.name == name.name)
+        .ok_or_else(|| Diagnostic {
+            code: Some("E0425"),
+            ..Diagnostic::new(
+                format!("cannot find value `{}` in this scope", name.name),
+                name.span,
+                "not found in this scope",
+            )
+        })
+}
+
+fn owns(name: &str, value: &Value) -> String {
+    match value {
+        Value::Str(_) => format!("{} owns {}", name, value),
+        _ => format!("{} holds {}", name, value),
+    }
+}
+
+fn mismatch(expected: Type, found: Type, span: Span) -> Diagnostic {
+    Diagnostic {
+        code: Some("E0308"),
+        ..Diagnostic::new(
+            "mismatched types",
+            span,
+            format!("expected `{}`, found `{}`", expected, found),
+        )
+    }
+}
+
+// Whether a `let` or assignment should say what the variable now holds; a move, copy or
+// clone already said where the value went
+fn announces(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Let {
+            value: Some(value), ..
+        }
+        | Stmt::Assign { value, .. }
+        | Stmt::Expr(value) => !matches!(value, Expr::Var(_) | Expr::Clone { .. }),
+        Stmt::Let { value: None, .. } | Stmt::Block(_) => false,
+    }
+}
+
+fn stmt_value_span(stmt: &Stmt) -> Span {
+    match stmt {
+        Stmt::Let {
+            value: Some(value), ..
+        }
+        | Stmt::Assign { value, .. }
+        | Stmt::Expr(value) => value.span(),
+        Stmt::Let { name, .. } => name.span,
+        Stmt::Block(block) => block.close,
+    }
+}
+
+// Fills in the `{}` placeholders of a println! format string
+fn substitute(format: &str, values: &[Value], span: Span) -> Result<String, Diagnostic> {
+    let mut line = String::new();
+    let mut values = values.iter();
+    let mut rest = format;
+    while let Some(index) = rest.find("{}") {
+        let Some(value) = values.next() else {
+            return Err(Diagnostic::new(
+                "more `{}` placeholders than arguments",
+                span,
+                "",
+            ));
+        };
+        line += &rest[..index];
+        match value {
+            Value::Str(text) => line += text,
+            Value::Char(c) => line.push(*c),
+            Value::Bool(value) => line += &value.to_string(),
+            value => line += &value.to_string(),
+        }
+        rest = &rest[index + 2..];
+    }
+    if values.next().is_some() {
+        return Err(Diagnostic::new(
+            "argument never used in the format string",
+            span,
+            "",
+        ));
+    }
+    line += rest;
+    Ok(line)
+}
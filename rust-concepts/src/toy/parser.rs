@@ -0,0 +1,642 @@
+use super::ast::{Block, Expr, Function, Ident, Param, Program, Stmt, Struct, Type};
+use super::lexer::{tokenize, Token, TokenKind};
+use super::{Diagnostic, Span};
+
+/// Parses a toy program
+///
+/// ```
+/// use rust_concepts::toy::parser::parse;
+///
+/// let program = parse("fn main() { let x = 5; }").unwrap();
+/// assert_eq!(program.functions[0].body.stmts.len(), 1);
+///
+/// let err = parse("fn main() { let x = 5 }").unwrap_err();
+/// assert_eq!(err.message, "expected `;`, found `}`");
+/// ```
+pub fn parse(source: &str) -> Result<Program, Diagnostic> {
+    let tokens = tokenize(source)?;
+    let end = tokens.last().map_or(Span::default(), |token| Span {
+        column: token.span.column + token.span.len,
+        len: 1,
+        ..token.span
+    });
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+        end,
+        depth: 0,
+    };
+    let mut program = Program {
+        structs: Vec::new(),
+        functions: Vec::new(),
+    };
+    while parser.peek().is_some() {
+        if parser.is_keyword("struct") {
+            program.structs.push(parser.struct_item()?);
+        } else {
+            program.functions.push(parser.function()?);
+        }
+    }
+    Ok(program)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    // Just past the last token, where "unexpected end of file" points
+    end: Span,
+    // How many blocks, expressions and types the parser is inside of
+    depth: usize,
+}
+
+/// How deeply blocks, expressions and types may nest before [`parse`] gives up
+///
+/// The parser, the interpreter and the borrow checker recurse once per level, so a limit
+/// keeps a program with hundreds of nested `if`s from overflowing the stack.
+pub const MAX_DEPTH: usize = 128;
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek_kind(&self, offset: usize) -> Option<&TokenKind> {
+        self.tokens.get(self.position + offset).map(|t| &t.kind)
+    }
+
+    fn is_punct(&self, punct: &str) -> bool {
+        matches!(self.peek_kind(0), Some(TokenKind::Punct(p)) if *p == punct)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek_kind(0), Some(TokenKind::Ident(name)) if name == keyword)
+    }
+
+    // An error pointing at the next token, or at the end of the file
+    fn unexpected(&self, expected: &str) -> Diagnostic {
+        match self.peek() {
+            Some(token) => Diagnostic::new(
+                format!("expected {}, found {}", expected, describe(&token.kind)),
+                token.span,
+                "",
+            ),
+            None => Diagnostic::new(
+                format!("expected {}, found the end of the file", expected),
+                self.end,
+                "",
+            ),
+        }
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    // Runs `parse` one level deeper, or fails past MAX_DEPTH
+    fn nested<T>(
+        &mut self,
+        parse: fn(&mut Parser) -> Result<T, Diagnostic>,
+    ) -> Result<T, Diagnostic> {
+        if self.depth == MAX_DEPTH {
+            let span = self.peek().map_or(self.end, |token| token.span);
+            return Err(Diagnostic::new(
+                format!("nested more than {} levels deep", MAX_DEPTH),
+                span,
+                "the toy language stops here",
+            ));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn expect_punct(&mut self, punct: &str) -> Result<Span, Diagnostic> {
+        if !self.is_punct(punct) {
+            return Err(self.unexpected(&format!("`{}`", punct)));
+        }
+        Ok(self.next().expect("the token was peeked").span)
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<Span, Diagnostic> {
+        if !self.is_keyword(keyword) {
+            return Err(self.unexpected(&format!("`{}`", keyword)));
+        }
+        Ok(self.next().expect("the token was peeked").span)
+    }
+
+    fn ident(&mut self) -> Result<Ident, Diagnostic> {
+        match self.peek() {
+            Some(Token {
+                kind: TokenKind::Ident(name),
+                span,
+            }) if !KEYWORDS.contains(&name.as_str()) => {
+                let ident = Ident {
+                    name: name.clone(),
+                    span: *span,
+                };
+                self.position += 1;
+                Ok(ident)
+            }
+            _ => Err(self.unexpected("a name")),
+        }
+    }
+
+    fn function(&mut self) -> Result<Function, Diagnostic> {
+        self.expect_keyword("fn")?;
+        let name = self.ident()?;
+        let lifetimes = self.lifetimes()?;
+        self.expect_punct("(")?;
+        let params = self.params(")")?;
+        let close = self.expect_punct(")")?;
+        let (ret, ret_span) = if self.is_punct("->") {
+            self.next();
+            self.ty()?
+        } else {
+            (Type::Unit, close)
+        };
+        Ok(Function {
+            name,
+            lifetimes,
+            params,
+            ret,
+            ret_span,
+            body: self.block()?,
+        })
+    }
+
+    fn struct_item(&mut self) -> Result<Struct, Diagnostic> {
+        self.expect_keyword("struct")?;
+        let name = self.ident()?;
+        let lifetimes = self.lifetimes()?;
+        self.expect_punct("{")?;
+        let fields = self.params("}")?;
+        self.expect_punct("}")?;
+        Ok(Struct {
+            name,
+            lifetimes,
+            fields,
+        })
+    }
+
+    // `name: Type` pairs separated by commas, up to `close`
+    fn params(&mut self, close: &str) -> Result<Vec<Param>, Diagnostic> {
+        let mut params = Vec::new();
+        while !self.is_punct(close) {
+            let name = self.ident()?;
+            self.expect_punct(":")?;
+            params.push(Param {
+                name,
+                ty: self.ty()?.0,
+            });
+            if !self.is_punct(close) {
+                self.expect_punct(",")?;
+            }
+        }
+        Ok(params)
+    }
+
+    // `<'a, 'b>` after the name of a function or struct, if it is there
+    fn lifetimes(&mut self) -> Result<Vec<Ident>, Diagnostic> {
+        let mut lifetimes = Vec::new();
+        if self.is_punct("<") {
+            self.next();
+            while !self.is_punct(">") {
+                lifetimes.push(self.lifetime()?);
+                if !self.is_punct(">") {
+                    self.expect_punct(",")?;
+                }
+            }
+            self.next();
+        }
+        Ok(lifetimes)
+    }
+
+    fn lifetime(&mut self) -> Result<Ident, Diagnostic> {
+        match self.peek() {
+            Some(Token {
+                kind: TokenKind::Lifetime(name),
+                span,
+            }) => {
+                let ident = Ident {
+                    name: name.clone(),
+                    span: *span,
+                };
+                self.position += 1;
+                Ok(ident)
+            }
+            _ => Err(self.unexpected("a lifetime")),
+        }
+    }
+
+    // A type and where it was written
+    fn ty(&mut self) -> Result<(Type, Span), Diagnostic> {
+        self.nested(Parser::ty_inner)
+    }
+
+    fn ty_inner(&mut self) -> Result<(Type, Span), Diagnostic> {
+        if self.is_punct("&") {
+            let start = self.next().expect("the token was peeked").span;
+            let lifetime = match self.peek_kind(0) {
+                Some(TokenKind::Lifetime(_)) => Some(self.lifetime()?.name),
+                _ => None,
+            };
+            let mutable = self.is_keyword("mut");
+            if mutable {
+                self.next();
+            }
+            let (target, end) = self.ty()?;
+            let ty = Type::Ref {
+                lifetime,
+                mutable,
+                target: Box::new(target),
+            };
+            return Ok((ty, join(start, end)));
+        }
+        match self.peek() {
+            Some(Token {
+                kind: TokenKind::Ident(name),
+                span,
+            }) => {
+                let ty = match name.as_str() {
+                    "i32" => Type::I32,
+                    "usize" => Type::Usize,
+                    "char" => Type::Char,
+                    "bool" => Type::Bool,
+                    "str" => Type::Str,
+                    "String" => Type::String,
+                    // Any other capitalized name is a struct
+                    name if name.starts_with(|c: char| c.is_uppercase()) => Type::Struct {
+                        name: name.to_string(),
+                        lifetimes: Vec::new(),
+                    },
+                    _ => return Err(Diagnostic::new(
+                        format!("unsupported type `{}`", name),
+                        *span,
+                        "the toy language only has `i32`, `usize`, `char`, `bool`, `str`, `String`, structs and references",
+                    )),
+                };
+                let mut span = *span;
+                self.position += 1;
+                if let Type::Struct { name, .. } = ty {
+                    let lifetimes = self.lifetimes()?;
+                    if let Some(last) = lifetimes.last() {
+                        span = join(
+                            span,
+                            Span {
+                                len: last.span.len + 1,
+                                ..last.span
+                            },
+                        );
+                    }
+                    let lifetimes = lifetimes.into_iter().map(|l| l.name).collect();
+                    return Ok((Type::Struct { name, lifetimes }, span));
+                }
+                Ok((ty, span))
+            }
+            _ => Err(self.unexpected("a type")),
+        }
+    }
+
+    fn block(&mut self) -> Result<Block, Diagnostic> {
+        self.nested(Parser::block_inner)
+    }
+
+    fn block_inner(&mut self) -> Result<Block, Diagnostic> {
+        self.expect_punct("{")?;
+        let mut stmts = Vec::new();
+        let mut tail = None;
+        while !self.is_punct("}") {
+            if self.peek().is_none() {
+                return Err(self.unexpected("`}`"));
+            }
+            if self.is_punct("{") {
+                stmts.push(Stmt::Block(self.block()?));
+            } else if self.is_keyword("let") {
+                self.next();
+                let mutable = self.is_keyword("mut");
+                if mutable {
+                    self.next();
+                }
+                let name = self.ident()?;
+                let ty = if self.is_punct(":") {
+                    self.next();
+                    Some(self.ty()?.0)
+                } else {
+                    None
+                };
+                let value = if self.is_punct(";") {
+                    None
+                } else {
+                    self.expect_punct("=")?;
+                    Some(self.expr()?)
+                };
+                self.expect_punct(";")?;
+                stmts.push(Stmt::Let {
+                    mutable,
+                    name,
+                    ty,
+                    value,
+                });
+            } else if matches!(self.peek_kind(1), Some(TokenKind::Punct("="))) {
+                let name = self.ident()?;
+                self.next();
+                let value = self.expr()?;
+                self.expect_punct(";")?;
+                stmts.push(Stmt::Assign { name, value });
+            } else {
+                let expr = self.expr()?;
+                if self.is_punct("}") {
+                    tail = Some(expr);
+                } else if matches!(expr, Expr::If { .. }) && !self.is_punct(";") {
+                    // An `if` is a statement of its own, without a `;`
+                    stmts.push(Stmt::Expr(expr));
+                } else {
+                    self.expect_punct(";")?;
+                    stmts.push(Stmt::Expr(expr));
+                }
+            }
+        }
+        let close = self.expect_punct("}")?;
+        Ok(Block { stmts, tail, close })
+    }
+
+    fn expr(&mut self) -> Result<Expr, Diagnostic> {
+        self.nested(Parser::expr_inner)
+    }
+
+    fn expr_inner(&mut self) -> Result<Expr, Diagnostic> {
+        let lhs = self.primary()?;
+        let Some(TokenKind::Punct(op)) = self.peek_kind(0) else {
+            return Ok(lhs);
+        };
+        let Some(op) = COMPARISONS.iter().find(|c| *c == op) else {
+            return Ok(lhs);
+        };
+        self.next();
+        let rhs = self.primary()?;
+        Ok(Expr::Compare {
+            op,
+            lhs: Box::new(lhs),
+            rhs: Box::new(rhs),
+        })
+    }
+
+    fn primary(&mut self) -> Result<Expr, Diagnostic> {
+        if self.is_keyword("if") {
+            return self.if_expr();
+        }
+        if self.is_keyword("true") || self.is_keyword("false") {
+            let value = self.is_keyword("true");
+            let span = self.next().expect("the token was peeked").span;
+            return Ok(Expr::Bool { value, span });
+        }
+        if let Some(Token {
+            kind: TokenKind::Int(value),
+            span,
+        }) = self.peek()
+        {
+            let value = i64::try_from(*value)
+                .map_err(|_| Diagnostic::new("integer literal is too large", *span, ""))?;
+            let expr = Expr::Int { value, span: *span };
+            self.position += 1;
+            return Ok(expr);
+        }
+        if let Some(Token {
+            kind: TokenKind::Char(value),
+            span,
+        }) = self.peek()
+        {
+            let expr = Expr::Char {
+                value: *value,
+                span: *span,
+            };
+            self.position += 1;
+            return Ok(expr);
+        }
+        if let Some(Token {
+            kind: TokenKind::Str(text),
+            span,
+        }) = self.peek()
+        {
+            let expr = Expr::Str {
+                text: text.clone(),
+                span: *span,
+            };
+            self.position += 1;
+            return Ok(expr);
+        }
+        if self.is_punct("&") {
+            let start = self.next().expect("the token was peeked").span;
+            let mutable = self.is_keyword("mut");
+            if mutable {
+                self.next();
+            }
+            let place = self.ident()?;
+            let span = join(start, place.span);
+            return Ok(Expr::Ref {
+                mutable,
+                place,
+                span,
+            });
+        }
+        if self.is_keyword("String") {
+            let start = self.next().expect("the token was peeked").span;
+            self.expect_punct("::")?;
+            self.expect_keyword("from")?;
+            self.expect_punct("(")?;
+            let text = self.string()?;
+            let close = self.expect_punct(")")?;
+            return Ok(Expr::StringFrom {
+                text,
+                span: join(start, close),
+            });
+        }
+
+        let name = self.ident()?;
+        if self.is_punct("!") {
+            if name.name != "println" {
+                return Err(Diagnostic::new(
+                    format!("unsupported macro `{}!`", name.name),
+                    name.span,
+                    "the toy language only has `println!`",
+                ));
+            }
+            self.next();
+            self.expect_punct("(")?;
+            let format = self.string()?;
+            let mut args = Vec::new();
+            while self.is_punct(",") {
+                self.next();
+                args.push(self.expr()?);
+            }
+            let close = self.expect_punct(")")?;
+            return Ok(Expr::Print {
+                format,
+                args,
+                span: join(name.span, close),
+            });
+        }
+        if self.is_punct("(") {
+            self.next();
+            let mut args = Vec::new();
+            while !self.is_punct(")") {
+                args.push(self.expr()?);
+                if !self.is_punct(")") {
+                    self.expect_punct(",")?;
+                }
+            }
+            self.expect_punct(")")?;
+            return Ok(Expr::Call { name, args });
+        }
+        if self.is_punct("{") && name.name.starts_with(|c: char| c.is_uppercase()) {
+            self.next();
+            let mut fields = Vec::new();
+            while !self.is_punct("}") {
+                let field = self.ident()?;
+                self.expect_punct(":")?;
+                fields.push((field, self.expr()?));
+                if !self.is_punct("}") {
+                    self.expect_punct(",")?;
+                }
+            }
+            let close = self.expect_punct("}")?;
+            let span = join(name.span, close);
+            return Ok(Expr::Struct { name, fields, span });
+        }
+        if self.is_punct(".") && !matches!(self.peek_kind(2), Some(TokenKind::Punct("("))) {
+            self.next();
+            let field = self.ident()?;
+            let span = join(name.span, field.span);
+            return Ok(Expr::Field {
+                base: name,
+                field,
+                span,
+            });
+        }
+        if self.is_punct(".") {
+            self.next();
+            let method = self.ident()?;
+            if !METHODS.contains(&method.name.as_str()) {
+                return Err(Diagnostic::new(
+                    format!("unsupported method `{}`", method.name),
+                    method.span,
+                    "the toy language only has `clone`, `len`, `push` and `push_str`",
+                ));
+            }
+            self.expect_punct("(")?;
+            if method.name == "clone" {
+                let close = self.expect_punct(")")?;
+                let span = join(name.span, close);
+                return Ok(Expr::Clone {
+                    receiver: name,
+                    span,
+                });
+            }
+            let mut args = Vec::new();
+            while !self.is_punct(")") {
+                args.push(self.expr()?);
+                if !self.is_punct(")") {
+                    self.expect_punct(",")?;
+                }
+            }
+            let close = self.expect_punct(")")?;
+            let span = join(name.span, close);
+            return Ok(Expr::Method {
+                receiver: name,
+                method,
+                args,
+                span,
+            });
+        }
+        Ok(Expr::Var(name))
+    }
+
+    // `if cond { then } else { otherwise }`, where the `else` part is optional
+    // Each `else if` nests one level deeper, like the block it stands for
+    fn if_expr(&mut self) -> Result<Expr, Diagnostic> {
+        self.nested(Parser::if_inner)
+    }
+
+    fn if_inner(&mut self) -> Result<Expr, Diagnostic> {
+        let start = self.expect_keyword("if")?;
+        let cond = self.expr()?;
+        let then = Box::new(self.block()?);
+        let mut end = then.close;
+        let otherwise = if self.is_keyword("else") {
+            self.next();
+            let block = if self.is_keyword("if") {
+                let nested = self.if_expr()?;
+                let close = match &nested {
+                    Expr::If {
+                        then, otherwise, ..
+                    } => otherwise.as_ref().unwrap_or(then).close,
+                    _ => unreachable!("if_expr returns an `if`"),
+                };
+                Box::new(Block {
+                    stmts: Vec::new(),
+                    tail: Some(nested),
+                    close,
+                })
+            } else {
+                Box::new(self.block()?)
+            };
+            end = block.close;
+            Some(block)
+        } else {
+            None
+        };
+        Ok(Expr::If {
+            cond: Box::new(cond),
+            then,
+            otherwise,
+            span: join(start, end),
+        })
+    }
+
+    fn string(&mut self) -> Result<String, Diagnostic> {
+        match self.peek_kind(0) {
+            Some(TokenKind::Str(text)) => {
+                let text = text.clone();
+                self.position += 1;
+                Ok(text)
+            }
+            _ => Err(self.unexpected("a string literal")),
+        }
+    }
+}
+
+// Words that can't be used as names
+const KEYWORDS: &[&str] = &[
+    "fn", "let", "mut", "if", "else", "struct", "true", "false", "String",
+];
+
+// The operators that compare two numbers
+const COMPARISONS: &[&str] = &["==", "!=", "<", "<=", ">", ">="];
+
+// The methods of `String` the toy language knows
+const METHODS: &[&str] = &["clone", "len", "push", "push_str"];
+
+// A span from the start of `first` to the end of `last`, which must be on the same line
+fn join(first: Span, last: Span) -> Span {
+    if first.line != last.line {
+        return first;
+    }
+    Span {
+        len: last.column + last.len - first.column,
+        ..first
+    }
+}
+
+fn describe(kind: &TokenKind) -> String {
+    match kind {
+        TokenKind::Ident(name) => format!("`{}`", name),
+        TokenKind::Int(value) => format!("`{}`", value),
+        TokenKind::Str(_) => "a string literal".to_string(),
+        TokenKind::Char(value) => format!("`{:?}`", value),
+        TokenKind::Lifetime(name) => format!("`'{}`", name),
+        TokenKind::Punct(punct) => format!("`{}`", punct),
+    }
+}
@@ -0,0 +1,971 @@
+use super::ast::Ident;
+use super::lexer::{tokenize, Token, TokenKind};
+use super::{Diagnostic, Span};
+use std::fmt;
+
+/// The integer types a pattern can match, with their smallest and largest values
+pub const INTEGERS: &[(&str, i128, i128)] = &[
+    ("i8", i8::MIN as i128, i8::MAX as i128),
+    ("i16", i16::MIN as i128, i16::MAX as i128),
+    ("i32", i32::MIN as i128, i32::MAX as i128),
+    ("i64", i64::MIN as i128, i64::MAX as i128),
+    ("u8", 0, u8::MAX as i128),
+    ("u16", 0, u16::MAX as i128),
+    ("u32", 0, u32::MAX as i128),
+    ("u64", 0, u64::MAX as i128),
+];
+
+/// The type of a value a `match` looks at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ty {
+    /// One of the [`INTEGERS`], such as `i32`
+    Int(&'static str),
+    Bool,
+    /// `(A, B)`; `()` is the tuple without elements
+    Tuple(Vec<Ty>),
+    /// An enum or struct, with its type arguments, such as `Option<i32>`
+    Adt {
+        name: String,
+        args: Vec<Ty>,
+    },
+    /// A type with too many values to list, such as `String` or `&str`: only bindings,
+    /// `_` and string literals match it
+    Opaque(String),
+}
+
+impl Ty {
+    /// The smallest and largest value of an integer type
+    pub fn range(&self) -> Option<(i128, i128)> {
+        match self {
+            Ty::Int(name) => INTEGERS
+                .iter()
+                .find(|(n, _, _)| n == name)
+                .map(|(_, min, max)| (*min, *max)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Ty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Ty::Int(name) => write!(f, "{}", name),
+            Ty::Bool => write!(f, "bool"),
+            Ty::Tuple(items) => write!(f, "({})", join(items, ", ")),
+            Ty::Adt { name, args } if args.is_empty() => write!(f, "{}", name),
+            Ty::Adt { name, args } => write!(f, "{}<{}>", name, join(args, ", ")),
+            Ty::Opaque(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// The fields of an enum variant or a struct
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fields {
+    Unit,
+    /// `Variant(A, B)`
+    Tuple(Vec<Ty>),
+    /// `Variant { a: A, b: B }`
+    Named(Vec<(String, Ty)>),
+}
+
+/// A variant of an enum; a struct is a type with a single variant named after it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variant {
+    pub name: String,
+    pub fields: Fields,
+}
+
+impl Variant {
+    /// The types of the fields, in order
+    pub fn types(&self) -> Vec<Ty> {
+        match &self.fields {
+            Fields::Unit => Vec::new(),
+            Fields::Tuple(types) => types.clone(),
+            Fields::Named(fields) => fields.iter().map(|(_, ty)| ty.clone()).collect(),
+        }
+    }
+}
+
+/// An enum or struct declared in the file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Adt {
+    pub name: String,
+    pub is_enum: bool,
+    pub variants: Vec<Variant>,
+}
+
+/// A path in a pattern, such as `Message::Quit` or `Some`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path {
+    pub segments: Vec<String>,
+    pub span: Span,
+}
+
+impl Path {
+    /// The last segment, which names the variant or struct
+    pub fn last(&self) -> &str {
+        self.segments.last().map_or("", String::as_str)
+    }
+}
+
+/// A pattern, as written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pat {
+    /// `_`
+    Wild(Span),
+    /// A name that binds whatever it matches, such as `x`, `ref x` or `mut x`
+    Binding(Ident),
+    /// An integer literal, such as `1` or `-2`
+    Int { value: i128, span: Span },
+    /// `lo..=hi`
+    Range { lo: i128, hi: i128, span: Span },
+    /// `true` or `false`
+    Bool { value: bool, span: Span },
+    /// A string literal
+    Str { text: String, span: Span },
+    /// `(a, b)`, where one element may be `..`
+    Tuple { items: Vec<Pat>, span: Span },
+    /// `..`, the rest of a tuple
+    Rest(Span),
+    /// A unit variant such as `Message::Quit` or `None`
+    Path(Path),
+    /// `Message::ChangeColor(r, g, b)` or `Some(x)`
+    TupleVariant { path: Path, items: Vec<Pat> },
+    /// `Point { x, y: 0 }` or `Message::Move { x, .. }`
+    Struct {
+        path: Path,
+        fields: Vec<(Ident, Pat)>,
+        rest: bool,
+    },
+    /// `a | b | c`
+    Or(Vec<Pat>),
+}
+
+impl Pat {
+    /// Where the pattern was written
+    pub fn span(&self) -> Span {
+        match self {
+            Pat::Wild(span)
+            | Pat::Int { span, .. }
+            | Pat::Range { span, .. }
+            | Pat::Bool { span, .. }
+            | Pat::Str { span, .. }
+            | Pat::Tuple { span, .. }
+            | Pat::Rest(span) => *span,
+            Pat::Binding(name) => name.span,
+            Pat::Path(path) | Pat::TupleVariant { path, .. } | Pat::Struct { path, .. } => {
+                path.span
+            }
+            Pat::Or(alts) => alts[0].span(),
+        }
+    }
+}
+
+impl fmt::Display for Pat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Pat::Wild(_) => write!(f, "_"),
+            Pat::Binding(name) => write!(f, "{}", name.name),
+            Pat::Int { value, .. } => write!(f, "{}", value),
+            Pat::Range { lo, hi, .. } => write!(f, "{}..={}", lo, hi),
+            Pat::Bool { value, .. } => write!(f, "{}", value),
+            Pat::Str { text, .. } => write!(f, "{:?}", text),
+            Pat::Tuple { items, .. } => write!(f, "({})", join(items, ", ")),
+            Pat::Rest(_) => write!(f, ".."),
+            Pat::Path(path) => write!(f, "{}", path.segments.join("::")),
+            Pat::TupleVariant { path, items } => {
+                write!(f, "{}({})", path.segments.join("::"), join(items, ", "))
+            }
+            Pat::Struct { path, fields, rest } => {
+                let mut parts: Vec<String> = fields
+                    .iter()
+                    .map(|(name, pat)| match pat {
+                        Pat::Binding(binding) if binding.name == name.name => name.name.clone(),
+                        pat => format!("{}: {}", name.name, pat),
+                    })
+                    .collect();
+                if *rest {
+                    parts.push("..".to_string());
+                }
+                write!(f, "{} {{ {} }}", path.segments.join("::"), parts.join(", "))
+            }
+            Pat::Or(alts) => write!(f, "{}", join(alts, " | ")),
+        }
+    }
+}
+
+fn join(items: &[impl fmt::Display], separator: &str) -> String {
+    items
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// An arm of a `match`: its pattern and whether it has an `if` guard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Arm {
+    pub pat: Pat,
+    pub guard: bool,
+}
+
+/// A `match` on a variable, with the type of the variable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    /// The function the `match` is in
+    pub function: String,
+    pub scrutinee: Ident,
+    pub ty: Ty,
+    pub arms: Vec<Arm>,
+}
+
+/// The enums, structs and `match` expressions of a Rust file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatchFile {
+    pub adts: Vec<Adt>,
+    pub matches: Vec<Match>,
+}
+
+impl MatchFile {
+    /// The variants of an enum or struct type, with `Option` and `Result` built in, and
+    /// whether it is an enum
+    pub fn variants(&self, ty: &Ty) -> Option<(bool, Vec<Variant>)> {
+        let Ty::Adt { name, args } = ty else {
+            return None;
+        };
+        let arg = |index: usize| args.get(index).cloned().unwrap_or(Ty::Opaque("_".into()));
+        let variant = |name: &str, fields| Variant {
+            name: name.to_string(),
+            fields,
+        };
+        match name.as_str() {
+            "Option" => Some((
+                true,
+                vec![
+                    variant("None", Fields::Unit),
+                    variant("Some", Fields::Tuple(vec![arg(0)])),
+                ],
+            )),
+            "Result" => Some((
+                true,
+                vec![
+                    variant("Ok", Fields::Tuple(vec![arg(0)])),
+                    variant("Err", Fields::Tuple(vec![arg(1)])),
+                ],
+            )),
+            _ => self
+                .adts
+                .iter()
+                .find(|adt| adt.name == *name)
+                .map(|adt| (adt.is_enum, adt.variants.clone())),
+        }
+    }
+
+    fn is_adt(&self, name: &str) -> bool {
+        matches!(name, "Option" | "Result") || self.adts.iter().any(|adt| adt.name == name)
+    }
+}
+
+/// Reads the enums, structs and `match` expressions of a Rust file
+///
+/// Everything else in a function is skipped, except `let` statements and parameters,
+/// which give variables their types. A variable needs a type annotation unless its value
+/// shows the type, as a literal, a tuple, `Some(..)`, a variant or a struct literal does.
+///
+/// ```
+/// use rust_concepts::toy::patterns::{parse, Ty};
+///
+/// let file = parse("fn main() {\n    let pair = (2, -2);\n    match pair {\n        (x, y) if x == y => {}\n        _ => {}\n    }\n}\n").unwrap();
+/// let found = &file.matches[0];
+/// assert_eq!(found.ty, Ty::Tuple(vec![Ty::Int("i32"), Ty::Int("i32")]));
+/// assert_eq!(found.arms[0].pat.to_string(), "(x, y)");
+/// assert!(found.arms[0].guard);
+/// ```
+pub fn parse(source: &str) -> Result<MatchFile, Diagnostic> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        file: MatchFile::default(),
+        depth: 0,
+    };
+    // Types first, so functions can use types declared after them
+    let mut functions = Vec::new();
+    while parser.pos < parser.tokens.len() {
+        if parser.is_punct("#") {
+            parser.pos += 1;
+            parser.skip_group()?;
+        } else if parser.is_ident("enum") || parser.is_ident("struct") {
+            let adt = parser.adt()?;
+            parser.file.adts.push(adt);
+        } else if parser.is_ident("fn") {
+            functions.push(parser.pos);
+            parser.pos += 1;
+            while !parser.is_punct("{") {
+                parser.next()?;
+            }
+            parser.skip_group()?;
+        } else {
+            return Err(parser.unexpected("an item"));
+        }
+    }
+    for start in functions {
+        parser.pos = start;
+        parser.function()?;
+    }
+    Ok(parser.file)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    file: MatchFile,
+    // How many patterns, types or initializers the parser is inside of
+    depth: usize,
+}
+
+/// How deeply patterns and types may nest before [`parse`] gives up
+///
+/// The reader and the [`usefulness`](super::usefulness) checker recurse once per level, so a
+/// limit keeps a file with thousands of nested `Some(` from overflowing the stack.
+pub const MAX_DEPTH: usize = 128;
+
+impl Parser {
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|token| &token.kind)
+    }
+
+    fn span(&self) -> Span {
+        match self.tokens.get(self.pos).or(self.tokens.last()) {
+            Some(token) => token.span,
+            None => Span {
+                line: 1,
+                column: 1,
+                len: 0,
+            },
+        }
+    }
+
+    fn next(&mut self) -> Result<Token, Diagnostic> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| Diagnostic::new("unexpected end of file", self.span(), ""))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn is_punct(&self, punct: &str) -> bool {
+        matches!(self.peek(), Some(TokenKind::Punct(p)) if *p == punct)
+    }
+
+    fn is_ident(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(TokenKind::Ident(n)) if n == name)
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool {
+        let found = self.is_punct(punct);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn unexpected(&self, expected: &str) -> Diagnostic {
+        let found = match self.peek() {
+            Some(TokenKind::Ident(name)) => format!("`{}`", name),
+            Some(TokenKind::Punct(punct)) => format!("`{}`", punct),
+            Some(TokenKind::Int(value)) => format!("`{}`", value),
+            Some(_) => "a literal".to_string(),
+            None => "the end of the file".to_string(),
+        };
+        Diagnostic::new(
+            format!("expected {}, found {}", expected, found),
+            self.span(),
+            format!("expected {}", expected),
+        )
+    }
+
+    // Runs `parse` one level deeper, or fails past MAX_DEPTH
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Parser) -> Result<T, Diagnostic>,
+    ) -> Result<T, Diagnostic> {
+        if self.depth == MAX_DEPTH {
+            return Err(Diagnostic::new(
+                format!("nested more than {} levels deep", MAX_DEPTH),
+                self.span(),
+                "the match checker stops here",
+            ));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn expect_punct(&mut self, punct: &'static str) -> Result<(), Diagnostic> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{}`", punct)))
+        }
+    }
+
+    fn ident(&mut self) -> Result<Ident, Diagnostic> {
+        match self.peek() {
+            Some(TokenKind::Ident(name)) => {
+                let ident = Ident {
+                    name: name.clone(),
+                    span: self.span(),
+                };
+                self.pos += 1;
+                Ok(ident)
+            }
+            _ => Err(self.unexpected("a name")),
+        }
+    }
+
+    // Skips a `( )`, `[ ]` or `{ }` group, with everything nested in it
+    fn skip_group(&mut self) -> Result<(), Diagnostic> {
+        let mut depth = 0;
+        loop {
+            match self.next()?.kind {
+                TokenKind::Punct("(" | "[" | "{") => depth += 1,
+                TokenKind::Punct(")" | "]" | "}") => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    // Skips tokens up to one of `ends` outside any group, leaving it unread
+    fn skip_until(&mut self, ends: &[&str]) -> Result<(), Diagnostic> {
+        while !ends.iter().any(|end| self.is_punct(end)) {
+            if self.is_punct("(") || self.is_punct("[") || self.is_punct("{") {
+                self.skip_group()?;
+            } else {
+                self.next()?;
+            }
+        }
+        Ok(())
+    }
+
+    // `enum Name { Variant, Variant(A), Variant { a: A } }` or a struct
+    fn adt(&mut self) -> Result<Adt, Diagnostic> {
+        let is_enum = self.ident()?.name == "enum";
+        let name = self.ident()?.name;
+        let mut variants = Vec::new();
+        if is_enum {
+            self.expect_punct("{")?;
+            while !self.eat_punct("}") {
+                while self.eat_punct("#") {
+                    self.skip_group()?;
+                }
+                let variant = self.ident()?.name;
+                let fields = self.fields()?;
+                variants.push(Variant {
+                    name: variant,
+                    fields,
+                });
+                if !self.eat_punct(",") {
+                    self.expect_punct("}")?;
+                    break;
+                }
+            }
+        } else {
+            let fields = self.fields()?;
+            if !matches!(fields, Fields::Named(_)) {
+                self.expect_punct(";")?;
+            }
+            variants.push(Variant {
+                name: name.clone(),
+                fields,
+            });
+        }
+        Ok(Adt {
+            name,
+            is_enum,
+            variants,
+        })
+    }
+
+    fn fields(&mut self) -> Result<Fields, Diagnostic> {
+        if self.eat_punct("(") {
+            let mut types = Vec::new();
+            while !self.eat_punct(")") {
+                types.push(self.ty()?);
+                if !self.eat_punct(",") {
+                    self.expect_punct(")")?;
+                    break;
+                }
+            }
+            Ok(Fields::Tuple(types))
+        } else if self.eat_punct("{") {
+            let mut fields = Vec::new();
+            while !self.eat_punct("}") {
+                if self.is_ident("pub") {
+                    self.pos += 1;
+                }
+                let name = self.ident()?.name;
+                self.expect_punct(":")?;
+                fields.push((name, self.ty()?));
+                if !self.eat_punct(",") {
+                    self.expect_punct("}")?;
+                    break;
+                }
+            }
+            Ok(Fields::Named(fields))
+        } else {
+            Ok(Fields::Unit)
+        }
+    }
+
+    fn ty(&mut self) -> Result<Ty, Diagnostic> {
+        self.nested(Parser::ty_inner)
+    }
+
+    fn ty_inner(&mut self) -> Result<Ty, Diagnostic> {
+        if self.eat_punct("&") {
+            if matches!(self.peek(), Some(TokenKind::Lifetime(_))) {
+                self.pos += 1;
+            }
+            let mutable = self.is_ident("mut");
+            if mutable {
+                self.pos += 1;
+            }
+            let target = self.ty()?;
+            let prefix = if mutable { "&mut " } else { "&" };
+            return Ok(Ty::Opaque(format!("{}{}", prefix, target)));
+        }
+        if self.eat_punct("(") {
+            let mut items = Vec::new();
+            while !self.eat_punct(")") {
+                items.push(self.ty()?);
+                if !self.eat_punct(",") {
+                    self.expect_punct(")")?;
+                    break;
+                }
+            }
+            return Ok(Ty::Tuple(items));
+        }
+        let name = self.ident()?;
+        if let Some((int, _, _)) = INTEGERS.iter().find(|(n, _, _)| *n == name.name) {
+            return Ok(Ty::Int(int));
+        }
+        let mut args = Vec::new();
+        if self.eat_punct("<") {
+            while !self.eat_punct(">") {
+                args.push(self.ty()?);
+                if !self.eat_punct(",") {
+                    self.expect_punct(">")?;
+                    break;
+                }
+            }
+        }
+        Ok(match name.name.as_str() {
+            "bool" => Ty::Bool,
+            _ if self.file.is_adt(&name.name) => Ty::Adt {
+                name: name.name,
+                args,
+            },
+            _ => Ty::Opaque(name.name),
+        })
+    }
+
+    // `fn name(params) -> Ret { body }`, keeping the `match` expressions in the body
+    fn function(&mut self) -> Result<(), Diagnostic> {
+        self.pos += 1;
+        let name = self.ident()?.name;
+        let mut locals: Vec<(String, Ty)> = Vec::new();
+        if self.is_punct("<") {
+            self.skip_until(&["("])?;
+        }
+        self.expect_punct("(")?;
+        while !self.eat_punct(")") {
+            if self.is_ident("mut") {
+                self.pos += 1;
+            }
+            let param = self.ident()?.name;
+            self.expect_punct(":")?;
+            locals.push((param, self.ty()?));
+            if !self.eat_punct(",") {
+                self.expect_punct(")")?;
+                break;
+            }
+        }
+        self.skip_until(&["{"])?;
+        let start = self.pos;
+        self.skip_group()?;
+        let end = self.pos - 1;
+        self.pos = start + 1;
+        while self.pos < end {
+            if self.is_ident("let") {
+                self.local(&mut locals)?;
+            } else if self.is_ident("match") {
+                self.match_expr(&name, &locals)?;
+            } else {
+                self.pos += 1;
+            }
+        }
+        self.pos = end + 1;
+        Ok(())
+    }
+
+    // `let [mut] name[: Type] = value;`, remembering the type of `name` when it's known
+    fn local(&mut self, locals: &mut Vec<(String, Ty)>) -> Result<(), Diagnostic> {
+        self.pos += 1;
+        if self.is_ident("mut") {
+            self.pos += 1;
+        }
+        let simple = matches!(self.peek(), Some(TokenKind::Ident(_)))
+            && matches!(
+                self.tokens.get(self.pos + 1).map(|token| &token.kind),
+                Some(TokenKind::Punct(":" | "=" | ";"))
+            );
+        if !simple {
+            // A destructuring `let`, which the checker leaves alone
+            return Ok(());
+        }
+        let name = self.ident()?.name;
+        let ty = if self.eat_punct(":") {
+            Some(self.ty()?)
+        } else if self.eat_punct("=") {
+            self.infer(locals)
+        } else {
+            None
+        };
+        if let Some(ty) = ty {
+            locals.push((name, ty));
+        }
+        Ok(())
+    }
+
+    // The type of a value whose type shows in how it's written, reading past it
+    fn infer(&mut self, locals: &[(String, Ty)]) -> Option<Ty> {
+        self.nested(|parser| Ok(parser.infer_inner(locals))).ok()?
+    }
+
+    fn infer_inner(&mut self, locals: &[(String, Ty)]) -> Option<Ty> {
+        let token = self.tokens.get(self.pos)?.kind.clone();
+        match token {
+            TokenKind::Int(_) => {
+                self.pos += 1;
+                Some(Ty::Int("i32"))
+            }
+            TokenKind::Punct("-") => {
+                self.pos += 1;
+                self.infer(locals)
+            }
+            TokenKind::Str(_) => {
+                self.pos += 1;
+                Some(Ty::Opaque("&str".into()))
+            }
+            TokenKind::Punct("(") => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                let mut comma = false;
+                while !self.eat_punct(")") {
+                    items.push(self.infer(locals)?);
+                    comma = self.eat_punct(",");
+                    if !comma && !self.is_punct(")") {
+                        return None;
+                    }
+                }
+                if items.len() == 1 && !comma {
+                    return items.pop();
+                }
+                Some(Ty::Tuple(items))
+            }
+            TokenKind::Ident(name) if name == "true" || name == "false" => {
+                self.pos += 1;
+                Some(Ty::Bool)
+            }
+            TokenKind::Ident(_) => {
+                let mut segments = vec![self.ident().ok()?.name];
+                while self.eat_punct("::") {
+                    segments.push(self.ident().ok()?.name);
+                }
+                let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+                match segments.as_slice() {
+                    ["Some"] => {
+                        self.expect_punct("(").ok()?;
+                        let inner = self.infer(locals)?;
+                        self.expect_punct(")").ok()?;
+                        Some(Ty::Adt {
+                            name: "Option".into(),
+                            args: vec![inner],
+                        })
+                    }
+                    ["String", "from"] | ["String", "new"] => Some(Ty::Opaque("String".into())),
+                    [adt, ..] if self.file.is_adt(adt) && !matches!(*adt, "Option" | "Result") => {
+                        Some(Ty::Adt {
+                            name: adt.to_string(),
+                            args: Vec::new(),
+                        })
+                    }
+                    // `x.len()` or `f(x)` has a type of its own
+                    [_] if self.is_punct(".") || self.is_punct("(") => None,
+                    [local] => locals
+                        .iter()
+                        .rev()
+                        .find(|(name, _)| name == local)
+                        .map(|(_, ty)| ty.clone()),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    // `match name { pattern [if guard] => body, ... }`
+    fn match_expr(&mut self, function: &str, locals: &[(String, Ty)]) -> Result<(), Diagnostic> {
+        self.pos += 1;
+        let scrutinee = self.ident()?;
+        if !self.is_punct("{") {
+            return Err(Diagnostic::new(
+                "the match checker only matches on a variable",
+                scrutinee.span,
+                "write the value into a variable with `let` first",
+            ));
+        }
+        let ty = locals
+            .iter()
+            .rev()
+            .find(|(name, _)| *name == scrutinee.name)
+            .map(|(_, ty)| ty.clone())
+            .ok_or_else(|| {
+                Diagnostic::new(
+                    format!("can't tell the type of `{}`", scrutinee.name),
+                    scrutinee.span,
+                    format!(
+                        "give it a type where it's declared: `let {}: Type = ...`",
+                        scrutinee.name
+                    ),
+                )
+            })?;
+        self.pos += 1;
+        let mut arms = Vec::new();
+        while !self.eat_punct("}") {
+            let pat = self.pat()?;
+            let guard = self.is_ident("if");
+            if guard {
+                self.skip_until(&["=>"])?;
+            }
+            self.expect_punct("=>")?;
+            if self.is_punct("{") {
+                self.skip_group()?;
+                self.eat_punct(",");
+            } else {
+                self.skip_until(&[",", "}"])?;
+                self.eat_punct(",");
+            }
+            arms.push(Arm { pat, guard });
+        }
+        self.file.matches.push(Match {
+            function: function.to_string(),
+            scrutinee,
+            ty,
+            arms,
+        });
+        Ok(())
+    }
+
+    // `alt | alt | ...`, with an optional leading `|`
+    fn pat(&mut self) -> Result<Pat, Diagnostic> {
+        self.nested(Parser::pat_inner)
+    }
+
+    fn pat_inner(&mut self) -> Result<Pat, Diagnostic> {
+        self.eat_punct("|");
+        let mut alts = vec![self.alt()?];
+        while self.eat_punct("|") {
+            alts.push(self.alt()?);
+        }
+        Ok(if alts.len() == 1 {
+            alts.remove(0)
+        } else {
+            Pat::Or(alts)
+        })
+    }
+
+    fn alt(&mut self) -> Result<Pat, Diagnostic> {
+        let span = self.span();
+        if self.eat_punct("..") {
+            return Ok(Pat::Rest(span));
+        }
+        if self.is_punct("-")
+            || matches!(self.peek(), Some(TokenKind::Int(_)))
+            || self.is_int_bound()
+        {
+            let lo = self.int()?;
+            if !self.eat_punct("..=") {
+                return Ok(Pat::Int {
+                    value: lo,
+                    span: self.since(span),
+                });
+            }
+            let hi = self.int()?;
+            return Ok(Pat::Range {
+                lo,
+                hi,
+                span: self.since(span),
+            });
+        }
+        if let Some(TokenKind::Str(text)) = self.peek() {
+            let text = text.clone();
+            self.pos += 1;
+            return Ok(Pat::Str { text, span });
+        }
+        if self.eat_punct("(") {
+            let mut items = Vec::new();
+            let mut comma = false;
+            while !self.eat_punct(")") {
+                items.push(self.pat()?);
+                comma = self.eat_punct(",");
+                if !comma {
+                    self.expect_punct(")")?;
+                    break;
+                }
+            }
+            // `(p)` is just `p` in parentheses
+            if items.len() == 1 && !comma && !matches!(items[0], Pat::Rest(_)) {
+                return Ok(items.remove(0));
+            }
+            return Ok(Pat::Tuple {
+                items,
+                span: self.since(span),
+            });
+        }
+        for keyword in ["ref", "mut"] {
+            if self.is_ident(keyword) {
+                self.pos += 1;
+            }
+        }
+        let first = self.ident()?;
+        match first.name.as_str() {
+            "_" => return Ok(Pat::Wild(first.span)),
+            "true" | "false" => {
+                return Ok(Pat::Bool {
+                    value: first.name == "true",
+                    span,
+                })
+            }
+            _ => {}
+        }
+        let mut segments = vec![first.name.clone()];
+        while self.eat_punct("::") {
+            segments.push(self.ident()?.name);
+        }
+        let path = Path {
+            segments,
+            span: self.since(span),
+        };
+        if self.eat_punct("(") {
+            let mut items = Vec::new();
+            while !self.eat_punct(")") {
+                items.push(self.pat()?);
+                if !self.eat_punct(",") {
+                    self.expect_punct(")")?;
+                    break;
+                }
+            }
+            return Ok(Pat::TupleVariant { path, items });
+        }
+        if self.eat_punct("{") {
+            let mut fields = Vec::new();
+            let mut rest = false;
+            while !self.eat_punct("}") {
+                if self.eat_punct("..") {
+                    rest = true;
+                    self.expect_punct("}")?;
+                    break;
+                }
+                for keyword in ["ref", "mut"] {
+                    if self.is_ident(keyword) {
+                        self.pos += 1;
+                    }
+                }
+                let field = self.ident()?;
+                let pat = if self.eat_punct(":") {
+                    self.pat()?
+                } else {
+                    Pat::Binding(field.clone())
+                };
+                fields.push((field, pat));
+                if !self.eat_punct(",") {
+                    self.expect_punct("}")?;
+                    break;
+                }
+            }
+            return Ok(Pat::Struct { path, fields, rest });
+        }
+        // A lone lowercase name binds; anything else names a unit variant or struct
+        let binds = path.segments.len() == 1 && !first.name.starts_with(char::is_uppercase);
+        Ok(if binds {
+            Pat::Binding(first)
+        } else {
+            Pat::Path(path)
+        })
+    }
+
+    // Whether the next tokens are `i32::MIN`, `u8::MAX` and so on
+    fn is_int_bound(&self) -> bool {
+        let (Some(TokenKind::Ident(name)), Some(TokenKind::Punct("::"))) = (
+            self.peek(),
+            self.tokens.get(self.pos + 1).map(|token| &token.kind),
+        ) else {
+            return false;
+        };
+        INTEGERS.iter().any(|(ty, _, _)| ty == name)
+    }
+
+    // An integer literal, possibly negative, or an integer type's `MIN` or `MAX`
+    //
+    // The sign is applied in i128, so `-9223372036854775808` reads as `i64::MIN`.
+    fn int(&mut self) -> Result<i128, Diagnostic> {
+        if self.is_int_bound() {
+            let ty = self.ident()?;
+            self.expect_punct("::")?;
+            let bound = self.ident()?;
+            let (_, min, max) = INTEGERS
+                .iter()
+                .find(|(name, _, _)| *name == ty.name)
+                .expect("is_int_bound checked the type");
+            return match bound.name.as_str() {
+                "MIN" => Ok(*min),
+                "MAX" => Ok(*max),
+                _ => Err(Diagnostic::new(
+                    format!("expected `MIN` or `MAX`, found `{}`", bound.name),
+                    bound.span,
+                    "",
+                )),
+            };
+        }
+        let negative = self.eat_punct("-");
+        match self.peek() {
+            Some(TokenKind::Int(value)) => {
+                let value = i128::from(*value);
+                self.pos += 1;
+                Ok(if negative { -value } else { value })
+            }
+            _ => Err(self.unexpected("an integer")),
+        }
+    }
+
+    // The span from `start` to the end of the token just read, when both are on one line
+    fn since(&self, start: Span) -> Span {
+        let end = self.tokens[self.pos - 1].span;
+        if end.line != start.line {
+            return start;
+        }
+        Span {
+            len: end.column + end.len - start.column,
+            ..start
+        }
+    }
+}
@@ -0,0 +1,418 @@
+use super::ast::{Block, Expr, Ident, Program, Stmt, Type};
+use super::borrowck::{self, Loan};
+use super::ir::{self, Action, Body};
+use super::parser::parse;
+use super::{Diagnostic, Span};
+
+/// One row of a timeline: the lines a variable, a borrow or a lifetime covers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bar {
+    /// The variable's name, the borrow such as "&string1", or the lifetime such as "'a"
+    pub label: String,
+    /// The first line the bar covers
+    pub first: usize,
+    /// The last line the bar covers
+    pub last: usize,
+    /// What the bar is for, printed after it
+    pub note: String,
+}
+
+/// The rows drawn under one function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timeline {
+    pub function: String,
+    /// The line of the `fn`
+    pub first: usize,
+    /// The line of the function's closing brace
+    pub last: usize,
+    /// The variables' scopes, then the borrows kept in variables, then the lifetimes
+    /// they were given
+    pub bars: Vec<Bar>,
+}
+
+/// The timelines of a toy program and the borrow errors found in it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Regions {
+    /// One timeline per function that declares variables
+    pub timelines: Vec<Timeline>,
+    pub errors: Vec<Diagnostic>,
+}
+
+impl Regions {
+    /// Renders each function's source lines, then its bars over the line numbers, then
+    /// the errors
+    ///
+    /// ```
+    /// use rust_concepts::toy::regions;
+    ///
+    /// let source = "fn main() {\n    let s = String::from(\"hi\");\n    let r = &s;\n    println!(\"{}\", r);\n}\n";
+    /// let rendered = regions::run(source).render("demo.rs", source);
+    /// assert!(rendered.contains("\n     1  2  3  4  5\ns     [==========]\nr        [=======]\n"));
+    /// assert!(rendered.contains("\n&s       [====]     held by r\n"));
+    /// ```
+    pub fn render(&self, name: &str, source: &str) -> String {
+        let lines: Vec<&str> = source.lines().collect();
+        let mut text = String::new();
+        for timeline in &self.timelines {
+            text += &format!("Regions in `{}`:\n\n", timeline.function);
+            let gutter = timeline.last.to_string().len();
+            for number in timeline.first..=timeline.last {
+                let line = lines.get(number - 1).copied().unwrap_or("");
+                text += format!("{:>gutter$} | {}", number, line).trim_end();
+                text += "\n";
+            }
+            text += "\n";
+            let width = timeline
+                .bars
+                .iter()
+                .map(|bar| bar.label.chars().count())
+                .max()
+                .unwrap_or(0);
+            // Each line gets a cell wide enough for its number and a space
+            let cell = (gutter + 1).max(3);
+            let mut header = " ".repeat(width + 1);
+            for number in timeline.first..=timeline.last {
+                header += &format!("{:>cell$}", number);
+            }
+            text += &header;
+            text += "\n";
+            for bar in &timeline.bars {
+                let mut row = format!("{:<width$} ", bar.label);
+                for number in timeline.first..=timeline.last {
+                    row += &draw_cell(bar, number, cell);
+                }
+                row += "  ";
+                row += &bar.note;
+                text += row.trim_end();
+                text += "\n";
+            }
+            text += "\n";
+        }
+        if self.errors.is_empty() {
+            text += "No borrow conflicts.\n";
+        }
+        for error in &self.errors {
+            text += &error.render(name, source);
+        }
+        text
+    }
+}
+
+// The part of a bar over one line: `[==` where it starts, `==]` where it ends, `===` in
+// between and blanks outside
+fn draw_cell(bar: &Bar, number: usize, cell: usize) -> String {
+    if number < bar.first || number > bar.last {
+        return " ".repeat(cell);
+    }
+    let mut text = "=".repeat(cell);
+    if number == bar.first {
+        text.replace_range(0..1, "[");
+    }
+    if number == bar.last {
+        text.replace_range(cell - 1.., "]");
+    }
+    text
+}
+
+/// Parses a toy program and draws where its variables live and its borrows and lifetimes
+/// hold
+///
+/// The bar of a lifetime such as `'a` covers every borrow it was given at one call or
+/// struct literal, and its note says which lines it has to stay within: the lines where
+/// all the borrowed variables are alive. The region of `'a` in `longest(&string1,
+/// &string2)` can't outlast the shorter-lived of the two:
+///
+/// ```
+/// use rust_concepts::toy::regions;
+///
+/// let source = "fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {\n    x\n}\n\nfn main() {\n    let string1 = String::from(\"long\");\n    {\n        let string2 = String::from(\"xyz\");\n        let result = longest(&string1, &string2);\n        println!(\"{}\", result);\n    }\n}\n";
+/// let regions = regions::run(source);
+/// let a = regions.timelines[0].bars.iter().find(|bar| bar.label == "'a").unwrap();
+/// assert_eq!((a.first, a.last), (9, 10));
+/// assert_eq!(a.note, "'a of `longest`, within string1 and string2 (lines 8-11)");
+/// ```
+pub fn run(source: &str) -> Regions {
+    match parse(source) {
+        Ok(program) => draw(&program),
+        Err(error) => Regions {
+            timelines: Vec::new(),
+            errors: vec![error],
+        },
+    }
+}
+
+/// Draws the timelines of a parsed toy program
+pub fn draw(program: &Program) -> Regions {
+    let check = borrowck::check(program);
+    let mut timelines = Vec::new();
+    for function in &program.functions {
+        let Ok(body) = ir::lower_function(program, function) else {
+            continue;
+        };
+        let loans: Vec<&Loan> = check
+            .loans
+            .iter()
+            .filter(|loan| loan.function == body.function)
+            .collect();
+        let mut bars = scopes(&body);
+        if bars.is_empty() {
+            continue;
+        }
+        let mut bindings = Vec::new();
+        block_bindings(program, &function.body, &mut bindings);
+        let borrows = loans
+            .iter()
+            .filter_map(|loan| Some((loan, loan.holder.as_ref()?)))
+            .map(|(loan, holder)| {
+                let (first, last) = region(loan);
+                Bar {
+                    label: describe(loan),
+                    first,
+                    last,
+                    note: format!("held by {}", holder),
+                }
+            })
+            .collect::<Vec<_>>();
+        let lifetimes = bindings
+            .iter()
+            .filter_map(|binding| lifetime_bar(binding, &loans, &bars))
+            .collect::<Vec<_>>();
+        bars.extend(borrows);
+        bars.extend(lifetimes);
+        bars.extend(statics(
+            &body,
+            function.name.span.line,
+            function.body.close.line,
+        ));
+        timelines.push(Timeline {
+            function: body.function.clone(),
+            first: function.name.span.line,
+            last: function.body.close.line,
+            bars,
+        });
+    }
+    Regions {
+        timelines,
+        errors: check.errors,
+    }
+}
+
+// The scope of every `let` variable: from its declaration to the brace that drops it
+fn scopes(body: &Body) -> Vec<Bar> {
+    let mut bars = Vec::new();
+    for (id, local) in body.locals.iter().enumerate().skip(body.params + 1) {
+        let Some(name) = &local.name else {
+            continue;
+        };
+        let dead = body
+            .points
+            .iter()
+            .find(|point| point.action == Action::StorageDead(id));
+        if let Some(dead) = dead {
+            bars.push(Bar {
+                label: name.clone(),
+                first: local.span.line,
+                last: dead.span.line,
+                note: String::new(),
+            });
+        }
+    }
+    bars
+}
+
+// A `'static` bar over the whole function for the variables that hold a string literal
+fn statics(body: &Body, first: usize, last: usize) -> Option<Bar> {
+    let holders: Vec<&str> = body
+        .locals
+        .iter()
+        .skip(body.params + 1)
+        .filter(|local| {
+            matches!(&local.ty, Type::Ref { lifetime: Some(lifetime), .. } if lifetime == "static")
+        })
+        .filter_map(|local| local.name.as_deref())
+        .collect();
+    if holders.is_empty() {
+        return None;
+    }
+    Some(Bar {
+        label: "'static".to_string(),
+        first,
+        last,
+        note: format!(
+            "held by {}, and outlives every variable",
+            holders.join(" and ")
+        ),
+    })
+}
+
+// The lines a loan holds: from the borrow to the last use of the reference
+fn region(loan: &Loan) -> (usize, usize) {
+    let first = loan.span.line;
+    let last = loan.last_use.map_or(first, |span| span.line.max(first));
+    (first, last)
+}
+
+fn describe(loan: &Loan) -> String {
+    if loan.mutable {
+        format!("&mut {}", loan.place)
+    } else {
+        format!("&{}", loan.place)
+    }
+}
+
+// A lifetime parameter given borrows at a call or struct literal, such as `'a` of
+// `longest` given `&string1` and `&string2`
+struct Binding {
+    lifetime: String,
+    // "`longest`" or "`Point`"
+    owner: String,
+    // The `&place` expressions it was given
+    borrows: Vec<Span>,
+}
+
+// The bar of a lifetime: the union of the regions of its loans, noting the lines where every
+// borrowed variable is alive
+fn lifetime_bar(binding: &Binding, loans: &[&Loan], scopes: &[Bar]) -> Option<Bar> {
+    let given: Vec<&&Loan> = loans
+        .iter()
+        .filter(|loan| binding.borrows.contains(&loan.span))
+        .collect();
+    let first = given.iter().map(|loan| region(loan).0).min()?;
+    let last = given.iter().map(|loan| region(loan).1).max()?;
+    let mut places: Vec<&str> = Vec::new();
+    let mut within: Option<(usize, usize)> = None;
+    for loan in &given {
+        let scope = scopes.iter().find(|scope| {
+            scope.label == loan.place
+                && scope.first <= loan.span.line
+                && loan.span.line <= scope.last
+        });
+        if let Some(scope) = scope {
+            if !places.contains(&loan.place.as_str()) {
+                places.push(&loan.place);
+            }
+            within = Some(match within {
+                Some((start, end)) => (start.max(scope.first), end.min(scope.last)),
+                None => (scope.first, scope.last),
+            });
+        }
+    }
+    let mut note = format!("'{} of {}", binding.lifetime, binding.owner);
+    if let Some((start, end)) = within {
+        note += &format!(
+            ", within {} (lines {}-{})",
+            places.join(" and "),
+            start,
+            end
+        );
+        if last > end {
+            note += &format!(", but needed until line {}", last);
+        }
+    }
+    Some(Bar {
+        label: format!("'{}", binding.lifetime),
+        first,
+        last,
+        note,
+    })
+}
+
+fn block_bindings(program: &Program, block: &Block, out: &mut Vec<Binding>) {
+    for stmt in &block.stmts {
+        match stmt {
+            Stmt::Let { value, .. } => {
+                if let Some(value) = value {
+                    bindings(program, value, out);
+                }
+            }
+            Stmt::Assign { value, .. } | Stmt::Expr(value) => bindings(program, value, out),
+            Stmt::Block(inner) => block_bindings(program, inner, out),
+        }
+    }
+    if let Some(tail) = &block.tail {
+        bindings(program, tail, out);
+    }
+}
+
+// Finds the calls and struct literals in an expression that give lifetime parameters a
+// borrow
+fn bindings(program: &Program, expr: &Expr, out: &mut Vec<Binding>) {
+    match expr {
+        Expr::Call { name, args } => {
+            if let Some(function) = program.function(&name.name) {
+                let params = function.params.iter().map(|param| &param.ty);
+                bind(
+                    &function.lifetimes,
+                    format!("`{}`", name.name),
+                    params.zip(args),
+                    out,
+                );
+            }
+            for arg in args {
+                bindings(program, arg, out);
+            }
+        }
+        Expr::Struct { name, fields, .. } => {
+            if let Some(def) = program.find_struct(&name.name) {
+                let given = fields
+                    .iter()
+                    .filter_map(|(field, value)| Some((&def.field(&field.name)?.ty, value)));
+                bind(&def.lifetimes, format!("`{}`", name.name), given, out);
+            }
+            for (_, value) in fields {
+                bindings(program, value, out);
+            }
+        }
+        Expr::Method { args, .. } | Expr::Print { args, .. } => {
+            for arg in args {
+                bindings(program, arg, out);
+            }
+        }
+        Expr::Compare { lhs, rhs, .. } => {
+            bindings(program, lhs, out);
+            bindings(program, rhs, out);
+        }
+        Expr::If {
+            cond,
+            then,
+            otherwise,
+            ..
+        } => {
+            bindings(program, cond, out);
+            block_bindings(program, then, out);
+            if let Some(otherwise) = otherwise {
+                block_bindings(program, otherwise, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+// Gives each lifetime the `&place` arguments whose parameter or field type uses it
+fn bind<'e>(
+    lifetimes: &[Ident],
+    owner: String,
+    given: impl Iterator<Item = (&'e Type, &'e Expr)>,
+    out: &mut Vec<Binding>,
+) {
+    let given: Vec<(&Type, &Expr)> = given.collect();
+    for lifetime in lifetimes {
+        let borrows: Vec<Span> = given
+            .iter()
+            .filter(
+                |(ty, _)| matches!(ty, Type::Ref { lifetime: Some(l), .. } if *l == lifetime.name),
+            )
+            .filter_map(|(_, value)| match value {
+                Expr::Ref { span, .. } => Some(*span),
+                _ => None,
+            })
+            .collect();
+        if !borrows.is_empty() {
+            out.push(Binding {
+                lifetime: lifetime.name.clone(),
+                owner: owner.clone(),
+                borrows,
+            });
+        }
+    }
+}
@@ -0,0 +1,933 @@
+use super::patterns::{self, Fields, Match, MatchFile, Pat, Path, Ty};
+use super::{Diagnostic, Span};
+
+/// What the checker found about one arm of a `match`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArmReport {
+    /// The arm's pattern, as written
+    pub pattern: String,
+    /// Whether the arm has an `if` guard, which keeps it from covering anything
+    pub guard: bool,
+    /// Whether some value reaches the arm
+    pub reachable: bool,
+    /// The alternatives of an or-pattern that no value reaches, though others do
+    pub unreachable: Vec<String>,
+    pub span: Span,
+}
+
+/// What the checker found about one `match`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchReport {
+    /// The function the `match` is in
+    pub function: String,
+    pub scrutinee: String,
+    pub ty: Ty,
+    pub span: Span,
+    pub arms: Vec<ArmReport>,
+    /// Patterns for the values no arm matches, such as `Message::Write(_)`; empty when
+    /// the `match` is exhaustive
+    pub missing: Vec<String>,
+}
+
+/// The reports on every `match` of a file, with its errors and its unreachable arms
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatchCheck {
+    pub matches: Vec<MatchReport>,
+    pub errors: Vec<Diagnostic>,
+    pub warnings: Vec<Diagnostic>,
+}
+
+impl MatchCheck {
+    /// Renders a table of each `match`'s arms, then the errors and warnings
+    ///
+    /// ```
+    /// use rust_concepts::toy::usefulness;
+    ///
+    /// let source = "fn main() {\n    let n: u8 = 3;\n    match n {\n        0 => {}\n        1..=9 => {}\n    }\n}\n";
+    /// let rendered = usefulness::run(source).render("demo.rs", source);
+    /// assert!(rendered.starts_with("`match n` in `main`, where n: u8\n  0      reachable\n  1..=9  reachable\n  Not covered: `10..=u8::MAX`\n"));
+    /// assert!(rendered.contains("error[E0004]: non-exhaustive patterns: `10..=u8::MAX` not covered\n"));
+    /// ```
+    pub fn render(&self, name: &str, source: &str) -> String {
+        let mut text = String::new();
+        for report in &self.matches {
+            text += &format!(
+                "`match {}` in `{}`, where {}: {}\n",
+                report.scrutinee, report.function, report.scrutinee, report.ty
+            );
+            let patterns: Vec<String> = report
+                .arms
+                .iter()
+                .map(|arm| match arm.guard {
+                    true => format!("{} if ..", arm.pattern),
+                    false => arm.pattern.clone(),
+                })
+                .collect();
+            let width = patterns
+                .iter()
+                .map(|p| p.chars().count())
+                .max()
+                .unwrap_or(0);
+            for (arm, pattern) in report.arms.iter().zip(&patterns) {
+                let status = if !arm.reachable {
+                    "unreachable".to_string()
+                } else if arm.unreachable.is_empty() {
+                    "reachable".to_string()
+                } else {
+                    format!("reachable, but not by {}", quoted(&arm.unreachable))
+                };
+                text += &format!("  {:<width$}  {}\n", pattern, status);
+            }
+            if report.missing.is_empty() {
+                text += "  Every value is covered.\n";
+            } else {
+                text += &format!("  Not covered: {}\n", quoted(&report.missing));
+            }
+            text += "\n";
+        }
+        if self.errors.is_empty() && self.warnings.is_empty() {
+            text += "Every match is exhaustive and every arm is reachable.\n";
+        }
+        for error in &self.errors {
+            text += &error.render(name, source);
+        }
+        for warning in &self.warnings {
+            text += &warning.render_warning(name, source);
+        }
+        text
+    }
+}
+
+// `a`, `b` and `c`, listing at most three and counting the rest
+fn quoted(items: &[String]) -> String {
+    let mut shown: Vec<String> = items.iter().take(3).map(|i| format!("`{}`", i)).collect();
+    if items.len() > 3 {
+        shown.push(format!("{} more", items.len() - 3));
+    }
+    match shown.split_last() {
+        Some((last, rest)) if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+        _ => shown.concat(),
+    }
+}
+
+/// Reads the `match` expressions of a Rust file and checks each one for values no arm
+/// matches and for arms no value reaches
+///
+/// ```
+/// use rust_concepts::toy::usefulness;
+///
+/// let check = usefulness::run("fn main() {\n    let x = Some(5);\n    match x {\n        Some(0) => {}\n        Some(_) | Some(1) => {}\n    }\n}\n");
+/// assert_eq!(check.matches[0].missing, ["None"]);
+/// assert_eq!(check.matches[0].arms[1].unreachable, ["Some(1)"]);
+/// assert_eq!(check.errors[0].code, Some("E0004"));
+/// assert_eq!(check.warnings[0].message, "unreachable pattern");
+/// ```
+pub fn run(source: &str) -> MatchCheck {
+    match patterns::parse(source) {
+        Ok(file) => check(&file),
+        Err(error) => MatchCheck {
+            errors: vec![error],
+            ..MatchCheck::default()
+        },
+    }
+}
+
+/// Checks every `match` of a parsed file
+///
+/// A value is covered when an arm without a guard matches it. Each arm is checked
+/// against the unguarded arms above it: it's unreachable when they already match every
+/// value it does. The `match` is exhaustive when a `_` after the last arm would be
+/// unreachable; otherwise the values that would reach it are the ones not covered.
+pub fn check(file: &MatchFile) -> MatchCheck {
+    let cx = Cx { file };
+    let mut result = MatchCheck::default();
+    for found in &file.matches {
+        let mut arms = Vec::new();
+        let mut errors = Vec::new();
+        for arm in &found.arms {
+            match cx.lower(&arm.pat, &found.ty) {
+                Ok(lowered) => arms.push(lowered),
+                Err(error) => errors.push(error),
+            }
+        }
+        if !errors.is_empty() {
+            // The arms must type-check before their coverage means anything
+            result.errors.extend(errors);
+            continue;
+        }
+        let report = cx.report(found, &arms, &mut result);
+        result.matches.push(report);
+    }
+    result
+}
+
+// A constructor: what a pattern's outermost part matches
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Ctor {
+    // `_` and bindings, which match anything
+    Wild,
+    // An or-pattern, whose fields are its alternatives
+    Or,
+    // The variant of an enum, by its index
+    Variant(usize),
+    // The integers from the first to the second, inclusive; a literal is a range of one
+    Range(i128, i128),
+    Bool(bool),
+    // The one constructor of a tuple or struct
+    Single,
+    Str(String),
+}
+
+// A pattern after checking it against its type: a constructor and the patterns of its
+// fields, one per field of the type
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Lowered {
+    ctor: Ctor,
+    fields: Vec<Lowered>,
+}
+
+static WILD: Lowered = Lowered {
+    ctor: Ctor::Wild,
+    fields: Vec::new(),
+};
+
+// A value, described as a pattern, that a list of patterns doesn't match
+#[derive(Debug, Clone)]
+struct Witness {
+    ctor: Ctor,
+    fields: Vec<Witness>,
+}
+
+impl Witness {
+    fn wild() -> Witness {
+        Witness {
+            ctor: Ctor::Wild,
+            fields: Vec::new(),
+        }
+    }
+}
+
+// One row of the pattern matrix: a pattern for each column
+type Row<'p> = Vec<&'p Lowered>;
+
+struct Cx<'f> {
+    file: &'f MatchFile,
+}
+
+impl Cx<'_> {
+    fn report(&self, found: &Match, arms: &[Lowered], result: &mut MatchCheck) -> MatchReport {
+        let tys = [found.ty.clone()];
+        let mut matrix: Vec<Row> = Vec::new();
+        let mut reports = Vec::new();
+        for (arm, lowered) in found.arms.iter().zip(arms) {
+            let alternatives: Vec<(&Pat, &Lowered)> = match (&arm.pat, &lowered.ctor) {
+                (Pat::Or(pats), Ctor::Or) => pats.iter().zip(&lowered.fields).collect(),
+                _ => vec![(&arm.pat, lowered)],
+            };
+            // An alternative is also checked against the ones before it in the same arm
+            let mut above = matrix.clone();
+            let mut dead: Vec<&Pat> = Vec::new();
+            for (pat, alt) in &alternatives {
+                if self.useful(&above, &vec![*alt], &tys, false).is_empty() {
+                    dead.push(pat);
+                }
+                above.push(vec![*alt]);
+            }
+            let reachable = dead.len() < alternatives.len();
+            if !reachable {
+                result.warnings.push(unreachable(arm.pat.span()));
+            } else {
+                result
+                    .warnings
+                    .extend(dead.iter().map(|pat| unreachable(pat.span())));
+            }
+            reports.push(ArmReport {
+                pattern: arm.pat.to_string(),
+                guard: arm.guard,
+                reachable,
+                unreachable: match reachable {
+                    true => dead.iter().map(|pat| pat.to_string()).collect(),
+                    false => Vec::new(),
+                },
+                span: arm.pat.span(),
+            });
+            if !arm.guard {
+                matrix.push(vec![lowered]);
+            }
+        }
+        let mut missing: Vec<String> = Vec::new();
+        for witness in self.useful(&matrix, &vec![&WILD], &tys, true) {
+            let text = self.show(&witness[0], &found.ty);
+            if !missing.contains(&text) {
+                missing.push(text);
+            }
+        }
+        if !missing.is_empty() {
+            result.errors.push(non_exhaustive(found, &missing));
+        }
+        MatchReport {
+            function: found.function.clone(),
+            scrutinee: found.scrutinee.name.clone(),
+            ty: found.ty.clone(),
+            span: found.scrutinee.span,
+            arms: reports,
+            missing,
+        }
+    }
+
+    // The values `row` matches that no row of `matrix` does, as one witness pattern per
+    // column; empty when `row` is useless after `matrix`. `top` is set for the first column
+    // of the whole `match`, where every missing constructor is listed even if none of the
+    // arms mention any.
+    fn useful<'p>(
+        &self,
+        matrix: &[Row<'p>],
+        row: &Row<'p>,
+        tys: &[Ty],
+        top: bool,
+    ) -> Vec<Vec<Witness>> {
+        let Some((head, rest)) = row.split_first() else {
+            return match matrix.is_empty() {
+                true => vec![Vec::new()],
+                false => Vec::new(),
+            };
+        };
+        if head.ctor == Ctor::Or {
+            return head
+                .fields
+                .iter()
+                .flat_map(|alt| {
+                    let mut row = vec![alt];
+                    row.extend(rest);
+                    self.useful(matrix, &row, tys, top)
+                })
+                .collect();
+        }
+        let matrix = expand(matrix);
+        let ty = &tys[0];
+        let heads: Vec<&Ctor> = matrix
+            .iter()
+            .map(|row| &row[0].ctor)
+            .filter(|ctor| **ctor != Ctor::Wild)
+            .collect();
+        let mut witnesses = Vec::new();
+        let ctors = match &head.ctor {
+            Ctor::Wild => {
+                let all = self.ctors(ty, &heads);
+                let (present, missing): (Vec<Ctor>, Vec<Ctor>) = match all {
+                    Some(all) => all
+                        .into_iter()
+                        .partition(|ctor| heads.iter().any(|head| covers(head, ctor))),
+                    None => (Vec::new(), vec![Ctor::Wild]),
+                };
+                if !missing.is_empty() {
+                    // Only the rows starting with `_` match the missing constructors, so
+                    // the rest of the columns decide
+                    let default: Vec<Row> = matrix
+                        .iter()
+                        .filter(|row| row[0].ctor == Ctor::Wild)
+                        .map(|row| row[1..].to_vec())
+                        .collect();
+                    let firsts: Vec<Witness> =
+                        if missing == [Ctor::Wild] || (!top && heads.is_empty()) {
+                            vec![Witness::wild()]
+                        } else {
+                            merge(missing)
+                                .into_iter()
+                                .map(|ctor| Witness {
+                                    fields: vec![Witness::wild(); self.fields(&ctor, ty).len()],
+                                    ctor,
+                                })
+                                .collect()
+                        };
+                    for witness in self.useful(&default, &rest.to_vec(), &tys[1..], false) {
+                        witnesses.extend(firsts.iter().map(|first| {
+                            let mut row = vec![first.clone()];
+                            row.extend(witness.iter().cloned());
+                            row
+                        }));
+                    }
+                }
+                present
+            }
+            Ctor::Range(lo, hi) => intervals(*lo, *hi, &heads),
+            ctor => vec![ctor.clone()],
+        };
+        for ctor in ctors {
+            let mut field_tys = self.fields(&ctor, ty);
+            let arity = field_tys.len();
+            field_tys.extend_from_slice(&tys[1..]);
+            let specialized: Vec<Row> = matrix
+                .iter()
+                .filter_map(|row| specialize(row, &ctor, arity))
+                .collect();
+            let Some(row) = specialize(row, &ctor, arity) else {
+                continue;
+            };
+            for mut witness in self.useful(&specialized, &row, &field_tys, false) {
+                let rest = witness.split_off(arity);
+                let mut row = vec![Witness {
+                    ctor: ctor.clone(),
+                    fields: witness,
+                }];
+                row.extend(rest);
+                witnesses.push(row);
+            }
+        }
+        // In the order the type declares its constructors, as rustc lists them
+        witnesses.sort_by_key(|witness| order(&witness[0].ctor));
+        witnesses
+    }
+
+    // Every constructor of a type, with the integers split into the ranges the `heads`
+    // agree on; `None` when there are too many to list
+    fn ctors(&self, ty: &Ty, heads: &[&Ctor]) -> Option<Vec<Ctor>> {
+        match ty {
+            Ty::Int(_) => ty.range().map(|(min, max)| intervals(min, max, heads)),
+            Ty::Bool => Some(vec![Ctor::Bool(false), Ctor::Bool(true)]),
+            Ty::Tuple(_) => Some(vec![Ctor::Single]),
+            Ty::Adt { .. } => self
+                .file
+                .variants(ty)
+                .map(|(is_enum, variants)| match is_enum {
+                    true => (0..variants.len()).map(Ctor::Variant).collect(),
+                    false => vec![Ctor::Single],
+                }),
+            Ty::Opaque(_) => None,
+        }
+    }
+
+    // The types of a constructor's fields
+    fn fields(&self, ctor: &Ctor, ty: &Ty) -> Vec<Ty> {
+        match (ctor, ty) {
+            (Ctor::Single, Ty::Tuple(items)) => items.clone(),
+            (Ctor::Single | Ctor::Variant(_), Ty::Adt { .. }) => {
+                let index = match ctor {
+                    Ctor::Variant(index) => *index,
+                    _ => 0,
+                };
+                self.file
+                    .variants(ty)
+                    .and_then(|(_, variants)| variants.get(index).map(|v| v.types()))
+                    .unwrap_or_default()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    // Checks a pattern against the type it matches
+    fn lower(&self, pat: &Pat, ty: &Ty) -> Result<Lowered, Diagnostic> {
+        let leaf = |ctor| Lowered {
+            ctor,
+            fields: Vec::new(),
+        };
+        match (pat, ty) {
+            (Pat::Wild(_) | Pat::Binding(_), _) => Ok(WILD.clone()),
+            (Pat::Or(alts), _) => Ok(Lowered {
+                ctor: Ctor::Or,
+                fields: alts
+                    .iter()
+                    .map(|alt| self.lower(alt, ty))
+                    .collect::<Result<_, _>>()?,
+            }),
+            (Pat::Int { value, span }, Ty::Int(_)) => {
+                in_range(*value, ty, *span)?;
+                Ok(leaf(Ctor::Range(*value, *value)))
+            }
+            (Pat::Range { lo, hi, span }, Ty::Int(_)) => {
+                in_range(*lo, ty, *span)?;
+                in_range(*hi, ty, *span)?;
+                if lo > hi {
+                    return Err(Diagnostic {
+                        code: Some("E0030"),
+                        ..Diagnostic::new(
+                            "lower range bound must be less than or equal to upper",
+                            *span,
+                            "lower bound larger than upper bound",
+                        )
+                    });
+                }
+                Ok(leaf(Ctor::Range(*lo, *hi)))
+            }
+            (Pat::Bool { value, .. }, Ty::Bool) => Ok(leaf(Ctor::Bool(*value))),
+            (Pat::Str { text, .. }, Ty::Opaque(name)) if name == "&str" => {
+                Ok(leaf(Ctor::Str(text.clone())))
+            }
+            (Pat::Tuple { items, span }, Ty::Tuple(types)) => {
+                let items = with_rest(items, types.len(), |found| Diagnostic {
+                    code: Some("E0308"),
+                    ..Diagnostic::new(
+                        "mismatched types",
+                        *span,
+                        format!(
+                            "expected a tuple with {} elements, found one with {} elements",
+                            types.len(),
+                            found
+                        ),
+                    )
+                })?;
+                Ok(Lowered {
+                    ctor: Ctor::Single,
+                    fields: self.lower_fields(&items, types)?,
+                })
+            }
+            (Pat::Rest(span), _) => Err(Diagnostic::new(
+                "`..` patterns are not allowed here",
+                *span,
+                "only tuples and tuple variants can skip their other elements with `..`",
+            )),
+            (Pat::Path(path) | Pat::TupleVariant { path, .. } | Pat::Struct { path, .. }, _) => {
+                self.lower_variant(pat, path, ty)
+            }
+            _ => Err(mismatched(pat, ty)),
+        }
+    }
+
+    fn lower_fields(&self, pats: &[Option<&Pat>], tys: &[Ty]) -> Result<Vec<Lowered>, Diagnostic> {
+        pats.iter()
+            .zip(tys)
+            .map(|(pat, ty)| match pat {
+                Some(pat) => self.lower(pat, ty),
+                None => Ok(WILD.clone()),
+            })
+            .collect()
+    }
+
+    // A pattern naming an enum variant or a struct, such as `Message::Write(text)`,
+    // `None` or `Point { x, .. }`
+    fn lower_variant(&self, pat: &Pat, path: &Path, ty: &Ty) -> Result<Lowered, Diagnostic> {
+        let variants = match ty {
+            Ty::Adt { name, .. } => self.file.variants(ty).map(|found| (name, found)),
+            _ => None,
+        };
+        let in_scope = matches!(path.last(), "Some" | "None" | "Ok" | "Err")
+            || self.file.adts.iter().any(|adt| adt.name == path.last());
+        let Some((name, (is_enum, variants))) = variants else {
+            // A capitalized name that isn't a variant or struct in scope is a binding
+            return match (pat, path.segments.len(), in_scope) {
+                (Pat::Path(_), 1, false) => Ok(WILD.clone()),
+                _ => Err(mismatched(pat, ty)),
+            };
+        };
+        let (owner, variant) = match path.segments.as_slice() {
+            [variant] => (None, variant),
+            [.., owner, variant] => (Some(owner), variant),
+            [] => return Err(mismatched(pat, ty)),
+        };
+        let builtin = matches!(name.as_str(), "Option" | "Result");
+        // A struct is named by itself; a variant by its enum, except for the variants of
+        // `Option` and `Result`, which are always in scope
+        let named_right = match (is_enum, owner) {
+            (false, None) => variant == name,
+            (true, None) => builtin && variants.iter().any(|v| v.name == *variant),
+            (true, Some(owner)) => owner == name,
+            (false, Some(_)) => false,
+        };
+        if !named_right {
+            return match (pat, owner, in_scope) {
+                (Pat::Path(_), None, false) => Ok(WILD.clone()),
+                _ => Err(mismatched(pat, ty)),
+            };
+        }
+        let Some(index) = variants.iter().position(|v| v.name == *variant) else {
+            return Err(Diagnostic {
+                code: Some("E0599"),
+                ..Diagnostic::new(
+                    format!("no variant named `{}` found for enum `{}`", variant, name),
+                    path.span,
+                    format!("variant not found in `{}`", name),
+                )
+            });
+        };
+        let ctor = match is_enum {
+            true => Ctor::Variant(index),
+            false => Ctor::Single,
+        };
+        let found = &variants[index];
+        let shown = path.segments.join("::");
+        let kind = |fields: &Fields| match (fields, is_enum) {
+            (Fields::Unit, true) => "unit variant",
+            (Fields::Unit, false) => "unit struct",
+            (Fields::Tuple(_), true) => "tuple variant",
+            (Fields::Tuple(_), false) => "tuple struct",
+            (Fields::Named(_), true) => "struct variant",
+            (Fields::Named(_), false) => "struct",
+        };
+        let fields = match (pat, &found.fields) {
+            (Pat::Path(_), Fields::Unit) => Vec::new(),
+            (Pat::TupleVariant { items, .. }, Fields::Tuple(types)) => {
+                let items = with_rest(items, types.len(), |count| Diagnostic {
+                    code: Some("E0023"),
+                    ..Diagnostic::new(
+                        format!(
+                            "this pattern has {} field{}, but the corresponding {} has {} field{}",
+                            count,
+                            plural(count),
+                            kind(&found.fields),
+                            types.len(),
+                            plural(types.len())
+                        ),
+                        path.span,
+                        format!(
+                            "expected {} field{}, found {}",
+                            types.len(),
+                            plural(types.len()),
+                            count
+                        ),
+                    )
+                })?;
+                self.lower_fields(&items, types)?
+            }
+            (Pat::Struct { fields, rest, .. }, Fields::Named(declared)) => {
+                let mut items: Vec<Option<&Pat>> = vec![None; declared.len()];
+                for (field, pat) in fields {
+                    let Some(at) = declared.iter().position(|(name, _)| *name == field.name) else {
+                        return Err(Diagnostic {
+                            code: Some("E0026"),
+                            ..Diagnostic::new(
+                                format!(
+                                    "{} `{}` does not have a field named `{}`",
+                                    if is_enum { "variant" } else { "struct" },
+                                    shown,
+                                    field.name
+                                ),
+                                field.span,
+                                format!(
+                                    "{} `{}` does not have this field",
+                                    kind(&found.fields),
+                                    shown
+                                ),
+                            )
+                        });
+                    };
+                    items[at] = Some(pat);
+                }
+                let unmentioned: Vec<String> = declared
+                    .iter()
+                    .zip(&items)
+                    .filter(|(_, item)| item.is_none())
+                    .map(|((name, _), _)| name.clone())
+                    .collect();
+                if !rest && !unmentioned.is_empty() {
+                    return Err(Diagnostic {
+                        code: Some("E0027"),
+                        ..Diagnostic::new(
+                            format!(
+                                "pattern does not mention field{} {}",
+                                plural(unmentioned.len()),
+                                quoted(&unmentioned)
+                            ),
+                            path.span,
+                            format!(
+                                "missing field{} {}",
+                                plural(unmentioned.len()),
+                                quoted(&unmentioned)
+                            ),
+                        )
+                    });
+                }
+                let types: Vec<Ty> = declared.iter().map(|(_, ty)| ty.clone()).collect();
+                self.lower_fields(&items, &types)?
+            }
+            (Pat::Struct { fields, .. }, Fields::Unit) if fields.is_empty() => Vec::new(),
+            (Pat::TupleVariant { .. }, fields) => {
+                return Err(Diagnostic {
+                    code: Some("E0532"),
+                    ..Diagnostic::new(
+                        format!(
+                            "expected tuple struct or tuple variant, found {} `{}`",
+                            kind(fields),
+                            shown
+                        ),
+                        path.span,
+                        "not a tuple struct or tuple variant",
+                    )
+                })
+            }
+            (Pat::Struct { .. }, fields) => {
+                return Err(Diagnostic {
+                    code: Some("E0769"),
+                    ..Diagnostic::new(
+                        format!("{} `{}` written as a struct pattern", kind(fields), shown),
+                        path.span,
+                        format!("write it as `{}(..)`", shown),
+                    )
+                })
+            }
+            (_, fields) => {
+                return Err(Diagnostic {
+                    code: Some("E0533"),
+                    ..Diagnostic::new(
+                        format!(
+                            "expected unit struct, unit variant or constant, found {} `{}`",
+                            kind(fields),
+                            shown
+                        ),
+                        path.span,
+                        "not a unit struct, unit variant or constant",
+                    )
+                })
+            }
+        };
+        Ok(Lowered { ctor, fields })
+    }
+
+    // Renders a witness as a pattern of type `ty`
+    fn show(&self, witness: &Witness, ty: &Ty) -> String {
+        let fields = |tys: &[Ty]| -> Vec<String> {
+            witness
+                .fields
+                .iter()
+                .zip(tys)
+                .map(|(field, ty)| self.show(field, ty))
+                .collect()
+        };
+        match (&witness.ctor, ty) {
+            (Ctor::Range(lo, hi), Ty::Int(name)) => {
+                let (min, max) = ty.range().unwrap_or((*lo, *hi));
+                let bound = |value: i128| match value {
+                    _ if value == min && min != 0 => format!("{}::MIN", name),
+                    _ if value == max => format!("{}::MAX", name),
+                    _ => value.to_string(),
+                };
+                match (*lo == min, *hi == max) {
+                    (true, true) => "_".to_string(),
+                    _ if lo == hi => bound(*lo),
+                    _ => format!("{}..={}", bound(*lo), bound(*hi)),
+                }
+            }
+            (Ctor::Bool(value), _) => value.to_string(),
+            (Ctor::Str(text), _) => format!("{:?}", text),
+            (Ctor::Single, Ty::Tuple(tys)) => match fields(tys).as_slice() {
+                [only] => format!("({},)", only),
+                items => format!("({})", items.join(", ")),
+            },
+            (Ctor::Single | Ctor::Variant(_), Ty::Adt { name, .. }) => {
+                let Some((is_enum, variants)) = self.file.variants(ty) else {
+                    return "_".to_string();
+                };
+                let index = match witness.ctor {
+                    Ctor::Variant(index) => index,
+                    _ => 0,
+                };
+                let variant = &variants[index];
+                let path = match is_enum && !matches!(name.as_str(), "Option" | "Result") {
+                    true => format!("{}::{}", name, variant.name),
+                    false => variant.name.clone(),
+                };
+                let shown = fields(&variant.types());
+                match &variant.fields {
+                    Fields::Unit => path,
+                    Fields::Tuple(_) => format!("{}({})", path, shown.join(", ")),
+                    Fields::Named(declared) => {
+                        let mut parts: Vec<String> = declared
+                            .iter()
+                            .zip(&shown)
+                            .filter(|(_, shown)| *shown != "_")
+                            .map(|((name, _), shown)| format!("{}: {}", name, shown))
+                            .collect();
+                        if parts.len() < declared.len() {
+                            parts.push("..".to_string());
+                        }
+                        format!("{} {{ {} }}", path, parts.join(", "))
+                    }
+                }
+            }
+            _ => "_".to_string(),
+        }
+    }
+}
+
+// Where a constructor comes among the constructors of its type, with `_` last
+fn order(ctor: &Ctor) -> i128 {
+    match ctor {
+        Ctor::Variant(index) => *index as i128,
+        Ctor::Range(lo, _) => *lo,
+        Ctor::Bool(value) => i128::from(*value),
+        Ctor::Wild | Ctor::Or | Ctor::Single | Ctor::Str(_) => i128::MAX,
+    }
+}
+
+// Replaces the or-patterns at the start of rows with one row per alternative
+fn expand<'p>(matrix: &[Row<'p>]) -> Vec<Row<'p>> {
+    let mut rows = Vec::new();
+    for row in matrix {
+        match row.first() {
+            Some(head) if head.ctor == Ctor::Or => {
+                let alternatives: Vec<Row> = head
+                    .fields
+                    .iter()
+                    .map(|alt| {
+                        let mut row = row.clone();
+                        row[0] = alt;
+                        row
+                    })
+                    .collect();
+                rows.extend(expand(&alternatives));
+            }
+            _ => rows.push(row.clone()),
+        }
+    }
+    rows
+}
+
+// The rest of a row whose first pattern matches everything `ctor` builds, with that
+// pattern replaced by its fields; `None` when it matches none of it
+fn specialize<'p>(row: &Row<'p>, ctor: &Ctor, arity: usize) -> Option<Row<'p>> {
+    let (head, rest) = row.split_first()?;
+    let mut specialized: Row = if head.ctor == Ctor::Wild {
+        vec![&WILD; arity]
+    } else if covers(&head.ctor, ctor) {
+        head.fields.iter().collect()
+    } else {
+        return None;
+    };
+    specialized.extend(rest);
+    Some(specialized)
+}
+
+// Whether a pattern starting with `head` matches every value `ctor` builds
+fn covers(head: &Ctor, ctor: &Ctor) -> bool {
+    match (head, ctor) {
+        (Ctor::Range(lo, hi), Ctor::Range(from, to)) => lo <= from && to <= hi,
+        _ => head == ctor,
+    }
+}
+
+// Splits `lo..=hi` at the ends of the ranges in `heads`, so that each piece is either
+// inside or outside each of them
+fn intervals(lo: i128, hi: i128, heads: &[&Ctor]) -> Vec<Ctor> {
+    let mut bounds = vec![lo, hi + 1];
+    for head in heads {
+        if let Ctor::Range(from, to) = head {
+            bounds.extend([*from, to + 1].into_iter().filter(|b| lo < *b && *b <= hi));
+        }
+    }
+    bounds.sort_unstable();
+    bounds.dedup();
+    bounds
+        .windows(2)
+        .map(|pair| Ctor::Range(pair[0], pair[1] - 1))
+        .collect()
+}
+
+// Joins adjacent integer ranges, so `0..=4` and `5..=9` are shown as `0..=9`
+fn merge(ctors: Vec<Ctor>) -> Vec<Ctor> {
+    let mut merged: Vec<Ctor> = Vec::new();
+    for ctor in ctors {
+        match (merged.last_mut(), &ctor) {
+            (Some(Ctor::Range(_, end)), Ctor::Range(lo, hi)) if *end + 1 == *lo => *end = *hi,
+            _ => merged.push(ctor),
+        }
+    }
+    merged
+}
+
+// The elements of a tuple pattern, one per element of the type, with `None` for those
+// skipped by `..`
+fn with_rest(
+    items: &[Pat],
+    arity: usize,
+    wrong_count: impl Fn(usize) -> Diagnostic,
+) -> Result<Vec<Option<&Pat>>, Diagnostic> {
+    let rest = items.iter().position(|item| matches!(item, Pat::Rest(_)));
+    let written = items.len() - usize::from(rest.is_some());
+    match rest {
+        None if written == arity => Ok(items.iter().map(Some).collect()),
+        Some(at) if written <= arity => {
+            let mut expanded: Vec<Option<&Pat>> = items[..at].iter().map(Some).collect();
+            expanded.extend(vec![None; arity - written]);
+            expanded.extend(items[at + 1..].iter().map(Some));
+            if expanded
+                .iter()
+                .flatten()
+                .any(|item| matches!(item, Pat::Rest(_)))
+            {
+                let second = items[at + 1..]
+                    .iter()
+                    .find(|item| matches!(item, Pat::Rest(_)));
+                return Err(Diagnostic::new(
+                    "`..` can only be used once per tuple pattern",
+                    second.map_or(Span::default(), Pat::span),
+                    "can only be used once per tuple pattern",
+                ));
+            }
+            Ok(expanded)
+        }
+        _ => Err(wrong_count(written)),
+    }
+}
+
+fn in_range(value: i128, ty: &Ty, span: Span) -> Result<(), Diagnostic> {
+    match ty.range() {
+        Some((min, max)) if value < min || value > max => Err(Diagnostic::new(
+            format!("literal out of range for `{}`", ty),
+            span,
+            format!(
+                "the literal `{}` does not fit into the type `{}`",
+                value, ty
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+// A pattern whose kind can't match the type, such as `Some(x)` on an `i32`
+fn mismatched(pat: &Pat, ty: &Ty) -> Diagnostic {
+    let found = match pat {
+        Pat::Int { .. } | Pat::Range { .. } => "integer".to_string(),
+        Pat::Bool { .. } => "`bool`".to_string(),
+        Pat::Str { .. } => "`&str`".to_string(),
+        Pat::Tuple { items, .. } => format!("a tuple with {} elements", items.len()),
+        Pat::Path(path) | Pat::TupleVariant { path, .. } | Pat::Struct { path, .. } => {
+            match path.segments.as_slice() {
+                [variant] if matches!(variant.as_str(), "Some" | "None") => {
+                    "`Option<_>`".to_string()
+                }
+                [variant] if matches!(variant.as_str(), "Ok" | "Err") => {
+                    "`Result<_, _>`".to_string()
+                }
+                [name] => format!("`{}`", name),
+                [.., owner, _] => format!("`{}`", owner),
+                [] => "a path".to_string(),
+            }
+        }
+        Pat::Wild(_) | Pat::Binding(_) | Pat::Rest(_) | Pat::Or(_) => "a pattern".to_string(),
+    };
+    Diagnostic {
+        code: Some("E0308"),
+        ..Diagnostic::new(
+            "mismatched types",
+            pat.span(),
+            format!("expected `{}`, found {}", ty, found),
+        )
+    }
+}
+
+fn unreachable(span: Span) -> Diagnostic {
+    Diagnostic::new("unreachable pattern", span, "no value can reach this")
+}
+
+// E0004, pointing at the value being matched
+fn non_exhaustive(found: &Match, missing: &[String]) -> Diagnostic {
+    let noun = match missing.len() {
+        1 => "pattern",
+        _ => "patterns",
+    };
+    Diagnostic {
+        code: Some("E0004"),
+        ..Diagnostic::new(
+            format!("non-exhaustive patterns: {} not covered", quoted(missing)),
+            found.scrutinee.span,
+            format!("{} {} not covered", noun, quoted(missing)),
+        )
+    }
+}
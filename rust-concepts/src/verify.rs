@@ -0,0 +1,258 @@
+use crate::chapter::{Chapter, Example};
+use std::fmt;
+use std::io;
+
+/// A single line of output that a chapter claims to print, taken from a `// Result:` comment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expectation {
+    /// The number of the "Example N" block the comment belongs to (0 if it comes before any example)
+    pub example: u32,
+    /// The 1-based line in the chapter source where the comment was found
+    pub source_line: usize,
+    /// The exact text the example is expected to print
+    pub text: String,
+}
+
+/// A `// Result:` line that doesn't match what the chapter actually printed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub chapter: &'static str,
+    pub example: u32,
+    pub source_line: usize,
+    pub expected: String,
+    /// The printed line that most likely corresponds to the expectation, if any
+    pub actual: Option<String>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ch{} example {} (line {}): expected `{}`, ",
+            self.chapter, self.example, self.source_line, self.expected
+        )?;
+        match &self.actual {
+            Some(actual) => write!(f, "got `{}`", actual),
+            None => write!(f, "got no corresponding output"),
+        }
+    }
+}
+
+/// Extracts every `// Result:` annotation from a chapter's source
+/// Two forms are supported:
+///
+/// ```text
+/// // Result: some text          (a single expected line)
+/// // Result:                    (followed by one expected line per `//` comment line)
+/// ```
+///
+/// ```
+/// use rust_concepts::verify::parse_expectations;
+///
+/// let source = "// Example 2: Greeting\n// Result:\n//   Hello\n//   World\nlet x = 1;\n";
+/// let expectations = parse_expectations(source);
+/// assert_eq!(expectations.len(), 2);
+/// assert_eq!(expectations[0].example, 2);
+/// assert_eq!(expectations[1].text, "  World");
+/// ```
+pub fn parse_expectations(source: &str) -> Vec<Expectation> {
+    let mut expectations = Vec::new();
+    let mut example = 0;
+    let mut lines = source.lines().enumerate().peekable();
+
+    while let Some((index, line)) = lines.next() {
+        let Some(comment) = line.trim().strip_prefix("//") else {
+            continue;
+        };
+        let comment = comment.trim();
+
+        if let Some(rest) = comment.strip_prefix("Example ") {
+            // "Example 4: Ownership and functions" -> 4
+            let number: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+            if let Ok(number) = number.parse() {
+                example = number;
+            }
+        } else if let Some(text) = comment.strip_prefix("Result:") {
+            let text = text.trim();
+            if !text.is_empty() {
+                expectations.push(Expectation {
+                    example,
+                    source_line: index + 1,
+                    text: text.to_string(),
+                });
+                continue;
+            }
+            // The multi-line form runs until the first line that isn't a comment
+            while let Some((index, line)) = lines.peek() {
+                let Some(text) = line.trim().strip_prefix("//") else {
+                    break;
+                };
+                // Only the single space after `//` is dropped, so indented output can be described
+                let text = text.strip_prefix(' ').unwrap_or(text);
+                expectations.push(Expectation {
+                    example,
+                    source_line: index + 1,
+                    text: text.trim_end().to_string(),
+                });
+                lines.next();
+            }
+        }
+    }
+
+    expectations
+}
+
+/// Compares the annotations against the captured output of a chapter
+/// Not every printed line is annotated, so the expectations are aligned with the output
+/// as a longest common subsequence; anything left unaligned is reported as a mismatch
+///
+/// ```
+/// use rust_concepts::verify::{compare, parse_expectations};
+///
+/// let source = "// Example 1: Printing\nprintln!(\"4\");\n// Result: 4\n";
+/// let expected = parse_expectations(source);
+/// assert!(compare("99", &expected, "4\n").is_empty());
+/// let mismatches = compare("99", &expected, "5\n");
+/// assert_eq!(mismatches[0].actual.as_deref(), Some("5"));
+/// ```
+pub fn compare(chapter: &'static str, expected: &[Expectation], output: &str) -> Vec<Mismatch> {
+    let actual: Vec<&str> = output.lines().collect();
+    let (n, m) = (expected.len(), actual.len());
+
+    // lcs[i][j] is the length of the longest common subsequence of expected[i..] and actual[j..]
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if expected[i].text == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    // Walk the table to find which output line each expectation matched
+    let mut matched = vec![None; n];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if expected[i].text == actual[j] {
+            matched[i] = Some(j);
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    let mut mismatches = Vec::new();
+    for (i, expectation) in expected.iter().enumerate() {
+        if matched[i].is_some() {
+            continue;
+        }
+        // The real output for this expectation must lie between the neighbouring matches,
+        // so pick the unmatched line in that gap that looks most like what was expected
+        let start = matched[..i]
+            .iter()
+            .rev()
+            .flatten()
+            .next()
+            .map_or(0, |j| j + 1);
+        let end = matched[i..].iter().flatten().next().copied().unwrap_or(m);
+        let candidate = actual[start..end]
+            .iter()
+            .filter(|line| !line.is_empty())
+            .max_by_key(|line| common_prefix(line, &expectation.text));
+        mismatches.push(Mismatch {
+            chapter,
+            example: expectation.example,
+            source_line: expectation.source_line,
+            expected: expectation.text.clone(),
+            actual: candidate.map(|line| line.to_string()),
+        });
+    }
+    mismatches
+}
+
+// Counts how many leading characters two strings share
+fn common_prefix(a: &str, b: &str) -> usize {
+    a.chars().zip(b.chars()).take_while(|(x, y)| x == y).count()
+}
+
+/// The result of verifying one chapter
+pub struct Report {
+    pub checked: usize,
+    pub mismatches: Vec<Mismatch>,
+}
+
+/// Runs every example of a chapter on its own and checks it against the chapter's annotations
+/// Annotations that don't belong to any registered example are reported as mismatches too
+///
+/// ```
+/// use rust_concepts::{registry, verify};
+///
+/// let report = verify::verify_chapter(registry::find("01").unwrap()).unwrap();
+/// assert!(report.checked > 0);
+/// assert!(report.mismatches.is_empty());
+/// ```
+pub fn verify_chapter(chapter: &dyn Chapter) -> io::Result<Report> {
+    let expected = parse_expectations(chapter.source());
+    let mut mismatches = Vec::new();
+    for example in chapter.examples() {
+        mismatches.extend(check_example(chapter, example, &expected)?);
+    }
+    for orphan in expected
+        .iter()
+        .filter(|e| chapter.example(e.example).is_none())
+    {
+        mismatches.push(Mismatch {
+            chapter: chapter.id(),
+            example: orphan.example,
+            source_line: orphan.source_line,
+            expected: orphan.text.clone(),
+            actual: None,
+        });
+    }
+    mismatches.sort_by_key(|m| m.source_line);
+    Ok(Report {
+        checked: expected.len(),
+        mismatches,
+    })
+}
+
+/// Runs a single example and checks it against the annotations inside that example
+///
+/// ```
+/// use rust_concepts::{registry, verify};
+///
+/// let borrowing = registry::find("02").unwrap();
+/// let example = borrowing.example(6).unwrap();
+/// let report = verify::verify_example(borrowing, example).unwrap();
+/// assert!(report.mismatches.is_empty());
+/// ```
+pub fn verify_example(chapter: &dyn Chapter, example: &Example) -> io::Result<Report> {
+    let expected = parse_expectations(chapter.source());
+    let mismatches = check_example(chapter, example, &expected)?;
+    Ok(Report {
+        checked: expected.iter().filter(|e| e.example == example.id).count(),
+        mismatches,
+    })
+}
+
+// Captures one example's output and compares it with the expectations carrying its number
+fn check_example(
+    chapter: &dyn Chapter,
+    example: &Example,
+    expected: &[Expectation],
+) -> io::Result<Vec<Mismatch>> {
+    let mut output = Vec::new();
+    (example.run)(&mut output)?;
+    let output = String::from_utf8_lossy(&output);
+    let expected: Vec<Expectation> = expected
+        .iter()
+        .filter(|e| e.example == example.id)
+        .cloned()
+        .collect();
+    Ok(compare(chapter.id(), &expected, &output))
+}
@@ -0,0 +1,510 @@
+// Integration tests for the command-line front end
+// Cargo builds the binary for integration tests and tells us where it is through CARGO_BIN_EXE_<name>
+
+use std::io::Write;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Runs the binary with a fresh data directory, so tests never touch the real progress file
+fn run(args: &[&str]) -> Output {
+    run_in(&data_dir(), args)
+}
+
+// Runs the binary with XDG_DATA_HOME pointing at the given directory
+fn run_in(data_home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_rust-concepts"))
+        .args(args)
+        .env("XDG_DATA_HOME", data_home)
+        .output()
+        .expect("the rust-concepts binary runs")
+}
+
+// Runs the binary with XDG_DATA_HOME pointing at the given directory, feeding it `input` on stdin
+fn run_with_input(data_home: &Path, args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rust-concepts"))
+        .args(args)
+        .env("XDG_DATA_HOME", data_home)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the rust-concepts binary runs");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+// A temporary directory that is deleted when the test is done with it
+struct DataDir(PathBuf);
+
+impl Deref for DataDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for DataDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+// A new, empty directory for each call
+fn data_dir() -> DataDir {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "rust-concepts-cli-{}-{}",
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    DataDir(dir)
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn test_no_arguments_prints_usage() {
+    let output = run(&[]);
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert!(text.starts_with("Please specify a module to run."));
+    assert!(text.contains("cargo run -- 10 (or ch10, or ffi, or unsafe)"));
+    assert!(text.contains("cargo run -- verify [chapter]"));
+}
+
+#[test]
+fn test_chapter_by_alias() {
+    let output = run(&["errors"]);
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert!(text.starts_with("Demonstrating Rust error handling!\n--------------\n"));
+}
+
+#[test]
+fn test_verify_exit_codes() {
+    let output = run(&["verify", "01"]);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "ch01 Ownership: 8 results ok\n");
+
+    // An unknown chapter or an extra argument prints the usage and fails
+    for args in [&["verify", "99"][..], &["verify", "01", "02"]] {
+        let output = run(args);
+        assert!(!output.status.success());
+        assert!(stdout(&output).starts_with("Please specify a module to run."));
+    }
+}
+
+#[test]
+fn test_single_example() {
+    let output = run(&["02", "--example", "6"]);
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert!(text.starts_with("Demonstrating Rust borrowing!\n--------------\nExample 6: "));
+    assert!(text.ends_with("\nLongest string: 10\n"));
+}
+
+#[test]
+fn test_list_examples() {
+    let output = run(&["ownership", "--list"]);
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert!(text.starts_with("ch01 Ownership examples:\n   1. "));
+    assert!(text.contains("\n   5. "));
+}
+
+#[test]
+fn test_unknown_example_fails() {
+    for id in ["99", "six"] {
+        let output = run(&["02", "--example", id]);
+        assert!(!output.status.success());
+        assert_eq!(
+            stdout(&output),
+            format!(
+                "ch02 has no example `{}`, see `cargo run -- 02 --list`\n",
+                id
+            )
+        );
+    }
+}
+
+#[test]
+fn test_break_shows_the_compiler_error() {
+    let output = run(&["01", "--break", "1"]);
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert!(text.contains("Broken variant `use_after_move`:"));
+    assert!(text.contains("error[E0382]: borrow of moved value: `s1`\n --> src/main.rs:6:20"));
+    assert!(text.contains("Why:\n`let s2 = s1;` moves the String"));
+}
+
+#[test]
+fn test_break_without_broken_variant_fails() {
+    let output = run(&["01", "--break", "2"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "ch01 example `2` has no broken variant\nTry one of these examples: 1, 4\n"
+    );
+}
+
+#[test]
+fn test_exercise_list_and_unknown_exercise() {
+    let output = run(&["exercise"]);
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("ch03/longest_word"));
+
+    let output = run(&["exercise", "ch03/no_such_exercise"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "There is no exercise `ch03/no_such_exercise`, see `cargo run -- exercise` for the list\n"
+    );
+}
+
+#[test]
+fn test_exercise_is_graded() {
+    // Whether it passes depends on the learner's work, but the report always starts the same way
+    let output = run(&["exercise", "ch03/longest_word"]);
+    let text = stdout(&output);
+    assert!(
+        text.starts_with("Exercise ch03/longest_word: exercises/ch03_lifetime/longest_word.rs\n")
+    );
+    assert!(text.contains("finds_the_longest_word"));
+}
+
+#[test]
+fn test_progress_is_recorded_and_reset() {
+    let data = data_dir();
+    let output = run_in(&data, &["next"]);
+    assert_eq!(
+        stdout(&output),
+        "Next: ch01 example 1: Move semantics\n      cargo run -- 01 --example 1\n"
+    );
+
+    // Running the whole chapter completes all of its examples, so the exercises come next
+    assert!(run_in(&data, &["01"]).status.success());
+    assert!(run_in(&data, &["02", "--example", "1"]).status.success());
+    let file = std::fs::read_to_string(data.join("rust-concepts/progress.toml")).unwrap();
+    assert!(
+        file.contains("examples = [\"01/1\", \"01/2\", \"01/3\", \"01/4\", \"01/5\", \"02/1\"]\n")
+    );
+
+    let text = stdout(&run_in(&data, &["progress"]));
+    assert!(text.contains("  ch01 Ownership                examples  5/5   exercises 0/2\n"));
+    assert!(text.contains("  ch02 Borrowing                examples  1/8   exercises 0/2\n"));
+    assert!(text.ends_with("Next: exercise ch01/exclaim, edit exercises/ch01_ownership/exclaim.rs\n      cargo run -- exercise ch01/exclaim\n"));
+
+    // Resetting one chapter keeps the others
+    assert_eq!(
+        stdout(&run_in(&data, &["reset", "01"])),
+        "Progress of ch01 Ownership reset\n"
+    );
+    let file = std::fs::read_to_string(data.join("rust-concepts/progress.toml")).unwrap();
+    assert!(file.contains("examples = [\"02/1\"]\n"));
+
+    assert!(stdout(&run_in(&data, &["reset"])).starts_with("Progress reset, removed "));
+    assert_eq!(
+        stdout(&run_in(&data, &["reset"])),
+        "There is no progress to reset\n"
+    );
+}
+
+#[test]
+fn test_broken_progress_file_does_not_stop_lessons() {
+    let data = data_dir();
+    std::fs::create_dir_all(data.join("rust-concepts")).unwrap();
+    std::fs::write(
+        data.join("rust-concepts/progress.toml"),
+        "chapters = oops\n",
+    )
+    .unwrap();
+
+    let output = run_in(&data, &["progress"]);
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("line 1: expected a list"));
+
+    // The lesson still runs; the problem is reported on stderr
+    let output = run_in(&data, &["01", "--example", "1"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("note: could not record progress"));
+}
+
+#[test]
+fn test_broken_quiz_history_is_not_overwritten() {
+    let data = data_dir();
+    let path = data.join("rust-concepts/quiz.toml");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let broken = "\"ch03/x\" = oops\n\"ch03/y\" = { asked = 9, correct = 9, streak = 9 }\n";
+    std::fs::write(&path, broken).unwrap();
+
+    // The quiz still runs; the problem is reported on stderr and the file is kept as it was
+    let output = run_with_input(&data, &["quiz", "03", "--count", "1"], "\n");
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("Question 1 of 1 (ch03, "));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("note: answers won't be saved"));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), broken);
+}
+
+#[test]
+fn test_quiz_asks_a_missed_question_again() {
+    let data = data_dir();
+    let output = run_with_input(&data, &["quiz", "03", "--count", "1"], "not the answer\n");
+    assert!(output.status.success());
+    let first = stdout(&output);
+    assert!(first.starts_with("Question 1 of 1 (ch03, "));
+    assert!(first.contains("Not quite. The answer is: "));
+    assert!(first.ends_with(
+        "Score: 0 of 1\nThe questions you missed will come up first in your next quiz.\n"
+    ));
+
+    // The history makes the missed question come back first, whatever the shuffle
+    let again = stdout(&run_with_input(
+        &data,
+        &["quiz", "03", "--count", "1"],
+        "\n",
+    ));
+    let prompt = |text: &str| text.lines().nth(1).unwrap().to_string();
+    assert_eq!(prompt(&again), prompt(&first));
+    let history = std::fs::read_to_string(data.join("rust-concepts/quiz.toml")).unwrap();
+    assert!(history.contains("{ asked = 2, correct = 0, streak = 0, ease = "));
+}
+
+#[test]
+fn test_quiz_rejects_bad_options() {
+    let output = run_with_input(&data_dir(), &["quiz", "--count", "zero"], "");
+    assert!(!output.status.success());
+    assert!(stdout(&output).starts_with("Please specify a module to run."));
+}
+
+#[test]
+fn test_review_asks_only_due_questions() {
+    let data = data_dir();
+    let output = run_with_input(&data, &["review"], "");
+    assert!(stdout(&output).starts_with("Nothing to review yet."));
+
+    // Day 1 is long past, so this question is due; the other one isn't due for decades
+    let history = data.join("rust-concepts/quiz.toml");
+    std::fs::create_dir_all(history.parent().unwrap()).unwrap();
+    std::fs::write(
+        &history,
+        "\"ch01/copy_integer\" = { asked = 1, correct = 0, streak = 0, ease = 250, interval = 1, due = 1 }\n\
+         \"ch01/move_string\" = { asked = 1, correct = 1, streak = 1, ease = 260, interval = 1, due = 99999 }\n",
+    )
+    .unwrap();
+    let output = stdout(&run_with_input(&data, &["review", "01"], "b\n"));
+    assert!(output.starts_with("Question 1 of 1 (ch01, multiple choice)\n"));
+    assert!(output.contains("Score: "));
+
+    // Answering it schedules it again, so there is nothing left to review today
+    let text = std::fs::read_to_string(&history).unwrap();
+    assert!(text.contains("\"ch01/copy_integer\" = { asked = 2, "));
+    assert!(!text.contains("due = 1 }"));
+    let output = run_with_input(&data, &["review"], "");
+    assert!(stdout(&output).starts_with("Nothing to review today. The next review is due in "));
+}
+
+#[test]
+fn test_export_writes_a_site() {
+    let data = data_dir();
+    let dir = data.join("book");
+    let output = run(&["export", dir.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("Wrote 12 files to "));
+    let index = std::fs::read_to_string(dir.join("index.html")).unwrap();
+    assert!(index.contains("<a href=\"ch01-ownership.html\">Ownership</a>"));
+    assert!(dir.join("ch10-unsafe.html").exists());
+
+    let output = run(&["export", dir.to_str().unwrap(), "--format", "pdf"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_json_output() {
+    let data = data_dir();
+    let output = run_in(&data, &["02", "--format", "json"]);
+    assert!(output.status.success());
+    let document = stdout(&output);
+    assert!(document.starts_with("{\n  \"schema_version\": 1,\n"));
+    assert!(document.contains("\"title\": \"Borrowing with references\""));
+    assert!(document.ends_with("}\n"));
+    // A tool reading the chapter doesn't make progress for the learner
+    assert!(!data.join("rust-concepts/progress.toml").exists());
+
+    let catalog = stdout(&run(&["list", "--format", "json"]));
+    assert!(catalog.contains("\"slug\": \"pattern_matching\""));
+    assert!(stdout(&run(&["list"])).starts_with("ch01 Ownership (5 examples, 2 exercises)\n"));
+
+    assert!(!run(&["02", "--format", "yaml"]).status.success());
+}
+
+#[test]
+fn test_simulate_ownership() {
+    let output = run(&["01", "--simulate", "1"]);
+    assert!(output.status.success());
+    let trace = stdout(&output);
+    assert!(trace.starts_with("Example 1: Move semantics (move_semantics.rs)\n\n4 | let s1 = String::from(\"hello\");\n       s1 owns \"hello\"\n"));
+    assert!(trace.contains("Example 1: Move semantics (use_after_move.rs)"));
+    assert!(trace.contains("error[E0382]: borrow of moved value: `s1`"));
+
+    // Chapters without toy programs say so
+    let output = run(&["05", "--simulate"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "ch05 has no programs for the ownership simulator\n"
+    );
+
+    // A program of your own fails when it uses a moved value
+    let data = data_dir();
+    let file = data.join("mine.rs");
+    std::fs::write(
+        &file,
+        "fn main() {\n    let a = String::from(\"x\");\n    let b = a;\n    let c = a;\n}\n",
+    )
+    .unwrap();
+    let output = run(&["simulate", file.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("error[E0382]: use of moved value: `a`"));
+}
+
+#[test]
+fn test_borrowck() {
+    let output = run(&["02", "--borrowck", "4"]);
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert!(text.starts_with("Example 4: Mutable and immutable references (not simultaneously) (mutable_and_immutable_references.rs)\n\nLoans in `main`:\n  r1 = &s3 at 6:18, live until 7:28\n"));
+    assert!(text.contains("(mutable_while_shared.rs)"));
+    assert!(text.contains(
+        "error[E0502]: cannot borrow `s3` as mutable because it is also borrowed as immutable"
+    ));
+    assert!(text.contains("note: immutable borrow later used here"));
+
+    // The simulator leaves programs with references to the borrow checker
+    let output = run(&["02", "--simulate", "4"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "ch02 has no programs for the ownership simulator in example `4`\n"
+    );
+
+    let data = data_dir();
+    let file = data.join("borrows.rs");
+    std::fs::write(
+        &file,
+        "fn main() {\n    let mut s = String::from(\"x\");\n    let r = &mut s;\n    let t = &mut s;\n    r.push('!');\n}\n",
+    )
+    .unwrap();
+    let output = run(&["borrowck", file.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(stdout(&output)
+        .contains("error[E0499]: cannot borrow `s` as mutable more than once at a time"));
+}
+
+#[test]
+fn test_visualize() {
+    let output = run(&["03", "--visualize", "1"]);
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert!(text.starts_with("Example 1: Basic lifetime annotations (basic_annotations.rs)\n\nRegions in `main`:\n\n11 | fn main() {\n"));
+    assert!(text.contains("'a of `longest`, within string1 and string2 (lines 13-16)"));
+    assert!(text.contains("(result_outlives_input.rs)"));
+    assert!(text.contains("but needed until line 18"));
+    assert!(text.contains("error[E0597]: `string2` does not live long enough"));
+
+    let output = run(&["03", "--visualize", "7"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "ch03 has no programs for the lifetime visualizer in example `7`\n"
+    );
+
+    let data = data_dir();
+    let file = data.join("regions.rs");
+    std::fs::write(
+        &file,
+        "fn main() {\n    let s: &'static str = \"hi\";\n    println!(\"{}\", s);\n}\n",
+    )
+    .unwrap();
+    let output = run(&["visualize", file.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert!(
+        stdout(&output).contains("'static [==========]  held by s, and outlives every variable")
+    );
+}
+
+#[test]
+fn test_matches() {
+    let output = run(&["04", "--matches", "1"]);
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert!(text.starts_with("Example 1: Basic match expression (basic_match.rs)\n\n`match number` in `main`, where number: i32\n"));
+    assert!(text.contains("\n  13..=19             reachable\n"));
+    assert!(text.ends_with("Every match is exhaustive and every arm is reachable.\n"));
+
+    let output = run(&["04", "--matches", "4"]);
+    assert!(output.status.success());
+    let text = stdout(&output);
+    assert!(text.contains("(non_exhaustive_match.rs)"));
+    assert!(text.contains("error[E0004]: non-exhaustive patterns: `Message::Write(_)` not covered"));
+
+    let output = run(&["04", "--matches", "6"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "ch04 has no programs for the match checker in example `6`\n"
+    );
+
+    let data = data_dir();
+    let file = data.join("matches.rs");
+    std::fs::write(
+        &file,
+        "fn main() {\n    let b = true;\n    match b {\n        true => {}\n        true => {}\n    }\n}\n",
+    )
+    .unwrap();
+    let output = run(&["matches", file.to_str().unwrap()]);
+    assert!(!output.status.success());
+    let text = stdout(&output);
+    assert!(text.contains("  true  unreachable\n  Not covered: `false`\n"));
+    assert!(text.contains("warning: unreachable pattern"));
+}
+
+#[test]
+fn test_calc() {
+    let output = run(&["04", "--calc", "1 + 2 * (3 - x)", "x=4"]);
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "Parsed: 1 + 2 * (3 - x)\nFolded: 1 + 2 * (3 - x)\nValue:  -1\n"
+    );
+
+    let output = run(&["04", "--calc", "x / (2 - 2)", "x=1"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "Parsed: x / (2 - 2)\nFolded: x / 0\nerror: division by zero\n"
+    );
+
+    // Errors in the expression itself point at where they are
+    let output = run(&["04", "--calc", "1 + )"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "error: expected an expression, found `)`\n  1 + )\n      ^\n"
+    );
+
+    // The calculator belongs to ch04
+    let output = run(&["01", "--calc", "1"]);
+    assert!(!output.status.success());
+}
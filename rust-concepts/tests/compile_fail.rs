@@ -0,0 +1,41 @@
+// Compile-fail tests: every snippet in compile_fail/ is handed to the local rustc,
+// which must reject it with exactly the error code the snippet declares
+
+use rust_concepts::compile_fail::{self, SNIPPETS};
+use std::thread;
+
+#[test]
+fn test_every_snippet_fails_with_its_declared_code() {
+    // rustc takes a moment per snippet, so they are checked on scoped threads
+    let failures: Vec<String> = thread::scope(|scope| {
+        let handles: Vec<_> = SNIPPETS
+            .iter()
+            .map(|snippet| scope.spawn(move || (snippet, snippet.compile())))
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|handle| {
+                let (snippet, diagnostics) = handle.join().unwrap();
+                let diagnostics = diagnostics.expect("the local rustc runs");
+                let expected = snippet.expected_code().unwrap();
+                if !diagnostics.success && diagnostics.codes == [expected] {
+                    return None;
+                }
+                Some(format!(
+                    "{}: expected only {}, got {:?}\n{}",
+                    snippet.path, expected, diagnostics.codes, diagnostics.rendered
+                ))
+            })
+            .collect()
+    });
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn test_fixed_program_compiles() {
+    // The harness must not reject everything: the corrected version of a snippet is accepted
+    let source = "fn main() {\n    let s1 = String::from(\"hello\");\n    let s2 = s1.clone();\n    println!(\"{} {}\", s1, s2);\n}\n";
+    let diagnostics = compile_fail::compile("fixed", source).unwrap();
+    assert!(diagnostics.success, "{}", diagnostics.rendered);
+    assert!(diagnostics.codes.is_empty());
+}
@@ -0,0 +1,90 @@
+// Exercise tests: the hidden tests of every exercise are compiled and run with the local rustc,
+// against the reference solution and against the stub the learner starts from
+
+use rust_concepts::exercises::{self, Grade, EXERCISES};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn test_every_solution_passes_its_hidden_tests() {
+    // Each grading compiles a test binary, so the exercises are graded on scoped threads
+    let failures: Vec<String> = thread::scope(|scope| {
+        let handles: Vec<_> = EXERCISES
+            .iter()
+            .map(|exercise| {
+                scope.spawn(move || (exercise, exercises::grade(exercise, exercise.solution)))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|handle| {
+                let (exercise, grade) = handle.join().unwrap();
+                let grade = grade.expect("the local rustc runs");
+                (!grade.passed()).then(|| format!("{}: {:?}", exercise.id(), grade))
+            })
+            .collect()
+    });
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn test_every_stub_compiles_with_its_hidden_tests() {
+    // The hidden tests may only use names the stub already declares, or a learner
+    // would get compile errors about code they can't see
+    for exercise in EXERCISES {
+        let stub = std::fs::read_to_string(exercise.learner_file()).unwrap();
+        let grade = exercises::grade(exercise, &stub).unwrap();
+        assert!(
+            matches!(grade, Grade::Tested { .. }),
+            "{}: {:?}",
+            exercise.id(),
+            grade
+        );
+    }
+}
+
+#[test]
+fn test_wrong_answer_reports_the_failing_tests() {
+    let exercise = exercises::find("ch01/exclaim").unwrap();
+    let grade = exercises::grade(exercise, "pub fn exclaim(s: String) -> String { s }").unwrap();
+    let Grade::Tested { results } = grade else {
+        panic!("expected the tests to run, got {:?}", grade);
+    };
+    let failed: Vec<&str> = results
+        .iter()
+        .filter(|r| !r.passed)
+        .map(|r| r.name.as_str())
+        .collect();
+    assert_eq!(
+        failed,
+        ["adds_an_exclamation_mark", "works_on_an_empty_string"]
+    );
+    assert!(results[0]
+        .message
+        .as_deref()
+        .unwrap()
+        .contains("right: \"hello!\""));
+}
+
+#[test]
+fn test_compile_errors_are_reported() {
+    let exercise = exercises::find("ch01/exclaim").unwrap();
+    let grade = exercises::grade(
+        exercise,
+        "pub fn exclaim(s: String) -> String { s.push('!'); s }",
+    )
+    .unwrap();
+    let Grade::DoesNotCompile { diagnostics } = grade else {
+        panic!("expected a compile error, got {:?}", grade);
+    };
+    assert!(diagnostics.contains("error[E0596]"), "{}", diagnostics);
+    assert!(diagnostics.contains("--> exclaim.rs:1:"), "{}", diagnostics);
+}
+
+#[test]
+fn test_endless_loop_times_out() {
+    let exercise = exercises::find("ch02/count_vowels").unwrap();
+    let source = "pub fn count_vowels(text: &str) -> usize { loop {} }";
+    let grade = exercises::grade_within(exercise, source, Duration::from_millis(500)).unwrap();
+    assert!(matches!(grade, Grade::TimedOut), "{:?}", grade);
+}
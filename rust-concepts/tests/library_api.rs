@@ -0,0 +1,76 @@
+// Integration tests: these use rust_concepts from the outside, exactly like another crate would,
+// so they only see what the library makes public
+
+use rust_concepts::ch05_traits::{Summary, Tweet};
+use rust_concepts::ch08_macros::Describe;
+use rust_concepts::{ch02_borrowing, ch03_lifetime, ch07_error_handling, registry, verify};
+
+#[test]
+fn test_every_chapter_is_registered_and_runs() {
+    let ids: Vec<&str> = registry::CHAPTERS.iter().map(|c| c.id()).collect();
+    assert_eq!(
+        ids,
+        ["01", "02", "03", "04", "05", "06", "07", "08", "09", "10"]
+    );
+    for chapter in registry::CHAPTERS {
+        let mut output = Vec::new();
+        chapter.run(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().next(), Some(chapter.banner().as_str()));
+    }
+}
+
+#[test]
+fn test_every_chapter_matches_its_result_comments() {
+    for chapter in registry::CHAPTERS {
+        let report = verify::verify_chapter(*chapter).unwrap();
+        assert!(
+            report.mismatches.is_empty(),
+            "ch{} has mismatches: {:?}",
+            chapter.id(),
+            report.mismatches
+        );
+    }
+}
+
+#[test]
+fn test_chapter_items_are_usable_from_another_crate() {
+    let s = String::from("hello");
+    assert_eq!(ch02_borrowing::calculate_length(&s), 5);
+
+    let text = String::from("First sentence. Second.");
+    let excerpt = ch03_lifetime::ImportantExcerpt {
+        part: text.split('.').next().unwrap(),
+    };
+    assert_eq!(excerpt.part, "First sentence");
+
+    let tweet = Tweet {
+        username: String::from("rustlang"),
+    };
+    assert_eq!(tweet.summarize(), "(Read more from @rustlang...)");
+
+    let error = ch07_error_handling::load_config("x.conf", "").unwrap_err();
+    assert_eq!(error.cause.to_string(), "missing key `port`");
+}
+
+#[test]
+fn test_exported_macros() {
+    let v: Vec<u8> = rust_concepts::my_vec![1, 2];
+    assert_eq!(v, [1, 2]);
+    assert_eq!(rust_concepts::rpn!(2 3 * 1 -), 5);
+    // The same macros are re-exported from the chapter module
+    assert_eq!(rust_concepts::ch08_macros::max!(1, 7, 3), 7);
+}
+
+// The derive macro expands to `impl Describe for ...`, so it works in any crate with the trait in scope
+#[allow(dead_code)]
+#[derive(Describe)]
+enum Signal {
+    Red,
+    Green,
+}
+
+#[test]
+fn test_derive_in_another_crate() {
+    assert_eq!(Signal::describe(), "enum Signal { Red, Green }");
+}
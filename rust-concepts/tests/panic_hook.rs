@@ -0,0 +1,30 @@
+// Chapter 7 silences the panics it catches on purpose. The panic hook is shared by the
+// whole process, so this runs in a binary of its own rather than next to the other tests
+
+use rust_concepts::ch07_error_handling;
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static PANICS_SEEN: AtomicUsize = AtomicUsize::new(0);
+
+fn count_panics() {
+    panic::set_hook(Box::new(|_| {
+        PANICS_SEEN.fetch_add(1, Ordering::SeqCst);
+    }));
+}
+
+#[test]
+fn test_demo_puts_the_panic_hook_back() {
+    count_panics();
+    ch07_error_handling::demo(&mut Vec::new()).unwrap();
+    // The demo's own panic is kept quiet, and afterwards panics reach the hook again
+    assert_eq!(PANICS_SEEN.load(Ordering::SeqCst), 0);
+    assert!(ch07_error_handling::catch_panic(|| panic!("after the demo")).is_err());
+    assert_eq!(PANICS_SEEN.load(Ordering::SeqCst), 1);
+
+    // A hook set between two demos is left in place, and still doesn't see the demo's panic
+    count_panics();
+    ch07_error_handling::demo(&mut Vec::new()).unwrap();
+    assert_eq!(PANICS_SEEN.load(Ordering::SeqCst), 1);
+    drop(panic::take_hook());
+}
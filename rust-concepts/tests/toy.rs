@@ -0,0 +1,97 @@
+// The toy programs are real Rust: rustc must agree with the ownership simulator, the
+// borrow checker and the match checker about them
+
+use rust_concepts::compile_fail::{self, SNIPPETS};
+use rust_concepts::toy::{self, borrowck, ownership, parser, usefulness, MATCHES, SAMPLES};
+use std::thread;
+
+#[test]
+fn test_samples_compile_with_rustc() {
+    let failures: Vec<String> = thread::scope(|scope| {
+        let handles: Vec<_> = SAMPLES
+            .iter()
+            .chain(MATCHES)
+            .map(|sample| {
+                scope.spawn(move || (sample, compile_fail::compile("sample", sample.source)))
+            })
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|handle| {
+                let (sample, diagnostics) = handle.join().unwrap();
+                let diagnostics = diagnostics.expect("the local rustc runs");
+                (!diagnostics.success)
+                    .then(|| format!("{}:\n{}", sample.path, diagnostics.rendered))
+            })
+            .collect()
+    });
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn test_simulator_reports_the_same_error_as_rustc() {
+    // Every compile-fail snippet written in the toy language must fail in the simulator too
+    let mut checked = 0;
+    for snippet in SNIPPETS {
+        let programs = toy::programs_for(snippet.chapter, snippet.example);
+        if !programs.iter().any(|(_, source)| *source == snippet.source) {
+            continue;
+        }
+        let program = parser::parse(snippet.source).unwrap();
+        if !ownership::supports(&program) {
+            continue;
+        }
+        let error = ownership::run(snippet.source).error;
+        let code = error.as_ref().and_then(|error| error.code);
+        assert_eq!(code, snippet.expected_code(), "{}", snippet.path);
+        checked += 1;
+    }
+    assert_eq!(checked, 2);
+}
+
+#[test]
+fn test_borrow_checker_reports_the_same_error_as_rustc() {
+    for sample in SAMPLES {
+        let errors = borrowck::run(sample.source).errors;
+        assert!(errors.is_empty(), "{}: {:?}", sample.path, errors);
+    }
+    // Every compile-fail snippet written in the toy language must fail in the checker too
+    let mut checked = 0;
+    for snippet in SNIPPETS {
+        let programs = toy::programs_for(snippet.chapter, snippet.example);
+        if !programs.iter().any(|(_, source)| *source == snippet.source) {
+            continue;
+        }
+        let errors = borrowck::run(snippet.source).errors;
+        let code = errors.first().and_then(|error| error.code);
+        assert_eq!(code, snippet.expected_code(), "{}", snippet.path);
+        checked += 1;
+    }
+    assert_eq!(checked, 7);
+}
+
+#[test]
+fn test_match_checker_reports_the_same_error_as_rustc() {
+    for fixture in MATCHES {
+        let check = usefulness::run(fixture.source);
+        assert!(
+            check.errors.is_empty(),
+            "{}: {:?}",
+            fixture.path,
+            check.errors
+        );
+    }
+    // Every compile-fail snippet the pattern reader understands must fail in the checker too
+    let mut checked = 0;
+    for snippet in SNIPPETS {
+        let files = toy::matches_for(snippet.chapter, snippet.example);
+        if !files.iter().any(|(_, source)| *source == snippet.source) {
+            continue;
+        }
+        let errors = usefulness::run(snippet.source).errors;
+        let code = errors.first().and_then(|error| error.code);
+        assert_eq!(code, snippet.expected_code(), "{}", snippet.path);
+        checked += 1;
+    }
+    assert_eq!(checked, 1);
+}
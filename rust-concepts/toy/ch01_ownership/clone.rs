@@ -0,0 +1,7 @@
+// Example 2: Clone
+// If we want to create a deep copy of the data, we can use the clone method
+fn main() {
+    let s3 = String::from("world");
+    let s4 = s3.clone();
+    println!("s3: {}, s4: {}", s3, s4);
+}
@@ -0,0 +1,7 @@
+// Example 3: Copy for stack-only data
+// For simple types that have a known size at compile time, the Copy trait is implemented
+fn main() {
+    let x = 5;
+    let y = x;
+    println!("x: {}, y: {}", x, y);
+}
@@ -0,0 +1,7 @@
+// Example 1: Move semantics
+// In Rust, when we assign a value to another variable, the ownership is moved
+fn main() {
+    let s1 = String::from("hello");
+    let s2 = s1;
+    println!("s2: {}", s2);
+}
@@ -0,0 +1,18 @@
+// Example 4: Ownership and functions
+// When we pass a value to a function, the ownership is transferred to that function
+fn takes_ownership(some_string: String) {
+    println!("{}", some_string);
+}
+
+fn makes_copy(some_integer: i32) {
+    println!("{}", some_integer);
+}
+
+fn main() {
+    let s5 = String::from("hello");
+    takes_ownership(s5);
+
+    let x = 5;
+    makes_copy(x);
+    println!("x is still accessible: {}", x);
+}
@@ -0,0 +1,19 @@
+// Example 5: Return values and scope
+// Functions can also transfer ownership of their return values
+fn gives_ownership() -> String {
+    let some_string = String::from("yours");
+    some_string
+}
+
+fn takes_and_gives_back(a_string: String) -> String {
+    a_string
+}
+
+fn main() {
+    let s6 = gives_ownership();
+    println!("s6: {}", s6);
+
+    let s7 = String::from("hello");
+    let s8 = takes_and_gives_back(s7);
+    println!("s8: {}", s8);
+}
@@ -0,0 +1,11 @@
+// Example 5: Preventing dangling references
+// Rust's borrow checker ensures we don't create dangling references
+fn no_dangle() -> String {
+    let s = String::from("hello");
+    s
+}
+
+fn main() {
+    let reference_to_nothing = no_dangle();
+    println!("Reference: {}", reference_to_nothing);
+}
@@ -0,0 +1,8 @@
+// Example 3: Multiple immutable references
+// Rust allows multiple immutable references to the same data
+fn main() {
+    let s2 = String::from("hello");
+    let r1 = &s2;
+    let r2 = &s2;
+    println!("r1: {}, r2: {}", r1, r2);
+}
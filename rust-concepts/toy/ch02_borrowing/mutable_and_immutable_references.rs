@@ -0,0 +1,11 @@
+// Example 4: Mutable and immutable references (not simultaneously)
+// Rust enforces the rule that you can have either one mutable reference or any number of immutable references, but not both at the same time
+fn main() {
+    let mut s3 = String::from("hello");
+    {
+        let r1 = &s3;
+        println!("r1: {}", r1);
+    }
+    let r2 = &mut s3;
+    println!("r2: {}", r2);
+}
@@ -0,0 +1,11 @@
+// Example 2: Mutable references
+// We create a mutable String and then borrow it mutably to change its contents
+fn change(some_string: &mut String) {
+    some_string.push_str(", world");
+}
+
+fn main() {
+    let mut s = String::from("hello");
+    change(&mut s);
+    println!("After change: {}", s);
+}
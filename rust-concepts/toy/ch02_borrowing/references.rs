@@ -0,0 +1,11 @@
+// Example 1: Borrowing with references
+// Here we create a String and then borrow it immutably to calculate its length
+fn calculate_length(s: &String) -> usize {
+    s.len()
+}
+
+fn main() {
+    let s1 = String::from("hello");
+    let len = calculate_length(&s1);
+    println!("The length of '{}' is {}.", s1, len);
+}
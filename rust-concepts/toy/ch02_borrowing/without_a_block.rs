@@ -0,0 +1,11 @@
+// Example 4: Mutable and immutable references (not simultaneously)
+// The block isn't needed: a borrow ends at the last use of its reference, so r1 is done
+// before r2 is created even though r1's scope runs to the end of main
+fn main() {
+    let mut s3 = String::from("hello");
+    let r1 = &s3;
+    println!("r1: {}", r1);
+    let r2 = &mut s3;
+    r2.push_str(", world");
+    println!("r2: {}", r2);
+}
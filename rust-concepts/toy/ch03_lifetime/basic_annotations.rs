@@ -0,0 +1,16 @@
+// Example 1: Basic lifetime annotations
+// Here we create two strings and pass references to them to the `longest` function
+fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {
+    if x.len() > y.len() {
+        x
+    } else {
+        y
+    }
+}
+
+fn main() {
+    let string1 = String::from("short");
+    let string2 = String::from("longer string");
+    let result = longest(&string1, &string2);
+    println!("Longest string: {}", result);
+}
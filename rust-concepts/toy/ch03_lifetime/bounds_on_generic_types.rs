@@ -0,0 +1,13 @@
+// Example 5: Lifetime bounds on generic types
+// We can use lifetime annotations with generic types to ensure references live long enough
+struct Point<'a, 'b> {
+    x: &'a i32,
+    y: &'b i32,
+}
+
+fn main() {
+    let x = 5;
+    let y = 10;
+    let point = Point { x: &x, y: &y };
+    println!("Point coordinates: ({}, {})", point.x, point.y);
+}
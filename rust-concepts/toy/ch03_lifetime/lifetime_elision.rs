@@ -0,0 +1,11 @@
+// Example 4: Lifetime elision
+// Rust has lifetime elision rules that allow us to omit lifetime annotations in common cases
+fn calculate_length(s: &str) -> usize {
+    s.len()
+}
+
+fn main() {
+    let s1 = String::from("hello");
+    let len = calculate_length(&s1);
+    println!("The length of '{}' is {}.", s1, len);
+}
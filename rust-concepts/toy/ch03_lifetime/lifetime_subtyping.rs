@@ -0,0 +1,16 @@
+// Example 6: Lifetime subtyping
+// Demonstrates how one lifetime can outlive another
+struct Announcer<'message, 'data> {
+    message: &'message str,
+    data: &'data str,
+}
+
+fn main() {
+    let s1 = String::from("short");
+    let s2 = String::from("longer");
+    let announce = Announcer {
+        message: &s1,
+        data: &s2,
+    };
+    println!("Announcement: {} (data: {})", announce.message, announce.data);
+}
@@ -0,0 +1,19 @@
+// Example 1: Basic lifetime annotations
+// 'a is the part of main where both arguments are alive, so the result must be used
+// before the block that owns string2 ends
+fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {
+    if x.len() > y.len() {
+        x
+    } else {
+        y
+    }
+}
+
+fn main() {
+    let string1 = String::from("long string is long");
+    {
+        let string2 = String::from("xyz");
+        let result = longest(&string1, &string2);
+        println!("Longest string: {}", result);
+    }
+}
@@ -0,0 +1,6 @@
+// Example 3: Static lifetime
+// The 'static lifetime is a special lifetime that lasts for the entire duration of the program
+fn main() {
+    let s: &'static str = "I have a static lifetime.";
+    println!("Static string: {}", s);
+}
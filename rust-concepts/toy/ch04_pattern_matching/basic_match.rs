@@ -0,0 +1,11 @@
+// Example 1: Basic match expression
+// The arms cover one number, a few primes, the teens and then everything else
+fn main() {
+    let number: i32 = 13;
+    match number {
+        1 => println!("One!"),
+        2 | 3 | 5 | 7 | 11 => println!("This is a prime"),
+        13..=19 => println!("A teen"),
+        _ => println!("Ain't special"),
+    }
+}
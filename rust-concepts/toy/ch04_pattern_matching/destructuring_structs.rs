@@ -0,0 +1,15 @@
+// Example 3: Destructuring structs
+// The last arm matches every point, so the match is exhaustive without a `_`
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn main() {
+    let point = Point { x: 0, y: 7 };
+    match point {
+        Point { x, y: 0 } => println!("On the x axis at {}", x),
+        Point { x: 0, y } => println!("On the y axis at {}", y),
+        Point { x, y } => println!("On neither axis: ({}, {})", x, y),
+    }
+}
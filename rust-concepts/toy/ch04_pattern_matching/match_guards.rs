@@ -0,0 +1,11 @@
+// Example 2: Matching with guards
+// Guarded arms cover nothing on their own, so the last arm still has to catch every pair
+fn main() {
+    let pair = (2, -2);
+    match pair {
+        (x, y) if x == y => println!("These are twins"),
+        (x, y) if x + y == 0 => println!("Antimatter, kaboom!"),
+        (x, _) if x % 2 == 1 => println!("The first one is odd"),
+        _ => println!("No correlation..."),
+    }
+}
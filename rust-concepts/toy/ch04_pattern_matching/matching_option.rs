@@ -0,0 +1,9 @@
+// Example 5: Pattern matching with Option
+// `Some(i)` and `None` are every value an `Option` can hold
+fn main() {
+    let some_number = Some(5);
+    match some_number {
+        Some(i) => println!("Got an integer: {}", i),
+        None => println!("No integer!"),
+    }
+}